@@ -0,0 +1,323 @@
+//! ACME DNS-01 challenge solving (RFC 8555 §8.4).
+//!
+//! A [`Dns01Solver`] publishes the TXT records for one or more [`Dns01Challenge`]s in any zone implementing [`CreateRecord`] and [`DeleteRecord`],
+//! optionally waits until they are visible, and removes exactly the records it published afterwards.
+//! Other TXT values at the same name, e.g. of concurrent challenges or other ACME clients, are left untouched.
+//!
+//! A wildcard identifier (`*.example.com`) uses the same `_acme-challenge` name as its base domain,
+//! so both can be solved at once and result in two TXT values for the same host.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use libdns::acme::{Dns01Challenge, Dns01Solver, SolverError, ZoneCheck};
+//! use libdns::{CreateRecord, DeleteRecord};
+//!
+//! async fn solve<Z: CreateRecord + DeleteRecord>(
+//!     zone: &Z,
+//!     key_authorizations: [&str; 2],
+//! ) -> Result<(), SolverError<Z>> {
+//!     let challenges = [
+//!         Dns01Challenge::new("example.com", key_authorizations[0]),
+//!         Dns01Challenge::new("*.example.com", key_authorizations[1]),
+//!     ];
+//!
+//!     let solver = Dns01Solver::new(zone);
+//!     solver.present(&challenges).await?;
+//!     let check = ZoneCheck::new(zone);
+//!     let timeout = Duration::from_secs(120);
+//!     solver
+//!         .wait_for_propagation(&challenges, &check, timeout, Duration::from_secs(5))
+//!         .await?;
+//!
+//!     // ... let the ACME server validate the challenges ...
+//!
+//!     solver.cleanup(&challenges).await
+//! }
+//! ```
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::{
+    ascii_name, normalize_host, same_content, with_listed_record, ClassifyError, CreateRecord,
+    CreateRecordError, DeleteRecord, DeleteRecordError, ErrorKind, RecordData, RetrieveRecordError,
+    Zone,
+};
+
+/// The label prepended to the identifier to form the challenge record name.
+const CHALLENGE_LABEL: &str = "_acme-challenge";
+
+/// A TTL short enough for resolvers not to cache stale challenges for long.
+const DEFAULT_TTL: u64 = 60;
+
+/// A single DNS-01 challenge for an identifier.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Dns01Challenge {
+    identifier: String,
+    value: String,
+}
+
+impl Dns01Challenge {
+    /// Creates a challenge for the identifier from the key authorization (`token.thumbprint`) of the ACME challenge.
+    ///
+    /// The TXT value is the unpadded base64url encoding of the key authorization's SHA-256 digest.
+    pub fn new(identifier: &str, key_authorization: &str) -> Self {
+        let digest = Sha256::digest(key_authorization.as_bytes());
+        Self::from_digest(identifier, &base64url(&digest))
+    }
+
+    /// Creates a challenge for the identifier from an already computed TXT value, as provided by many ACME clients.
+    pub fn from_digest(identifier: &str, value: &str) -> Self {
+        Self {
+            identifier: identifier.trim_end_matches('.').to_ascii_lowercase(),
+            value: value.to_string(),
+        }
+    }
+
+    /// Returns the identifier the challenge was created for.
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    /// Returns the TXT value to publish.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns the fully qualified record name (without trailing dot), e.g. `_acme-challenge.example.com` for `*.example.com`.
+    pub fn record_name(&self) -> String {
+        let domain = self
+            .identifier
+            .strip_prefix("*.")
+            .unwrap_or(&self.identifier);
+        format!("{}.{}", CHALLENGE_LABEL, domain)
+    }
+
+    /// Returns the record host relative to the zone of the given domain, or `None` if the identifier is not part of the zone.
+    pub fn record_host(&self, zone_domain: &str) -> Option<String> {
+        let name = ascii_name(&self.record_name());
+        let host = normalize_host(&name, zone_domain);
+        (host != name).then_some(host)
+    }
+}
+
+/// A way to tell whether a challenge record can be seen by the ACME server.
+pub trait PropagationCheck: Send + Sync {
+    /// Returns whether the TXT `value` is visible at the fully qualified `name`.
+    fn is_visible(&self, name: &str, value: &str) -> impl Future<Output = bool> + Send;
+}
+
+/// A [`PropagationCheck`] that waits until the provider reports the record through [`Zone::list_records`].
+///
+/// This only guarantees that the provider has accepted the record, which suffices for providers that publish changes immediately.
+#[derive(Debug, Clone, Copy)]
+pub struct ZoneCheck<'a, Z> {
+    zone: &'a Z,
+}
+
+impl<'a, Z: Zone> ZoneCheck<'a, Z> {
+    /// Creates a check listing the records of the given zone.
+    pub fn new(zone: &'a Z) -> Self {
+        Self { zone }
+    }
+}
+
+impl<Z: Zone> PropagationCheck for ZoneCheck<'_, Z> {
+    async fn is_visible(&self, name: &str, value: &str) -> bool {
+        let data = RecordData::TXT(value.to_string());
+        let domain = self.zone.domain();
+
+        self.zone.list_records().await.is_ok_and(|records| {
+            records
+                .iter()
+                .any(|record| same_content(record, name, &data, domain))
+        })
+    }
+}
+
+/// Publishes and removes DNS-01 challenge records in a zone.
+#[derive(Debug, Clone, Copy)]
+pub struct Dns01Solver<'a, Z> {
+    zone: &'a Z,
+    ttl: u64,
+}
+
+impl<'a, Z> Dns01Solver<'a, Z>
+where
+    Z: CreateRecord + DeleteRecord,
+{
+    /// Creates a solver for the given zone, publishing records with a TTL of 60 seconds.
+    pub fn new(zone: &'a Z) -> Self {
+        Self {
+            zone,
+            ttl: DEFAULT_TTL,
+        }
+    }
+
+    /// Sets the TTL of the published records.
+    pub fn with_ttl(mut self, ttl: u64) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Publishes the TXT records of all challenges.
+    ///
+    /// If a record cannot be created, the records published so far are removed again before the error is returned.
+    pub async fn present(&self, challenges: &[Dns01Challenge]) -> Result<(), SolverError<Z>> {
+        let hosts = self.hosts(challenges)?;
+
+        for (index, (host, challenge)) in hosts.iter().zip(challenges).enumerate() {
+            let data = RecordData::TXT(challenge.value.clone());
+            if let Err(err) = self.zone.create_record(host, &data, self.ttl).await {
+                // Best effort, the creation error is more relevant to the caller
+                let _ = self.cleanup(&challenges[..index]).await;
+                return Err(Dns01Error::Create(err));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Waits until all challenges are visible according to `check`, polling every `interval`.
+    pub async fn wait_for_propagation(
+        &self,
+        challenges: &[Dns01Challenge],
+        check: &impl PropagationCheck,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<(), SolverError<Z>> {
+        let deadline = Instant::now() + timeout;
+        let mut pending: Vec<&Dns01Challenge> = challenges.iter().collect();
+
+        loop {
+            let mut still_pending = Vec::with_capacity(pending.len());
+            for challenge in pending {
+                if !check
+                    .is_visible(&challenge.record_name(), &challenge.value)
+                    .await
+                {
+                    still_pending.push(challenge);
+                }
+            }
+            pending = still_pending;
+
+            if pending.is_empty() {
+                return Ok(());
+            }
+            if Instant::now() + interval > deadline {
+                return Err(Dns01Error::Timeout);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Removes the TXT records of all challenges.
+    ///
+    /// Records are looked up by name and value, so only the values of the given challenges are removed.
+    /// Challenges whose record no longer exists are ignored.
+    pub async fn cleanup(&self, challenges: &[Dns01Challenge]) -> Result<(), SolverError<Z>> {
+        let hosts = self.hosts(challenges)?;
+
+        let mut listing = None;
+        for (host, challenge) in hosts.iter().zip(challenges) {
+            let data = RecordData::TXT(challenge.value.clone());
+            with_listed_record(
+                self.zone,
+                &mut listing,
+                host,
+                &data,
+                Dns01Error::Retrieve,
+                |record| async move {
+                    self.zone
+                        .delete_record(&record.id)
+                        .await
+                        .map_err(Dns01Error::Delete)
+                },
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the record hosts of all challenges relative to the zone.
+    fn hosts(&self, challenges: &[Dns01Challenge]) -> Result<Vec<String>, SolverError<Z>> {
+        challenges
+            .iter()
+            .map(|challenge| {
+                challenge
+                    .record_host(self.zone.domain())
+                    .ok_or(Dns01Error::NotInZone)
+            })
+            .collect()
+    }
+}
+
+/// The [`Dns01Error`] returned by a [`Dns01Solver`] for the zone `Z`.
+pub type SolverError<Z> = Dns01Error<
+    <Z as Zone>::CustomRetrieveError,
+    <Z as CreateRecord>::CustomCreateError,
+    <Z as DeleteRecord>::CustomDeleteError,
+>;
+
+/// Represents an error that occured when solving DNS-01 challenges using a [`Dns01Solver`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+pub enum Dns01Error<R, C, D> {
+    /// Indicates that an identifier does not belong to the zone of the solver.
+    #[error("the identifier is not part of the zone")]
+    NotInZone,
+
+    /// Indicates that the records of the zone could not be retrieved.
+    #[error(transparent)]
+    Retrieve(RetrieveRecordError<R>),
+
+    /// Indicates that a challenge record could not be created.
+    #[error(transparent)]
+    Create(CreateRecordError<C>),
+
+    /// Indicates that a challenge record could not be deleted.
+    #[error(transparent)]
+    Delete(DeleteRecordError<D>),
+
+    /// Indicates that the challenges did not become visible in time.
+    #[error("the challenge records did not propagate in time")]
+    Timeout,
+}
+
+impl<R: ClassifyError, C: ClassifyError, D: ClassifyError> ClassifyError for Dns01Error<R, C, D> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Dns01Error::NotInZone => ErrorKind::Validation,
+            Dns01Error::Retrieve(e) => e.kind(),
+            Dns01Error::Create(e) => e.kind(),
+            Dns01Error::Delete(e) => e.kind(),
+            Dns01Error::Timeout => ErrorKind::Transient,
+        }
+    }
+}
+
+/// Encodes bytes as unpadded base64url (RFC 4648 §5).
+fn base64url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let buf = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]);
+
+        for i in 0..=chunk.len() {
+            encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    encoded
+}
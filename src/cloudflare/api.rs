@@ -11,8 +11,11 @@ use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::retry::{RequestBuilderExt, RetryPolicy};
+use crate::{ClassifyError, ErrorKind};
+
 /// The Cloudflare API base URL.
-const CLOUDFLARE_API_URL: &str = "https://api.cloudflare.com/client/v4";
+pub(crate) const CLOUDFLARE_API_URL: &str = "https://api.cloudflare.com/client/v4";
 
 /// Errors that may occur when interacting with the Cloudflare API.
 #[derive(Debug, Error)]
@@ -45,6 +48,29 @@ impl std::fmt::Display for ApiError {
     }
 }
 
+impl ClassifyError for CloudflareError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            CloudflareError::Api(api_err) => match api_err.code {
+                9103 | 9106 | 10000 | 10001 => ErrorKind::Unauthorized,
+                // 81044 = Record not found, 9109/7003/1003 = Zone not found or invalid zone ID
+                81044 | 9109 | 7003 | 1003 => ErrorKind::NotFound,
+                // 81053 = Conflicting A, AAAA or CNAME record, 81057/81058 = Record already exists, 1061 = Zone already exists
+                81053 | 81057 | 81058 | 1061 => ErrorKind::AlreadyExists,
+                // 971 = Request throttled
+                971 => ErrorKind::RateLimited,
+                // 81045 = Record quota exceeded
+                81045 => ErrorKind::QuotaExceeded,
+                // 1004 = DNS validation error, 9000-9099 = Invalid record content, name or TTL
+                1004 | 9000..=9099 => ErrorKind::Validation,
+                _ => ErrorKind::Other,
+            },
+            CloudflareError::Request(e) => e.kind(),
+            CloudflareError::Serialization(_) => ErrorKind::Other,
+        }
+    }
+}
+
 /// Cloudflare API response wrapper.
 #[derive(Debug, Deserialize)]
 struct ApiResponse<T> {
@@ -174,13 +200,66 @@ pub struct CreateRecordRequest {
     /// Priority for MX records.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub priority: Option<u16>,
-    /// SRV record data.
+    /// Structured data of SRV, CAA, TLSA, SSHFP, DS, HTTPS, SVCB and NAPTR records.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub data: Option<SrvData>,
+    pub data: Option<StructuredData>,
+}
+
+/// Structured record data, which Cloudflare requires instead of the content for some record types.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum StructuredData {
+    /// SRV record data.
+    Srv(SrvData),
+    /// CAA record data.
+    Caa {
+        flags: u8,
+        tag: String,
+        value: String,
+    },
+    /// TLSA record data.
+    Tlsa {
+        usage: u8,
+        selector: u8,
+        matching_type: u8,
+        certificate: String,
+    },
+    /// SSHFP record data.
+    Sshfp {
+        algorithm: u8,
+        #[serde(rename = "type")]
+        fingerprint_type: u8,
+        fingerprint: String,
+    },
+    /// DS record data.
+    Ds {
+        key_tag: u16,
+        algorithm: u8,
+        digest_type: u8,
+        digest: String,
+    },
+    /// HTTPS or SVCB record data.
+    ServiceBinding {
+        priority: u16,
+        target: String,
+        value: String,
+    },
+    /// NAPTR record data.
+    Naptr {
+        order: u16,
+        preference: u16,
+        flags: String,
+        service: String,
+        regex: String,
+        replacement: String,
+    },
 }
 
 /// Supported DNS record types for Cloudflare.
-const SUPPORTED_RECORD_TYPES: &[&str] = &["A", "AAAA", "CNAME", "MX", "NS", "TXT", "SRV"];
+pub(crate) const SUPPORTED_RECORD_TYPES: &[&str] = &[
+    "A", "AAAA", "CNAME", "MX", "NS", "TXT", "SRV", "PTR", "CAA", "TLSA", "SSHFP", "DS", "HTTPS",
+    "SVCB", "NAPTR",
+];
 
 /// Error returned when a record cannot be converted to a [`crate::Record`].
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -288,6 +367,19 @@ impl TryFrom<DnsRecordWithZone<'_>> for crate::Record {
                         }
                     }
                 }
+                "PTR" => RecordData::PTR(record.content.clone()),
+                // The content holds these records in presentation format
+                "CAA" | "TLSA" | "SSHFP" | "DS" | "HTTPS" | "SVCB" | "NAPTR" => {
+                    match RecordData::from_raw(&record.record_type, &record.content) {
+                        RecordData::Other { .. } => {
+                            return Err(RecordConversionError {
+                                record_type: record.record_type.clone(),
+                                reason: "invalid record content",
+                            });
+                        }
+                        data => data,
+                    }
+                }
                 _ => {
                     return Err(RecordConversionError {
                         record_type: record.record_type.clone(),
@@ -342,7 +434,7 @@ impl CreateRecordRequest {
             format!("{}.{}", host, zone_name)
         };
 
-        let (record_type, content, priority, srv_data) = match data {
+        let (record_type, content, priority, structured) = match data {
             RecordData::A(ip) => ("A".to_string(), ip.to_string(), None, None),
             RecordData::AAAA(ip) => ("AAAA".to_string(), ip.to_string(), None, None),
             RecordData::CNAME(target) => ("CNAME".to_string(), target.clone(), None, None),
@@ -370,8 +462,111 @@ impl CreateRecordRequest {
                 };
                 // Content format for SRV: "weight port target"
                 let content = format!("{} {} {}", weight, port, target);
-                ("SRV".to_string(), content, Some(*priority), Some(srv_data))
+                (
+                    "SRV".to_string(),
+                    content,
+                    Some(*priority),
+                    Some(StructuredData::Srv(srv_data)),
+                )
             }
+            RecordData::PTR(target) => ("PTR".to_string(), target.clone(), None, None),
+            RecordData::CAA { flags, tag, value } => (
+                "CAA".to_string(),
+                data.get_value(),
+                None,
+                Some(StructuredData::Caa {
+                    flags: *flags,
+                    tag: tag.clone(),
+                    value: value.clone(),
+                }),
+            ),
+            RecordData::TLSA {
+                usage,
+                selector,
+                matching_type,
+                certificate,
+            } => (
+                "TLSA".to_string(),
+                data.get_value(),
+                None,
+                Some(StructuredData::Tlsa {
+                    usage: *usage,
+                    selector: *selector,
+                    matching_type: *matching_type,
+                    certificate: certificate.clone(),
+                }),
+            ),
+            RecordData::SSHFP {
+                algorithm,
+                fingerprint_type,
+                fingerprint,
+            } => (
+                "SSHFP".to_string(),
+                data.get_value(),
+                None,
+                Some(StructuredData::Sshfp {
+                    algorithm: *algorithm,
+                    fingerprint_type: *fingerprint_type,
+                    fingerprint: fingerprint.clone(),
+                }),
+            ),
+            RecordData::DS {
+                key_tag,
+                algorithm,
+                digest_type,
+                digest,
+            } => (
+                "DS".to_string(),
+                data.get_value(),
+                None,
+                Some(StructuredData::Ds {
+                    key_tag: *key_tag,
+                    algorithm: *algorithm,
+                    digest_type: *digest_type,
+                    digest: digest.clone(),
+                }),
+            ),
+            RecordData::HTTPS {
+                priority,
+                target,
+                params,
+            }
+            | RecordData::SVCB {
+                priority,
+                target,
+                params,
+            } => (
+                data.get_type().to_string(),
+                data.get_value(),
+                None,
+                Some(StructuredData::ServiceBinding {
+                    priority: *priority,
+                    target: target.clone(),
+                    value: params.clone(),
+                }),
+            ),
+            RecordData::NAPTR {
+                order,
+                preference,
+                flags,
+                services,
+                regexp,
+                replacement,
+            } => (
+                "NAPTR".to_string(),
+                data.get_value(),
+                None,
+                Some(StructuredData::Naptr {
+                    order: *order,
+                    preference: *preference,
+                    flags: flags.clone(),
+                    service: services.clone(),
+                    regex: regexp.clone(),
+                    replacement: replacement.clone(),
+                }),
+            ),
+            // Cloudflare manages the SOA record itself
+            RecordData::SOA { .. } => ("SOA".to_string(), data.get_value(), None, None),
             RecordData::Other { typ, value } => {
                 // Pass through other record types as-is
                 (typ.clone(), value.clone(), None, None)
@@ -390,7 +585,7 @@ impl CreateRecordRequest {
             ttl: if ttl == 0 { 1 } else { ttl as u32 }, // 1 = automatic TTL
             proxied: Some(false),                       // Don't proxy DNS records by default
             priority,
-            data: srv_data,
+            data: structured,
         })
     }
 }
@@ -401,15 +596,64 @@ pub struct DeleteResponse {
     pub id: String,
 }
 
+/// Request body for the batch DNS records endpoint.
+///
+/// Cloudflare applies the operations atomically in the order deletes, patches, posts.
+#[derive(Debug, Default, Serialize)]
+pub struct BatchRequest {
+    /// Records to delete.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deletes: Vec<BatchDelete>,
+    /// Records to update in place.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patches: Vec<BatchPatch>,
+    /// Records to create.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub posts: Vec<CreateRecordRequest>,
+}
+
+/// A record deletion within a [`BatchRequest`].
+#[derive(Debug, Serialize)]
+pub struct BatchDelete {
+    /// ID of the record to delete.
+    pub id: String,
+}
+
+/// A record update within a [`BatchRequest`].
+#[derive(Debug, Serialize)]
+pub struct BatchPatch {
+    /// ID of the record to update.
+    pub id: String,
+    /// The new record data.
+    #[serde(flatten)]
+    pub record: CreateRecordRequest,
+}
+
+/// Batch response.
+#[derive(Debug, Default, Deserialize)]
+pub struct BatchResponse {
+    /// The deleted records.
+    #[serde(default)]
+    pub deletes: Vec<DnsRecord>,
+    /// The updated records, in request order.
+    #[serde(default)]
+    pub patches: Vec<DnsRecord>,
+    /// The created records, in request order.
+    #[serde(default)]
+    pub posts: Vec<DnsRecord>,
+}
+
 // =============================================================================
 // API Client
 // =============================================================================
 
 /// Cloudflare API client.
+#[derive(Clone)]
 pub struct Client {
     http_client: reqwest::Client,
     api_token: String,
     base_url: String,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
@@ -434,15 +678,39 @@ impl Client {
         api_token: &str,
         base_url: &str,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let http_client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
+        let http_client = Self::http_client_builder().build()?;
+        Ok(Self::from_http_client(http_client, api_token, base_url))
+    }
 
-        Ok(Self {
+    /// Returns the builder of the HTTP client used unless a preconfigured one is given.
+    pub(crate) fn http_client_builder() -> reqwest::ClientBuilder {
+        reqwest::Client::builder().timeout(std::time::Duration::from_secs(30))
+    }
+
+    /// Creates a new Cloudflare API client sending its requests using the given HTTP client.
+    pub(crate) fn from_http_client(
+        http_client: reqwest::Client,
+        api_token: &str,
+        base_url: &str,
+    ) -> Self {
+        Self {
             http_client,
             api_token: api_token.to_string(),
             base_url: base_url.to_string(),
-        })
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Replaces the HTTP client, e.g. with one using custom timeouts, proxies or root certificates.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
     }
 
     /// Build headers for API requests.
@@ -463,7 +731,7 @@ impl Client {
             .http_client
             .get(&url)
             .headers(self.headers())
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await?;
 
         let api_response: ApiResponse<T> = response.json().await?;
@@ -503,7 +771,7 @@ impl Client {
                 .http_client
                 .get(&url)
                 .headers(self.headers())
-                .send()
+                .send_with_retry(&self.retry_policy, true)
                 .await?;
 
             let api_response: ApiResponse<Vec<T>> = response.json().await?;
@@ -549,7 +817,42 @@ impl Client {
             .post(&url)
             .headers(self.headers())
             .json(body)
-            .send()
+            .send_with_retry(&self.retry_policy, false)
+            .await?;
+
+        let api_response: ApiResponse<Resp> = response.json().await?;
+
+        if !api_response.success {
+            let error = api_response.errors.into_iter().next().unwrap_or(ApiError {
+                code: 0,
+                message: "Unknown error".to_string(),
+            });
+            return Err(CloudflareError::Api(error));
+        }
+
+        api_response.result.ok_or_else(|| {
+            CloudflareError::Api(ApiError {
+                code: 0,
+                message: "No result in response".to_string(),
+            })
+        })
+    }
+
+    /// Make a PATCH request.
+    ///
+    /// Patches always carry the complete record, so they are retried like idempotent requests.
+    async fn patch<Req: Serialize, Resp: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        body: &Req,
+    ) -> Result<Resp, CloudflareError> {
+        let url = format!("{}{}", self.base_url, path);
+        let response = self
+            .http_client
+            .patch(&url)
+            .headers(self.headers())
+            .json(body)
+            .send_with_retry(&self.retry_policy, true)
             .await?;
 
         let api_response: ApiResponse<Resp> = response.json().await?;
@@ -577,7 +880,7 @@ impl Client {
             .http_client
             .delete(&url)
             .headers(self.headers())
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await?;
 
         let api_response: ApiResponse<T> = response.json().await?;
@@ -653,6 +956,20 @@ impl Client {
             .await
     }
 
+    /// Updates an existing DNS record in place.
+    pub async fn update_record(
+        &self,
+        zone_id: &str,
+        record_id: &str,
+        request: &CreateRecordRequest,
+    ) -> Result<DnsRecord, CloudflareError> {
+        self.patch(
+            &format!("/zones/{}/dns_records/{}", zone_id, record_id),
+            request,
+        )
+        .await
+    }
+
     /// Deletes a DNS record.
     pub async fn delete_record(
         &self,
@@ -662,4 +979,14 @@ impl Client {
         self.delete(&format!("/zones/{}/dns_records/{}", zone_id, record_id))
             .await
     }
+
+    /// Applies multiple record operations as a single atomic batch.
+    pub async fn batch_records(
+        &self,
+        zone_id: &str,
+        request: &BatchRequest,
+    ) -> Result<BatchResponse, CloudflareError> {
+        self.post(&format!("/zones/{}/dns_records/batch", zone_id), request)
+            .await
+    }
 }
@@ -44,12 +44,22 @@ pub mod api;
 
 use std::error::Error as StdErr;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub use api::{ApiError, Client, CloudflareError, DnsRecordWithZone, RecordConversionError};
 
+#[cfg(feature = "ddns")]
+use crate::ddns::DynamicDns;
+use crate::dynamic::{DynProvider, DynProviderAdapter};
+use crate::http::HttpOptions;
+use crate::migrate::MigrationTarget;
+use crate::retry::RetryPolicy;
+use crate::types::to_ascii_name;
 use crate::{
-    CreateRecord, CreateRecordError, DeleteRecord, DeleteRecordError, Provider, Record, RecordData,
-    RetrieveRecordError, RetrieveZoneError, Zone,
+    AppendRecords, ApplyChanges, ApplyChangesError, Capabilities, ChangeError, CreateRecord,
+    CreateRecordError, DeleteRecord, DeleteRecordError, DeleteRecords, Provider, Record,
+    RecordChange, RecordData, RetrieveRecordError, RetrieveZoneError, SetRecords, UpdateRecord,
+    UpdateRecordError, Zone,
 };
 
 /// Cloudflare DNS provider.
@@ -89,10 +99,7 @@ impl CloudflareProvider {
     /// let provider = CloudflareProvider::new("your_api_token").unwrap();
     /// ```
     pub fn new(api_token: &str) -> Result<Self, Box<dyn StdErr + Send + Sync>> {
-        let api_client = Client::new(api_token)?;
-        Ok(Self {
-            api_client: Arc::new(api_client),
-        })
+        Self::builder(api_token).build()
     }
 
     /// Creates a new Cloudflare provider with a custom API base URL.
@@ -107,13 +114,120 @@ impl CloudflareProvider {
         api_token: &str,
         base_url: &str,
     ) -> Result<Self, Box<dyn StdErr + Send + Sync>> {
-        let api_client = Client::with_base_url(api_token, base_url)?;
-        Ok(Self {
+        Self::builder(api_token).with_base_url(base_url).build()
+    }
+
+    /// Returns a builder to configure the HTTP client, API base URL and retry policy of the provider.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use libdns::cloudflare::CloudflareProvider;
+    ///
+    /// let provider = CloudflareProvider::builder("your_api_token")
+    ///     .with_timeout(Duration::from_secs(10))
+    ///     .with_user_agent("my-app/1.0")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(api_token: &str) -> CloudflareProviderBuilder {
+        CloudflareProviderBuilder {
+            api_token: api_token.to_string(),
+            base_url: api::CLOUDFLARE_API_URL.to_string(),
+            http: HttpOptions::default(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        let api_client = self.api_client.as_ref().clone().with_retry_policy(policy);
+        Self {
+            api_client: Arc::new(api_client),
+        }
+    }
+}
+
+/// Builder for a [`CloudflareProvider`], created using [`CloudflareProvider::builder`].
+#[derive(Clone)]
+pub struct CloudflareProviderBuilder {
+    api_token: String,
+    base_url: String,
+    http: HttpOptions,
+    retry_policy: RetryPolicy,
+}
+
+impl CloudflareProviderBuilder {
+    /// Sets a custom API base URL, e.g. of a mock server.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Sends all requests using the given HTTP client, e.g. one with custom root certificates or connection pool limits.
+    ///
+    /// The timeout, proxy and user agent set on the builder are ignored in that case.
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http.client = Some(client);
+        self
+    }
+
+    /// Sets the timeout of every request, 30 seconds by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends all requests through the given proxy.
+    pub fn with_proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.http.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets the `User-Agent` header of every request.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.http.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Creates the provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn build(self) -> Result<CloudflareProvider, Box<dyn StdErr + Send + Sync>> {
+        let http_client = self.http.build(Client::http_client_builder())?;
+        let api_client = Client::from_http_client(http_client, &self.api_token, &self.base_url)
+            .with_retry_policy(self.retry_policy);
+        Ok(CloudflareProvider {
             api_client: Arc::new(api_client),
         })
     }
 }
 
+impl From<CloudflareProvider> for Box<dyn DynProvider> {
+    fn from(provider: CloudflareProvider) -> Self {
+        Box::new(
+            DynProviderAdapter::new(provider)
+                .with_create_record()
+                .with_update_record()
+                .with_delete_record(),
+        )
+    }
+}
+
 impl Provider for CloudflareProvider {
     type Zone = CloudflareZone;
     type CustomRetrieveError = CloudflareError;
@@ -122,6 +236,7 @@ impl Provider for CloudflareProvider {
         &self,
         zone_id: &str,
     ) -> Result<Self::Zone, RetrieveZoneError<Self::CustomRetrieveError>> {
+        let zone_id: &str = &to_ascii_name(zone_id).ok_or(RetrieveZoneError::NotFound)?;
         // zone_id can be either a zone ID (32-char hex) or domain name
         let zone = if zone_id.len() == 32 && zone_id.chars().all(|c| c.is_ascii_hexdigit()) {
             self.api_client.get_zone(zone_id).await
@@ -169,6 +284,10 @@ impl Provider for CloudflareProvider {
             })
             .collect())
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl Zone for CloudflareZone {
@@ -232,6 +351,10 @@ impl Zone for CloudflareZone {
             },
         )
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl CreateRecord for CloudflareZone {
@@ -243,6 +366,7 @@ impl CreateRecord for CloudflareZone {
         data: &RecordData,
         ttl: u64,
     ) -> Result<Record, CreateRecordError<Self::CustomCreateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(CreateRecordError::InvalidRecord)?;
         let request = api::CreateRecordRequest::from_record_data(host, data, ttl, &self.repr.name)
             .map_err(|_| CreateRecordError::UnsupportedType)?;
 
@@ -253,8 +377,6 @@ impl CreateRecord for CloudflareZone {
             .map_err(|err| match &err {
                 CloudflareError::Api(api_err) => match api_err.code {
                     9106 | 10000 => CreateRecordError::Unauthorized,
-                    // 81057 = Record already exists
-                    81057 => CreateRecordError::InvalidRecord,
                     _ => CreateRecordError::Custom(err),
                 },
                 _ => CreateRecordError::Custom(err),
@@ -271,6 +393,45 @@ impl CreateRecord for CloudflareZone {
     }
 }
 
+impl UpdateRecord for CloudflareZone {
+    type CustomUpdateError = CloudflareError;
+
+    async fn update_record(
+        &self,
+        record_id: &str,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, UpdateRecordError<Self::CustomUpdateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(UpdateRecordError::InvalidRecord)?;
+        let request = api::CreateRecordRequest::from_record_data(host, data, ttl, &self.repr.name)
+            .map_err(|_| UpdateRecordError::UnsupportedType)?;
+
+        let record = self
+            .api_client
+            .update_record(&self.repr.id, record_id, &request)
+            .await
+            .map_err(|err| match &err {
+                CloudflareError::Api(api_err) => match api_err.code {
+                    // 81044 = Record not found
+                    81044 => UpdateRecordError::NotFound,
+                    9106 | 10000 => UpdateRecordError::Unauthorized,
+                    _ => UpdateRecordError::Custom(err),
+                },
+                _ => UpdateRecordError::Custom(err),
+            })?;
+
+        crate::Record::try_from(api::DnsRecordWithZone::new(&record, &self.repr.name)).map_err(
+            |e| {
+                UpdateRecordError::Custom(CloudflareError::Api(ApiError {
+                    code: 0,
+                    message: format!("Failed to convert record: {}", e),
+                }))
+            },
+        )
+    }
+}
+
 impl DeleteRecord for CloudflareZone {
     type CustomDeleteError = CloudflareError;
 
@@ -294,3 +455,115 @@ impl DeleteRecord for CloudflareZone {
         Ok(())
     }
 }
+
+impl ApplyChanges for CloudflareZone {
+    type CustomApplyError = CloudflareError;
+
+    async fn apply_changes(
+        &self,
+        changes: &[RecordChange],
+    ) -> Result<Vec<Record>, ApplyChangesError<Self::CustomApplyError>> {
+        let mut request = api::BatchRequest::default();
+        for (index, change) in changes.iter().enumerate() {
+            let record_request = |host: &str, data: &RecordData, ttl: u64| {
+                let rejected = |cause| ApplyChangesError {
+                    index: Some(index),
+                    rolled_back: true,
+                    cause,
+                };
+                let host =
+                    to_ascii_name(host).ok_or_else(|| rejected(ChangeError::InvalidRecord))?;
+                api::CreateRecordRequest::from_record_data(&host, data, ttl, &self.repr.name)
+                    .map_err(|_| rejected(ChangeError::UnsupportedType))
+            };
+
+            match change {
+                RecordChange::Create { host, data, ttl } => {
+                    request.posts.push(record_request(host, data, *ttl)?);
+                }
+                RecordChange::Update {
+                    record_id,
+                    host,
+                    data,
+                    ttl,
+                } => request.patches.push(api::BatchPatch {
+                    id: record_id.clone(),
+                    record: record_request(host, data, *ttl)?,
+                }),
+                RecordChange::Delete { record_id } => request.deletes.push(api::BatchDelete {
+                    id: record_id.clone(),
+                }),
+            }
+        }
+
+        let response = self
+            .api_client
+            .batch_records(&self.repr.id, &request)
+            .await
+            .map_err(|err| ApplyChangesError {
+                index: None,
+                // Cloudflare rejects the whole batch, but a failed request may have been applied
+                rolled_back: matches!(err, CloudflareError::Api(_)),
+                cause: match &err {
+                    CloudflareError::Api(api_err) => match api_err.code {
+                        // 81044 = Record not found
+                        81044 => ChangeError::NotFound,
+                        9106 | 10000 => ChangeError::Unauthorized,
+                        _ => ChangeError::Custom(err),
+                    },
+                    _ => ChangeError::Custom(err),
+                },
+            })?;
+
+        // Restore the order of the changeset from the grouped response
+        let mut posts = response.posts.iter();
+        let mut patches = response.patches.iter();
+        changes
+            .iter()
+            .filter_map(|change| match change {
+                RecordChange::Create { .. } => posts.next(),
+                RecordChange::Update { .. } => patches.next(),
+                RecordChange::Delete { .. } => None,
+            })
+            .map(|record| {
+                crate::Record::try_from(api::DnsRecordWithZone::new(record, &self.repr.name))
+                    .map_err(|e| ApplyChangesError {
+                        index: None,
+                        rolled_back: false,
+                        cause: ChangeError::Custom(CloudflareError::Api(ApiError {
+                            code: 0,
+                            message: format!("Failed to convert record: {}", e),
+                        })),
+                    })
+            })
+            .collect()
+    }
+}
+
+impl AppendRecords for CloudflareZone {}
+
+impl SetRecords for CloudflareZone {}
+
+impl DeleteRecords for CloudflareZone {}
+
+#[cfg(feature = "ddns")]
+impl DynamicDns for CloudflareZone {}
+
+/// Describes what the provider and all of its zones support.
+fn capabilities() -> Capabilities {
+    Capabilities {
+        list_zones: true,
+        create_zone: false,
+        delete_zone: false,
+        create_record: true,
+        update_record: true,
+        delete_record: true,
+        atomic_update: true,
+        // A TTL of 1 means "automatic", so TTLs are kept explicit
+        ttl_range: 60..=86400,
+        ..Capabilities::default()
+    }
+    .with_record_types(api::SUPPORTED_RECORD_TYPES)
+}
+
+impl MigrationTarget for CloudflareZone {}
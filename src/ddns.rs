@@ -0,0 +1,377 @@
+//! Dynamic DNS (DDNS) updates.
+//!
+//! A [`DdnsUpdater`] keeps the A and AAAA records of a set of hosts pointed at the current addresses of a machine.
+//! Updates are idempotent: the records of the zone are compared to the current addresses and only hosts whose address changed are written to.
+//!
+//! For every host and address family:
+//!
+//! - If no record of the family exists, one is created.
+//! - If a record already holds the current address, it is kept.
+//! - Otherwise, an existing record is pointed at the current address using [`DynamicDns::update_address`].
+//! - Any further records of the family at the host are deleted, so the host resolves to the current address only.
+//!
+//! Address families without a current address are left alone, e.g. the AAAA records when the machine has no IPv6 connectivity.
+//!
+//! Where the current addresses come from is up to the [`AddressSource`] passed to the updater.
+//! A fixed [`Addresses`] value is a source itself, which suffices for single updates.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use libdns::ddns::{Addresses, DdnsUpdater, DynamicDns};
+//!
+//! async fn update<Z: DynamicDns>(zone: &Z) {
+//!     let addresses = Addresses {
+//!         ipv4: Some("203.0.113.7".parse().unwrap()),
+//!         ipv6: None,
+//!     };
+//!
+//!     let updater = DdnsUpdater::new(zone, addresses, ["@", "www"])
+//!         .with_ttl(120)
+//!         .with_interval(Duration::from_secs(60));
+//!     updater
+//!         .run(|result| match result {
+//!             Ok(changes) => changes.iter().for_each(|change| println!("{:?}", change)),
+//!             Err(err) => eprintln!("update failed: {:?}", err),
+//!         })
+//!         .await;
+//! }
+//! ```
+
+use std::convert::Infallible;
+use std::fmt::Debug;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::{
+    normalize_host, with_listed_record, ClassifyError, CreateRecord, CreateRecordError,
+    DeleteRecord, DeleteRecordError, ErrorKind, Record, RecordData, RetrieveRecordError,
+    UpdateRecord, UpdateRecordError, Zone,
+};
+
+/// The TTL of created records, short enough for address changes to be picked up quickly.
+const DEFAULT_TTL: u64 = 300;
+
+/// The time between two updates of a running [`DdnsUpdater`].
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(300);
+
+/// The current addresses of a machine, one per address family.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Addresses {
+    /// The current IPv4 address, or `None` to leave the A records alone.
+    pub ipv4: Option<Ipv4Addr>,
+    /// The current IPv6 address, or `None` to leave the AAAA records alone.
+    pub ipv6: Option<Ipv6Addr>,
+}
+
+impl Addresses {
+    /// Returns the present addresses, IPv4 first.
+    fn iter(&self) -> impl Iterator<Item = IpAddr> {
+        self.ipv4
+            .map(IpAddr::V4)
+            .into_iter()
+            .chain(self.ipv6.map(IpAddr::V6))
+    }
+}
+
+/// A way to determine the current addresses of a machine, e.g. by asking the router or an external "what is my IP" service.
+pub trait AddressSource: Send + Sync {
+    type Error: Debug + Send + Sync;
+
+    /// Returns the current addresses.
+    fn addresses(&self) -> impl Future<Output = Result<Addresses, Self::Error>> + Send;
+}
+
+impl AddressSource for Addresses {
+    type Error = Infallible;
+
+    async fn addresses(&self) -> Result<Addresses, Self::Error> {
+        Ok(*self)
+    }
+}
+
+/// A zone whose address records can be kept up to date by a [`DdnsUpdater`].
+///
+/// The default implementation points records at a new address using [`UpdateRecord::update_record`].
+/// Providers with a dedicated DDNS endpoint override [`DynamicDns::update_address`] to use it instead.
+pub trait DynamicDns: CreateRecord + UpdateRecord + DeleteRecord {
+    /// Points the `existing` A or AAAA record at the given address, which is of the same family.
+    ///
+    /// The host of `existing` is relative to the zone (`@` for the apex).
+    fn update_address(
+        &self,
+        existing: &Record,
+        address: IpAddr,
+        ttl: u64,
+    ) -> impl Future<Output = Result<Record, UpdateRecordError<Self::CustomUpdateError>>> + Send
+    {
+        async move {
+            self.update_record(&existing.id, &existing.host, &address_data(address), ttl)
+                .await
+        }
+    }
+}
+
+/// A change made to a zone by a [`DdnsUpdater`].
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum AddressChange {
+    /// A record was created for a host without a record of the address family.
+    Created(Record),
+
+    /// An existing record was pointed at the current address.
+    Updated { from: Record, to: Record },
+
+    /// A superfluous record of the address family was deleted.
+    Deleted(Record),
+}
+
+/// Keeps the address records of a set of hosts in sync with the current addresses.
+///
+/// See the [module documentation](self) for the rules applied to every host.
+#[derive(Debug, Clone)]
+pub struct DdnsUpdater<'a, Z, S> {
+    zone: &'a Z,
+    source: S,
+    hosts: Vec<String>,
+    ttl: u64,
+    interval: Duration,
+}
+
+impl<'a, Z, S> DdnsUpdater<'a, Z, S>
+where
+    Z: DynamicDns,
+    S: AddressSource,
+{
+    /// Creates an updater for the given hosts of the zone, which may be relative (`@` for the apex) or fully qualified.
+    ///
+    /// Created records have a TTL of 300 seconds and [`DdnsUpdater::run`] updates every 300 seconds.
+    pub fn new(zone: &'a Z, source: S, hosts: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        let hosts = hosts
+            .into_iter()
+            .map(|host| normalize_host(host.as_ref(), zone.domain()))
+            .collect();
+
+        Self {
+            zone,
+            source,
+            hosts,
+            ttl: DEFAULT_TTL,
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+
+    /// Sets the TTL of created and updated records.
+    pub fn with_ttl(mut self, ttl: u64) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets the time [`DdnsUpdater::run`] waits between two updates.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Brings the records of all hosts in line with the given addresses.
+    ///
+    /// Returns the changes made to the zone, which are empty if every host already pointed at the addresses.
+    /// On error, the changes made before the failing request stay in place and the next update continues from there.
+    pub async fn sync(
+        &self,
+        addresses: &Addresses,
+    ) -> Result<Vec<AddressChange>, UpdaterError<Z, S>> {
+        let mut listing = None;
+
+        let mut changes = Vec::new();
+        for host in &self.hosts {
+            for address in addresses.iter() {
+                let data = address_data(address);
+                let mut existing = self.address_records(&mut listing, host, &data).await?;
+
+                if let Some(position) = existing.iter().position(|record| record.data == data) {
+                    existing.remove(position);
+                } else if existing.is_empty() {
+                    let created = self
+                        .zone
+                        .create_record(host, &data, self.ttl)
+                        .await
+                        .map_err(DdnsError::Create)?;
+                    changes.push(AddressChange::Created(created));
+                } else {
+                    let from = existing.remove(0);
+                    let updated = self
+                        .with_address_record(&mut listing, &from, |from| async move {
+                            let to = self
+                                .zone
+                                .update_address(&from, address, self.ttl)
+                                .await
+                                .map_err(DdnsError::Update)?;
+                            Ok(AddressChange::Updated { from, to })
+                        })
+                        .await?;
+                    changes.extend(updated);
+                }
+
+                for stale in existing {
+                    let deleted = self
+                        .with_address_record(&mut listing, &stale, |stale| async move {
+                            self.zone
+                                .delete_record(&stale.id)
+                                .await
+                                .map_err(DdnsError::Delete)?;
+                            Ok(AddressChange::Deleted(stale))
+                        })
+                        .await?;
+                    changes.extend(deleted);
+                }
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Returns the records of the host with the type of `data`, with the host relative to the zone.
+    ///
+    /// The records of the zone are only listed if `listing` is missing.
+    async fn address_records(
+        &self,
+        listing: &mut Option<Vec<Record>>,
+        host: &str,
+        data: &RecordData,
+    ) -> Result<Vec<Record>, UpdaterError<Z, S>> {
+        let records = match listing {
+            Some(records) => records,
+            None => listing.insert(
+                self.zone
+                    .list_records()
+                    .await
+                    .map_err(DdnsError::Retrieve)?,
+            ),
+        };
+        let domain = self.zone.domain();
+
+        Ok(records
+            .iter()
+            .filter(|record| {
+                normalize_host(&record.host, domain) == host
+                    && record.data.get_type() == data.get_type()
+            })
+            .map(|record| Record {
+                host: host.to_string(),
+                ..record.clone()
+            })
+            .collect())
+    }
+
+    /// Runs `op` on the current version of a record returned by [`DdnsUpdater::address_records`], skipping it if it no longer exists.
+    async fn with_address_record<T, F>(
+        &self,
+        listing: &mut Option<Vec<Record>>,
+        record: &Record,
+        mut op: impl FnMut(Record) -> F,
+    ) -> Result<Option<T>, UpdaterError<Z, S>>
+    where
+        F: Future<Output = Result<T, UpdaterError<Z, S>>>,
+    {
+        with_listed_record(
+            self.zone,
+            listing,
+            &record.host,
+            &record.data,
+            DdnsError::Retrieve,
+            |current| {
+                op(Record {
+                    host: record.host.clone(),
+                    ..current
+                })
+            },
+        )
+        .await
+    }
+
+    /// Retrieves the current addresses from the source and brings the records of all hosts in line with them.
+    pub async fn update(&self) -> Result<Vec<AddressChange>, UpdaterError<Z, S>> {
+        let addresses = self.source.addresses().await.map_err(DdnsError::Source)?;
+        self.sync(&addresses).await
+    }
+
+    /// Runs [`DdnsUpdater::update`] forever, waiting for the configured interval after every update.
+    ///
+    /// The outcome of every update is passed to `report`. Errors do not stop the loop, the next update simply tries again.
+    /// To stop the updater, drop the returned future, e.g. using `tokio::select!` or by aborting the task it was spawned onto.
+    pub async fn run<F>(&self, mut report: F)
+    where
+        F: FnMut(Result<Vec<AddressChange>, UpdaterError<Z, S>>) + Send,
+    {
+        loop {
+            report(self.update().await);
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+/// The [`DdnsError`] returned by a [`DdnsUpdater`] for the zone `Z` and the address source `S`.
+pub type UpdaterError<Z, S> = DdnsError<
+    <S as AddressSource>::Error,
+    <Z as Zone>::CustomRetrieveError,
+    <Z as CreateRecord>::CustomCreateError,
+    <Z as UpdateRecord>::CustomUpdateError,
+    <Z as DeleteRecord>::CustomDeleteError,
+>;
+
+/// Represents an error that occured when updating address records using a [`DdnsUpdater`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+pub enum DdnsError<S, R, C, U, D> {
+    /// Indicates that the current addresses could not be determined.
+    #[error("the current addresses could not be determined: {0:?}")]
+    Source(S),
+
+    /// Indicates that the records of the zone could not be retrieved.
+    #[error(transparent)]
+    Retrieve(RetrieveRecordError<R>),
+
+    /// Indicates that an address record could not be created.
+    #[error(transparent)]
+    Create(CreateRecordError<C>),
+
+    /// Indicates that an address record could not be updated.
+    #[error(transparent)]
+    Update(UpdateRecordError<U>),
+
+    /// Indicates that a superfluous address record could not be deleted.
+    #[error(transparent)]
+    Delete(DeleteRecordError<D>),
+}
+
+/// Failures of the [`AddressSource`] are classified as [`ErrorKind::Other`], as its error type is not required to be classifiable.
+impl<S, R, C, U, D> ClassifyError for DdnsError<S, R, C, U, D>
+where
+    R: ClassifyError,
+    C: ClassifyError,
+    U: ClassifyError,
+    D: ClassifyError,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            DdnsError::Source(_) => ErrorKind::Other,
+            DdnsError::Retrieve(e) => e.kind(),
+            DdnsError::Create(e) => e.kind(),
+            DdnsError::Update(e) => e.kind(),
+            DdnsError::Delete(e) => e.kind(),
+        }
+    }
+}
+
+/// Returns the A or AAAA record data for the address.
+fn address_data(address: IpAddr) -> RecordData {
+    match address {
+        IpAddr::V4(ip) => RecordData::A(ip),
+        IpAddr::V6(ip) => RecordData::AAAA(ip),
+    }
+}
@@ -2,11 +2,14 @@ use std::error::Error;
 
 use reqwest::{
     header::{HeaderMap, HeaderValue},
-    Client as HttpClient,
+    Client as HttpClient, ClientBuilder, RequestBuilder, Response,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-const DNSPOD_API_URL: &str = "https://api.dnspod.com";
+use crate::retry::{RequestBuilderExt, RetryPolicy};
+use crate::{ClassifyError, ErrorKind};
+
+pub(crate) const DNSPOD_API_URL: &str = "https://api.dnspod.com";
 
 /// Helper module for deserializing fields that can be either strings or integers.
 /// DNSPod API inconsistently returns some IDs as strings and others as integers.
@@ -122,7 +125,10 @@ impl ClientConfig {
 #[derive(Debug, Clone)]
 pub struct Client {
     http_client: HttpClient,
+    headers: HeaderMap,
+    base_url: String,
     login_token: String,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
@@ -143,6 +149,33 @@ impl Client {
     ///
     /// See: <https://docs.dnspod.com/api/api-development/>
     pub fn new(login_token: &str, config: &ClientConfig) -> Result<Self, Box<dyn Error>> {
+        Self::with_base_url(login_token, config, DNSPOD_API_URL)
+    }
+
+    /// Creates a new DNSPod API client with a custom base URL.
+    ///
+    /// This is primarily useful for testing with mock servers.
+    pub fn with_base_url(
+        login_token: &str,
+        config: &ClientConfig,
+        base_url: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        let http_client = Self::http_client_builder().build()?;
+        Self::from_http_client(http_client, login_token, config, base_url)
+    }
+
+    /// Returns the builder of the HTTP client used unless a preconfigured one is given.
+    pub(crate) fn http_client_builder() -> ClientBuilder {
+        HttpClient::builder()
+    }
+
+    /// Creates a new DNSPod API client sending its requests using the given HTTP client.
+    pub(crate) fn from_http_client(
+        http_client: HttpClient,
+        login_token: &str,
+        config: &ClientConfig,
+        base_url: &str,
+    ) -> Result<Self, Box<dyn Error>> {
         let user_agent = config.user_agent();
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -156,13 +189,36 @@ impl Client {
             HeaderValue::from_str(&user_agent).map_err(|e| Box::new(e) as Box<dyn Error>)?,
         );
 
-        let http_client = HttpClient::builder().default_headers(headers).build()?;
         Ok(Self {
             http_client,
+            headers,
+            base_url: base_url.trim_end_matches('/').to_string(),
             login_token: login_token.to_string(),
+            retry_policy: RetryPolicy::default(),
         })
     }
 
+    /// Replaces the HTTP client, e.g. with one using custom timeouts, proxies or root certificates.
+    ///
+    /// The User-Agent of the [`ClientConfig`] is still sent with every request.
+    pub fn with_http_client(mut self, http_client: HttpClient) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Starts a request to the given API function, e.g. `Domain.List`.
+    fn post(&self, function: &str) -> RequestBuilder {
+        self.http_client
+            .post(format!("{}/{}", self.base_url, function))
+            .headers(self.headers.clone())
+    }
+
     fn build_form_params(&self, params: &[(&str, &str)]) -> String {
         let mut form = format!("login_token={}&format=json", self.login_token);
         for (key, value) in params {
@@ -192,40 +248,26 @@ impl Client {
         }
 
         let response = self
-            .http_client
-            .post(format!("{}/Domain.List", DNSPOD_API_URL))
+            .post("Domain.List")
             .body(self.build_form_params(&params))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: DomainListResponse = response.json().await.map_err(DnspodError::Request)?;
-
-        if result.status.code != "1" {
-            return Err(DnspodError::Api(result.status));
-        }
-
-        Ok(result)
+        parse_response(response).await
     }
 
     pub async fn get_domain(&self, domain_id: &str) -> Result<DomainInfoResponse, DnspodError> {
         let params = [("domain_id", domain_id)];
 
         let response = self
-            .http_client
-            .post(format!("{}/Domain.Info", DNSPOD_API_URL))
+            .post("Domain.Info")
             .body(self.build_form_params(&params))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: DomainInfoResponse = response.json().await.map_err(DnspodError::Request)?;
-
-        if result.status.code != "1" {
-            return Err(DnspodError::Api(result.status));
-        }
-
-        Ok(result)
+        parse_response(response).await
     }
 
     pub async fn get_domain_by_name(
@@ -235,60 +277,39 @@ impl Client {
         let params = [("domain", domain)];
 
         let response = self
-            .http_client
-            .post(format!("{}/Domain.Info", DNSPOD_API_URL))
+            .post("Domain.Info")
             .body(self.build_form_params(&params))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: DomainInfoResponse = response.json().await.map_err(DnspodError::Request)?;
-
-        if result.status.code != "1" {
-            return Err(DnspodError::Api(result.status));
-        }
-
-        Ok(result)
+        parse_response(response).await
     }
 
     pub async fn create_domain(&self, domain: &str) -> Result<DomainCreateResponse, DnspodError> {
         let params = [("domain", domain)];
 
         let response = self
-            .http_client
-            .post(format!("{}/Domain.Create", DNSPOD_API_URL))
+            .post("Domain.Create")
             .body(self.build_form_params(&params))
-            .send()
+            .send_with_retry(&self.retry_policy, false)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: DomainCreateResponse = response.json().await.map_err(DnspodError::Request)?;
-
-        if result.status.code != "1" {
-            return Err(DnspodError::Api(result.status));
-        }
-
-        Ok(result)
+        parse_response(response).await
     }
 
     pub async fn delete_domain(&self, domain_id: &str) -> Result<StatusResponse, DnspodError> {
         let params = [("domain_id", domain_id)];
 
         let response = self
-            .http_client
-            .post(format!("{}/Domain.Remove", DNSPOD_API_URL))
+            .post("Domain.Remove")
             .body(self.build_form_params(&params))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: StatusResponse = response.json().await.map_err(DnspodError::Request)?;
-
-        if result.status.code != "1" {
-            return Err(DnspodError::Api(result.status));
-        }
-
-        Ok(result)
+        parse_response(response).await
     }
 
     // Record APIs
@@ -314,29 +335,22 @@ impl Client {
         );
 
         let response = self
-            .http_client
-            .post(format!("{}/Record.List", DNSPOD_API_URL))
+            .post("Record.List")
             .body(form)
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: RecordListResponse = response.json().await.map_err(DnspodError::Request)?;
-
-        if result.status.code != "1" {
+        match parse_response(response).await {
             // Empty result is code 10, which is not an error for listing
-            if result.status.code == "10" {
-                return Ok(RecordListResponse {
-                    status: result.status,
-                    domain: result.domain,
-                    info: result.info,
-                    records: Some(Vec::new()),
-                });
-            }
-            return Err(DnspodError::Api(result.status));
+            Err(DnspodError::Api(status)) if status.code == "10" => Ok(RecordListResponse {
+                status,
+                domain: None,
+                info: None,
+                records: Some(Vec::new()),
+            }),
+            result => result,
         }
-
-        Ok(result)
     }
 
     pub async fn get_record(
@@ -347,22 +361,16 @@ impl Client {
         let params = [("domain_id", domain_id), ("record_id", record_id)];
 
         let response = self
-            .http_client
-            .post(format!("{}/Record.Info", DNSPOD_API_URL))
+            .post("Record.Info")
             .body(self.build_form_params(&params))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: RecordInfoResponse = response.json().await.map_err(DnspodError::Request)?;
-
-        if result.status.code != "1" {
-            return Err(DnspodError::Api(result.status));
-        }
-
-        Ok(result)
+        parse_response(response).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_record(
         &self,
         domain_id: &str,
@@ -394,22 +402,16 @@ impl Client {
         );
 
         let response = self
-            .http_client
-            .post(format!("{}/Record.Create", DNSPOD_API_URL))
+            .post("Record.Create")
             .body(form)
-            .send()
+            .send_with_retry(&self.retry_policy, false)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: RecordCreateResponse = response.json().await.map_err(DnspodError::Request)?;
-
-        if result.status.code != "1" {
-            return Err(DnspodError::Api(result.status));
-        }
-
-        Ok(result)
+        parse_response(response).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn modify_record(
         &self,
         domain_id: &str,
@@ -439,24 +441,43 @@ impl Client {
 
         let form = params.iter().fold(
             format!("login_token={}&format=json", self.login_token),
-            |acc, (k, v)| format!("{}&{}={}", acc, k, url_encode(&v)),
+            |acc, (k, v)| format!("{}&{}={}", acc, k, url_encode(v)),
         );
 
         let response = self
-            .http_client
-            .post(format!("{}/Record.Modify", DNSPOD_API_URL))
+            .post("Record.Modify")
             .body(form)
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: RecordModifyResponse = response.json().await.map_err(DnspodError::Request)?;
+        parse_response(response).await
+    }
 
-        if result.status.code != "1" {
-            return Err(DnspodError::Api(result.status));
-        }
+    pub async fn ddns_record(
+        &self,
+        domain_id: &str,
+        record_id: &str,
+        sub_domain: &str,
+        record_line: &str,
+        value: &str,
+    ) -> Result<RecordModifyResponse, DnspodError> {
+        let params = [
+            ("domain_id", domain_id),
+            ("record_id", record_id),
+            ("sub_domain", sub_domain),
+            ("record_line", record_line),
+            ("value", value),
+        ];
 
-        Ok(result)
+        let response = self
+            .post("Record.Ddns")
+            .body(self.build_form_params(&params))
+            .send_with_retry(&self.retry_policy, true)
+            .await
+            .map_err(DnspodError::Request)?;
+
+        parse_response(response).await
     }
 
     pub async fn delete_record(
@@ -467,20 +488,13 @@ impl Client {
         let params = [("domain_id", domain_id), ("record_id", record_id)];
 
         let response = self
-            .http_client
-            .post(format!("{}/Record.Remove", DNSPOD_API_URL))
+            .post("Record.Remove")
             .body(self.build_form_params(&params))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: StatusResponse = response.json().await.map_err(DnspodError::Request)?;
-
-        if result.status.code != "1" {
-            return Err(DnspodError::Api(result.status));
-        }
-
-        Ok(result)
+        parse_response(response).await
     }
 
     pub async fn set_record_status(
@@ -496,21 +510,28 @@ impl Client {
         ];
 
         let response = self
-            .http_client
-            .post(format!("{}/Record.Status", DNSPOD_API_URL))
+            .post("Record.Status")
             .body(self.build_form_params(&params))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(DnspodError::Request)?;
 
-        let result: RecordStatusResponse = response.json().await.map_err(DnspodError::Request)?;
+        parse_response(response).await
+    }
+}
 
-        if result.status.code != "1" {
-            return Err(DnspodError::Api(result.status));
-        }
+/// Parses the body of a response, failing with its status unless it is successful.
+///
+/// Error responses only consist of the status, so it is checked before the fields specific to the function are parsed.
+async fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, DnspodError> {
+    let body = response.bytes().await.map_err(DnspodError::Request)?;
 
-        Ok(result)
+    let result: StatusResponse = serde_json::from_slice(&body).map_err(DnspodError::Parse)?;
+    if result.status.code != "1" {
+        return Err(DnspodError::Api(result.status));
     }
+
+    serde_json::from_slice(&body).map_err(DnspodError::Parse)
 }
 
 // Error types
@@ -519,6 +540,7 @@ impl Client {
 pub enum DnspodError {
     Request(reqwest::Error),
     Api(Status),
+    Parse(serde_json::Error),
 }
 
 impl std::fmt::Display for DnspodError {
@@ -528,6 +550,7 @@ impl std::fmt::Display for DnspodError {
             DnspodError::Api(status) => {
                 write!(f, "API error {}: {}", status.code, status.message)
             }
+            DnspodError::Parse(e) => write!(f, "Invalid response: {}", e),
         }
     }
 }
@@ -537,6 +560,24 @@ impl std::error::Error for DnspodError {
         match self {
             DnspodError::Request(e) => Some(e),
             DnspodError::Api(_) => None,
+            DnspodError::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl ClassifyError for DnspodError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            DnspodError::Request(e) => e.kind(),
+            DnspodError::Parse(_) => ErrorKind::Other,
+            // Positive codes mean different things for every endpoint, so only the shared negative codes are classified
+            DnspodError::Api(status) => match status.code.as_str() {
+                // Login failed, invalid agent, no permission, account or domain prohibited
+                "-1" | "-3" | "-4" | "-7" | "-8" | "-15" => ErrorKind::Unauthorized,
+                "-2" => ErrorKind::RateLimited, // API usage exceeded
+                "-99" => ErrorKind::Transient,  // Temporarily unavailable
+                _ => ErrorKind::Other,
+            },
         }
     }
 }
@@ -46,12 +46,22 @@
 //!
 //! For more information, see the [DNSPod API documentation](https://docs.dnspod.com/api/).
 
-use std::{error::Error as StdErr, sync::Arc};
-
+#[cfg(feature = "ddns")]
+use std::net::IpAddr;
+use std::{error::Error as StdErr, sync::Arc, time::Duration};
+
+#[cfg(feature = "ddns")]
+use crate::ddns::DynamicDns;
+use crate::dynamic::{DynProvider, DynProviderAdapter};
+use crate::http::HttpOptions;
+use crate::migrate::MigrationTarget;
+use crate::retry::RetryPolicy;
+use crate::types::to_ascii_name;
 use crate::{
+    apply_changes_sequentially, AppendRecords, ApplyChanges, ApplyChangesError, Capabilities,
     CreateRecord, CreateRecordError, CreateZone, CreateZoneError, DeleteRecord, DeleteRecordError,
-    DeleteZone, DeleteZoneError, Provider, Record, RecordData, RetrieveRecordError,
-    RetrieveZoneError, Zone,
+    DeleteRecords, DeleteZone, DeleteZoneError, Provider, Record, RecordChange, RecordData,
+    RetrieveRecordError, RetrieveZoneError, SetRecords, UpdateRecord, UpdateRecordError, Zone,
 };
 
 pub mod api;
@@ -103,13 +113,125 @@ impl DnspodProvider {
     /// let provider = DnspodProvider::new("secret_id,secret_key", &config).unwrap();
     /// ```
     pub fn new(login_token: &str, config: &api::ClientConfig) -> Result<Self, Box<dyn StdErr>> {
-        let api_client = api::Client::new(login_token, config)?;
-        Ok(Self {
+        Self::builder(login_token, config).build()
+    }
+
+    /// Returns a builder to configure the HTTP client, API base URL and retry policy of the provider.
+    ///
+    /// The User-Agent is always taken from the [`ClientConfig`], as DNSPod requires it to identify your application.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use libdns::dnspod::{ClientConfig, DnspodProvider};
+    ///
+    /// let config = ClientConfig::new("My DDNS App", "1.0.0", "me@example.com");
+    /// let provider = DnspodProvider::builder("secret_id,secret_key", &config)
+    ///     .with_timeout(Duration::from_secs(10))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(login_token: &str, config: &api::ClientConfig) -> DnspodProviderBuilder {
+        DnspodProviderBuilder {
+            login_token: login_token.to_string(),
+            config: config.clone(),
+            base_url: api::DNSPOD_API_URL.to_string(),
+            http: HttpOptions::default(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        let api_client = self.api_client.as_ref().clone().with_retry_policy(policy);
+        Self {
+            api_client: Arc::new(api_client),
+        }
+    }
+}
+
+/// Builder for a [`DnspodProvider`], created using [`DnspodProvider::builder`].
+#[derive(Clone)]
+pub struct DnspodProviderBuilder {
+    login_token: String,
+    config: api::ClientConfig,
+    base_url: String,
+    http: HttpOptions,
+    retry_policy: RetryPolicy,
+}
+
+impl DnspodProviderBuilder {
+    /// Sets a custom API base URL, e.g. of a mock server.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Sends all requests using the given HTTP client, e.g. one with custom root certificates or connection pool limits.
+    ///
+    /// The timeout and proxy set on the builder are ignored in that case.
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http.client = Some(client);
+        self
+    }
+
+    /// Sets the timeout of every request, unlimited by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends all requests through the given proxy.
+    pub fn with_proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.http.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Creates the provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn build(self) -> Result<DnspodProvider, Box<dyn StdErr>> {
+        let http_client = self.http.build(api::Client::http_client_builder())?;
+        let api_client = api::Client::from_http_client(
+            http_client,
+            &self.login_token,
+            &self.config,
+            &self.base_url,
+        )?
+        .with_retry_policy(self.retry_policy);
+        Ok(DnspodProvider {
             api_client: Arc::new(api_client),
         })
     }
 }
 
+impl From<DnspodProvider> for Box<dyn DynProvider> {
+    fn from(provider: DnspodProvider) -> Self {
+        Box::new(
+            DynProviderAdapter::new(provider)
+                .with_create_zone()
+                .with_delete_zone()
+                .with_create_record()
+                .with_update_record()
+                .with_delete_record(),
+        )
+    }
+}
+
 impl Provider for DnspodProvider {
     type Zone = DnspodZone;
     type CustomRetrieveError = DnspodError;
@@ -118,6 +240,7 @@ impl Provider for DnspodProvider {
         &self,
         zone_id: &str,
     ) -> Result<Self::Zone, RetrieveZoneError<Self::CustomRetrieveError>> {
+        let zone_id: &str = &to_ascii_name(zone_id).ok_or(RetrieveZoneError::NotFound)?;
         let response = self.api_client.get_domain(zone_id).await.map_err(|err| {
             match &err {
                 DnspodError::Api(status) => {
@@ -128,7 +251,7 @@ impl Provider for DnspodProvider {
                         _ => RetrieveZoneError::Custom(err),
                     }
                 }
-                DnspodError::Request(_) => RetrieveZoneError::Custom(err),
+                _ => RetrieveZoneError::Custom(err),
             }
         })?;
 
@@ -156,7 +279,7 @@ impl Provider for DnspodProvider {
                         "9" => RetrieveZoneError::NotFound, // Empty result
                         _ => RetrieveZoneError::Custom(err),
                     },
-                    DnspodError::Request(_) => RetrieveZoneError::Custom(err),
+                    _ => RetrieveZoneError::Custom(err),
                 });
 
             match result {
@@ -186,6 +309,10 @@ impl Provider for DnspodProvider {
 
         Ok(zones)
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl CreateZone for DnspodProvider {
@@ -195,6 +322,7 @@ impl CreateZone for DnspodProvider {
         &self,
         domain: &str,
     ) -> Result<Self::Zone, CreateZoneError<Self::CustomCreateError>> {
+        let domain: &str = &to_ascii_name(domain).ok_or(CreateZoneError::InvalidDomainName)?;
         let create_response =
             self.api_client
                 .create_domain(domain)
@@ -209,7 +337,7 @@ impl CreateZone for DnspodProvider {
                         "41" => CreateZoneError::InvalidDomainName, // Terms of service
                         _ => CreateZoneError::Custom(err),
                     },
-                    DnspodError::Request(_) => CreateZoneError::Custom(err),
+                    _ => CreateZoneError::Custom(err),
                 })?;
 
         // Fetch the full domain info
@@ -217,7 +345,7 @@ impl CreateZone for DnspodProvider {
             .api_client
             .get_domain(&create_response.domain.id)
             .await
-            .map_err(|err| CreateZoneError::Custom(err))?;
+            .map_err(CreateZoneError::Custom)?;
 
         Ok(DnspodZone {
             api_client: self.api_client.clone(),
@@ -246,7 +374,7 @@ impl DeleteZone for DnspodProvider {
                     "9" => DeleteZoneError::Unauthorized,   // No permission
                     _ => DeleteZoneError::Custom(err),
                 },
-                DnspodError::Request(_) => DeleteZoneError::Custom(err),
+                _ => DeleteZoneError::Custom(err),
             })?;
 
         Ok(())
@@ -292,7 +420,7 @@ impl Zone for DnspodZone {
                         "10" => RetrieveRecordError::NotFound, // Empty result (handled below)
                         _ => RetrieveRecordError::Custom(err),
                     },
-                    DnspodError::Request(_) => RetrieveRecordError::Custom(err),
+                    _ => RetrieveRecordError::Custom(err),
                 });
 
             match result {
@@ -342,7 +470,7 @@ impl Zone for DnspodZone {
                     "8" => RetrieveRecordError::NotFound, // Invalid record id
                     _ => RetrieveRecordError::Custom(err),
                 },
-                DnspodError::Request(_) => RetrieveRecordError::Custom(err),
+                _ => RetrieveRecordError::Custom(err),
             })?;
 
         Ok(crate::Record::from(api::RecordInfoWithTtl::new(
@@ -350,6 +478,10 @@ impl Zone for DnspodZone {
             default_ttl,
         )))
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl CreateRecord for DnspodZone {
@@ -361,8 +493,9 @@ impl CreateRecord for DnspodZone {
         data: &RecordData,
         ttl: u64,
     ) -> Result<Record, CreateRecordError<Self::CustomCreateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(CreateRecordError::InvalidRecord)?;
         let typ = data.get_type();
-        if !SUPPORTED_RECORD_TYPES.iter().any(|r| *r == typ) {
+        if !SUPPORTED_RECORD_TYPES.contains(&typ) {
             return Err(CreateRecordError::UnsupportedType);
         }
 
@@ -402,7 +535,7 @@ impl CreateRecord for DnspodZone {
                     "34" => CreateRecordError::InvalidRecord,   // Invalid record value
                     _ => CreateRecordError::Custom(err),
                 },
-                DnspodError::Request(_) => CreateRecordError::Custom(err),
+                _ => CreateRecordError::Custom(err),
             })?;
 
         // Get the record from response (should always be present if status was successful)
@@ -424,6 +557,75 @@ impl CreateRecord for DnspodZone {
     }
 }
 
+impl UpdateRecord for DnspodZone {
+    type CustomUpdateError = DnspodError;
+
+    async fn update_record(
+        &self,
+        record_id: &str,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, UpdateRecordError<Self::CustomUpdateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(UpdateRecordError::InvalidRecord)?;
+        let typ = data.get_type();
+        if !SUPPORTED_RECORD_TYPES.contains(&typ) {
+            return Err(UpdateRecordError::UnsupportedType);
+        }
+
+        let mx = match data {
+            RecordData::MX { priority, .. } => Some(*priority),
+            _ => None,
+        };
+
+        let value = data.get_api_value();
+
+        let response = self
+            .api_client
+            .modify_record(
+                &self.repr.id,
+                record_id,
+                host,
+                typ,
+                "default", // DNSPod uses "default" as the default record line
+                &value,
+                mx,
+                Some(ttl),
+            )
+            .await
+            .map_err(update_error)?;
+
+        Ok(Record {
+            id: response.record.id,
+            host: host.to_string(),
+            data: data.clone(),
+            ttl,
+        })
+    }
+}
+
+/// Maps an error of a record modification to the generic error.
+fn update_error(err: DnspodError) -> UpdateRecordError<DnspodError> {
+    match &err {
+        DnspodError::Api(status) => match status.code.as_str() {
+            "-1" => UpdateRecordError::Unauthorized,
+            "-15" => UpdateRecordError::Unauthorized, // Domain prohibited
+            "6" => UpdateRecordError::NotFound,       // Invalid domain id
+            "7" => UpdateRecordError::Unauthorized,   // No permission
+            "8" => UpdateRecordError::NotFound,       // Invalid record id
+            "21" => UpdateRecordError::Unauthorized,  // Domain locked
+            "22" | "23" | "24" | "25" => UpdateRecordError::InvalidRecord, // Invalid subdomain
+            "26" => UpdateRecordError::InvalidRecord, // Invalid line
+            "27" => UpdateRecordError::UnsupportedType, // Invalid record type
+            "30" => UpdateRecordError::InvalidRecord, // Invalid MX
+            "31" | "32" | "33" => UpdateRecordError::InvalidRecord, // Limit reached
+            "34" => UpdateRecordError::InvalidRecord, // Invalid record value
+            _ => UpdateRecordError::Custom(err),
+        },
+        _ => UpdateRecordError::Custom(err),
+    }
+}
+
 impl DeleteRecord for DnspodZone {
     type CustomDeleteError = DnspodError;
 
@@ -444,9 +646,90 @@ impl DeleteRecord for DnspodZone {
                     "21" => DeleteRecordError::Unauthorized,  // Domain locked
                     _ => DeleteRecordError::Custom(err),
                 },
-                DnspodError::Request(_) => DeleteRecordError::Custom(err),
+                _ => DeleteRecordError::Custom(err),
             })?;
 
         Ok(())
     }
 }
+
+impl ApplyChanges for DnspodZone {
+    type CustomApplyError = DnspodError;
+
+    async fn apply_changes(
+        &self,
+        changes: &[RecordChange],
+    ) -> Result<Vec<Record>, ApplyChangesError<Self::CustomApplyError>> {
+        // No batch endpoint, so changes are applied one by one with best-effort rollback
+        apply_changes_sequentially(self, changes).await
+    }
+}
+
+impl AppendRecords for DnspodZone {}
+
+impl SetRecords for DnspodZone {}
+
+impl DeleteRecords for DnspodZone {}
+
+#[cfg(feature = "ddns")]
+impl DynamicDns for DnspodZone {
+    async fn update_address(
+        &self,
+        existing: &Record,
+        address: IpAddr,
+        ttl: u64,
+    ) -> Result<Record, UpdateRecordError<Self::CustomUpdateError>> {
+        // The DDNS endpoint only handles A records and keeps the TTL of the record
+        let ip = match address {
+            IpAddr::V4(ip) if existing.ttl == ttl => ip,
+            IpAddr::V4(ip) => {
+                return self
+                    .update_record(&existing.id, &existing.host, &RecordData::A(ip), ttl)
+                    .await
+            }
+            IpAddr::V6(ip) => {
+                return self
+                    .update_record(&existing.id, &existing.host, &RecordData::AAAA(ip), ttl)
+                    .await
+            }
+        };
+
+        let response = self
+            .api_client
+            .ddns_record(
+                &self.repr.id,
+                &existing.id,
+                &existing.host,
+                "default", // DNSPod uses "default" as the default record line
+                &ip.to_string(),
+            )
+            .await
+            .map_err(update_error)?;
+
+        Ok(Record {
+            id: response.record.id,
+            host: existing.host.clone(),
+            data: RecordData::A(ip),
+            ttl,
+        })
+    }
+}
+
+/// Describes what the provider and all of its zones support.
+fn capabilities() -> Capabilities {
+    Capabilities {
+        list_zones: true,
+        create_zone: true,
+        delete_zone: true,
+        create_record: true,
+        update_record: true,
+        delete_record: true,
+        atomic_update: true,
+        // The lower bound depends on the plan of the domain (600 seconds on the free plan)
+        ttl_range: 1..=604800,
+        ..Capabilities::default()
+    }
+    .with_record_types(SUPPORTED_RECORD_TYPES)
+}
+
+impl MigrationTarget for DnspodZone {}
@@ -0,0 +1,659 @@
+//! Object-safe wrappers around the [`Provider`] and [`Zone`] traits.
+//!
+//! [`Provider`] and [`Zone`] return `impl Future` and carry associated error types, so they cannot be used as trait objects.
+//! [`DynProvider`] and [`DynZone`] box their futures and unify all errors into [`DynError`] instead, allowing a provider to be picked at runtime (e.g. from configuration).
+//!
+//! Any [`Provider`] can be turned into a [`DynProvider`] using [`DynProviderAdapter`].
+//! As the optional capabilities cannot be detected automatically, they have to be enabled explicitly; disabled capabilities report [`DynError::Unsupported`].
+//! The providers shipped with this crate implement `From<...> for Box<dyn DynProvider>` with all of their capabilities enabled.
+//!
+//! # Example
+//!
+//! ```
+//! use libdns::dynamic::{DynError, DynProvider};
+//!
+//! async fn create_txt(provider: &dyn DynProvider, zone_id: &str) -> Result<(), DynError> {
+//!     let zone = provider.get_zone(zone_id).await?;
+//!     if !zone.supports_create_record() {
+//!         return Err(DynError::Unsupported);
+//!     }
+//!
+//!     let data = libdns::RecordData::TXT("hello".to_string());
+//!     zone.create_record("_test", &data, 300).await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::{error::Error as StdError, future::Future, pin::Pin};
+
+use thiserror::Error;
+
+use crate::{
+    Capabilities, ClassifyError, CreateRecord, CreateRecordError, CreateZone, CreateZoneError,
+    DeleteRecord, DeleteRecordError, DeleteZone, DeleteZoneError, ErrorKind, Provider, Record,
+    RecordData, RetrieveRecordError, RetrieveZoneError, UpdateRecord, UpdateRecordError, Zone,
+};
+
+/// A heap-allocated, type-erased future as returned by [`DynProvider`] and [`DynZone`].
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A heap-allocated, type-erased error used for [`DynError::Custom`].
+pub type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// Represents any error returned by a [`DynProvider`] or [`DynZone`].
+///
+/// The well-defined variants of the capability-specific error types are mapped onto their counterparts here, while provider-specific errors are boxed into [`DynError::Custom`].
+#[derive(Debug, Error)]
+pub enum DynError {
+    /// Indicates that the DNS provider is not authorized to execute this action.
+    #[error("the DNS provider is unauthorized")]
+    Unauthorized,
+
+    /// Indicates that the requested zone or record was not found.
+    #[error("the requested resource was not found")]
+    NotFound,
+
+    /// Indicates that the specified domain name was not accepted.
+    #[error("the given domain name is invalid")]
+    InvalidDomainName,
+
+    /// Indicates that the DNS provider does not support the specified record type.
+    #[error("the DNS provider does not support the specified record type")]
+    UnsupportedType,
+
+    /// Indicates that the record value is invalid.
+    #[error("the given record value is invalid")]
+    InvalidRecord,
+
+    /// Indicates that the provider or zone does not implement the requested capability.
+    #[error("the DNS provider does not support this operation")]
+    Unsupported,
+
+    /// Provides a boxed custom, provider-specific error along with its [`ErrorKind`], which is lost by boxing it.
+    #[error("{1}")]
+    Custom(ErrorKind, #[source] BoxError),
+}
+
+impl ClassifyError for DynError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            DynError::Unauthorized => ErrorKind::Unauthorized,
+            DynError::NotFound => ErrorKind::NotFound,
+            DynError::InvalidDomainName | DynError::UnsupportedType | DynError::InvalidRecord => {
+                ErrorKind::Validation
+            }
+            DynError::Unsupported => ErrorKind::Other,
+            DynError::Custom(kind, _) => *kind,
+        }
+    }
+}
+
+impl<T: StdError + ClassifyError + Send + Sync + 'static> From<RetrieveZoneError<T>> for DynError {
+    fn from(err: RetrieveZoneError<T>) -> Self {
+        match err {
+            RetrieveZoneError::Unauthorized => DynError::Unauthorized,
+            RetrieveZoneError::NotFound => DynError::NotFound,
+            RetrieveZoneError::Custom(e) => DynError::Custom(e.kind(), Box::new(e)),
+        }
+    }
+}
+
+impl<T: StdError + ClassifyError + Send + Sync + 'static> From<CreateZoneError<T>> for DynError {
+    fn from(err: CreateZoneError<T>) -> Self {
+        match err {
+            CreateZoneError::Unauthorized => DynError::Unauthorized,
+            CreateZoneError::InvalidDomainName => DynError::InvalidDomainName,
+            CreateZoneError::Custom(e) => DynError::Custom(e.kind(), Box::new(e)),
+        }
+    }
+}
+
+impl<T: StdError + ClassifyError + Send + Sync + 'static> From<DeleteZoneError<T>> for DynError {
+    fn from(err: DeleteZoneError<T>) -> Self {
+        match err {
+            DeleteZoneError::Unauthorized => DynError::Unauthorized,
+            DeleteZoneError::NotFound => DynError::NotFound,
+            DeleteZoneError::Custom(e) => DynError::Custom(e.kind(), Box::new(e)),
+        }
+    }
+}
+
+impl<T: StdError + ClassifyError + Send + Sync + 'static> From<RetrieveRecordError<T>>
+    for DynError
+{
+    fn from(err: RetrieveRecordError<T>) -> Self {
+        match err {
+            RetrieveRecordError::Unauthorized => DynError::Unauthorized,
+            RetrieveRecordError::NotFound => DynError::NotFound,
+            RetrieveRecordError::Custom(e) => DynError::Custom(e.kind(), Box::new(e)),
+        }
+    }
+}
+
+impl<T: StdError + ClassifyError + Send + Sync + 'static> From<CreateRecordError<T>> for DynError {
+    fn from(err: CreateRecordError<T>) -> Self {
+        match err {
+            CreateRecordError::Unauthorized => DynError::Unauthorized,
+            CreateRecordError::UnsupportedType => DynError::UnsupportedType,
+            CreateRecordError::InvalidRecord => DynError::InvalidRecord,
+            CreateRecordError::Custom(e) => DynError::Custom(e.kind(), Box::new(e)),
+        }
+    }
+}
+
+impl<T: StdError + ClassifyError + Send + Sync + 'static> From<UpdateRecordError<T>> for DynError {
+    fn from(err: UpdateRecordError<T>) -> Self {
+        match err {
+            UpdateRecordError::Unauthorized => DynError::Unauthorized,
+            UpdateRecordError::NotFound => DynError::NotFound,
+            UpdateRecordError::UnsupportedType => DynError::UnsupportedType,
+            UpdateRecordError::InvalidRecord => DynError::InvalidRecord,
+            UpdateRecordError::Custom(e) => DynError::Custom(e.kind(), Box::new(e)),
+        }
+    }
+}
+
+impl<T: StdError + ClassifyError + Send + Sync + 'static> From<DeleteRecordError<T>> for DynError {
+    fn from(err: DeleteRecordError<T>) -> Self {
+        match err {
+            DeleteRecordError::Unauthorized => DynError::Unauthorized,
+            DeleteRecordError::NotFound => DynError::NotFound,
+            DeleteRecordError::Custom(e) => DynError::Custom(e.kind(), Box::new(e)),
+        }
+    }
+}
+
+/// An object-safe version of [`Provider`] including its optional capabilities.
+///
+/// Capabilities the underlying provider does not implement are reported by the `supports_*` methods and fail with [`DynError::Unsupported`].
+pub trait DynProvider: Send + Sync {
+    /// Retrieves all available zones. See [`Provider::list_zones`].
+    fn list_zones(&self) -> BoxFuture<'_, Result<Vec<Box<dyn DynZone>>, DynError>>;
+
+    /// Retrieves a zone by its provider-specific ID. See [`Provider::get_zone`].
+    fn get_zone<'a>(
+        &'a self,
+        zone_id: &'a str,
+    ) -> BoxFuture<'a, Result<Box<dyn DynZone>, DynError>>;
+
+    /// Describes what the provider and its zones support. See [`Provider::capabilities`].  
+    /// Operations that are not enabled are reported as unsupported.
+    fn capabilities(&self) -> Capabilities;
+
+    /// Returns whether [`DynProvider::create_zone`] is supported.
+    fn supports_create_zone(&self) -> bool;
+
+    /// Creates a new DNS zone with the given domain. See [`CreateZone::create_zone`].
+    fn create_zone<'a>(
+        &'a self,
+        domain: &'a str,
+    ) -> BoxFuture<'a, Result<Box<dyn DynZone>, DynError>>;
+
+    /// Returns whether [`DynProvider::delete_zone`] is supported.
+    fn supports_delete_zone(&self) -> bool;
+
+    /// Deletes a zone by its provider-specific ID. See [`DeleteZone::delete_zone`].
+    fn delete_zone<'a>(&'a self, zone_id: &'a str) -> BoxFuture<'a, Result<(), DynError>>;
+}
+
+/// An object-safe version of [`Zone`] including its optional capabilities.
+///
+/// Capabilities the underlying zone does not implement are reported by the `supports_*` methods and fail with [`DynError::Unsupported`].
+pub trait DynZone: Send + Sync {
+    /// Returns the provider-specific ID of the zone.
+    fn id(&self) -> &str;
+
+    /// Returns the domain the zone manages.
+    fn domain(&self) -> &str;
+
+    /// Retrieves all available records. See [`Zone::list_records`].
+    fn list_records(&self) -> BoxFuture<'_, Result<Vec<Record>, DynError>>;
+
+    /// Retrieves a record by its provider-specific ID. See [`Zone::get_record`].
+    fn get_record<'a>(&'a self, record_id: &'a str) -> BoxFuture<'a, Result<Record, DynError>>;
+
+    /// Describes what the zone supports. See [`Zone::capabilities`].  
+    /// Operations that are not enabled are reported as unsupported.
+    fn capabilities(&self) -> Capabilities;
+
+    /// Returns whether [`DynZone::create_record`] is supported.
+    fn supports_create_record(&self) -> bool;
+
+    /// Creates a new record. See [`CreateRecord::create_record`].
+    fn create_record<'a>(
+        &'a self,
+        host: &'a str,
+        data: &'a RecordData,
+        ttl: u64,
+    ) -> BoxFuture<'a, Result<Record, DynError>>;
+
+    /// Returns whether [`DynZone::update_record`] is supported.
+    fn supports_update_record(&self) -> bool;
+
+    /// Replaces the host, value and TTL of a record. See [`UpdateRecord::update_record`].
+    fn update_record<'a>(
+        &'a self,
+        record_id: &'a str,
+        host: &'a str,
+        data: &'a RecordData,
+        ttl: u64,
+    ) -> BoxFuture<'a, Result<Record, DynError>>;
+
+    /// Returns whether [`DynZone::delete_record`] is supported.
+    fn supports_delete_record(&self) -> bool;
+
+    /// Deletes a record by its ID. See [`DeleteRecord::delete_record`].
+    fn delete_record<'a>(&'a self, record_id: &'a str) -> BoxFuture<'a, Result<(), DynError>>;
+}
+
+type CreateZoneFn<P> =
+    for<'a> fn(&'a P, &'a str) -> BoxFuture<'a, Result<<P as Provider>::Zone, DynError>>;
+type DeleteZoneFn<P> = for<'a> fn(&'a P, &'a str) -> BoxFuture<'a, Result<(), DynError>>;
+type CreateRecordFn<Z> =
+    for<'a> fn(&'a Z, &'a str, &'a RecordData, u64) -> BoxFuture<'a, Result<Record, DynError>>;
+type UpdateRecordFn<Z> = for<'a> fn(
+    &'a Z,
+    &'a str,
+    &'a str,
+    &'a RecordData,
+    u64,
+) -> BoxFuture<'a, Result<Record, DynError>>;
+type DeleteRecordFn<Z> = for<'a> fn(&'a Z, &'a str) -> BoxFuture<'a, Result<(), DynError>>;
+
+fn create_zone_erased<'a, P>(
+    provider: &'a P,
+    domain: &'a str,
+) -> BoxFuture<'a, Result<P::Zone, DynError>>
+where
+    P: CreateZone,
+    P::CustomCreateError: StdError + Send + Sync + 'static,
+{
+    Box::pin(async move { Ok(provider.create_zone(domain).await?) })
+}
+
+fn delete_zone_erased<'a, P>(
+    provider: &'a P,
+    zone_id: &'a str,
+) -> BoxFuture<'a, Result<(), DynError>>
+where
+    P: DeleteZone,
+    P::CustomDeleteError: StdError + Send + Sync + 'static,
+{
+    Box::pin(async move { Ok(provider.delete_zone(zone_id).await?) })
+}
+
+fn create_record_erased<'a, Z>(
+    zone: &'a Z,
+    host: &'a str,
+    data: &'a RecordData,
+    ttl: u64,
+) -> BoxFuture<'a, Result<Record, DynError>>
+where
+    Z: CreateRecord,
+    Z::CustomCreateError: StdError + Send + Sync + 'static,
+{
+    Box::pin(async move { Ok(zone.create_record(host, data, ttl).await?) })
+}
+
+fn update_record_erased<'a, Z>(
+    zone: &'a Z,
+    record_id: &'a str,
+    host: &'a str,
+    data: &'a RecordData,
+    ttl: u64,
+) -> BoxFuture<'a, Result<Record, DynError>>
+where
+    Z: UpdateRecord,
+    Z::CustomUpdateError: StdError + Send + Sync + 'static,
+{
+    Box::pin(async move { Ok(zone.update_record(record_id, host, data, ttl).await?) })
+}
+
+fn delete_record_erased<'a, Z>(
+    zone: &'a Z,
+    record_id: &'a str,
+) -> BoxFuture<'a, Result<(), DynError>>
+where
+    Z: DeleteRecord,
+    Z::CustomDeleteError: StdError + Send + Sync + 'static,
+{
+    Box::pin(async move { Ok(zone.delete_record(record_id).await?) })
+}
+
+fn unsupported<'a, T: Send + 'a>() -> BoxFuture<'a, Result<T, DynError>> {
+    Box::pin(std::future::ready(Err(DynError::Unsupported)))
+}
+
+/// The record capabilities enabled for a [`DynZoneAdapter`].
+struct ZoneCapabilities<Z> {
+    create_record: Option<CreateRecordFn<Z>>,
+    update_record: Option<UpdateRecordFn<Z>>,
+    delete_record: Option<DeleteRecordFn<Z>>,
+}
+
+impl<Z> Clone for ZoneCapabilities<Z> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Z> Copy for ZoneCapabilities<Z> {}
+
+impl<Z> ZoneCapabilities<Z> {
+    /// Limits the record operations of the given capabilities to the enabled ones.
+    fn restrict(&self, capabilities: Capabilities) -> Capabilities {
+        Capabilities {
+            create_record: capabilities.create_record && self.create_record.is_some(),
+            update_record: capabilities.update_record && self.update_record.is_some(),
+            delete_record: capabilities.delete_record && self.delete_record.is_some(),
+            ..capabilities
+        }
+    }
+}
+
+impl<Z> Default for ZoneCapabilities<Z> {
+    fn default() -> Self {
+        Self {
+            create_record: None,
+            update_record: None,
+            delete_record: None,
+        }
+    }
+}
+
+/// Wraps a [`Zone`] to implement [`DynZone`].
+///
+/// Only record retrieval is enabled by default; use the `with_*` methods to enable the capabilities the zone implements.
+pub struct DynZoneAdapter<Z> {
+    zone: Z,
+    capabilities: ZoneCapabilities<Z>,
+}
+
+impl<Z: Zone> DynZoneAdapter<Z> {
+    /// Wraps the given zone with only record retrieval enabled.
+    pub fn new(zone: Z) -> Self {
+        Self {
+            zone,
+            capabilities: ZoneCapabilities::default(),
+        }
+    }
+
+    /// Returns the wrapped zone.
+    pub fn into_inner(self) -> Z {
+        self.zone
+    }
+
+    /// Enables [`DynZone::create_record`].
+    pub fn with_create_record(mut self) -> Self
+    where
+        Z: CreateRecord,
+        Z::CustomCreateError: StdError + Send + Sync + 'static,
+    {
+        self.capabilities.create_record = Some(create_record_erased::<Z>);
+        self
+    }
+
+    /// Enables [`DynZone::update_record`].
+    pub fn with_update_record(mut self) -> Self
+    where
+        Z: UpdateRecord,
+        Z::CustomUpdateError: StdError + Send + Sync + 'static,
+    {
+        self.capabilities.update_record = Some(update_record_erased::<Z>);
+        self
+    }
+
+    /// Enables [`DynZone::delete_record`].
+    pub fn with_delete_record(mut self) -> Self
+    where
+        Z: DeleteRecord,
+        Z::CustomDeleteError: StdError + Send + Sync + 'static,
+    {
+        self.capabilities.delete_record = Some(delete_record_erased::<Z>);
+        self
+    }
+}
+
+impl<Z> DynZone for DynZoneAdapter<Z>
+where
+    Z: Zone,
+    Z::CustomRetrieveError: StdError + Send + Sync + 'static,
+{
+    fn id(&self) -> &str {
+        self.zone.id()
+    }
+
+    fn domain(&self) -> &str {
+        self.zone.domain()
+    }
+
+    fn list_records(&self) -> BoxFuture<'_, Result<Vec<Record>, DynError>> {
+        Box::pin(async move { Ok(self.zone.list_records().await?) })
+    }
+
+    fn get_record<'a>(&'a self, record_id: &'a str) -> BoxFuture<'a, Result<Record, DynError>> {
+        Box::pin(async move { Ok(self.zone.get_record(record_id).await?) })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities.restrict(self.zone.capabilities())
+    }
+
+    fn supports_create_record(&self) -> bool {
+        self.capabilities.create_record.is_some()
+    }
+
+    fn create_record<'a>(
+        &'a self,
+        host: &'a str,
+        data: &'a RecordData,
+        ttl: u64,
+    ) -> BoxFuture<'a, Result<Record, DynError>> {
+        match self.capabilities.create_record {
+            Some(create_record) => create_record(&self.zone, host, data, ttl),
+            None => unsupported(),
+        }
+    }
+
+    fn supports_update_record(&self) -> bool {
+        self.capabilities.update_record.is_some()
+    }
+
+    fn update_record<'a>(
+        &'a self,
+        record_id: &'a str,
+        host: &'a str,
+        data: &'a RecordData,
+        ttl: u64,
+    ) -> BoxFuture<'a, Result<Record, DynError>> {
+        match self.capabilities.update_record {
+            Some(update_record) => update_record(&self.zone, record_id, host, data, ttl),
+            None => unsupported(),
+        }
+    }
+
+    fn supports_delete_record(&self) -> bool {
+        self.capabilities.delete_record.is_some()
+    }
+
+    fn delete_record<'a>(&'a self, record_id: &'a str) -> BoxFuture<'a, Result<(), DynError>> {
+        match self.capabilities.delete_record {
+            Some(delete_record) => delete_record(&self.zone, record_id),
+            None => unsupported(),
+        }
+    }
+}
+
+/// Wraps a [`Provider`] to implement [`DynProvider`].
+///
+/// Only zone and record retrieval are enabled by default; use the `with_*` methods to enable the capabilities the provider and its zones implement.
+/// Record capabilities enabled here apply to every zone returned by the provider.
+///
+/// # Example
+///
+/// ```
+/// use libdns::dynamic::{DynProvider, DynProviderAdapter};
+/// use libdns::{CreateRecord, DeleteRecord, Provider};
+///
+/// fn erase<P>(provider: P) -> Box<dyn DynProvider>
+/// where
+///     P: Provider + 'static,
+///     P::Zone: CreateRecord + DeleteRecord + 'static,
+///     P::CustomRetrieveError: std::error::Error + Send + Sync + 'static,
+///     <P::Zone as libdns::Zone>::CustomRetrieveError: std::error::Error + Send + Sync + 'static,
+///     <P::Zone as CreateRecord>::CustomCreateError: std::error::Error + Send + Sync + 'static,
+///     <P::Zone as DeleteRecord>::CustomDeleteError: std::error::Error + Send + Sync + 'static,
+/// {
+///     Box::new(
+///         DynProviderAdapter::new(provider)
+///             .with_create_record()
+///             .with_delete_record(),
+///     )
+/// }
+/// ```
+pub struct DynProviderAdapter<P: Provider> {
+    provider: P,
+    create_zone: Option<CreateZoneFn<P>>,
+    delete_zone: Option<DeleteZoneFn<P>>,
+    zone_capabilities: ZoneCapabilities<P::Zone>,
+}
+
+impl<P: Provider> DynProviderAdapter<P> {
+    /// Wraps the given provider with only zone and record retrieval enabled.
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            create_zone: None,
+            delete_zone: None,
+            zone_capabilities: ZoneCapabilities::default(),
+        }
+    }
+
+    /// Returns the wrapped provider.
+    pub fn into_inner(self) -> P {
+        self.provider
+    }
+
+    /// Enables [`DynProvider::create_zone`].
+    pub fn with_create_zone(mut self) -> Self
+    where
+        P: CreateZone,
+        P::CustomCreateError: StdError + Send + Sync + 'static,
+    {
+        self.create_zone = Some(create_zone_erased::<P>);
+        self
+    }
+
+    /// Enables [`DynProvider::delete_zone`].
+    pub fn with_delete_zone(mut self) -> Self
+    where
+        P: DeleteZone,
+        P::CustomDeleteError: StdError + Send + Sync + 'static,
+    {
+        self.delete_zone = Some(delete_zone_erased::<P>);
+        self
+    }
+
+    /// Enables [`DynZone::create_record`] on all zones returned by this provider.
+    pub fn with_create_record(mut self) -> Self
+    where
+        P::Zone: CreateRecord,
+        <P::Zone as CreateRecord>::CustomCreateError: StdError + Send + Sync + 'static,
+    {
+        self.zone_capabilities.create_record = Some(create_record_erased::<P::Zone>);
+        self
+    }
+
+    /// Enables [`DynZone::update_record`] on all zones returned by this provider.
+    pub fn with_update_record(mut self) -> Self
+    where
+        P::Zone: UpdateRecord,
+        <P::Zone as UpdateRecord>::CustomUpdateError: StdError + Send + Sync + 'static,
+    {
+        self.zone_capabilities.update_record = Some(update_record_erased::<P::Zone>);
+        self
+    }
+
+    /// Enables [`DynZone::delete_record`] on all zones returned by this provider.
+    pub fn with_delete_record(mut self) -> Self
+    where
+        P::Zone: DeleteRecord,
+        <P::Zone as DeleteRecord>::CustomDeleteError: StdError + Send + Sync + 'static,
+    {
+        self.zone_capabilities.delete_record = Some(delete_record_erased::<P::Zone>);
+        self
+    }
+
+    fn wrap_zone(&self, zone: P::Zone) -> Box<dyn DynZone>
+    where
+        P::Zone: 'static,
+        <P::Zone as Zone>::CustomRetrieveError: StdError + Send + Sync + 'static,
+    {
+        Box::new(DynZoneAdapter {
+            zone,
+            capabilities: self.zone_capabilities,
+        })
+    }
+}
+
+impl<P> DynProvider for DynProviderAdapter<P>
+where
+    P: Provider,
+    P::Zone: 'static,
+    P::CustomRetrieveError: StdError + Send + Sync + 'static,
+    <P::Zone as Zone>::CustomRetrieveError: StdError + Send + Sync + 'static,
+{
+    fn list_zones(&self) -> BoxFuture<'_, Result<Vec<Box<dyn DynZone>>, DynError>> {
+        Box::pin(async move {
+            let zones = self.provider.list_zones().await?;
+            Ok(zones.into_iter().map(|zone| self.wrap_zone(zone)).collect())
+        })
+    }
+
+    fn get_zone<'a>(
+        &'a self,
+        zone_id: &'a str,
+    ) -> BoxFuture<'a, Result<Box<dyn DynZone>, DynError>> {
+        Box::pin(async move {
+            let zone = self.provider.get_zone(zone_id).await?;
+            Ok(self.wrap_zone(zone))
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let capabilities = self.provider.capabilities();
+        Capabilities {
+            create_zone: capabilities.create_zone && self.create_zone.is_some(),
+            delete_zone: capabilities.delete_zone && self.delete_zone.is_some(),
+            ..self.zone_capabilities.restrict(capabilities)
+        }
+    }
+
+    fn supports_create_zone(&self) -> bool {
+        self.create_zone.is_some()
+    }
+
+    fn create_zone<'a>(
+        &'a self,
+        domain: &'a str,
+    ) -> BoxFuture<'a, Result<Box<dyn DynZone>, DynError>> {
+        match self.create_zone {
+            Some(create_zone) => Box::pin(async move {
+                let zone = create_zone(&self.provider, domain).await?;
+                Ok(self.wrap_zone(zone))
+            }),
+            None => unsupported(),
+        }
+    }
+
+    fn supports_delete_zone(&self) -> bool {
+        self.delete_zone.is_some()
+    }
+
+    fn delete_zone<'a>(&'a self, zone_id: &'a str) -> BoxFuture<'a, Result<(), DynError>> {
+        match self.delete_zone {
+            Some(delete_zone) => delete_zone(&self.provider, zone_id),
+            None => unsupported(),
+        }
+    }
+}
@@ -30,11 +30,13 @@ use std::error::Error;
 
 use reqwest::{
     header::{HeaderMap, HeaderValue, AUTHORIZATION},
-    Client as HttpClient,
+    Client as HttpClient, ClientBuilder, Method, RequestBuilder,
 };
 use serde::{Deserialize, Serialize};
 
-const HETZNER_API_URL: &str = "https://api.hetzner.cloud/v1";
+use crate::retry::{RequestBuilderExt, RetryPolicy};
+
+pub(crate) const HETZNER_API_URL: &str = "https://api.hetzner.cloud/v1";
 
 /// Low-level Hetzner Cloud DNS API client.
 ///
@@ -43,7 +45,9 @@ const HETZNER_API_URL: &str = "https://api.hetzner.cloud/v1";
 #[derive(Debug, Clone)]
 pub struct Client {
     http_client: HttpClient,
+    headers: HeaderMap,
     base_url: String,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
@@ -65,18 +69,53 @@ impl Client {
     /// * `api_key` - Hetzner Cloud API token
     /// * `base_url` - Base URL for API requests
     pub fn with_base_url(api_key: &str, base_url: &str) -> Result<Self, Box<dyn Error>> {
+        let http_client = Self::http_client_builder().build()?;
+        Self::from_http_client(http_client, api_key, base_url)
+    }
+
+    /// Returns the builder of the HTTP client used unless a preconfigured one is given.
+    pub(crate) fn http_client_builder() -> ClientBuilder {
+        HttpClient::builder()
+    }
+
+    /// Creates a new client sending its requests using the given HTTP client.
+    pub(crate) fn from_http_client(
+        http_client: HttpClient,
+        api_key: &str,
+        base_url: &str,
+    ) -> Result<Self, Box<dyn Error>> {
         let mut headers = HeaderMap::new();
         let mut auth_value = HeaderValue::from_str(&format!("Bearer {}", api_key))?;
         auth_value.set_sensitive(true);
         headers.append(AUTHORIZATION, auth_value);
 
-        let http_client = HttpClient::builder().default_headers(headers).build()?;
         Ok(Self {
             http_client,
+            headers,
             base_url: base_url.to_string(),
+            retry_policy: RetryPolicy::default(),
         })
     }
 
+    /// Replaces the HTTP client, e.g. with one using custom timeouts, proxies or root certificates.
+    pub fn with_http_client(mut self, http_client: HttpClient) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Starts a request with the authorization header.
+    fn request(&self, method: Method, url: String) -> RequestBuilder {
+        self.http_client
+            .request(method, url)
+            .headers(self.headers.clone())
+    }
+
     /// Retrieves a paginated list of zones.
     ///
     /// # Arguments
@@ -88,15 +127,18 @@ impl Client {
         page: u32,
         per_page: u32,
     ) -> Result<ZonesResponse, reqwest::Error> {
-        self.http_client
-            .get(format!(
+        self.request(
+            Method::GET,
+            format!(
                 "{}/zones?page={}&per_page={}",
                 self.base_url, page, per_page
-            ))
-            .send()
-            .await?
-            .json::<ZonesResponse>()
-            .await
+            ),
+        )
+        .send_with_retry(&self.retry_policy, true)
+        .await?
+        .error_for_status()?
+        .json::<ZonesResponse>()
+        .await
     }
 
     /// Retrieves a zone by ID or name.
@@ -108,12 +150,15 @@ impl Client {
         &self,
         zone_id_or_name: &str,
     ) -> Result<ZoneResponse, reqwest::Error> {
-        self.http_client
-            .get(format!("{}/zones/{}", self.base_url, zone_id_or_name))
-            .send()
-            .await?
-            .json()
-            .await
+        self.request(
+            Method::GET,
+            format!("{}/zones/{}", self.base_url, zone_id_or_name),
+        )
+        .send_with_retry(&self.retry_policy, true)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
     }
 
     /// Creates a new zone.
@@ -133,11 +178,11 @@ impl Client {
             ttl: ttl.unwrap_or(3600),
         };
 
-        self.http_client
-            .post(format!("{}/zones", self.base_url))
+        self.request(Method::POST, format!("{}/zones", self.base_url))
             .json(&request_body)
-            .send()
+            .send_with_retry(&self.retry_policy, false)
             .await?
+            .error_for_status()?
             .json()
             .await
     }
@@ -148,11 +193,13 @@ impl Client {
     ///
     /// * `zone_id_or_name` - Zone identifier or domain name
     pub async fn delete_zone(&self, zone_id_or_name: &str) -> Result<(), reqwest::Error> {
-        self.http_client
-            .delete(format!("{}/zones/{}", self.base_url, zone_id_or_name))
-            .send()
-            .await?
-            .error_for_status()?;
+        self.request(
+            Method::DELETE,
+            format!("{}/zones/{}", self.base_url, zone_id_or_name),
+        )
+        .send_with_retry(&self.retry_policy, true)
+        .await?
+        .error_for_status()?;
         Ok(())
     }
 
@@ -169,15 +216,18 @@ impl Client {
         page: u32,
         per_page: u32,
     ) -> Result<RRSetsResponse, reqwest::Error> {
-        self.http_client
-            .get(format!(
+        self.request(
+            Method::GET,
+            format!(
                 "{}/zones/{}/rrsets?page={}&per_page={}",
                 self.base_url, zone_id_or_name, page, per_page
-            ))
-            .send()
-            .await?
-            .json()
-            .await
+            ),
+        )
+        .send_with_retry(&self.retry_policy, true)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
     }
 
     /// Retrieves a specific RRSet by name and type.
@@ -193,15 +243,18 @@ impl Client {
         rr_name: &str,
         rr_type: &str,
     ) -> Result<RRSetResponse, reqwest::Error> {
-        self.http_client
-            .get(format!(
+        self.request(
+            Method::GET,
+            format!(
                 "{}/zones/{}/rrsets/{}/{}",
                 self.base_url, zone_id_or_name, rr_name, rr_type
-            ))
-            .send()
-            .await?
-            .json()
-            .await
+            ),
+        )
+        .send_with_retry(&self.retry_policy, true)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
     }
 
     /// Creates a new RRSet in a zone.
@@ -228,16 +281,16 @@ impl Client {
             ttl,
         };
 
-        self.http_client
-            .post(format!(
-                "{}/zones/{}/rrsets",
-                self.base_url, zone_id_or_name
-            ))
-            .json(&request_body)
-            .send()
-            .await?
-            .json()
-            .await
+        self.request(
+            Method::POST,
+            format!("{}/zones/{}/rrsets", self.base_url, zone_id_or_name),
+        )
+        .json(&request_body)
+        .send_with_retry(&self.retry_policy, false)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
     }
 
     /// Adds records to an existing RRSet (creates it if it doesn't exist).
@@ -259,16 +312,19 @@ impl Client {
     ) -> Result<ActionResponse, reqwest::Error> {
         let request_body = AddRecordsRequest { records, ttl };
 
-        self.http_client
-            .post(format!(
+        self.request(
+            Method::POST,
+            format!(
                 "{}/zones/{}/rrsets/{}/{}/actions/add_records",
                 self.base_url, zone_id_or_name, rr_name, rr_type
-            ))
-            .json(&request_body)
-            .send()
-            .await?
-            .json()
-            .await
+            ),
+        )
+        .json(&request_body)
+        .send_with_retry(&self.retry_policy, false)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
     }
 
     /// Removes records from an RRSet.
@@ -288,16 +344,83 @@ impl Client {
     ) -> Result<ActionResponse, reqwest::Error> {
         let request_body = RemoveRecordsRequest { records };
 
-        self.http_client
-            .post(format!(
+        self.request(
+            Method::POST,
+            format!(
                 "{}/zones/{}/rrsets/{}/{}/actions/remove_records",
                 self.base_url, zone_id_or_name, rr_name, rr_type
-            ))
-            .json(&request_body)
-            .send()
-            .await?
-            .json()
-            .await
+            ),
+        )
+        .json(&request_body)
+        .send_with_retry(&self.retry_policy, false)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+    }
+
+    /// Replaces all records of an RRSet.
+    ///
+    /// # Arguments
+    ///
+    /// * `zone_id_or_name` - Zone identifier or domain name
+    /// * `rr_name` - Record name (e.g., "www" or "@" for apex)
+    /// * `rr_type` - Record type (A, AAAA, CNAME, etc.)
+    /// * `records` - Complete list of record values the RRSet should contain
+    pub async fn set_rrset_records(
+        &self,
+        zone_id_or_name: &str,
+        rr_name: &str,
+        rr_type: &str,
+        records: Vec<RecordValue>,
+    ) -> Result<ActionResponse, reqwest::Error> {
+        let request_body = SetRecordsRequest { records };
+
+        self.request(
+            Method::POST,
+            format!(
+                "{}/zones/{}/rrsets/{}/{}/actions/set_records",
+                self.base_url, zone_id_or_name, rr_name, rr_type
+            ),
+        )
+        .json(&request_body)
+        .send_with_retry(&self.retry_policy, true)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+    }
+
+    /// Changes the TTL of an RRSet.
+    ///
+    /// # Arguments
+    ///
+    /// * `zone_id_or_name` - Zone identifier or domain name
+    /// * `rr_name` - Record name (e.g., "www" or "@" for apex)
+    /// * `rr_type` - Record type (A, AAAA, CNAME, etc.)
+    /// * `ttl` - TTL in seconds (None uses zone default)
+    pub async fn change_rrset_ttl(
+        &self,
+        zone_id_or_name: &str,
+        rr_name: &str,
+        rr_type: &str,
+        ttl: Option<u64>,
+    ) -> Result<ActionResponse, reqwest::Error> {
+        let request_body = ChangeTtlRequest { ttl };
+
+        self.request(
+            Method::POST,
+            format!(
+                "{}/zones/{}/rrsets/{}/{}/actions/change_ttl",
+                self.base_url, zone_id_or_name, rr_name, rr_type
+            ),
+        )
+        .json(&request_body)
+        .send_with_retry(&self.retry_policy, true)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
     }
 
     /// Deletes an entire RRSet.
@@ -313,15 +436,18 @@ impl Client {
         rr_name: &str,
         rr_type: &str,
     ) -> Result<ActionResponse, reqwest::Error> {
-        self.http_client
-            .delete(format!(
+        self.request(
+            Method::DELETE,
+            format!(
                 "{}/zones/{}/rrsets/{}/{}",
                 self.base_url, zone_id_or_name, rr_name, rr_type
-            ))
-            .send()
-            .await?
-            .json()
-            .await
+            ),
+        )
+        .send_with_retry(&self.retry_policy, true)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
     }
 }
 
@@ -362,6 +488,18 @@ struct RemoveRecordsRequest {
     records: Vec<RecordValue>,
 }
 
+/// Request body for replacing the records of an RRSet.
+#[derive(Debug, Serialize)]
+struct SetRecordsRequest {
+    records: Vec<RecordValue>,
+}
+
+/// Request body for changing the TTL of an RRSet.
+#[derive(Debug, Serialize)]
+struct ChangeTtlRequest {
+    ttl: Option<u64>,
+}
+
 // ============================================================================
 // Response Types
 // ============================================================================
@@ -57,6 +57,22 @@
 //! Unlike some providers, Hetzner supports creating and deleting zones
 //! through the API. See [`CreateZone`] and [`DeleteZone`] traits.
 //!
+//! # Record Updates
+//!
+//! Hetzner has no per-record update endpoint, so [`UpdateRecord`] is emulated:
+//!
+//! - If the host and type stay the same, the RRSet's records are replaced in a
+//!   single `set_records` action. Because TTLs are stored per RRSet, changing
+//!   the TTL affects every record sharing the same name and type.
+//! - Otherwise the new record is added before the old one is removed, so the
+//!   name never stops resolving.
+//!
+//! # Changesets
+//!
+//! [`ApplyChanges`] computes the resulting state of every affected RRSet first and
+//! then writes each RRSet with a single request, so every RRSet changes atomically.
+//! If writing an RRSet fails, the RRSets already written are restored.
+//!
 //! # API Reference
 //!
 //! - [Hetzner Cloud API Documentation](https://docs.hetzner.cloud/)
@@ -67,11 +83,20 @@ pub mod api;
 
 use std::error::Error as StdErr;
 use std::sync::Arc;
+use std::time::Duration;
 
+#[cfg(feature = "ddns")]
+use crate::ddns::DynamicDns;
+use crate::dynamic::{DynProvider, DynProviderAdapter};
+use crate::http::HttpOptions;
+use crate::migrate::MigrationTarget;
+use crate::retry::RetryPolicy;
+use crate::types::{to_ascii_name, MAX_TTL};
 use crate::{
+    normalize_host, AppendRecords, ApplyChanges, ApplyChangesError, Capabilities, ChangeError,
     CreateRecord, CreateRecordError, CreateZone, CreateZoneError, DeleteRecord, DeleteRecordError,
-    DeleteZone, DeleteZoneError, Provider, Record, RecordData, RetrieveRecordError,
-    RetrieveZoneError, Zone,
+    DeleteRecords, DeleteZone, DeleteZoneError, Provider, Record, RecordChange, RecordData,
+    RetrieveRecordError, RetrieveZoneError, SetRecords, UpdateRecord, UpdateRecordError, Zone,
 };
 
 /// Supported record types for Hetzner Cloud DNS.
@@ -111,10 +136,7 @@ impl HetznerProvider {
     /// let provider = HetznerProvider::new("your_api_token").unwrap();
     /// ```
     pub fn new(api_key: &str) -> Result<Self, Box<dyn StdErr>> {
-        let api_client = api::Client::new(api_key)?;
-        Ok(Self {
-            api_client: Arc::new(api_client),
-        })
+        Self::builder(api_key).build()
     }
 
     /// Creates a new Hetzner Cloud DNS provider with a custom API base URL.
@@ -126,13 +148,122 @@ impl HetznerProvider {
     /// * `api_key` - Hetzner Cloud API token
     /// * `base_url` - Custom base URL for the API
     pub fn with_base_url(api_key: &str, base_url: &str) -> Result<Self, Box<dyn StdErr>> {
-        let api_client = api::Client::with_base_url(api_key, base_url)?;
-        Ok(Self {
+        Self::builder(api_key).with_base_url(base_url).build()
+    }
+
+    /// Returns a builder to configure the HTTP client, API base URL and retry policy of the provider.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use libdns::hetzner::HetznerProvider;
+    ///
+    /// let provider = HetznerProvider::builder("your_api_token")
+    ///     .with_timeout(Duration::from_secs(10))
+    ///     .with_proxy(reqwest::Proxy::https("http://proxy.example.com:3128").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(api_key: &str) -> HetznerProviderBuilder {
+        HetznerProviderBuilder {
+            api_key: api_key.to_string(),
+            base_url: api::HETZNER_API_URL.to_string(),
+            http: HttpOptions::default(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        let api_client = self.api_client.as_ref().clone().with_retry_policy(policy);
+        Self {
+            api_client: Arc::new(api_client),
+        }
+    }
+}
+
+/// Builder for a [`HetznerProvider`], created using [`HetznerProvider::builder`].
+#[derive(Clone)]
+pub struct HetznerProviderBuilder {
+    api_key: String,
+    base_url: String,
+    http: HttpOptions,
+    retry_policy: RetryPolicy,
+}
+
+impl HetznerProviderBuilder {
+    /// Sets a custom API base URL, e.g. of a mock server.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Sends all requests using the given HTTP client, e.g. one with custom root certificates or connection pool limits.
+    ///
+    /// The timeout, proxy and user agent set on the builder are ignored in that case.
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http.client = Some(client);
+        self
+    }
+
+    /// Sets the timeout of every request, unlimited by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends all requests through the given proxy.
+    pub fn with_proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.http.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets the `User-Agent` header of every request.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.http.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Creates the provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn build(self) -> Result<HetznerProvider, Box<dyn StdErr>> {
+        let http_client = self.http.build(api::Client::http_client_builder())?;
+        let api_client = api::Client::from_http_client(http_client, &self.api_key, &self.base_url)?
+            .with_retry_policy(self.retry_policy);
+        Ok(HetznerProvider {
             api_client: Arc::new(api_client),
         })
     }
 }
 
+impl From<HetznerProvider> for Box<dyn DynProvider> {
+    fn from(provider: HetznerProvider) -> Self {
+        Box::new(
+            DynProviderAdapter::new(provider)
+                .with_create_zone()
+                .with_delete_zone()
+                .with_create_record()
+                .with_update_record()
+                .with_delete_record(),
+        )
+    }
+}
+
 impl Provider for HetznerProvider {
     type Zone = HetznerZone;
     type CustomRetrieveError = reqwest::Error;
@@ -141,6 +272,7 @@ impl Provider for HetznerProvider {
         &self,
         zone_id: &str,
     ) -> Result<Self::Zone, RetrieveZoneError<Self::CustomRetrieveError>> {
+        let zone_id: &str = &to_ascii_name(zone_id).ok_or(RetrieveZoneError::NotFound)?;
         let response = self
             .api_client
             .retrieve_zone(zone_id)
@@ -216,6 +348,10 @@ impl Provider for HetznerProvider {
 
         Ok(zones)
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl CreateZone for HetznerProvider {
@@ -225,6 +361,7 @@ impl CreateZone for HetznerProvider {
         &self,
         domain: &str,
     ) -> Result<Self::Zone, CreateZoneError<Self::CustomCreateError>> {
+        let domain: &str = &to_ascii_name(domain).ok_or(CreateZoneError::InvalidDomainName)?;
         let response = self
             .api_client
             .create_zone(domain, None)
@@ -335,6 +472,89 @@ impl HetznerZone {
             zone_id_str,
         }
     }
+
+    /// Returns the TTL to send for an RRSet, omitting it if it equals the zone default.
+    fn rrset_ttl(&self, ttl: u64) -> Option<u64> {
+        if ttl != self.repr.ttl {
+            Some(ttl)
+        } else {
+            None
+        }
+    }
+
+    /// Transitions an RRSet from one state to another with as few requests as possible.
+    ///
+    /// On error, the state the RRSet was left in by the preceding requests is returned along with it.
+    async fn write_rrset(
+        &self,
+        name: &str,
+        typ: &str,
+        from: &RRSetState,
+        to: &RRSetState,
+    ) -> Result<(), (reqwest::Error, RRSetState)> {
+        let values = |state: &RRSetState| -> Vec<api::RecordValue> {
+            state.values.iter().map(api::RecordValue::new).collect()
+        };
+
+        let unchanged = |err| (err, from.clone());
+
+        if to.values.is_empty() {
+            if !from.values.is_empty() {
+                self.api_client
+                    .delete_rrset(&self.zone_id_str, name, typ)
+                    .await
+                    .map_err(unchanged)?;
+            }
+        } else if from.values.is_empty() {
+            self.api_client
+                .create_rrset(
+                    &self.zone_id_str,
+                    name,
+                    typ,
+                    values(to),
+                    self.rrset_ttl(to.ttl),
+                )
+                .await
+                .map_err(unchanged)?;
+        } else {
+            if from.values != to.values {
+                self.api_client
+                    .set_rrset_records(&self.zone_id_str, name, typ, values(to))
+                    .await
+                    .map_err(unchanged)?;
+            }
+            if from.ttl != to.ttl {
+                self.api_client
+                    .change_rrset_ttl(&self.zone_id_str, name, typ, self.rrset_ttl(to.ttl))
+                    .await
+                    .map_err(|err| {
+                        // The values may already have been written
+                        let reached = RRSetState {
+                            values: to.values.clone(),
+                            ttl: from.ttl,
+                        };
+                        (err, reached)
+                    })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The values and TTL of an RRSet as sent to the API.
+#[derive(Clone, PartialEq)]
+struct RRSetState {
+    values: Vec<String>,
+    ttl: u64,
+}
+
+/// An RRSet affected by a changeset.
+struct RRSetChange {
+    name: String,
+    typ: String,
+    before: RRSetState,
+    after: RRSetState,
 }
 
 impl Zone for HetznerZone {
@@ -457,6 +677,10 @@ impl Zone for HetznerZone {
             ttl: rrset.ttl.unwrap_or(self.repr.ttl),
         })
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 /// Format a record value for the Hetzner Cloud API.
@@ -485,8 +709,9 @@ impl CreateRecord for HetznerZone {
         data: &RecordData,
         ttl: u64,
     ) -> Result<Record, CreateRecordError<Self::CustomCreateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(CreateRecordError::InvalidRecord)?;
         let typ = data.get_type();
-        if !SUPPORTED_RECORD_TYPES.iter().any(|r| *r == typ) {
+        if !SUPPORTED_RECORD_TYPES.contains(&typ) {
             return Err(CreateRecordError::UnsupportedType);
         }
 
@@ -529,6 +754,357 @@ impl CreateRecord for HetznerZone {
     }
 }
 
+impl UpdateRecord for HetznerZone {
+    type CustomUpdateError = reqwest::Error;
+
+    async fn update_record(
+        &self,
+        record_id: &str,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, UpdateRecordError<Self::CustomUpdateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(UpdateRecordError::InvalidRecord)?;
+        // Parse record ID format: "name/type/value"
+        let parts: Vec<&str> = record_id.splitn(3, '/').collect();
+        if parts.len() != 3 {
+            return Err(UpdateRecordError::NotFound);
+        }
+        let (name, old_typ, old_value) = (parts[0], parts[1], parts[2]);
+
+        let typ = data.get_type();
+        if !SUPPORTED_RECORD_TYPES.contains(&typ) {
+            return Err(UpdateRecordError::UnsupportedType);
+        }
+
+        let value = format_value_for_api(data);
+        let opt_ttl = if ttl != self.repr.ttl {
+            Some(ttl)
+        } else {
+            None
+        };
+
+        let map_err = |err: reqwest::Error| {
+            if err.is_status() {
+                return match err.status().unwrap() {
+                    reqwest::StatusCode::NOT_FOUND => UpdateRecordError::NotFound,
+                    reqwest::StatusCode::UNAUTHORIZED => UpdateRecordError::Unauthorized,
+                    reqwest::StatusCode::UNPROCESSABLE_ENTITY => UpdateRecordError::InvalidRecord,
+                    _ => UpdateRecordError::Custom(err),
+                };
+            }
+            UpdateRecordError::Custom(err)
+        };
+
+        if name == host && old_typ == typ {
+            // Same RRSet: swap the value and write the whole set back in one action
+            let response = self
+                .api_client
+                .retrieve_rrset(&self.zone_id_str, name, typ)
+                .await
+                .map_err(map_err)?;
+            let rrset = response.rrset;
+
+            let mut records = rrset.records;
+            let position = records
+                .iter()
+                .position(|r| r.value == old_value)
+                .ok_or(UpdateRecordError::NotFound)?;
+            records[position].value = value.clone();
+            let mut index = 0;
+            records.retain(|r| {
+                let keep = index == position || r.value != value;
+                index += 1;
+                keep
+            });
+
+            self.api_client
+                .set_rrset_records(&self.zone_id_str, name, typ, records)
+                .await
+                .map_err(map_err)?;
+
+            if rrset.ttl.unwrap_or(self.repr.ttl) != ttl {
+                self.api_client
+                    .change_rrset_ttl(&self.zone_id_str, name, typ, opt_ttl)
+                    .await
+                    .map_err(map_err)?;
+            }
+        } else {
+            // Different RRSet: make sure the old record exists, then add before removing
+            self.get_record(record_id).await.map_err(|err| match err {
+                RetrieveRecordError::Unauthorized => UpdateRecordError::Unauthorized,
+                RetrieveRecordError::NotFound => UpdateRecordError::NotFound,
+                RetrieveRecordError::Custom(e) => UpdateRecordError::Custom(e),
+            })?;
+
+            self.api_client
+                .add_records_to_rrset(
+                    &self.zone_id_str,
+                    host,
+                    typ,
+                    vec![api::RecordValue::new(&value)],
+                    opt_ttl,
+                )
+                .await
+                .map_err(map_err)?;
+
+            self.api_client
+                .remove_records_from_rrset(
+                    &self.zone_id_str,
+                    name,
+                    old_typ,
+                    vec![api::RecordValue::new(old_value)],
+                )
+                .await
+                .map_err(map_err)?;
+        }
+
+        Ok(Record {
+            id: format!("{}/{}/{}", host, typ, value),
+            host: host.to_string(),
+            data: data.clone(),
+            ttl,
+        })
+    }
+}
+
+impl ApplyChanges for HetznerZone {
+    type CustomApplyError = reqwest::Error;
+
+    async fn apply_changes(
+        &self,
+        changes: &[RecordChange],
+    ) -> Result<Vec<Record>, ApplyChangesError<Self::CustomApplyError>> {
+        let fail = |index: Option<usize>, cause: ChangeError<reqwest::Error>| ApplyChangesError {
+            index,
+            rolled_back: true,
+            cause,
+        };
+
+        let existing = self
+            .list_records()
+            .await
+            .map_err(|err| fail(None, err.into()))?;
+
+        // Returns the affected RRSet, starting from its current state
+        let rrset = |rrsets: &mut Vec<RRSetChange>, name: &str, typ: &str| -> usize {
+            if let Some(position) = rrsets.iter().position(|r| r.name == name && r.typ == typ) {
+                return position;
+            }
+
+            let mut before = RRSetState {
+                values: Vec::new(),
+                ttl: self.repr.ttl,
+            };
+            for record in &existing {
+                let mut parts = record.id.splitn(3, '/');
+                if parts.next() == Some(name) && parts.next() == Some(typ) {
+                    before.values.extend(parts.next().map(String::from));
+                    before.ttl = record.ttl;
+                }
+            }
+            rrsets.push(RRSetChange {
+                name: name.to_string(),
+                typ: typ.to_string(),
+                after: before.clone(),
+                before,
+            });
+            rrsets.len() - 1
+        };
+
+        // Apply all changes to the affected RRSets in memory first
+        let mut rrsets = Vec::new();
+        let mut results = Vec::new();
+        for (index, change) in changes.iter().enumerate() {
+            let removed = match change {
+                RecordChange::Create { .. } => None,
+                RecordChange::Update { record_id, .. } | RecordChange::Delete { record_id } => {
+                    // Parse record ID format: "name/type/value"
+                    let parts: Vec<&str> = record_id.splitn(3, '/').collect();
+                    if parts.len() != 3 {
+                        return Err(fail(Some(index), ChangeError::NotFound));
+                    }
+                    Some((parts[0], parts[1], parts[2]))
+                }
+            };
+            if let Some((name, typ, value)) = removed {
+                let set = rrset(&mut rrsets, name, typ);
+                let values = &mut rrsets[set].after.values;
+                let position = values
+                    .iter()
+                    .position(|v| v == value)
+                    .ok_or_else(|| fail(Some(index), ChangeError::NotFound))?;
+                values.remove(position);
+            }
+
+            let added = match change {
+                RecordChange::Create { host, data, ttl }
+                | RecordChange::Update {
+                    host, data, ttl, ..
+                } => Some((host, data, *ttl)),
+                RecordChange::Delete { .. } => None,
+            };
+            if let Some((host, data, ttl)) = added {
+                let typ = data.get_type();
+                if !SUPPORTED_RECORD_TYPES.contains(&typ) {
+                    return Err(fail(Some(index), ChangeError::UnsupportedType));
+                }
+
+                let name = normalize_host(host, &self.repr.name);
+                let value = format_value_for_api(data);
+                let set = rrset(&mut rrsets, &name, typ);
+                let after = &mut rrsets[set].after;
+                if !after.values.contains(&value) {
+                    after.values.push(value.clone());
+                }
+                // TTLs are stored per RRSet, so the last change wins
+                after.ttl = ttl;
+
+                results.push(Record {
+                    id: format!("{}/{}/{}", name, typ, value),
+                    host: name,
+                    data: data.clone(),
+                    ttl,
+                });
+            }
+        }
+
+        // Write every changed RRSet, restoring the written ones and the failing one if a write fails
+        let changed: Vec<&RRSetChange> = rrsets.iter().filter(|r| r.before != r.after).collect();
+        for (written, set) in changed.iter().enumerate() {
+            let Err((err, reached)) = self
+                .write_rrset(&set.name, &set.typ, &set.before, &set.after)
+                .await
+            else {
+                continue;
+            };
+
+            // Without a response, the failing request may have been applied anyway
+            let mut rolled_back = err.is_status();
+            if reached != set.before {
+                rolled_back &= self
+                    .write_rrset(&set.name, &set.typ, &reached, &set.before)
+                    .await
+                    .is_ok();
+            }
+            for done in changed[..written].iter().rev() {
+                rolled_back &= self
+                    .write_rrset(&done.name, &done.typ, &done.after, &done.before)
+                    .await
+                    .is_ok();
+            }
+
+            let cause = if err.is_status() {
+                match err.status().unwrap() {
+                    reqwest::StatusCode::NOT_FOUND => ChangeError::NotFound,
+                    reqwest::StatusCode::UNAUTHORIZED => ChangeError::Unauthorized,
+                    reqwest::StatusCode::UNPROCESSABLE_ENTITY => ChangeError::InvalidRecord,
+                    _ => ChangeError::Custom(err),
+                }
+            } else {
+                ChangeError::Custom(err)
+            };
+            return Err(ApplyChangesError {
+                index: None,
+                rolled_back,
+                cause,
+            });
+        }
+
+        Ok(results)
+    }
+}
+
+impl AppendRecords for HetznerZone {
+    async fn append_records(
+        &self,
+        records: &[Record],
+    ) -> Result<Vec<Record>, CreateRecordError<Self::CustomCreateError>> {
+        if records
+            .iter()
+            .any(|r| !SUPPORTED_RECORD_TYPES.contains(&r.data.get_type()))
+        {
+            return Err(CreateRecordError::UnsupportedType);
+        }
+
+        // Group the records by RRSet so every set is extended with a single action
+        let mut rrsets: Vec<(String, &str, u64, Vec<api::RecordValue>)> = Vec::new();
+        let mut created = Vec::with_capacity(records.len());
+        for record in records {
+            let host = normalize_host(&record.host, &self.repr.name);
+            let typ = record.data.get_type();
+            let value = format_value_for_api(&record.data);
+
+            created.push(Record {
+                id: format!("{}/{}/{}", host, typ, value),
+                host: host.clone(),
+                data: record.data.clone(),
+                ttl: record.ttl,
+            });
+
+            match rrsets
+                .iter_mut()
+                .find(|(name, t, _, _)| *name == host && *t == typ)
+            {
+                Some((_, _, _, values)) => values.push(api::RecordValue::new(&value)),
+                None => rrsets.push((host, typ, record.ttl, vec![api::RecordValue::new(&value)])),
+            }
+        }
+
+        for (name, typ, ttl, values) in rrsets {
+            let opt_ttl = if ttl != self.repr.ttl {
+                Some(ttl)
+            } else {
+                None
+            };
+
+            self.api_client
+                .add_records_to_rrset(&self.zone_id_str, &name, typ, values, opt_ttl)
+                .await
+                .map_err(|err| {
+                    if err.is_status() {
+                        return match err.status().unwrap() {
+                            reqwest::StatusCode::UNAUTHORIZED => CreateRecordError::Unauthorized,
+                            reqwest::StatusCode::UNPROCESSABLE_ENTITY => {
+                                CreateRecordError::InvalidRecord
+                            }
+                            _ => CreateRecordError::Custom(err),
+                        };
+                    }
+                    CreateRecordError::Custom(err)
+                })?;
+        }
+
+        Ok(created)
+    }
+}
+
+impl SetRecords for HetznerZone {}
+
+impl DeleteRecords for HetznerZone {}
+
+#[cfg(feature = "ddns")]
+impl DynamicDns for HetznerZone {}
+
+/// Describes what the provider and all of its zones support.
+fn capabilities() -> Capabilities {
+    Capabilities {
+        list_zones: true,
+        create_zone: true,
+        delete_zone: true,
+        create_record: true,
+        update_record: true,
+        delete_record: true,
+        // Moving a record to another RRSet adds it there before removing it, and TTL changes take a separate action
+        atomic_update: false,
+        ttl_range: 60..=MAX_TTL as u64,
+        ..Capabilities::default()
+    }
+    .with_record_types(SUPPORTED_RECORD_TYPES)
+}
+
+impl MigrationTarget for HetznerZone {}
+
 impl DeleteRecord for HetznerZone {
     type CustomDeleteError = reqwest::Error;
 
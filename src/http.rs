@@ -0,0 +1,37 @@
+//! Configuring the HTTP clients of the HTTP-based providers.
+
+use std::time::Duration;
+
+use reqwest::{Client, ClientBuilder, Proxy};
+
+/// The HTTP client options collected by the builders of the providers.
+///
+/// A preconfigured client takes precedence; the other options are applied on top of the provider's defaults otherwise.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HttpOptions {
+    pub(crate) client: Option<Client>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) proxy: Option<Proxy>,
+    pub(crate) user_agent: Option<String>,
+}
+
+impl HttpOptions {
+    /// Returns the preconfigured client, or builds one from the given defaults and the options.
+    pub(crate) fn build(self, defaults: ClientBuilder) -> Result<Client, reqwest::Error> {
+        if let Some(client) = self.client {
+            return Ok(client);
+        }
+
+        let mut builder = defaults;
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(user_agent) = self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        builder.build()
+    }
+}
@@ -16,15 +16,93 @@
 //! The following capabilities can be implemented additionally:
 //!
 //! - [`CreateRecord`]
+//! - [`UpdateRecord`]
 //! - [`DeleteRecord`]
+//!
+//! Building on these, [`AppendRecords`], [`SetRecords`] and [`DeleteRecords`] work on records matched by host, type and value instead of provider-specific IDs, like their counterparts in the Go `libdns` ecosystem.
+//!
+//! Zones implementing [`ApplyChanges`] accept a whole changeset of creates, updates and deletes at once, using the provider's native batch API where one exists.
+//!
+//! Record IDs are provider-specific and may change when a record is saved. [`Record::fingerprint`] identifies a record by its content instead and stays the same across providers and releases.
+//!
+//! Internationalized zone names and hosts like `münchen.de` are converted to their ASCII form (see [`types::DomainName::to_ascii`]) before they are sent to the provider or matched against the records it reports, e.g. by [`SetRecords`] and [`DeleteRecords`].
+//!
+//! # Capabilities
+//!
+//! What a provider or zone supports, including the accepted record types and TTLs, is described at runtime by [`Provider::capabilities`] and [`Zone::capabilities`].
+//!
+//! # Typed records
+//!
+//! [`typed::TypedRecord`] builds on the RFC types of [`types`], like [`types::DomainName`] and [`types::Ttl`], to reject invalid names and TTLs before any request is sent.
+//! [`typed::TypedRecords`] creates and updates them in any zone and they convert from and to [`Record`].
+//!
+//! # Reconciliation
+//!
+//! To manage a zone declaratively, describe its desired records and let a [`reconcile::Reconciler`] compute and apply the required changes.
+//!
+//! # ACME
+//!
+//! With the `acme` feature enabled, `acme::Dns01Solver` publishes and removes the TXT records of ACME DNS-01 challenges in any zone supporting record creation and deletion.
+//!
+//! # Dynamic DNS
+//!
+//! With the `ddns` feature enabled, `ddns::DdnsUpdater` keeps the A and AAAA records of a set of hosts pointed at the current addresses of a machine.
+//!
+//! # Self-hosted nameservers
+//!
+//! With the `rfc2136` feature enabled, `rfc2136::Rfc2136Provider` manages zones of any nameserver accepting TSIG-signed dynamic updates, such as BIND or Knot DNS.
+//!
+//! # Propagation
+//!
+//! With the `propagation` feature enabled, `propagation::PropagationChecker` queries the authoritative nameservers of a zone directly to tell when a new record is actually served.
+//!
+//! # Migration
+//!
+//! [`migrate::migrate_zone`] copies all records of a zone to a zone of another provider, adapting them to the limits of the target.
+//!
+//! # Zone files
+//!
+//! Records can be exported to and imported from RFC 1035 master files (BIND-style zone files) using [`zonefile::ZoneFile`], e.g. for backups or to review changes.
+//!
+//! # Wire format
+//!
+//! [`wire::ResourceRecord`] encodes records in and decodes them from the DNS wire format, e.g. for DNSSEC digests or zone transfers.
+//!
+//! # Testing
+//!
+//! With the `memory` feature enabled, `memory::MemoryProvider` keeps zones and records in memory, with configurable limits and injectable faults,
+//! to test code written against the traits without a real provider or mock server.
+//! With the `testing` feature enabled, `testing::ConformanceSuite` runs a provider implementation through standard scenarios and reports where it deviates from the behavior the traits promise.
+//!
+//! # Retries
+//!
+//! The HTTP-based providers retry rate-limited and transiently failed requests with exponential backoff.
+//! How often and after which failures is configured using `retry::RetryPolicy`.
+//!
+//! # Errors
+//!
+//! Every error type implements [`ClassifyError`], which maps the provider-specific error codes onto a common [`ErrorKind`],
+//! e.g. to tell rate limiting, existing records or exceeded quotas apart without matching on the custom error type of each provider.
+//!
+//! # Thread safety
+//!
+//! Providers, zones and their custom error types are required to be [`Send`] and [`Sync`], and every future returned by the traits is [`Send`].
+//! This allows generic code to spawn them onto multi-threaded executors such as `tokio::spawn`.
+//!
+//! # Dynamic dispatch
+//!
+//! The traits above cannot be used as trait objects. When the provider is only known at runtime, use the object-safe wrappers in [`dynamic`] instead.
 
 #![deny(rustdoc::broken_intra_doc_links)]
 #![forbid(unsafe_code)]
 
 use std::{
-    fmt::Debug,
+    borrow::Cow,
+    convert::Infallible,
+    fmt::{self, Debug, Display, Formatter},
     future::Future,
     net::{Ipv4Addr, Ipv6Addr},
+    ops::RangeInclusive,
     str::FromStr,
 };
 
@@ -33,7 +111,52 @@ use serde::{Deserialize, Serialize};
 
 use thiserror::Error;
 
+#[cfg(feature = "acme")]
+pub mod acme;
+#[cfg(feature = "ddns")]
+pub mod ddns;
+pub mod dynamic;
+#[cfg(any(
+    feature = "cloudflare",
+    feature = "dnspod",
+    feature = "hetzner",
+    feature = "namecheap",
+    feature = "technitium-dns",
+    feature = "tencent"
+))]
+mod http;
+#[cfg(feature = "memory")]
+pub mod memory;
+pub mod migrate;
+#[cfg(feature = "propagation")]
+pub mod propagation;
+pub mod reconcile;
+#[cfg(any(
+    feature = "cloudflare",
+    feature = "dnspod",
+    feature = "hetzner",
+    feature = "namecheap",
+    feature = "technitium-dns",
+    feature = "tencent"
+))]
+#[cfg_attr(
+    not(any(
+        feature = "cloudflare",
+        feature = "dnspod",
+        feature = "hetzner",
+        feature = "namecheap",
+        feature = "technitium-dns"
+    )),
+    allow(dead_code)
+)]
+pub mod retry;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod typed;
 pub mod types;
+#[cfg_attr(not(feature = "rfc2136"), allow(dead_code))]
+pub mod wire;
+pub mod zonefile;
 
 #[cfg(feature = "dnspod")]
 pub mod dnspod;
@@ -53,6 +176,9 @@ pub mod technitium;
 #[cfg(feature = "namecheap")]
 pub mod namecheap;
 
+#[cfg(feature = "rfc2136")]
+pub mod rfc2136;
+
 /// Represents a DNS zone provider.
 ///
 /// Providers implement [`Zone`] management, which in turn implement [`Record`] management.
@@ -60,26 +186,32 @@ pub mod namecheap;
 ///
 /// - [`CreateZone`]
 /// - [`DeleteZone`]
-pub trait Provider {
+pub trait Provider: Send + Sync {
     /// The provider-specific zone type.
     type Zone: Zone;
 
     /// The provider-specific custom zone retrieval error type used for [`RetrieveZoneError::Custom`].  
     /// If no custom errors should be provided, use `()`.
-    type CustomRetrieveError: Debug;
+    type CustomRetrieveError: Debug + Send + Sync + ClassifyError;
 
     /// Retrieves all available zones.  
     /// When no record exists, an [`Ok`] value with an empty [`Vec`] will be returned, not [`RetrieveZoneError::NotFound`].
     fn list_zones(
         &self,
-    ) -> impl Future<Output = Result<Vec<Self::Zone>, RetrieveZoneError<Self::CustomRetrieveError>>>;
+    ) -> impl Future<Output = Result<Vec<Self::Zone>, RetrieveZoneError<Self::CustomRetrieveError>>> + Send;
 
     /// Retrieves a zone by its provider-specific ID.  
     /// Refer to the provider's documentation to figure out which value is used as the ID.
     fn get_zone(
         &self,
         zone_id: &str,
-    ) -> impl Future<Output = Result<Self::Zone, RetrieveZoneError<Self::CustomRetrieveError>>>;
+    ) -> impl Future<Output = Result<Self::Zone, RetrieveZoneError<Self::CustomRetrieveError>>> + Send;
+
+    /// Describes what the provider and its zones support.  
+    /// The default implementation only reports zone and record retrieval without any restrictions, so providers should override it.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
 }
 
 /// Represents an error that occured when retrieving DNS zones using [`Provider::list_zones`] or [`Provider::get_zone`].
@@ -106,13 +238,13 @@ pub enum RetrieveZoneError<T> {
 pub trait CreateZone: Provider {
     /// The provider-specific custom zone creation error type used for [`CreateZoneError::Custom`].  
     /// If no custom errors should be provided, use `()`.
-    type CustomCreateError: Debug;
+    type CustomCreateError: Debug + Send + Sync + ClassifyError;
 
     /// Creates a new DNS zone with the given domain.
     fn create_zone(
         &self,
         domain: &str,
-    ) -> impl Future<Output = Result<Self::Zone, CreateZoneError<Self::CustomCreateError>>>;
+    ) -> impl Future<Output = Result<Self::Zone, CreateZoneError<Self::CustomCreateError>>> + Send;
 }
 
 /// Represents an error that occured when creating DNS zones using [`CreateZone::create_zone`].
@@ -139,14 +271,14 @@ pub enum CreateZoneError<T> {
 pub trait DeleteZone: Provider {
     /// The provider-specific custom zone deletion error type used for [`DeleteZoneError::Custom`].  
     /// If no custom errors should be provided, use `()`.
-    type CustomDeleteError: Debug;
+    type CustomDeleteError: Debug + Send + Sync + ClassifyError;
 
     /// Deletes a zone by its provider-specific ID.  
     /// Refer to the provider's documentation to figure out which value is used as the ID.
     fn delete_zone(
         &self,
         zone_id: &str,
-    ) -> impl Future<Output = Result<(), DeleteZoneError<Self::CustomDeleteError>>>;
+    ) -> impl Future<Output = Result<(), DeleteZoneError<Self::CustomDeleteError>>> + Send;
 }
 
 /// Represents an error that occured when deleting DNS zones using [`DeleteZone::delete_zone`].
@@ -169,7 +301,90 @@ pub enum DeleteZoneError<T> {
     Custom(#[from] T),
 }
 
+/// Describes the operations and limits of a [`Provider`] or [`Zone`], as returned by [`Provider::capabilities`] and [`Zone::capabilities`].
+///
+/// Which capability traits are implemented is only known at compile time; this descriptor allows generic code (e.g. migrations) to adapt at runtime instead.
+/// A provider reports the capabilities shared by all of its zones, while a zone may report tighter limits.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Capabilities {
+    /// Whether [`Provider::list_zones`] returns all zones; otherwise they can only be retrieved by their ID.
+    pub list_zones: bool,
+    /// Whether zones can be created using [`CreateZone`].
+    pub create_zone: bool,
+    /// Whether zones can be deleted using [`DeleteZone`].
+    pub delete_zone: bool,
+    /// Whether records can be created using [`CreateRecord`].
+    pub create_record: bool,
+    /// Whether records can be updated using [`UpdateRecord`].
+    pub update_record: bool,
+    /// Whether records can be deleted using [`DeleteRecord`].
+    pub delete_record: bool,
+    /// Whether [`UpdateRecord::update_record`] replaces a record in a single step, so it is never missing or duplicated in between.
+    pub atomic_update: bool,
+    /// The uppercase record types that can be created, or `None` if any type is accepted.
+    pub record_types: Option<Vec<String>>,
+    /// The range of TTLs (in seconds) accepted for records.
+    pub ttl_range: RangeInclusive<u64>,
+}
+
+impl Capabilities {
+    /// Returns whether records of the given type can be created.
+    pub fn supports_type(&self, typ: &str) -> bool {
+        self.record_types
+            .as_ref()
+            .is_none_or(|types| types.iter().any(|t| t.eq_ignore_ascii_case(typ)))
+    }
+
+    /// Returns whether the given TTL is accepted.
+    pub fn supports_ttl(&self, ttl: u64) -> bool {
+        self.ttl_range.contains(&ttl)
+    }
+
+    /// Returns the closest accepted TTL to the given one.
+    pub fn clamp_ttl(&self, ttl: u64) -> u64 {
+        ttl.clamp(*self.ttl_range.start(), *self.ttl_range.end())
+    }
+
+    /// Restricts the record types to the given ones.
+    pub fn with_record_types(mut self, types: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.record_types = Some(
+            types
+                .into_iter()
+                .map(|typ| typ.as_ref().to_ascii_uppercase())
+                .collect(),
+        );
+        self
+    }
+
+    /// Restricts the accepted TTLs to the given range.
+    pub fn with_ttl_range(mut self, ttl_range: RangeInclusive<u64>) -> Self {
+        self.ttl_range = ttl_range;
+        self
+    }
+}
+
+/// Only supports listing and retrieving zones and records, accepting any record type and TTL up to [`types::MAX_TTL`].
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            list_zones: true,
+            create_zone: false,
+            delete_zone: false,
+            create_record: false,
+            update_record: false,
+            delete_record: false,
+            atomic_update: false,
+            record_types: None,
+            ttl_range: 0..=types::MAX_TTL as u64,
+        }
+    }
+}
+
 /// Represents a DNS record value.
+///
+/// Values of types without a dedicated variant, or which could not be parsed, are kept in presentation format as [`RecordData::Other`].
+/// Binary fields (e.g. digests) are hexadecimal strings and domain names are kept as reported, with or without a trailing dot.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum RecordData {
@@ -188,6 +403,64 @@ pub enum RecordData {
         target: String,
     },
     TXT(String),
+    /// A certification authority authorization (RFC 8659).
+    CAA {
+        flags: u8,
+        tag: String,
+        value: String,
+    },
+    /// A TLS certificate association for DANE (RFC 6698).
+    TLSA {
+        usage: u8,
+        selector: u8,
+        matching_type: u8,
+        certificate: String,
+    },
+    /// An SSH host key fingerprint (RFC 4255).
+    SSHFP {
+        algorithm: u8,
+        fingerprint_type: u8,
+        fingerprint: String,
+    },
+    PTR(String),
+    /// The start of authority of a zone (RFC 1035 §3.3.13), with `rname` being the mailbox of the responsible person in domain name form.
+    SOA {
+        mname: String,
+        rname: String,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    /// A delegation signer of a DNSSEC-signed child zone (RFC 4034 §5).
+    DS {
+        key_tag: u16,
+        algorithm: u8,
+        digest_type: u8,
+        digest: String,
+    },
+    /// An HTTPS service binding (RFC 9460), with the `params` in presentation format (e.g. `alpn=h2,h3`).
+    HTTPS {
+        priority: u16,
+        target: String,
+        params: String,
+    },
+    /// A general service binding (RFC 9460), with the `params` in presentation format (e.g. `alpn=h2,h3`).
+    SVCB {
+        priority: u16,
+        target: String,
+        params: String,
+    },
+    /// A naming authority pointer (RFC 3403).
+    NAPTR {
+        order: u16,
+        preference: u16,
+        flags: String,
+        services: String,
+        regexp: String,
+        replacement: String,
+    },
     Other {
         typ: String,
         value: String,
@@ -239,6 +512,129 @@ impl RecordData {
                 }
             }
             "TXT" => Some(RecordData::TXT(value.to_owned())),
+            "CAA" => match presentation_fields(value).as_deref() {
+                Some([flags, tag, value]) if !tag.is_empty() => {
+                    flags.parse().ok().map(|flags| RecordData::CAA {
+                        flags,
+                        tag: tag.clone(),
+                        value: value.clone(),
+                    })
+                }
+                _ => None,
+            },
+            "TLSA" => match value.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [usage, selector, matching_type, certificate @ ..] => {
+                    match (
+                        usage.parse(),
+                        selector.parse(),
+                        matching_type.parse(),
+                        hex_field(certificate),
+                    ) {
+                        (Ok(usage), Ok(selector), Ok(matching_type), Some(certificate)) => {
+                            Some(RecordData::TLSA {
+                                usage,
+                                selector,
+                                matching_type,
+                                certificate,
+                            })
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            "SSHFP" => match value.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [algorithm, fingerprint_type, fingerprint @ ..] => {
+                    match (
+                        algorithm.parse(),
+                        fingerprint_type.parse(),
+                        hex_field(fingerprint),
+                    ) {
+                        (Ok(algorithm), Ok(fingerprint_type), Some(fingerprint)) => {
+                            Some(RecordData::SSHFP {
+                                algorithm,
+                                fingerprint_type,
+                                fingerprint,
+                            })
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            "PTR" => Some(RecordData::PTR(value.to_owned())),
+            "SOA" => match value.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [mname, rname, serial, refresh, retry, expire, minimum] => {
+                    match (
+                        serial.parse(),
+                        refresh.parse(),
+                        retry.parse(),
+                        expire.parse(),
+                        minimum.parse(),
+                    ) {
+                        (Ok(serial), Ok(refresh), Ok(retry), Ok(expire), Ok(minimum)) => {
+                            Some(RecordData::SOA {
+                                mname: mname.to_string(),
+                                rname: rname.to_string(),
+                                serial,
+                                refresh,
+                                retry,
+                                expire,
+                                minimum,
+                            })
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            "DS" => match value.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [key_tag, algorithm, digest_type, digest @ ..] => {
+                    match (
+                        key_tag.parse(),
+                        algorithm.parse(),
+                        digest_type.parse(),
+                        hex_field(digest),
+                    ) {
+                        (Ok(key_tag), Ok(algorithm), Ok(digest_type), Some(digest)) => {
+                            Some(RecordData::DS {
+                                key_tag,
+                                algorithm,
+                                digest_type,
+                                digest,
+                            })
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            "HTTPS" => service_binding(value).map(|(priority, target, params)| RecordData::HTTPS {
+                priority,
+                target,
+                params,
+            }),
+            "SVCB" => service_binding(value).map(|(priority, target, params)| RecordData::SVCB {
+                priority,
+                target,
+                params,
+            }),
+            "NAPTR" => match presentation_fields(value).as_deref() {
+                Some([order, preference, flags, services, regexp, replacement]) => {
+                    match (order.parse(), preference.parse()) {
+                        (Ok(order), Ok(preference)) => Some(RecordData::NAPTR {
+                            order,
+                            preference,
+                            flags: flags.clone(),
+                            services: services.clone(),
+                            regexp: regexp.clone(),
+                            replacement: replacement.clone(),
+                        }),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
             _ => None,
         };
 
@@ -257,6 +653,15 @@ impl RecordData {
             RecordData::NS(_) => "NS",
             RecordData::SRV { .. } => "SRV",
             RecordData::TXT(_) => "TXT",
+            RecordData::CAA { .. } => "CAA",
+            RecordData::TLSA { .. } => "TLSA",
+            RecordData::SSHFP { .. } => "SSHFP",
+            RecordData::PTR(_) => "PTR",
+            RecordData::SOA { .. } => "SOA",
+            RecordData::DS { .. } => "DS",
+            RecordData::HTTPS { .. } => "HTTPS",
+            RecordData::SVCB { .. } => "SVCB",
+            RecordData::NAPTR { .. } => "NAPTR",
             RecordData::Other { typ, .. } => typ.as_str(),
         }
     }
@@ -278,6 +683,67 @@ impl RecordData {
                 target,
             } => format!("{} {} {} {}", priority, weight, port, target),
             RecordData::TXT(val) => val.clone(),
+            RecordData::CAA { flags, tag, value } => {
+                format!("{} {} {}", flags, tag, quote(value))
+            }
+            RecordData::TLSA {
+                usage,
+                selector,
+                matching_type,
+                certificate,
+            } => format!("{} {} {} {}", usage, selector, matching_type, certificate),
+            RecordData::SSHFP {
+                algorithm,
+                fingerprint_type,
+                fingerprint,
+            } => format!("{} {} {}", algorithm, fingerprint_type, fingerprint),
+            RecordData::PTR(name) => name.clone(),
+            RecordData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => format!(
+                "{} {} {} {} {} {} {}",
+                mname, rname, serial, refresh, retry, expire, minimum
+            ),
+            RecordData::DS {
+                key_tag,
+                algorithm,
+                digest_type,
+                digest,
+            } => format!("{} {} {} {}", key_tag, algorithm, digest_type, digest),
+            RecordData::HTTPS {
+                priority,
+                target,
+                params,
+            }
+            | RecordData::SVCB {
+                priority,
+                target,
+                params,
+            } => format!("{} {} {}", priority, target, params)
+                .trim_end()
+                .to_string(),
+            RecordData::NAPTR {
+                order,
+                preference,
+                flags,
+                services,
+                regexp,
+                replacement,
+            } => format!(
+                "{} {} {} {} {} {}",
+                order,
+                preference,
+                quote(flags),
+                quote(services),
+                quote(regexp),
+                replacement
+            ),
             RecordData::Other { value, .. } => value.clone(),
         }
     }
@@ -295,6 +761,62 @@ impl RecordData {
     }
 }
 
+/// Splits a value in presentation format into its fields, unquoting character strings and resolving their backslash escapes.  
+/// Returns `None` if a quoted string is not terminated.
+fn presentation_fields(value: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = value.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(first) = chars.next() else {
+            return Some(fields);
+        };
+
+        let mut field = String::new();
+        if first == '"' {
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => field.push(chars.next()?),
+                    c => field.push(c),
+                }
+            }
+        } else {
+            field.push(first);
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                field.push(c);
+            }
+        }
+        fields.push(field);
+    }
+}
+
+/// Quotes a character string for presentation format, escaping quotes and backslashes.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Joins hexadecimal fields, which may be split by whitespace in presentation format.  
+/// Returns `None` if there is no field or a non-hexadecimal character.
+fn hex_field(fields: &[&str]) -> Option<String> {
+    let hex = fields.concat();
+    (!hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit())).then_some(hex)
+}
+
+/// Parses the priority, target and parameters of an HTTPS or SVCB record.
+fn service_binding(value: &str) -> Option<(u16, String, String)> {
+    let (priority, rest) = value.trim().split_once(char::is_whitespace)?;
+    let rest = rest.trim_start();
+    let (target, params) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+    Some((
+        priority.parse().ok()?,
+        target.to_string(),
+        params.trim().to_string(),
+    ))
+}
+
 /// Represents a DNS record.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -305,17 +827,206 @@ pub struct Record {
     pub ttl: u64,
 }
 
+impl Record {
+    /// Returns the [`Fingerprint`] of the record in the zone of the given domain.  
+    /// The host may be relative to the domain or fully qualified, so the fingerprint does not depend on how the provider reports it.
+    pub fn fingerprint(&self, domain: &str) -> Fingerprint {
+        let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+        let fqdn = match normalize_host(&self.host, &domain).as_str() {
+            "@" => domain,
+            relative => format!("{}.{}", relative, domain),
+        };
+
+        Fingerprint::new(&fqdn, &self.data)
+    }
+}
+
+/// A stable, provider-independent identifier of the content of a record, as returned by [`Record::fingerprint`].
+///
+/// It is computed from the fully qualified host, the type and the canonical value of a record, ignoring its TTL and provider-specific ID.
+/// Unlike IDs assigned by providers, it can be derived from the record itself, so it can be stored to find a record again after it was recreated or the provider reassigned its ID.
+///
+/// The 64-bit FNV-1a hash is used, so fingerprints are the same across restarts, platforms and Rust releases (unlike [`std::hash::Hash`]).
+/// Their [`Display`] representation is a fixed-width lowercase hexadecimal string.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+    /// Computes the fingerprint of a record with the given fully qualified host and value.
+    ///
+    /// Names are compared case-insensitively and without their trailing dot, and the quotes some providers put around TXT values are ignored.
+    pub fn new(fqdn: &str, data: &RecordData) -> Self {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+
+        let name = canonical_name(fqdn);
+        let typ = data.get_type().to_ascii_uppercase();
+        let value = canonical_value(data);
+
+        // The fields are separated by NUL bytes, so they cannot shift into each other
+        let mut hash = OFFSET_BASIS;
+        for byte in [name.as_bytes(), typ.as_bytes(), value.as_bytes()].join(&0) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+        Fingerprint(hash)
+    }
+
+    /// Creates a fingerprint from its numeric value, e.g. as previously returned by [`Fingerprint::as_u64`].
+    pub fn from_u64(value: u64) -> Self {
+        Fingerprint(value)
+    }
+
+    /// Returns the numeric value of the fingerprint.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Returns the canonical form of a domain name: lowercase and without the trailing dot.
+fn canonical_name(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Returns the canonical form of a record value as used for [`Fingerprint`]s.
+///
+/// Domain names are canonicalized, TXT values are unquoted, and unknown values have their whitespace collapsed.
+fn canonical_value(data: &RecordData) -> String {
+    match data {
+        RecordData::A(addr) => addr.to_string(),
+        RecordData::AAAA(addr) => addr.to_string(),
+        RecordData::CNAME(name) | RecordData::NS(name) => canonical_name(name),
+        RecordData::MX {
+            priority,
+            mail_server,
+        } => format!("{} {}", priority, canonical_name(mail_server)),
+        RecordData::SRV {
+            priority,
+            weight,
+            port,
+            target,
+        } => format!(
+            "{} {} {} {}",
+            priority,
+            weight,
+            port,
+            canonical_name(target)
+        ),
+        RecordData::TXT(text) => text
+            .strip_prefix('"')
+            .and_then(|t| t.strip_suffix('"'))
+            .unwrap_or(text)
+            .to_string(),
+        RecordData::CAA { flags, tag, value } => {
+            format!("{} {} {}", flags, tag.to_ascii_lowercase(), quote(value))
+        }
+        RecordData::TLSA {
+            usage,
+            selector,
+            matching_type,
+            certificate,
+        } => format!(
+            "{} {} {} {}",
+            usage,
+            selector,
+            matching_type,
+            certificate.to_ascii_lowercase()
+        ),
+        RecordData::SSHFP {
+            algorithm,
+            fingerprint_type,
+            fingerprint,
+        } => format!(
+            "{} {} {}",
+            algorithm,
+            fingerprint_type,
+            fingerprint.to_ascii_lowercase()
+        ),
+        RecordData::PTR(name) => canonical_name(name),
+        RecordData::SOA {
+            mname,
+            rname,
+            serial,
+            refresh,
+            retry,
+            expire,
+            minimum,
+        } => format!(
+            "{} {} {} {} {} {} {}",
+            canonical_name(mname),
+            canonical_name(rname),
+            serial,
+            refresh,
+            retry,
+            expire,
+            minimum
+        ),
+        RecordData::DS {
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+        } => format!(
+            "{} {} {} {}",
+            key_tag,
+            algorithm,
+            digest_type,
+            digest.to_ascii_lowercase()
+        ),
+        RecordData::HTTPS {
+            priority,
+            target,
+            params,
+        }
+        | RecordData::SVCB {
+            priority,
+            target,
+            params,
+        } => format!(
+            "{} {} {}",
+            priority,
+            canonical_name(target),
+            params.split_whitespace().collect::<Vec<_>>().join(" ")
+        ),
+        RecordData::NAPTR {
+            order,
+            preference,
+            flags,
+            services,
+            regexp,
+            replacement,
+        } => format!(
+            "{} {} {} {} {} {}",
+            order,
+            preference,
+            quote(&flags.to_ascii_lowercase()),
+            quote(&services.to_ascii_lowercase()),
+            quote(regexp),
+            canonical_name(replacement)
+        ),
+        RecordData::Other { value, .. } => value.split_whitespace().collect::<Vec<_>>().join(" "),
+    }
+}
+
 /// Represents a DNS zone.
 ///
 /// DNS zones are provided by a DNS [`Provider`] and implement [`Record`] management.
 /// By default, only record retrieval is supported, but the following capabilities may be implemented to allow further record management:
 ///
 /// - [`CreateRecord`]
-/// - [`CreateRecord`]
-pub trait Zone {
+/// - [`UpdateRecord`]
+/// - [`DeleteRecord`]
+pub trait Zone: Send + Sync {
     /// The provider-specific custom record retrieval error type used for [`RetrieveRecordError::Custom`].  
     /// If no custom errors should be provided, use `()`.
-    type CustomRetrieveError: Debug;
+    type CustomRetrieveError: Debug + Send + Sync + ClassifyError;
 
     /// Returns the provider-specific ID of the zone.
     fn id(&self) -> &str;
@@ -327,14 +1038,20 @@ pub trait Zone {
     /// When no record exists, an [`Ok`] value with an empty [`Vec`] will be returned, not [`RetrieveRecordError::NotFound`].
     fn list_records(
         &self,
-    ) -> impl Future<Output = Result<Vec<Record>, RetrieveRecordError<Self::CustomRetrieveError>>>;
+    ) -> impl Future<Output = Result<Vec<Record>, RetrieveRecordError<Self::CustomRetrieveError>>> + Send;
 
     /// Retrieves a record by its provider-specific ID.  
     /// Refer to the provider's documentation to figure out which value is used as the ID.
     fn get_record(
         &self,
         record_id: &str,
-    ) -> impl Future<Output = Result<Record, RetrieveRecordError<Self::CustomRetrieveError>>>;
+    ) -> impl Future<Output = Result<Record, RetrieveRecordError<Self::CustomRetrieveError>>> + Send;
+
+    /// Describes what the zone supports, which may differ between the zones of a provider (e.g. depending on their plan).  
+    /// The default implementation only reports record retrieval without any restrictions, so providers should override it.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
 }
 
 /// Represents an error that occured when retrieving DNS records using [`Zone::list_records`] or [`Zone::get_record`].
@@ -361,7 +1078,7 @@ pub enum RetrieveRecordError<T> {
 pub trait CreateRecord: Zone {
     /// The provider-specific custom record creation error type used for [`CreateRecordError::Custom`].  
     /// If no custom errors should be provided, use `()`.
-    type CustomCreateError: Debug;
+    type CustomCreateError: Debug + Send + Sync + ClassifyError;
 
     /// Creates a new record.
     fn create_record(
@@ -369,7 +1086,7 @@ pub trait CreateRecord: Zone {
         host: &str,
         data: &RecordData,
         ttl: u64,
-    ) -> impl Future<Output = Result<Record, CreateRecordError<Self::CustomCreateError>>>;
+    ) -> impl Future<Output = Result<Record, CreateRecordError<Self::CustomCreateError>>> + Send;
 }
 
 /// Represents an error that occured when creating DNS records using [`CreateRecord::create_record`].
@@ -396,17 +1113,65 @@ pub enum CreateRecordError<T> {
     Custom(#[from] T),
 }
 
+/// Represents a [`Zone`] that supports in-place record modification.
+///
+/// Unlike a [`DeleteRecord::delete_record`] followed by a [`CreateRecord::create_record`], an update never leaves the record absent.
+/// Providers without a native update operation emulate it in the safest way their API allows; refer to the provider's documentation for details.
+pub trait UpdateRecord: Zone {
+    /// The provider-specific custom record update error type used for [`UpdateRecordError::Custom`].  
+    /// If no custom errors should be provided, use `()`.
+    type CustomUpdateError: Debug + Send + Sync + ClassifyError;
+
+    /// Replaces the host, value and TTL of the record with the given ID.  
+    /// The returned [`Record`] carries the record's current ID, which may differ from `record_id` for providers that derive IDs from the record content.
+    fn update_record(
+        &self,
+        record_id: &str,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> impl Future<Output = Result<Record, UpdateRecordError<Self::CustomUpdateError>>> + Send;
+}
+
+/// Represents an error that occured when updating DNS records using [`UpdateRecord::update_record`].
+///
+/// Providers can provide a custom error type ([`UpdateRecord::CustomUpdateError`]) and return it using [`UpdateRecordError::Custom`] to extend the pool of well-defined errors.  
+/// Refer to the provider's documentation for more information.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UpdateRecordError<T> {
+    /// Indicates that the DNS provider is not authorized to execute this action.
+    #[error("the DNS provider is unauthorized")]
+    Unauthorized,
+
+    /// Indicates that there is no record with the given ID.
+    #[error("the requested record was not found")]
+    NotFound,
+
+    /// Indicates that the DNS provider does not support the specified record type.
+    #[error("the DNS provider does not support the specified record type")]
+    UnsupportedType,
+
+    /// Indicates that the record value is invalid.
+    #[error("the given record value is invalid")]
+    InvalidRecord,
+
+    /// Provides a custom, provider-specific error of type `T`.
+    #[error(transparent)]
+    Custom(#[from] T),
+}
+
 /// Represents a [`Zone`] that supports record deletion.
 pub trait DeleteRecord: Zone {
     /// The provider-specific custom record creation error type used for [`DeleteRecordError::Custom`].  
     /// If no custom errors should be provided, use `()`.
-    type CustomDeleteError: Debug;
+    type CustomDeleteError: Debug + Send + Sync + ClassifyError;
 
     /// Deletes a record by its ID.
     fn delete_record(
         &self,
         record_id: &str,
-    ) -> impl Future<Output = Result<(), DeleteRecordError<Self::CustomDeleteError>>>;
+    ) -> impl Future<Output = Result<(), DeleteRecordError<Self::CustomDeleteError>>> + Send;
 }
 
 /// Represents an error that occured when deleting DNS records using [`DeleteRecord::delete_record`].
@@ -428,3 +1193,710 @@ pub enum DeleteRecordError<T> {
     #[error(transparent)]
     Custom(#[from] T),
 }
+
+/// Represents a [`Zone`] that supports appending records without knowing their IDs.
+///
+/// This mirrors `AppendRecords` of the Go `libdns` ecosystem.
+/// The default implementation creates the records one by one using [`CreateRecord::create_record`]; providers may override it with a native batch operation.
+pub trait AppendRecords: CreateRecord {
+    /// Creates all given records and returns them as they were created.  
+    /// The `id` of the given records is ignored; their `host` is interpreted like the one passed to [`CreateRecord::create_record`].
+    fn append_records(
+        &self,
+        records: &[Record],
+    ) -> impl Future<Output = Result<Vec<Record>, CreateRecordError<Self::CustomCreateError>>> + Send
+    {
+        async move {
+            let mut created = Vec::with_capacity(records.len());
+            for record in records {
+                created.push(
+                    self.create_record(&record.host, &record.data, record.ttl)
+                        .await?,
+                );
+            }
+            Ok(created)
+        }
+    }
+}
+
+/// Represents a [`Zone`] that supports replacing record sets without knowing record IDs.
+///
+/// This mirrors `SetRecords` of the Go `libdns` ecosystem: for every host and type present in the input, the zone's records of that host and type are made to match the input exactly.
+/// Records of other hosts or types are left untouched.
+///
+/// The default implementation compares the input against [`Zone::list_records`] and creates missing records before deleting stale ones.
+/// A record whose value is kept but whose TTL changes is deleted before it is recreated, as providers commonly reject duplicate values; this happens after all missing records are created, but a record set whose records only change their TTL is briefly empty.
+/// Providers may override it with a native operation.
+pub trait SetRecords: CreateRecord + DeleteRecord {
+    /// Replaces the record sets covered by the given records and returns the resulting records.  
+    /// The `id` of the given records is ignored; records are matched by host, type and value.
+    #[allow(clippy::type_complexity)]
+    fn set_records(
+        &self,
+        records: &[Record],
+    ) -> impl Future<
+        Output = Result<
+            Vec<Record>,
+            SetRecordsError<
+                Self::CustomRetrieveError,
+                Self::CustomCreateError,
+                Self::CustomDeleteError,
+            >,
+        >,
+    > + Send {
+        async move {
+            let existing = self
+                .list_records()
+                .await
+                .map_err(SetRecordsError::Retrieve)?;
+            let domain = self.domain();
+
+            let mut result = Vec::with_capacity(records.len());
+            let mut to_create = Vec::new();
+            let mut to_delete = Vec::new();
+            let mut ttl_changes = Vec::new();
+
+            let mut handled: Vec<(String, &str)> = Vec::new();
+            for record in records {
+                let key = (normalize_host(&record.host, domain), record.data.get_type());
+                if handled.contains(&key) {
+                    continue;
+                }
+
+                let desired: Vec<&Record> = records
+                    .iter()
+                    .filter(|r| same_rrset(r, &key.0, key.1, domain))
+                    .collect();
+                let mut current: Vec<&Record> = existing
+                    .iter()
+                    .filter(|r| same_rrset(r, &key.0, key.1, domain))
+                    .collect();
+
+                for wanted in desired {
+                    let position = current
+                        .iter()
+                        .position(|r| same_value(&r.data, &wanted.data));
+                    match position.map(|p| current.remove(p)) {
+                        Some(found) if found.ttl == wanted.ttl => result.push(found.clone()),
+                        // The value is already present with another TTL; it has to be removed before it can be recreated,
+                        // which is deferred until the other records of the set exist
+                        Some(found) => ttl_changes.push((found, wanted)),
+                        None => to_create.push(wanted),
+                    }
+                }
+                to_delete.extend(current);
+                handled.push(key);
+            }
+
+            for record in to_create {
+                result.push(
+                    self.create_record(&record.host, &record.data, record.ttl)
+                        .await
+                        .map_err(SetRecordsError::Create)?,
+                );
+            }
+            for (found, wanted) in ttl_changes {
+                self.delete_record(&found.id)
+                    .await
+                    .map_err(SetRecordsError::Delete)?;
+                result.push(
+                    self.create_record(&wanted.host, &wanted.data, wanted.ttl)
+                        .await
+                        .map_err(SetRecordsError::Create)?,
+                );
+            }
+            for record in to_delete {
+                self.delete_record(&record.id)
+                    .await
+                    .map_err(SetRecordsError::Delete)?;
+            }
+
+            Ok(result)
+        }
+    }
+}
+
+/// Represents an error that occured when replacing record sets using [`SetRecords::set_records`].
+///
+/// The variant indicates which underlying operation failed. As the default implementation consists of multiple requests, some changes may already have been applied.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SetRecordsError<R, C, D> {
+    /// Indicates that the existing records could not be retrieved.
+    #[error(transparent)]
+    Retrieve(RetrieveRecordError<R>),
+
+    /// Indicates that a record could not be created.
+    #[error(transparent)]
+    Create(CreateRecordError<C>),
+
+    /// Indicates that a stale record could not be deleted.
+    #[error(transparent)]
+    Delete(DeleteRecordError<D>),
+}
+
+/// Represents a [`Zone`] that supports deleting records without knowing their IDs.
+///
+/// This mirrors `DeleteRecords` of the Go `libdns` ecosystem.
+/// The default implementation looks the records up using [`Zone::list_records`] and deletes them one by one; providers may override it with a native operation.
+pub trait DeleteRecords: DeleteRecord {
+    /// Deletes all records matching the host, type and value of the given records and returns the deleted records.  
+    /// The `id` and `ttl` of the given records are ignored. Records that do not exist are skipped.
+    fn delete_records(
+        &self,
+        records: &[Record],
+    ) -> impl Future<
+        Output = Result<
+            Vec<Record>,
+            DeleteRecordsError<Self::CustomRetrieveError, Self::CustomDeleteError>,
+        >,
+    > + Send {
+        async move {
+            let existing = self
+                .list_records()
+                .await
+                .map_err(DeleteRecordsError::Retrieve)?;
+            let domain = self.domain();
+
+            let mut deleted = Vec::new();
+            for record in existing {
+                let matched = records
+                    .iter()
+                    .any(|r| same_content(&record, &r.host, &r.data, domain));
+                if !matched {
+                    continue;
+                }
+
+                self.delete_record(&record.id)
+                    .await
+                    .map_err(DeleteRecordsError::Delete)?;
+                deleted.push(record);
+            }
+
+            Ok(deleted)
+        }
+    }
+}
+
+/// Represents an error that occured when deleting records using [`DeleteRecords::delete_records`].
+///
+/// The variant indicates which underlying operation failed. As the default implementation consists of multiple requests, some records may already have been deleted.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeleteRecordsError<R, D> {
+    /// Indicates that the existing records could not be retrieved.
+    #[error(transparent)]
+    Retrieve(RetrieveRecordError<R>),
+
+    /// Indicates that a record could not be deleted.
+    #[error(transparent)]
+    Delete(DeleteRecordError<D>),
+}
+
+/// Converts a name to its lowercase ASCII form without trailing dot.
+///
+/// Names that are no valid internationalized domain names are only lowercased, so they are rejected by the provider instead.
+pub(crate) fn ascii_name(name: &str) -> String {
+    let name = name.trim_end_matches('.');
+    types::to_ascii_name(name)
+        .unwrap_or(Cow::Borrowed(name))
+        .to_ascii_lowercase()
+}
+
+/// Normalizes a record host relative to the zone's domain, using `@` for the apex.
+///
+/// Providers report hosts either relative to the zone or fully qualified; this makes both comparable.
+/// Internationalized hosts are converted to their ASCII form, in which providers report them.
+pub(crate) fn normalize_host(host: &str, domain: &str) -> String {
+    let host = ascii_name(host);
+    let domain = ascii_name(domain);
+
+    if host.is_empty() || host == "@" || host == domain {
+        "@".to_string()
+    } else if let Some(relative) = host.strip_suffix(&format!(".{}", domain)) {
+        relative.to_string()
+    } else {
+        host
+    }
+}
+
+/// Compares two record values, ignoring trailing dots of domain names and the quotes some providers put around TXT values.
+pub(crate) fn same_value(a: &RecordData, b: &RecordData) -> bool {
+    fn normalize(data: &RecordData) -> String {
+        match data {
+            RecordData::TXT(text) => text
+                .strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .unwrap_or(text)
+                .to_string(),
+            _ => data.get_value().trim_end_matches('.').to_ascii_lowercase(),
+        }
+    }
+
+    a.get_type().eq_ignore_ascii_case(b.get_type()) && normalize(a) == normalize(b)
+}
+
+/// Returns whether `record` belongs to the record set of the given normalized host and type.
+fn same_rrset(record: &Record, host: &str, typ: &str, domain: &str) -> bool {
+    record.data.get_type().eq_ignore_ascii_case(typ) && normalize_host(&record.host, domain) == host
+}
+
+/// Returns whether `record` has the given host and value.
+pub(crate) fn same_content(record: &Record, host: &str, data: &RecordData, domain: &str) -> bool {
+    normalize_host(&record.host, domain) == normalize_host(host, domain)
+        && same_value(&record.data, data)
+}
+
+/// Runs `op` on the record of the zone with the given host and value, returning `None` if there is no such record.
+///
+/// Some providers reassign the IDs of all records on every change, so the ID a record has in an earlier listing may be stale.
+/// The record is therefore looked up by content in `listing`, which is only retrieved again if it is missing or `op` reports the ID as not found.
+/// On success, the record is removed from `listing`, as `op` is expected to replace or delete it.
+pub(crate) async fn with_listed_record<Z, T, E, F>(
+    zone: &Z,
+    listing: &mut Option<Vec<Record>>,
+    host: &str,
+    data: &RecordData,
+    retrieve_error: impl Fn(RetrieveRecordError<Z::CustomRetrieveError>) -> E,
+    mut op: impl FnMut(Record) -> F,
+) -> Result<Option<T>, E>
+where
+    Z: Zone,
+    E: ClassifyError,
+    F: Future<Output = Result<T, E>>,
+{
+    let domain = zone.domain();
+    let mut relisted = false;
+    loop {
+        let records = match listing {
+            Some(records) => records,
+            None => {
+                relisted = true;
+                listing.insert(zone.list_records().await.map_err(&retrieve_error)?)
+            }
+        };
+        let Some(position) = records
+            .iter()
+            .position(|record| same_content(record, host, data, domain))
+        else {
+            return Ok(None);
+        };
+
+        match op(records[position].clone()).await {
+            Ok(value) => {
+                records.remove(position);
+                return Ok(Some(value));
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound && !relisted => *listing = None,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Represents a single change of a changeset applied using [`ApplyChanges::apply_changes`].
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RecordChange {
+    /// Creates a new record, like [`CreateRecord::create_record`].
+    Create {
+        host: String,
+        data: RecordData,
+        ttl: u64,
+    },
+    /// Replaces an existing record, like [`UpdateRecord::update_record`].
+    Update {
+        record_id: String,
+        host: String,
+        data: RecordData,
+        ttl: u64,
+    },
+    /// Deletes an existing record, like [`DeleteRecord::delete_record`].
+    Delete { record_id: String },
+}
+
+/// Represents a [`Zone`] that supports applying a set of record changes as one unit.
+///
+/// Providers with a native batch operation apply all changes atomically.
+/// Others fall back to [`apply_changes_sequentially`], which applies the changes one by one and tries to roll back the applied ones if a change fails.
+/// Refer to the provider's documentation for the guarantees it gives.
+pub trait ApplyChanges: Zone {
+    /// The provider-specific custom changeset error type used for [`ChangeError::Custom`].  
+    /// If no custom errors should be provided, use `()`.
+    type CustomApplyError: Debug + Send + Sync + ClassifyError;
+
+    /// Applies all given changes and returns the resulting records of all [`RecordChange::Create`] and [`RecordChange::Update`] changes in their original order.
+    fn apply_changes(
+        &self,
+        changes: &[RecordChange],
+    ) -> impl Future<Output = Result<Vec<Record>, ApplyChangesError<Self::CustomApplyError>>> + Send;
+}
+
+/// Represents an error that occured when applying a changeset using [`ApplyChanges::apply_changes`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[error("failed to apply the changeset: {cause}")]
+pub struct ApplyChangesError<T> {
+    /// The index of the change that failed, if the failure can be attributed to a single change.
+    pub index: Option<usize>,
+
+    /// Whether the zone is known to be in its original state, either because no change was applied or because all applied changes were rolled back.
+    pub rolled_back: bool,
+
+    /// The error that caused the changeset to fail.
+    #[source]
+    pub cause: ChangeError<T>,
+}
+
+/// Represents the cause of an [`ApplyChangesError`].
+///
+/// Providers can provide a custom error type ([`ApplyChanges::CustomApplyError`]) and return it using [`ChangeError::Custom`] to extend the pool of well-defined errors.  
+/// Refer to the provider's documentation for more information.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ChangeError<T> {
+    /// Indicates that the DNS provider is not authorized to execute this action.
+    #[error("the DNS provider is unauthorized")]
+    Unauthorized,
+
+    /// Indicates that a record referenced by the changeset was not found.
+    #[error("the requested record was not found")]
+    NotFound,
+
+    /// Indicates that the DNS provider does not support the specified record type.
+    #[error("the DNS provider does not support the specified record type")]
+    UnsupportedType,
+
+    /// Indicates that a record value is invalid.
+    #[error("the given record value is invalid")]
+    InvalidRecord,
+
+    /// Provides a custom, provider-specific error of type `T`.
+    #[error(transparent)]
+    Custom(#[from] T),
+}
+
+impl<T> From<RetrieveRecordError<T>> for ChangeError<T> {
+    fn from(err: RetrieveRecordError<T>) -> Self {
+        match err {
+            RetrieveRecordError::Unauthorized => ChangeError::Unauthorized,
+            RetrieveRecordError::NotFound => ChangeError::NotFound,
+            RetrieveRecordError::Custom(e) => ChangeError::Custom(e),
+        }
+    }
+}
+
+impl<T> From<CreateRecordError<T>> for ChangeError<T> {
+    fn from(err: CreateRecordError<T>) -> Self {
+        match err {
+            CreateRecordError::Unauthorized => ChangeError::Unauthorized,
+            CreateRecordError::UnsupportedType => ChangeError::UnsupportedType,
+            CreateRecordError::InvalidRecord => ChangeError::InvalidRecord,
+            CreateRecordError::Custom(e) => ChangeError::Custom(e),
+        }
+    }
+}
+
+impl<T> From<UpdateRecordError<T>> for ChangeError<T> {
+    fn from(err: UpdateRecordError<T>) -> Self {
+        match err {
+            UpdateRecordError::Unauthorized => ChangeError::Unauthorized,
+            UpdateRecordError::NotFound => ChangeError::NotFound,
+            UpdateRecordError::UnsupportedType => ChangeError::UnsupportedType,
+            UpdateRecordError::InvalidRecord => ChangeError::InvalidRecord,
+            UpdateRecordError::Custom(e) => ChangeError::Custom(e),
+        }
+    }
+}
+
+impl<T> From<DeleteRecordError<T>> for ChangeError<T> {
+    fn from(err: DeleteRecordError<T>) -> Self {
+        match err {
+            DeleteRecordError::Unauthorized => ChangeError::Unauthorized,
+            DeleteRecordError::NotFound => ChangeError::NotFound,
+            DeleteRecordError::Custom(e) => ChangeError::Custom(e),
+        }
+    }
+}
+
+/// Represents the provider-agnostic category of an error, as returned by [`ClassifyError::kind`].
+///
+/// It allows handling failures generically without matching on the custom error type of every provider.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ErrorKind {
+    /// Indicates that the credentials are invalid or lack the permission for the action.
+    Unauthorized,
+
+    /// Indicates that the requested zone or record does not exist.
+    NotFound,
+
+    /// Indicates that the zone or record already exists or conflicts with an existing one.
+    AlreadyExists,
+
+    /// Indicates that the request was rejected because of rate limiting.
+    RateLimited,
+
+    /// Indicates that a limit of the account, like the number of zones or records, has been reached.
+    QuotaExceeded,
+
+    /// Indicates a network failure or a temporary failure of the provider.
+    Transient,
+
+    /// Indicates that the provider rejected the input, like an invalid name, value or TTL, or an unsupported record type.
+    Validation,
+
+    /// Indicates any other failure.
+    Other,
+}
+
+impl ErrorKind {
+    /// Returns whether the failed action may succeed when retried later without changes.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, ErrorKind::RateLimited | ErrorKind::Transient)
+    }
+}
+
+/// Represents an error that can be classified into an [`ErrorKind`].
+///
+/// It is implemented by all error types of this crate, and the custom error types of providers are required to implement it.
+/// Providers map their own error codes onto the kinds, so callers can e.g. retry rate-limited actions or skip existing records for any provider.
+pub trait ClassifyError {
+    /// Returns the category of the error.
+    fn kind(&self) -> ErrorKind;
+}
+
+/// Used by providers without custom errors, which never construct it.
+impl ClassifyError for () {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// Used for operations that cannot fail, e.g. the updates of a [`reconcile::Plan`] applied without [`UpdateRecord`].
+impl ClassifyError for Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+impl<T: ClassifyError> ClassifyError for RetrieveZoneError<T> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            RetrieveZoneError::Unauthorized => ErrorKind::Unauthorized,
+            RetrieveZoneError::NotFound => ErrorKind::NotFound,
+            RetrieveZoneError::Custom(e) => e.kind(),
+        }
+    }
+}
+
+impl<T: ClassifyError> ClassifyError for CreateZoneError<T> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            CreateZoneError::Unauthorized => ErrorKind::Unauthorized,
+            CreateZoneError::InvalidDomainName => ErrorKind::Validation,
+            CreateZoneError::Custom(e) => e.kind(),
+        }
+    }
+}
+
+impl<T: ClassifyError> ClassifyError for DeleteZoneError<T> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            DeleteZoneError::Unauthorized => ErrorKind::Unauthorized,
+            DeleteZoneError::NotFound => ErrorKind::NotFound,
+            DeleteZoneError::Custom(e) => e.kind(),
+        }
+    }
+}
+
+impl<T: ClassifyError> ClassifyError for RetrieveRecordError<T> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            RetrieveRecordError::Unauthorized => ErrorKind::Unauthorized,
+            RetrieveRecordError::NotFound => ErrorKind::NotFound,
+            RetrieveRecordError::Custom(e) => e.kind(),
+        }
+    }
+}
+
+impl<T: ClassifyError> ClassifyError for CreateRecordError<T> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            CreateRecordError::Unauthorized => ErrorKind::Unauthorized,
+            CreateRecordError::UnsupportedType | CreateRecordError::InvalidRecord => {
+                ErrorKind::Validation
+            }
+            CreateRecordError::Custom(e) => e.kind(),
+        }
+    }
+}
+
+impl<T: ClassifyError> ClassifyError for UpdateRecordError<T> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            UpdateRecordError::Unauthorized => ErrorKind::Unauthorized,
+            UpdateRecordError::NotFound => ErrorKind::NotFound,
+            UpdateRecordError::UnsupportedType | UpdateRecordError::InvalidRecord => {
+                ErrorKind::Validation
+            }
+            UpdateRecordError::Custom(e) => e.kind(),
+        }
+    }
+}
+
+impl<T: ClassifyError> ClassifyError for DeleteRecordError<T> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            DeleteRecordError::Unauthorized => ErrorKind::Unauthorized,
+            DeleteRecordError::NotFound => ErrorKind::NotFound,
+            DeleteRecordError::Custom(e) => e.kind(),
+        }
+    }
+}
+
+impl<R: ClassifyError, C: ClassifyError, D: ClassifyError> ClassifyError
+    for SetRecordsError<R, C, D>
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            SetRecordsError::Retrieve(e) => e.kind(),
+            SetRecordsError::Create(e) => e.kind(),
+            SetRecordsError::Delete(e) => e.kind(),
+        }
+    }
+}
+
+impl<R: ClassifyError, D: ClassifyError> ClassifyError for DeleteRecordsError<R, D> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            DeleteRecordsError::Retrieve(e) => e.kind(),
+            DeleteRecordsError::Delete(e) => e.kind(),
+        }
+    }
+}
+
+impl<T: ClassifyError> ClassifyError for ApplyChangesError<T> {
+    fn kind(&self) -> ErrorKind {
+        self.cause.kind()
+    }
+}
+
+impl<T: ClassifyError> ClassifyError for ChangeError<T> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            ChangeError::Unauthorized => ErrorKind::Unauthorized,
+            ChangeError::NotFound => ErrorKind::NotFound,
+            ChangeError::UnsupportedType | ChangeError::InvalidRecord => ErrorKind::Validation,
+            ChangeError::Custom(e) => e.kind(),
+        }
+    }
+}
+
+/// A change applied by [`apply_changes_sequentially`], kept to be able to roll it back.
+enum AppliedChange {
+    Created(Record),
+    Updated { before: Record, after: Record },
+    Deleted(Record),
+}
+
+/// Applies the given changes one by one, rolling back the already applied ones on failure.
+///
+/// This is the fallback for providers without a native batch operation and can be used to implement [`ApplyChanges`].
+/// Updated and deleted records are retrieved before they are changed so they can be restored.
+/// Rolling back is best-effort: recreated records may receive new IDs, and [`ApplyChangesError::rolled_back`] is `false` if any step of the rollback failed.
+pub async fn apply_changes_sequentially<Z, T>(
+    zone: &Z,
+    changes: &[RecordChange],
+) -> Result<Vec<Record>, ApplyChangesError<T>>
+where
+    Z: Zone<CustomRetrieveError = T>
+        + CreateRecord<CustomCreateError = T>
+        + UpdateRecord<CustomUpdateError = T>
+        + DeleteRecord<CustomDeleteError = T>,
+    T: Debug + Send + Sync,
+{
+    let mut applied = Vec::with_capacity(changes.len());
+    let mut results = Vec::new();
+
+    for (index, change) in changes.iter().enumerate() {
+        let outcome: Result<AppliedChange, ChangeError<T>> = async {
+            match change {
+                RecordChange::Create { host, data, ttl } => {
+                    let created = zone.create_record(host, data, *ttl).await?;
+                    results.push(created.clone());
+                    Ok(AppliedChange::Created(created))
+                }
+                RecordChange::Update {
+                    record_id,
+                    host,
+                    data,
+                    ttl,
+                } => {
+                    let before = zone.get_record(record_id).await?;
+                    let after = zone.update_record(record_id, host, data, *ttl).await?;
+                    results.push(after.clone());
+                    Ok(AppliedChange::Updated { before, after })
+                }
+                RecordChange::Delete { record_id } => {
+                    let before = zone.get_record(record_id).await?;
+                    zone.delete_record(record_id).await?;
+                    Ok(AppliedChange::Deleted(before))
+                }
+            }
+        }
+        .await;
+
+        match outcome {
+            Ok(change) => applied.push(change),
+            Err(cause) => {
+                let rolled_back = roll_back(zone, applied).await;
+                return Err(ApplyChangesError {
+                    index: Some(index),
+                    rolled_back,
+                    cause,
+                });
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Reverts the given applied changes in reverse order and returns whether all of them could be reverted.
+async fn roll_back<Z, T>(zone: &Z, applied: Vec<AppliedChange>) -> bool
+where
+    Z: Zone<CustomRetrieveError = T>
+        + CreateRecord<CustomCreateError = T>
+        + UpdateRecord<CustomUpdateError = T>
+        + DeleteRecord<CustomDeleteError = T>,
+    T: Debug + Send + Sync,
+{
+    let domain = zone.domain();
+    let mut complete = true;
+
+    for change in applied.into_iter().rev() {
+        let reverted = match change {
+            AppliedChange::Created(record) => zone.delete_record(&record.id).await.is_ok(),
+            AppliedChange::Updated { before, after } => zone
+                .update_record(
+                    &after.id,
+                    &normalize_host(&before.host, domain),
+                    &before.data,
+                    before.ttl,
+                )
+                .await
+                .is_ok(),
+            AppliedChange::Deleted(record) => zone
+                .create_record(
+                    &normalize_host(&record.host, domain),
+                    &record.data,
+                    record.ttl,
+                )
+                .await
+                .is_ok(),
+        };
+        complete &= reverted;
+    }
+
+    complete
+}
@@ -0,0 +1,754 @@
+//! In-memory provider for tests and local development.
+//!
+//! A [`MemoryProvider`] keeps its zones and records in memory and implements every trait of this crate
+//! with the semantics of a hosted provider, so code written against the traits can be tested without mock servers:
+//!
+//! - Zones and records receive IDs that stay the same for their whole life, including record updates.
+//! - Unknown IDs result in `NotFound` errors.
+//! - Records are rejected as invalid if their TTL is out of range, a name is not a valid domain name,
+//!   the same value already exists at the host, or a CNAME would coexist with other records at the same host.
+//! - Records of types outside the configured set of supported types are rejected as unsupported.
+//! - [`ApplyChanges`] applies either all changes or none of them.
+//!
+//! Clones of a provider share its state, so a clone can be handed to the code under test while the original is used to inspect the outcome.
+//!
+//! # Fault injection
+//!
+//! Failures of real providers can be simulated per [`Operation`] using [`MemoryProvider::fail_next`] and [`MemoryProvider::fail_always`].
+//! A failing call has no effect on the stored zones and records.
+//!
+//! # Example
+//!
+//! ```
+//! use libdns::memory::{Fault, MemoryError, MemoryProvider, Operation};
+//! use libdns::{CreateRecord, CreateRecordError, CreateZone, RecordData};
+//!
+//! # async fn example() {
+//! let provider = MemoryProvider::new().with_supported_types(["A", "AAAA", "TXT"]);
+//! let zone = provider.create_zone("example.com").await.unwrap();
+//!
+//! let data = RecordData::A("10.0.0.1".parse().unwrap());
+//! provider.fail_next(Operation::CreateRecord, Fault::Unavailable);
+//! assert_eq!(
+//!     zone.create_record("www", &data, 300).await,
+//!     Err(CreateRecordError::Custom(MemoryError::Unavailable))
+//! );
+//! assert!(zone.create_record("www", &data, 300).await.is_ok());
+//! # }
+//! ```
+
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use thiserror::Error;
+
+#[cfg(feature = "ddns")]
+use crate::ddns::DynamicDns;
+use crate::dynamic::{DynProvider, DynProviderAdapter};
+use crate::migrate::MigrationTarget;
+use crate::types::{to_ascii_name, DomainName, MAX_TTL};
+use crate::{
+    normalize_host, same_value, AppendRecords, ApplyChanges, ApplyChangesError, Capabilities,
+    ChangeError, ClassifyError, CreateRecord, CreateRecordError, CreateZone, CreateZoneError,
+    DeleteRecord, DeleteRecordError, DeleteRecords, DeleteZone, DeleteZoneError, ErrorKind,
+    Provider, Record, RecordChange, RecordData, RetrieveRecordError, RetrieveZoneError, SetRecords,
+    UpdateRecord, UpdateRecordError, Zone,
+};
+
+/// An operation of the provider or its zones that faults can be injected into.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Operation {
+    ListZones,
+    GetZone,
+    CreateZone,
+    DeleteZone,
+    ListRecords,
+    GetRecord,
+    CreateRecord,
+    UpdateRecord,
+    DeleteRecord,
+    ApplyChanges,
+}
+
+/// A failure injected into an [`Operation`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Fault {
+    /// The operation fails with the `Unauthorized` variant of its error, like with revoked credentials.
+    Unauthorized,
+
+    /// The operation fails with [`MemoryError::Unavailable`], like a provider that is temporarily down or rate limits requests.
+    Unavailable,
+}
+
+/// Represents the custom errors of the [`MemoryProvider`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+pub enum MemoryError {
+    /// Indicates a transient failure injected using [`Fault::Unavailable`].
+    #[error("the provider is temporarily unavailable")]
+    Unavailable,
+
+    /// Indicates that a zone with the same domain already exists.
+    #[error("the zone already exists")]
+    ZoneExists,
+
+    /// Indicates that the zone of a record was deleted.
+    #[error("the zone does not exist")]
+    ZoneNotFound,
+}
+
+impl ClassifyError for MemoryError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            MemoryError::Unavailable => ErrorKind::Transient,
+            MemoryError::ZoneExists => ErrorKind::AlreadyExists,
+            MemoryError::ZoneNotFound => ErrorKind::NotFound,
+        }
+    }
+}
+
+/// Provider keeping zones and records in memory.
+///
+/// See the [module documentation](self) for the semantics it implements.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryProvider {
+    state: Arc<Mutex<State>>,
+}
+
+impl MemoryProvider {
+    /// Creates an empty provider supporting every record type and TTLs from 0 to [`MAX_TTL`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the record types that can be created, e.g. to mirror the limits of a hosted provider.
+    pub fn with_supported_types(self, types: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.lock().limits.supported_types = Some(
+            types
+                .into_iter()
+                .map(|typ| typ.as_ref().to_ascii_uppercase())
+                .collect(),
+        );
+        self
+    }
+
+    /// Restricts the TTLs (in seconds) records can be created with.
+    pub fn with_ttl_range(self, ttl_range: RangeInclusive<u64>) -> Self {
+        self.lock().limits.ttl_range = ttl_range;
+        self
+    }
+
+    /// Makes the next call of the operation fail with the fault.
+    ///
+    /// Calling this repeatedly queues several failures, e.g. to let a retrying caller succeed on the third attempt.
+    pub fn fail_next(&self, operation: Operation, fault: Fault) {
+        self.lock().faults.push(InjectedFault {
+            operation,
+            fault,
+            persistent: false,
+        });
+    }
+
+    /// Makes every call of the operation fail with the fault until [`MemoryProvider::clear_faults`] is called.
+    pub fn fail_always(&self, operation: Operation, fault: Fault) {
+        self.lock().faults.push(InjectedFault {
+            operation,
+            fault,
+            persistent: true,
+        });
+    }
+
+    /// Removes all injected faults.
+    pub fn clear_faults(&self) {
+        self.lock().faults.clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // A panicking test must not poison the state for others sharing it
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn zone(&self, zone: &ZoneState) -> MemoryZone {
+        MemoryZone {
+            provider: self.clone(),
+            id: zone.id.clone(),
+            domain: zone.domain.clone(),
+        }
+    }
+}
+
+impl From<MemoryProvider> for Box<dyn DynProvider> {
+    fn from(provider: MemoryProvider) -> Self {
+        Box::new(
+            DynProviderAdapter::new(provider)
+                .with_create_zone()
+                .with_delete_zone()
+                .with_create_record()
+                .with_update_record()
+                .with_delete_record(),
+        )
+    }
+}
+
+impl Provider for MemoryProvider {
+    type Zone = MemoryZone;
+    type CustomRetrieveError = MemoryError;
+
+    async fn list_zones(
+        &self,
+    ) -> Result<Vec<Self::Zone>, RetrieveZoneError<Self::CustomRetrieveError>> {
+        let mut state = self.lock();
+        state.fail(
+            Operation::ListZones,
+            RetrieveZoneError::Unauthorized,
+            RetrieveZoneError::Custom,
+        )?;
+
+        Ok(state.zones.iter().map(|zone| self.zone(zone)).collect())
+    }
+
+    async fn get_zone(
+        &self,
+        zone_id: &str,
+    ) -> Result<Self::Zone, RetrieveZoneError<Self::CustomRetrieveError>> {
+        let mut state = self.lock();
+        state.fail(
+            Operation::GetZone,
+            RetrieveZoneError::Unauthorized,
+            RetrieveZoneError::Custom,
+        )?;
+
+        state
+            .zone(zone_id)
+            .map(|zone| self.zone(zone))
+            .ok_or(RetrieveZoneError::NotFound)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.lock().limits.capabilities()
+    }
+}
+
+impl CreateZone for MemoryProvider {
+    type CustomCreateError = MemoryError;
+
+    async fn create_zone(
+        &self,
+        domain: &str,
+    ) -> Result<Self::Zone, CreateZoneError<Self::CustomCreateError>> {
+        let domain: &str = &to_ascii_name(domain).ok_or(CreateZoneError::InvalidDomainName)?;
+        let mut state = self.lock();
+        state.fail(
+            Operation::CreateZone,
+            CreateZoneError::Unauthorized,
+            CreateZoneError::Custom,
+        )?;
+
+        let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+        if domain.is_empty() || DomainName::from_dotted(&domain).is_none() {
+            return Err(CreateZoneError::InvalidDomainName);
+        }
+        if state.zones.iter().any(|zone| zone.domain == domain) {
+            return Err(CreateZoneError::Custom(MemoryError::ZoneExists));
+        }
+
+        let zone = ZoneState {
+            id: format!("zone-{}", state.next_id()),
+            domain,
+            records: Vec::new(),
+        };
+        let handle = self.zone(&zone);
+        state.zones.push(zone);
+        Ok(handle)
+    }
+}
+
+impl DeleteZone for MemoryProvider {
+    type CustomDeleteError = MemoryError;
+
+    async fn delete_zone(
+        &self,
+        zone_id: &str,
+    ) -> Result<(), DeleteZoneError<Self::CustomDeleteError>> {
+        let mut state = self.lock();
+        state.fail(
+            Operation::DeleteZone,
+            DeleteZoneError::Unauthorized,
+            DeleteZoneError::Custom,
+        )?;
+
+        let position = state
+            .zones
+            .iter()
+            .position(|zone| zone.id == zone_id)
+            .ok_or(DeleteZoneError::NotFound)?;
+        state.zones.remove(position);
+        Ok(())
+    }
+}
+
+/// Represents a zone of a [`MemoryProvider`].
+///
+/// The zone is a handle to the shared state of the provider. Once the zone is deleted, its record operations fail.
+#[derive(Debug, Clone)]
+pub struct MemoryZone {
+    provider: MemoryProvider,
+    id: String,
+    domain: String,
+}
+
+impl Zone for MemoryZone {
+    type CustomRetrieveError = MemoryError;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    async fn list_records(
+        &self,
+    ) -> Result<Vec<Record>, RetrieveRecordError<Self::CustomRetrieveError>> {
+        let mut state = self.provider.lock();
+        state.fail(
+            Operation::ListRecords,
+            RetrieveRecordError::Unauthorized,
+            RetrieveRecordError::Custom,
+        )?;
+
+        let zone = state.zone(&self.id).ok_or(RetrieveRecordError::NotFound)?;
+        Ok(zone.records.clone())
+    }
+
+    async fn get_record(
+        &self,
+        record_id: &str,
+    ) -> Result<Record, RetrieveRecordError<Self::CustomRetrieveError>> {
+        let mut state = self.provider.lock();
+        state.fail(
+            Operation::GetRecord,
+            RetrieveRecordError::Unauthorized,
+            RetrieveRecordError::Custom,
+        )?;
+
+        state
+            .zone(&self.id)
+            .and_then(|zone| zone.records.iter().find(|record| record.id == record_id))
+            .cloned()
+            .ok_or(RetrieveRecordError::NotFound)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.provider.lock().limits.capabilities()
+    }
+}
+
+impl CreateRecord for MemoryZone {
+    type CustomCreateError = MemoryError;
+
+    async fn create_record(
+        &self,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, CreateRecordError<Self::CustomCreateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(CreateRecordError::InvalidRecord)?;
+        let mut state = self.provider.lock();
+        state.fail(
+            Operation::CreateRecord,
+            CreateRecordError::Unauthorized,
+            CreateRecordError::Custom,
+        )?;
+
+        let State {
+            zones,
+            limits,
+            last_id,
+            ..
+        } = &mut *state;
+        let zone = zones
+            .iter_mut()
+            .find(|zone| zone.id == self.id)
+            .ok_or(CreateRecordError::Custom(MemoryError::ZoneNotFound))?;
+
+        Ok(zone.create(limits, last_id, host, data, ttl)?)
+    }
+}
+
+impl UpdateRecord for MemoryZone {
+    type CustomUpdateError = MemoryError;
+
+    async fn update_record(
+        &self,
+        record_id: &str,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, UpdateRecordError<Self::CustomUpdateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(UpdateRecordError::InvalidRecord)?;
+        let mut state = self.provider.lock();
+        state.fail(
+            Operation::UpdateRecord,
+            UpdateRecordError::Unauthorized,
+            UpdateRecordError::Custom,
+        )?;
+
+        let State { zones, limits, .. } = &mut *state;
+        let zone = zones
+            .iter_mut()
+            .find(|zone| zone.id == self.id)
+            .ok_or(UpdateRecordError::NotFound)?;
+
+        zone.update(limits, record_id, host, data, ttl)
+    }
+}
+
+impl DeleteRecord for MemoryZone {
+    type CustomDeleteError = MemoryError;
+
+    async fn delete_record(
+        &self,
+        record_id: &str,
+    ) -> Result<(), DeleteRecordError<Self::CustomDeleteError>> {
+        let mut state = self.provider.lock();
+        state.fail(
+            Operation::DeleteRecord,
+            DeleteRecordError::Unauthorized,
+            DeleteRecordError::Custom,
+        )?;
+
+        let zone = state
+            .zone_mut(&self.id)
+            .ok_or(DeleteRecordError::NotFound)?;
+        zone.delete(record_id)
+    }
+}
+
+impl ApplyChanges for MemoryZone {
+    type CustomApplyError = MemoryError;
+
+    /// Applies all changes to a copy of the zone, which replaces the zone only if every change succeeded.
+    async fn apply_changes(
+        &self,
+        changes: &[RecordChange],
+    ) -> Result<Vec<Record>, ApplyChangesError<Self::CustomApplyError>> {
+        let failed = |index: Option<usize>, cause: ChangeError<MemoryError>| ApplyChangesError {
+            index,
+            rolled_back: true,
+            cause,
+        };
+
+        let mut state = self.provider.lock();
+        state.fail(
+            Operation::ApplyChanges,
+            failed(None, ChangeError::Unauthorized),
+            |err| failed(None, ChangeError::Custom(err)),
+        )?;
+
+        let State {
+            zones,
+            limits,
+            last_id,
+            ..
+        } = &mut *state;
+        let zone = zones
+            .iter_mut()
+            .find(|zone| zone.id == self.id)
+            .ok_or(failed(None, ChangeError::Custom(MemoryError::ZoneNotFound)))?;
+
+        let mut copy = zone.clone();
+        let mut next_id = *last_id;
+        let mut results = Vec::new();
+        for (index, change) in changes.iter().enumerate() {
+            let outcome: Result<(), ChangeError<MemoryError>> = match change {
+                RecordChange::Create { host, data, ttl } => copy
+                    .create(limits, &mut next_id, host, data, *ttl)
+                    .map(|record| results.push(record))
+                    .map_err(|err| CreateRecordError::from(err).into()),
+                RecordChange::Update {
+                    record_id,
+                    host,
+                    data,
+                    ttl,
+                } => copy
+                    .update(limits, record_id, host, data, *ttl)
+                    .map(|record| results.push(record))
+                    .map_err(ChangeError::from),
+                RecordChange::Delete { record_id } => {
+                    copy.delete(record_id).map_err(ChangeError::from)
+                }
+            };
+            outcome.map_err(|cause| failed(Some(index), cause))?;
+        }
+
+        *zone = copy;
+        *last_id = next_id;
+        Ok(results)
+    }
+}
+
+impl AppendRecords for MemoryZone {}
+
+impl SetRecords for MemoryZone {}
+
+impl DeleteRecords for MemoryZone {}
+
+#[cfg(feature = "ddns")]
+impl DynamicDns for MemoryZone {}
+
+impl MigrationTarget for MemoryZone {}
+
+/// The shared state of a [`MemoryProvider`] and its zones.
+#[derive(Debug, Default)]
+struct State {
+    zones: Vec<ZoneState>,
+    limits: Limits,
+    faults: Vec<InjectedFault>,
+    /// The last ID given to a zone or record, shared so IDs are unique across zones.
+    last_id: u64,
+}
+
+impl State {
+    fn next_id(&mut self) -> u64 {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    fn zone(&self, zone_id: &str) -> Option<&ZoneState> {
+        self.zones.iter().find(|zone| zone.id == zone_id)
+    }
+
+    fn zone_mut(&mut self, zone_id: &str) -> Option<&mut ZoneState> {
+        self.zones.iter_mut().find(|zone| zone.id == zone_id)
+    }
+
+    /// Fails with the error for the fault injected into the operation, consuming the fault unless it is persistent.
+    fn fail<E>(
+        &mut self,
+        operation: Operation,
+        unauthorized: E,
+        custom: impl FnOnce(MemoryError) -> E,
+    ) -> Result<(), E> {
+        let Some(position) = self
+            .faults
+            .iter()
+            .position(|fault| fault.operation == operation)
+        else {
+            return Ok(());
+        };
+
+        let injected = self.faults[position];
+        if !injected.persistent {
+            self.faults.remove(position);
+        }
+        match injected.fault {
+            Fault::Unauthorized => Err(unauthorized),
+            Fault::Unavailable => Err(custom(MemoryError::Unavailable)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct InjectedFault {
+    operation: Operation,
+    fault: Fault,
+    persistent: bool,
+}
+
+/// The records accepted by the provider.
+#[derive(Debug)]
+struct Limits {
+    /// The uppercase record types that can be created, or `None` for all types.
+    supported_types: Option<Vec<String>>,
+    ttl_range: RangeInclusive<u64>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            supported_types: None,
+            ttl_range: 0..=MAX_TTL as u64,
+        }
+    }
+}
+
+impl Limits {
+    fn supports_type(&self, typ: &str) -> bool {
+        self.supported_types
+            .as_ref()
+            .is_none_or(|types| types.iter().any(|t| t.eq_ignore_ascii_case(typ)))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            create_zone: true,
+            delete_zone: true,
+            create_record: true,
+            update_record: true,
+            delete_record: true,
+            atomic_update: true,
+            record_types: self.supported_types.clone(),
+            ttl_range: self.ttl_range.clone(),
+            ..Capabilities::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ZoneState {
+    id: String,
+    domain: String,
+    records: Vec<Record>,
+}
+
+impl ZoneState {
+    fn create(
+        &mut self,
+        limits: &Limits,
+        last_id: &mut u64,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, Rejection> {
+        let host = self.check(limits, None, host, data, ttl)?;
+
+        *last_id += 1;
+        let record = Record {
+            id: format!("record-{}", last_id),
+            host,
+            data: data.clone(),
+            ttl,
+        };
+        self.records.push(record.clone());
+        Ok(record)
+    }
+
+    /// Replaces the record, keeping its ID.
+    fn update(
+        &mut self,
+        limits: &Limits,
+        record_id: &str,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, UpdateRecordError<MemoryError>> {
+        let position = self
+            .records
+            .iter()
+            .position(|record| record.id == record_id)
+            .ok_or(UpdateRecordError::NotFound)?;
+        let host = self.check(limits, Some(record_id), host, data, ttl)?;
+
+        let record = Record {
+            id: record_id.to_string(),
+            host,
+            data: data.clone(),
+            ttl,
+        };
+        self.records[position] = record.clone();
+        Ok(record)
+    }
+
+    fn delete(&mut self, record_id: &str) -> Result<(), DeleteRecordError<MemoryError>> {
+        let position = self
+            .records
+            .iter()
+            .position(|record| record.id == record_id)
+            .ok_or(DeleteRecordError::NotFound)?;
+        self.records.remove(position);
+        Ok(())
+    }
+
+    /// Validates a record against the limits and the other records of the zone, returning its normalized host.
+    ///
+    /// The record with the ID `replacing` is ignored, as it is about to be replaced.
+    fn check(
+        &self,
+        limits: &Limits,
+        replacing: Option<&str>,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<String, Rejection> {
+        if !limits.supports_type(data.get_type()) {
+            return Err(Rejection::UnsupportedType);
+        }
+        if !limits.ttl_range.contains(&ttl) || !valid_data(data) {
+            return Err(Rejection::InvalidRecord);
+        }
+
+        let host = normalize_host(host, &self.domain);
+        let name = match host.as_str() {
+            "@" => self.domain.clone(),
+            relative => format!("{}.{}", relative, self.domain),
+        };
+        if DomainName::from_dotted(&name).is_none() {
+            return Err(Rejection::InvalidRecord);
+        }
+
+        let is_cname = |data: &RecordData| data.get_type().eq_ignore_ascii_case("CNAME");
+        let conflict = self
+            .records
+            .iter()
+            .filter(|record| Some(record.id.as_str()) != replacing && record.host == host)
+            .any(|record| {
+                same_value(&record.data, data) || is_cname(&record.data) || is_cname(data)
+            });
+        if conflict {
+            return Err(Rejection::InvalidRecord);
+        }
+
+        Ok(host)
+    }
+}
+
+/// Returns whether the names within the data are valid domain names and other values are not empty.
+fn valid_data(data: &RecordData) -> bool {
+    let valid_name = |name: &str| !name.is_empty() && DomainName::from_dotted(name).is_some();
+    let valid_target = |name: &str| name == "." || valid_name(name);
+    let valid_hex = |hex: &str| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit());
+
+    match data {
+        RecordData::A(_) | RecordData::AAAA(_) | RecordData::TXT(_) => true,
+        RecordData::CNAME(target) | RecordData::NS(target) | RecordData::PTR(target) => {
+            valid_name(target)
+        }
+        RecordData::MX { mail_server, .. } => valid_name(mail_server),
+        RecordData::SRV { target, .. } => valid_name(target),
+        RecordData::SOA { mname, rname, .. } => valid_name(mname) && valid_name(rname),
+        RecordData::CAA { tag, .. } => {
+            !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric())
+        }
+        RecordData::TLSA { certificate, .. } => valid_hex(certificate),
+        RecordData::SSHFP { fingerprint, .. } => valid_hex(fingerprint),
+        RecordData::DS { digest, .. } => valid_hex(digest),
+        RecordData::HTTPS { target, .. } | RecordData::SVCB { target, .. } => valid_target(target),
+        RecordData::NAPTR { replacement, .. } => valid_target(replacement),
+        RecordData::Other { typ, value } => !typ.is_empty() && !value.is_empty(),
+    }
+}
+
+/// The reason a record was rejected.
+enum Rejection {
+    UnsupportedType,
+    InvalidRecord,
+}
+
+impl From<Rejection> for CreateRecordError<MemoryError> {
+    fn from(rejection: Rejection) -> Self {
+        match rejection {
+            Rejection::UnsupportedType => CreateRecordError::UnsupportedType,
+            Rejection::InvalidRecord => CreateRecordError::InvalidRecord,
+        }
+    }
+}
+
+impl From<Rejection> for UpdateRecordError<MemoryError> {
+    fn from(rejection: Rejection) -> Self {
+        match rejection {
+            Rejection::UnsupportedType => UpdateRecordError::UnsupportedType,
+            Rejection::InvalidRecord => UpdateRecordError::InvalidRecord,
+        }
+    }
+}
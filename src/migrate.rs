@@ -0,0 +1,207 @@
+//! Copying records between zones of different providers.
+//!
+//! [`migrate_zone`] reads every record of a source [`Zone`] and creates it in a target zone implementing [`MigrationTarget`].
+//! Along the way, records are adapted to the conventions and limits of the target:
+//!
+//! - Hosts are translated to the relative form (`@` for the apex) accepted by every provider, as some providers report fully qualified hosts.
+//! - TTLs are clamped to the range accepted by the target.
+//! - Records of types the target does not support are skipped, as are the SOA and NS records at the apex, which are managed by the target provider itself.
+//!
+//! The returned [`MigrationReport`] lists the outcome for every source record.
+//!
+//! # Example
+//!
+//! ```
+//! use libdns::migrate::{migrate_zone, MigrationOutcome, MigrationTarget};
+//! use libdns::Zone;
+//!
+//! async fn migrate<S: Zone, T: MigrationTarget>(source: &S, target: &T)
+//! where
+//!     T::CustomCreateError: std::fmt::Display,
+//! {
+//!     let Ok(report) = migrate_zone(source, target).await else {
+//!         return;
+//!     };
+//!     for entry in &report.entries {
+//!         if let MigrationOutcome::Skipped(reason) = &entry.outcome {
+//!             println!("skipped {} {}: {}", entry.source.host, entry.source.data.get_type(), reason);
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::ops::RangeInclusive;
+
+use thiserror::Error;
+
+use crate::{
+    normalize_host, ClassifyError, CreateRecord, CreateRecordError, ErrorKind, Record,
+    RetrieveRecordError, Zone,
+};
+
+/// A zone records can be migrated to using [`migrate_zone`].
+///
+/// Describes the limits of the target provider, so records can be adapted before they are created.
+/// By default, they are taken from [`Zone::capabilities`].
+pub trait MigrationTarget: CreateRecord {
+    /// Returns whether records of the given type can be created in this zone.
+    fn supports_type(&self, typ: &str) -> bool {
+        self.capabilities().supports_type(typ)
+    }
+
+    /// Returns the range of TTLs (in seconds) accepted by the provider.
+    fn ttl_range(&self) -> RangeInclusive<u64> {
+        self.capabilities().ttl_range
+    }
+}
+
+/// The result of a [`migrate_zone`] run.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MigrationReport<C> {
+    /// The outcome for every record of the source zone, in the order they were listed.
+    pub entries: Vec<MigrationEntry<C>>,
+}
+
+impl<C> MigrationReport<C> {
+    /// Returns the number of records that were created in the target zone, with or without adjustments.
+    pub fn copied(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| matches!(e.outcome, MigrationOutcome::Copied { .. }))
+            .count()
+    }
+
+    /// Returns the number of records that were not created in the target zone.
+    pub fn skipped(&self) -> usize {
+        self.entries.len() - self.copied()
+    }
+}
+
+/// The outcome of migrating a single record.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MigrationEntry<C> {
+    /// The record as listed by the source zone.
+    pub source: Record,
+    /// What happened to the record.
+    pub outcome: MigrationOutcome<C>,
+}
+
+/// Represents what happened to a single record during a migration.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MigrationOutcome<C> {
+    /// The record was created in the target zone.
+    Copied {
+        /// The record as returned by the target zone.
+        record: Record,
+        /// The changes made to the record to fit the target, empty if it was copied verbatim.
+        adjustments: Vec<Adjustment>,
+    },
+
+    /// The record was not created in the target zone.
+    Skipped(SkipReason<C>),
+}
+
+/// A change made to a record to fit the target zone.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum Adjustment {
+    /// The host was translated to the relative form.
+    Host { from: String, to: String },
+
+    /// The TTL was clamped to the range accepted by the target.
+    Ttl { from: u64, to: u64 },
+}
+
+/// Represents the reason a record was not migrated.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+pub enum SkipReason<C> {
+    /// Indicates an SOA or NS record at the apex, which is managed by the target provider.
+    #[error("the record is managed by the target provider")]
+    ApexAuthority,
+
+    /// Indicates that the target provider does not support the record type.
+    #[error("the target does not support the record type")]
+    UnsupportedType,
+
+    /// Indicates that the target zone rejected the record.
+    #[error(transparent)]
+    Rejected(CreateRecordError<C>),
+}
+
+impl<C: ClassifyError> ClassifyError for SkipReason<C> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            SkipReason::ApexAuthority => ErrorKind::Other,
+            SkipReason::UnsupportedType => ErrorKind::Validation,
+            SkipReason::Rejected(e) => e.kind(),
+        }
+    }
+}
+
+/// Copies every record of the `source` zone into the `target` zone.
+///
+/// Failing to create a single record does not abort the migration; the error is reported as [`SkipReason::Rejected`] instead.
+/// Records already present in the target are not detected, so running a migration twice may create duplicates or be rejected by the target.
+pub async fn migrate_zone<S, T>(
+    source: &S,
+    target: &T,
+) -> Result<MigrationReport<T::CustomCreateError>, RetrieveRecordError<S::CustomRetrieveError>>
+where
+    S: Zone,
+    T: MigrationTarget,
+{
+    let records = source.list_records().await?;
+    let ttl_range = target.ttl_range();
+
+    let mut entries = Vec::with_capacity(records.len());
+    for record in records {
+        let typ = record.data.get_type();
+        let host = normalize_host(&record.host, source.domain());
+
+        let skipped = if host == "@" && (typ == "SOA" || typ == "NS") {
+            Some(SkipReason::ApexAuthority)
+        } else if !target.supports_type(typ) {
+            Some(SkipReason::UnsupportedType)
+        } else {
+            None
+        };
+        if let Some(reason) = skipped {
+            entries.push(MigrationEntry {
+                source: record,
+                outcome: MigrationOutcome::Skipped(reason),
+            });
+            continue;
+        }
+
+        let mut adjustments = Vec::new();
+        if host != record.host {
+            adjustments.push(Adjustment::Host {
+                from: record.host.clone(),
+                to: host.clone(),
+            });
+        }
+        let ttl = record.ttl.clamp(*ttl_range.start(), *ttl_range.end());
+        if ttl != record.ttl {
+            adjustments.push(Adjustment::Ttl {
+                from: record.ttl,
+                to: ttl,
+            });
+        }
+
+        let outcome = match target.create_record(&host, &record.data, ttl).await {
+            Ok(created) => MigrationOutcome::Copied {
+                record: created,
+                adjustments,
+            },
+            Err(CreateRecordError::UnsupportedType) => {
+                MigrationOutcome::Skipped(SkipReason::UnsupportedType)
+            }
+            Err(err) => MigrationOutcome::Skipped(SkipReason::Rejected(err)),
+        };
+        entries.push(MigrationEntry {
+            source: record,
+            outcome,
+        });
+    }
+
+    Ok(MigrationReport { entries })
+}
@@ -6,11 +6,15 @@
 use std::error::Error;
 use std::fmt;
 
+use quick_xml::escape::unescape;
+use quick_xml::events::attributes::Attribute;
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use reqwest::Client as HttpClient;
 
+use crate::retry::{RequestBuilderExt, RetryPolicy};
 use crate::types::Environment;
+use crate::{ClassifyError, ErrorKind};
 
 /// Namecheap API endpoints.
 const PRODUCTION_API_URL: &str = "https://api.namecheap.com/xml.response";
@@ -70,6 +74,24 @@ impl Error for NamecheapError {
     }
 }
 
+impl ClassifyError for NamecheapError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            NamecheapError::Request(e) => e.kind(),
+            NamecheapError::Api(e) => match e.code.as_str() {
+                // 1011150 = Request IP is not whitelisted
+                "1011150" => ErrorKind::Unauthorized,
+                // 500000 = Too many requests
+                "500000" => ErrorKind::RateLimited,
+                _ => ErrorKind::Other,
+            },
+            NamecheapError::Parse(_) => ErrorKind::Other,
+            NamecheapError::DomainNotFound => ErrorKind::NotFound,
+            NamecheapError::Unauthorized => ErrorKind::Unauthorized,
+        }
+    }
+}
+
 impl From<reqwest::Error> for NamecheapError {
     fn from(err: reqwest::Error) -> Self {
         NamecheapError::Request(err)
@@ -165,19 +187,57 @@ impl ClientConfig {
 pub struct Client {
     http_client: HttpClient,
     config: ClientConfig,
+    base_url: String,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
     /// Creates a new Namecheap API client.
     pub fn new(config: ClientConfig) -> Result<Self, Box<dyn Error + Send + Sync>> {
-        let http_client = HttpClient::builder()
-            .user_agent("libdns-rs/0.1.0")
-            .build()?;
+        let base_url = config.api_url();
+        Self::with_base_url(config, base_url)
+    }
 
-        Ok(Self {
+    /// Creates a new Namecheap API client with a custom base URL instead of the one of the configured environment.
+    ///
+    /// This is primarily useful for testing with mock servers.
+    pub fn with_base_url(
+        config: ClientConfig,
+        base_url: &str,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let http_client = Self::http_client_builder().build()?;
+        Ok(Self::from_http_client(http_client, config, base_url))
+    }
+
+    /// Returns the builder of the HTTP client used unless a preconfigured one is given.
+    pub(crate) fn http_client_builder() -> reqwest::ClientBuilder {
+        HttpClient::builder().user_agent("libdns-rs/0.1.0")
+    }
+
+    /// Creates a new Namecheap API client sending its requests using the given HTTP client.
+    pub(crate) fn from_http_client(
+        http_client: HttpClient,
+        config: ClientConfig,
+        base_url: &str,
+    ) -> Self {
+        Self {
             http_client,
             config,
-        })
+            base_url: base_url.to_string(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Replaces the HTTP client, e.g. with one using custom timeouts, proxies or root certificates.
+    pub fn with_http_client(mut self, http_client: HttpClient) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
     }
 
     /// Returns the configured environment.
@@ -230,9 +290,14 @@ impl Client {
 
         // Build URL with query string
         let query_string = Self::build_query_string(&query_params);
-        let url = format!("{}?{}", self.config.api_url(), query_string);
+        let url = format!("{}?{}", self.base_url, query_string);
 
-        let response = self.http_client.get(&url).send().await?;
+        // Every command either reads or replaces all host records, so requests are idempotent
+        let response = self
+            .http_client
+            .get(&url)
+            .send_with_retry(&self.retry_policy, true)
+            .await?;
 
         let text = response.text().await?;
 
@@ -258,10 +323,8 @@ impl Client {
                     if name.as_ref() == b"ApiResponse" {
                         // Check Status attribute
                         for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"Status" {
-                                if attr.value.as_ref() == b"ERROR" {
-                                    is_error_status = true;
-                                }
+                            if attr.key.as_ref() == b"Status" && attr.value.as_ref() == b"ERROR" {
+                                is_error_status = true;
                             }
                         }
                     } else if name.as_ref() == b"Error" {
@@ -276,7 +339,16 @@ impl Client {
                     }
                 }
                 Ok(Event::Text(ref e)) if in_error => {
-                    error_message = Some(String::from_utf8_lossy(e.as_ref()).into_owned());
+                    error_message
+                        .get_or_insert_with(String::new)
+                        .push_str(&String::from_utf8_lossy(e.as_ref()));
+                }
+                // Entities such as `&amp;` split the message into several events
+                Ok(Event::GeneralRef(ref e)) if in_error => {
+                    let message = error_message.get_or_insert_with(String::new);
+                    message.push('&');
+                    message.push_str(&String::from_utf8_lossy(e.as_ref()));
+                    message.push(';');
                 }
                 Ok(Event::End(ref e)) if e.local_name().as_ref() == b"Error" => {
                     in_error = false;
@@ -302,7 +374,8 @@ impl Client {
                     _ => {}
                 }
 
-                return Err(NamecheapError::Api(ApiError { code, message: msg }));
+                let message = unescape(&msg).map(|m| m.into_owned()).unwrap_or(msg);
+                return Err(NamecheapError::Api(ApiError { code, message }));
             }
 
             return Err(NamecheapError::Parse(
@@ -439,7 +512,7 @@ pub fn parse_host_records(xml: &str) -> Result<Vec<HostRecord>, NamecheapError>
                     let mut mx_pref: Option<u16> = None;
 
                     for attr in e.attributes().flatten() {
-                        let value = String::from_utf8_lossy(&attr.value).into_owned();
+                        let value = attr_value(&attr);
                         match attr.key.as_ref() {
                             b"HostId" => host_id = value,
                             b"Name" => name = value,
@@ -486,15 +559,15 @@ pub fn get_element_attr(
 
     loop {
         match reader.read_event() {
-            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
-                if e.local_name().as_ref() == tag_bytes {
-                    for a in e.attributes().flatten() {
-                        if a.key.as_ref() == attr_bytes {
-                            return Ok(Some(String::from_utf8_lossy(&a.value).into_owned()));
-                        }
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if e.local_name().as_ref() == tag_bytes =>
+            {
+                for a in e.attributes().flatten() {
+                    if a.key.as_ref() == attr_bytes {
+                        return Ok(Some(attr_value(&a)));
                     }
-                    return Ok(None);
                 }
+                return Ok(None);
             }
             Ok(Event::Eof) => break,
             Err(e) => {
@@ -506,3 +579,12 @@ pub fn get_element_attr(
 
     Ok(None)
 }
+
+/// Returns the unescaped value of an XML attribute, e.g. `"` for `&quot;`.
+///
+/// Values with malformed escapes are returned as they are.
+fn attr_value(attr: &Attribute) -> String {
+    attr.unescape_value()
+        .map(|value| value.into_owned())
+        .unwrap_or_else(|_| String::from_utf8_lossy(&attr.value).into_owned())
+}
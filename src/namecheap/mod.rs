@@ -8,6 +8,8 @@
 //!   before API calls will work.
 //! - **Destructive Updates**: The `setHosts` API replaces ALL records. This provider handles
 //!   this by fetching existing records before modifications.
+//! - **Atomic Changesets**: [`ApplyChanges`] applies all changes to the
+//!   fetched host list and saves it with a single `setHosts` call.
 //! - **Zone ID Format**: Use the domain name as the zone ID (e.g., "example.com").
 //!
 //! # Environments
@@ -52,7 +54,7 @@
 //! - A (IPv4 address)
 //! - AAAA (IPv6 address)
 //! - CNAME (Canonical name)
-//! - MX (Mail exchange)
+//! - MX, MXE (Mail exchange)
 //! - NS (Name server)
 //! - TXT (Text record)
 //! - URL, URL301 (URL redirects)
@@ -68,17 +70,32 @@ pub mod api;
 
 use std::error::Error as StdErr;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub use api::{
     get_element_attr, parse_host_records, ApiError, Client, ClientConfig, HostRecord,
     NamecheapError,
 };
 
+#[cfg(feature = "ddns")]
+use crate::ddns::DynamicDns;
+use crate::dynamic::{DynProvider, DynProviderAdapter};
+use crate::http::HttpOptions;
+use crate::migrate::MigrationTarget;
+use crate::retry::RetryPolicy;
+use crate::types::to_ascii_name;
 use crate::{
-    CreateRecord, CreateRecordError, DeleteRecord, DeleteRecordError, Provider, Record, RecordData,
-    RetrieveRecordError, RetrieveZoneError, Zone,
+    normalize_host, same_content, AppendRecords, ApplyChanges, ApplyChangesError, Capabilities,
+    ChangeError, CreateRecord, CreateRecordError, DeleteRecord, DeleteRecordError, DeleteRecords,
+    DeleteRecordsError, Provider, Record, RecordChange, RecordData, RetrieveRecordError,
+    RetrieveZoneError, SetRecords, SetRecordsError, UpdateRecord, UpdateRecordError, Zone,
 };
 
+/// Supported record types for Namecheap (`setHosts` rejects all others).
+const SUPPORTED_RECORD_TYPES: &[&str] = &[
+    "A", "AAAA", "ALIAS", "CAA", "CNAME", "MX", "MXE", "NS", "TXT", "URL", "URL301", "FRAME",
+];
+
 /// Namecheap DNS provider.
 ///
 /// Manages DNS records through the Namecheap API.
@@ -222,13 +239,120 @@ impl NamecheapProvider {
     /// let provider = NamecheapProvider::new(config).unwrap();
     /// ```
     pub fn new(config: ClientConfig) -> Result<Self, Box<dyn StdErr + Send + Sync>> {
-        let api_client = Client::new(config)?;
-        Ok(Self {
+        Self::builder(config).build()
+    }
+
+    /// Returns a builder to configure the HTTP client, API base URL and retry policy of the provider.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use libdns::namecheap::{ClientConfig, NamecheapProvider};
+    ///
+    /// let config = ClientConfig::production("username", "api_key", "1.2.3.4");
+    /// let provider = NamecheapProvider::builder(config)
+    ///     .with_timeout(Duration::from_secs(30))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(config: ClientConfig) -> NamecheapProviderBuilder {
+        NamecheapProviderBuilder {
+            base_url: config.api_url().to_string(),
+            config,
+            http: HttpOptions::default(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        let api_client = self.api_client.as_ref().clone().with_retry_policy(policy);
+        Self {
+            api_client: Arc::new(api_client),
+        }
+    }
+}
+
+/// Builder for a [`NamecheapProvider`], created using [`NamecheapProvider::builder`].
+#[derive(Clone)]
+pub struct NamecheapProviderBuilder {
+    config: ClientConfig,
+    base_url: String,
+    http: HttpOptions,
+    retry_policy: RetryPolicy,
+}
+
+impl NamecheapProviderBuilder {
+    /// Sets a custom API base URL instead of the one of the configured environment, e.g. of a mock server.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Sends all requests using the given HTTP client, e.g. one with custom root certificates or connection pool limits.
+    ///
+    /// The timeout, proxy and user agent set on the builder are ignored in that case.
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http.client = Some(client);
+        self
+    }
+
+    /// Sets the timeout of every request, unlimited by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends all requests through the given proxy.
+    pub fn with_proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.http.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets the `User-Agent` header of every request.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.http.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Creates the provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn build(self) -> Result<NamecheapProvider, Box<dyn StdErr + Send + Sync>> {
+        let http_client = self.http.build(Client::http_client_builder())?;
+        let api_client = Client::from_http_client(http_client, self.config, &self.base_url)
+            .with_retry_policy(self.retry_policy);
+        Ok(NamecheapProvider {
             api_client: Arc::new(api_client),
         })
     }
 }
 
+impl From<NamecheapProvider> for Box<dyn DynProvider> {
+    fn from(provider: NamecheapProvider) -> Self {
+        Box::new(
+            DynProviderAdapter::new(provider)
+                .with_create_record()
+                .with_update_record()
+                .with_delete_record(),
+        )
+    }
+}
+
 impl Provider for NamecheapProvider {
     type Zone = NamecheapZone;
     type CustomRetrieveError = NamecheapError;
@@ -237,6 +361,7 @@ impl Provider for NamecheapProvider {
         &self,
         zone_id: &str,
     ) -> Result<Self::Zone, RetrieveZoneError<Self::CustomRetrieveError>> {
+        let zone_id: &str = &to_ascii_name(zone_id).ok_or(RetrieveZoneError::NotFound)?;
         let (sld, tld) = split_domain(zone_id).ok_or_else(|| {
             RetrieveZoneError::Custom(NamecheapError::Parse(format!(
                 "Invalid domain format: {}",
@@ -270,6 +395,10 @@ impl Provider for NamecheapProvider {
         // For now, return an empty list as this would require additional API implementation.
         Ok(vec![])
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl Zone for NamecheapZone {
@@ -314,6 +443,10 @@ impl Zone for NamecheapZone {
             .map(|hr| host_record_to_record(hr, &self.domain))
             .ok_or(RetrieveRecordError::NotFound)
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl CreateRecord for NamecheapZone {
@@ -325,6 +458,11 @@ impl CreateRecord for NamecheapZone {
         data: &RecordData,
         ttl: u64,
     ) -> Result<Record, CreateRecordError<Self::CustomCreateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(CreateRecordError::InvalidRecord)?;
+        if !SUPPORTED_RECORD_TYPES.contains(&data.get_type()) {
+            return Err(CreateRecordError::UnsupportedType);
+        }
+
         // Fetch existing records
         let mut records = self
             .fetch_records()
@@ -332,20 +470,7 @@ impl CreateRecord for NamecheapZone {
             .map_err(CreateRecordError::Custom)?;
 
         // Create new record
-        let new_record = HostRecord {
-            host_id: String::new(), // Will be assigned by Namecheap
-            name: host.to_string(),
-            record_type: data.get_type().to_string(),
-            address: data.get_api_value(),
-            mx_pref: if let RecordData::MX { priority, .. } = data {
-                Some(*priority)
-            } else {
-                None
-            },
-            ttl: ttl.clamp(60, 60000), // Namecheap TTL range
-        };
-
-        records.push(new_record);
+        records.push(new_host_record(host, data, ttl));
 
         // Save all records (Namecheap replaces all)
         self.save_records(&records).await.map_err(|e| match e {
@@ -383,12 +508,11 @@ impl CreateRecord for NamecheapZone {
 
         updated
             .into_iter()
-            .filter(|r| {
+            .rfind(|r| {
                 r.name == host
                     && r.record_type == expected_type
                     && r.address.trim_end_matches('.').to_lowercase() == expected_address
             })
-            .last()
             .map(|hr| host_record_to_record(hr, &self.domain))
             .ok_or_else(|| {
                 CreateRecordError::Custom(NamecheapError::Parse(
@@ -398,6 +522,64 @@ impl CreateRecord for NamecheapZone {
     }
 }
 
+impl UpdateRecord for NamecheapZone {
+    type CustomUpdateError = NamecheapError;
+
+    async fn update_record(
+        &self,
+        record_id: &str,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, UpdateRecordError<Self::CustomUpdateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(UpdateRecordError::InvalidRecord)?;
+        // Fetch existing records
+        let mut records = self.fetch_records().await.map_err(|e| match e {
+            NamecheapError::Unauthorized => UpdateRecordError::Unauthorized,
+            other => UpdateRecordError::Custom(other),
+        })?;
+
+        // Replace the record in place so the whole change is a single setHosts call
+        let existing = records
+            .iter_mut()
+            .find(|r| r.host_id == record_id)
+            .ok_or(UpdateRecordError::NotFound)?;
+        *existing = HostRecord {
+            host_id: existing.host_id.clone(),
+            ..new_host_record(host, data, ttl)
+        };
+
+        // Save all records (Namecheap replaces all)
+        self.save_records(&records).await.map_err(|e| match e {
+            NamecheapError::Unauthorized => UpdateRecordError::Unauthorized,
+            other => UpdateRecordError::Custom(other),
+        })?;
+
+        // Host IDs are reassigned on every setHosts call, so look the record up again
+        let updated = self
+            .fetch_records()
+            .await
+            .map_err(UpdateRecordError::Custom)?;
+
+        let expected_address = data.get_api_value().trim_end_matches('.').to_lowercase();
+        let expected_type = data.get_type();
+
+        updated
+            .into_iter()
+            .rfind(|r| {
+                r.name == host
+                    && r.record_type == expected_type
+                    && r.address.trim_end_matches('.').to_lowercase() == expected_address
+            })
+            .map(|hr| host_record_to_record(hr, &self.domain))
+            .ok_or_else(|| {
+                UpdateRecordError::Custom(NamecheapError::Parse(
+                    "Failed to find updated record".to_string(),
+                ))
+            })
+    }
+}
+
 impl DeleteRecord for NamecheapZone {
     type CustomDeleteError = NamecheapError;
 
@@ -437,6 +619,290 @@ impl DeleteRecord for NamecheapZone {
     }
 }
 
+impl ApplyChanges for NamecheapZone {
+    type CustomApplyError = NamecheapError;
+
+    async fn apply_changes(
+        &self,
+        changes: &[RecordChange],
+    ) -> Result<Vec<Record>, ApplyChangesError<Self::CustomApplyError>> {
+        // Nothing is written before the final setHosts call, so every early failure leaves the zone untouched
+        let fail = |index: Option<usize>, cause: ChangeError<NamecheapError>| ApplyChangesError {
+            index,
+            rolled_back: true,
+            cause,
+        };
+
+        let mut host_records = self.fetch_records().await.map_err(|e| match e {
+            NamecheapError::Unauthorized => fail(None, ChangeError::Unauthorized),
+            other => fail(None, ChangeError::Custom(other)),
+        })?;
+
+        // Apply all changes to the host list in memory
+        let mut expected = Vec::new();
+        for (index, change) in changes.iter().enumerate() {
+            match change {
+                RecordChange::Create { host, data, ttl } => {
+                    let name = normalize_host(host, &self.domain);
+                    host_records.push(new_host_record(&name, data, *ttl));
+                    expected.push((name, data));
+                }
+                RecordChange::Update {
+                    record_id,
+                    host,
+                    data,
+                    ttl,
+                } => {
+                    let existing = host_records
+                        .iter_mut()
+                        .find(|r| r.host_id == *record_id)
+                        .ok_or_else(|| fail(Some(index), ChangeError::NotFound))?;
+                    let name = normalize_host(host, &self.domain);
+                    *existing = HostRecord {
+                        host_id: existing.host_id.clone(),
+                        ..new_host_record(&name, data, *ttl)
+                    };
+                    expected.push((name, data));
+                }
+                RecordChange::Delete { record_id } => {
+                    let position = host_records
+                        .iter()
+                        .position(|r| r.host_id == *record_id)
+                        .ok_or_else(|| fail(Some(index), ChangeError::NotFound))?;
+                    host_records.remove(position);
+                }
+            }
+        }
+
+        // Save all records at once (Namecheap replaces all)
+        self.save_records(&host_records)
+            .await
+            .map_err(|e| match e {
+                NamecheapError::Unauthorized => fail(None, ChangeError::Unauthorized),
+                // Without a readable response, the records may have been saved anyway
+                NamecheapError::Request(_) | NamecheapError::Parse(_) => ApplyChangesError {
+                    index: None,
+                    rolled_back: false,
+                    cause: ChangeError::Custom(e),
+                },
+                other => fail(None, ChangeError::Custom(other)),
+            })?;
+
+        // Host IDs are reassigned on save, so look the resulting records up again
+        let lookup_failed = |cause| ApplyChangesError {
+            index: None,
+            rolled_back: false,
+            cause: ChangeError::Custom(cause),
+        };
+        let updated: Vec<Record> = self
+            .fetch_records()
+            .await
+            .map_err(lookup_failed)?
+            .into_iter()
+            .map(|hr| host_record_to_record(hr, &self.domain))
+            .collect();
+
+        expected
+            .into_iter()
+            .map(|(name, data)| {
+                updated
+                    .iter()
+                    .rfind(|r| same_content(r, &name, data, &self.domain))
+                    .cloned()
+                    .ok_or_else(|| {
+                        lookup_failed(NamecheapError::Parse(
+                            "Failed to find changed record".to_string(),
+                        ))
+                    })
+            })
+            .collect()
+    }
+}
+
+impl AppendRecords for NamecheapZone {
+    async fn append_records(
+        &self,
+        records: &[Record],
+    ) -> Result<Vec<Record>, CreateRecordError<Self::CustomCreateError>> {
+        let mut host_records = self.fetch_records().await.map_err(|e| match e {
+            NamecheapError::Unauthorized => CreateRecordError::Unauthorized,
+            other => CreateRecordError::Custom(other),
+        })?;
+
+        host_records.extend(
+            records
+                .iter()
+                .map(|r| new_host_record(&normalize_host(&r.host, &self.domain), &r.data, r.ttl)),
+        );
+
+        // Save all records at once (Namecheap replaces all)
+        self.save_records(&host_records)
+            .await
+            .map_err(|e| match e {
+                NamecheapError::Unauthorized => CreateRecordError::Unauthorized,
+                other => CreateRecordError::Custom(other),
+            })?;
+
+        // Host IDs are assigned on save, so look the appended records up again
+        let updated = self
+            .fetch_records()
+            .await
+            .map_err(CreateRecordError::Custom)?;
+        let updated: Vec<Record> = updated
+            .into_iter()
+            .map(|hr| host_record_to_record(hr, &self.domain))
+            .collect();
+
+        records
+            .iter()
+            .map(|wanted| {
+                updated
+                    .iter()
+                    .rfind(|r| same_content(r, &wanted.host, &wanted.data, &self.domain))
+                    .cloned()
+                    .ok_or_else(|| {
+                        CreateRecordError::Custom(NamecheapError::Parse(
+                            "Failed to find created record".to_string(),
+                        ))
+                    })
+            })
+            .collect()
+    }
+}
+
+impl SetRecords for NamecheapZone {
+    async fn set_records(
+        &self,
+        records: &[Record],
+    ) -> Result<Vec<Record>, SetRecordsError<NamecheapError, NamecheapError, NamecheapError>> {
+        let host_records = self.fetch_records().await.map_err(|e| match e {
+            NamecheapError::Unauthorized => {
+                SetRecordsError::Retrieve(RetrieveRecordError::Unauthorized)
+            }
+            other => SetRecordsError::Retrieve(RetrieveRecordError::Custom(other)),
+        })?;
+
+        // Host names and types covered by the input
+        let covered: Vec<(String, &str)> = records
+            .iter()
+            .map(|r| (normalize_host(&r.host, &self.domain), r.data.get_type()))
+            .collect();
+        let is_covered = |name: &str, typ: &str| {
+            covered
+                .iter()
+                .any(|(host, t)| host == &normalize_host(name, &self.domain) && *t == typ)
+        };
+
+        // Keep everything outside of the covered sets and write the input for the rest
+        let mut replaced: Vec<HostRecord> = host_records
+            .into_iter()
+            .filter(|hr| !is_covered(&hr.name, &hr.record_type))
+            .collect();
+        replaced.extend(
+            records
+                .iter()
+                .map(|r| new_host_record(&normalize_host(&r.host, &self.domain), &r.data, r.ttl)),
+        );
+
+        // A single setHosts call replaces all sets at once
+        self.save_records(&replaced).await.map_err(|e| match e {
+            NamecheapError::Unauthorized => {
+                SetRecordsError::Create(CreateRecordError::Unauthorized)
+            }
+            other => SetRecordsError::Create(CreateRecordError::Custom(other)),
+        })?;
+
+        let updated = self
+            .fetch_records()
+            .await
+            .map_err(|e| SetRecordsError::Retrieve(RetrieveRecordError::Custom(e)))?;
+
+        Ok(updated
+            .into_iter()
+            .filter(|hr| is_covered(&hr.name, &hr.record_type))
+            .map(|hr| host_record_to_record(hr, &self.domain))
+            .collect())
+    }
+}
+
+impl DeleteRecords for NamecheapZone {
+    async fn delete_records(
+        &self,
+        records: &[Record],
+    ) -> Result<Vec<Record>, DeleteRecordsError<NamecheapError, NamecheapError>> {
+        let host_records = self.fetch_records().await.map_err(|e| match e {
+            NamecheapError::Unauthorized => {
+                DeleteRecordsError::Retrieve(RetrieveRecordError::Unauthorized)
+            }
+            other => DeleteRecordsError::Retrieve(RetrieveRecordError::Custom(other)),
+        })?;
+
+        let (deleted, remaining): (Vec<HostRecord>, Vec<HostRecord>) =
+            host_records.into_iter().partition(|hr| {
+                let record = host_record_to_record(hr.clone(), &self.domain);
+                records
+                    .iter()
+                    .any(|r| same_content(&record, &r.host, &r.data, &self.domain))
+            });
+
+        if deleted.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Save remaining records
+        self.save_records(&remaining).await.map_err(|e| match e {
+            NamecheapError::Unauthorized => {
+                DeleteRecordsError::Delete(DeleteRecordError::Unauthorized)
+            }
+            other => DeleteRecordsError::Delete(DeleteRecordError::Custom(other)),
+        })?;
+
+        Ok(deleted
+            .into_iter()
+            .map(|hr| host_record_to_record(hr, &self.domain))
+            .collect())
+    }
+}
+
+#[cfg(feature = "ddns")]
+impl DynamicDns for NamecheapZone {}
+
+/// Describes what the provider and all of its zones support.
+fn capabilities() -> Capabilities {
+    Capabilities {
+        // Zones can only be retrieved by their domain
+        list_zones: false,
+        create_zone: false,
+        delete_zone: false,
+        create_record: true,
+        update_record: true,
+        delete_record: true,
+        // All hosts are replaced at once using `setHosts`
+        atomic_update: true,
+        ttl_range: 60..=60000,
+        ..Capabilities::default()
+    }
+    .with_record_types(SUPPORTED_RECORD_TYPES)
+}
+
+impl MigrationTarget for NamecheapZone {}
+
+/// Builds a host record that has not been saved yet.
+fn new_host_record(name: &str, data: &RecordData, ttl: u64) -> HostRecord {
+    HostRecord {
+        host_id: String::new(), // Will be assigned by Namecheap
+        name: name.to_string(),
+        record_type: data.get_type().to_string(),
+        address: data.get_api_value(),
+        mx_pref: if let RecordData::MX { priority, .. } = data {
+            Some(*priority)
+        } else {
+            None
+        },
+        ttl: ttl.clamp(60, 60000), // Namecheap TTL range
+    }
+}
+
 /// Converts a Namecheap HostRecord to a libdns Record.
 ///
 /// This is useful for custom transformations of Namecheap API responses.
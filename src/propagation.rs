@@ -0,0 +1,464 @@
+//! Verifying record propagation against authoritative nameservers.
+//!
+//! A provider accepting a record does not mean the record is served yet: most providers push changes to their nameservers asynchronously.
+//! A [`PropagationChecker`] queries every authoritative nameserver of a zone directly over UDP (falling back to TCP for truncated responses)
+//! and reports which of them do not serve a record yet.
+//!
+//! The nameservers are either given explicitly or discovered through a recursive resolver using [`PropagationChecker::discover`].
+//!
+//! With the `acme` feature enabled, the checker implements `acme::PropagationCheck`,
+//! so it can be passed to `acme::Dns01Solver::wait_for_propagation`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use libdns::propagation::PropagationChecker;
+//! use libdns::RecordData;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let checker = PropagationChecker::discover("example.com", "1.1.1.1:53".parse()?).await?;
+//! let data = RecordData::A("10.0.0.1".parse()?);
+//! checker
+//!     .wait("www.example.com", &data, Duration::from_secs(120))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+
+use crate::types::{DomainName, RecordClass, RecordType};
+use crate::wire::{self, next_id, Malformed, Message};
+use crate::{same_value, ClassifyError, ErrorKind, RecordData};
+
+/// The port nameservers listen on.
+const DNS_PORT: u16 = 53;
+
+/// The UDP payload size advertised using EDNS(0) (RFC 6891), large enough for most TXT record sets.
+const UDP_PAYLOAD_SIZE: u16 = 4096;
+
+/// The type code of the EDNS(0) OPT pseudo-record.
+const OPT_TYPE: u16 = 41;
+
+/// The response code for a name that does not exist (RFC 1035 §4.1.1).
+const NXDOMAIN: u8 = 3;
+
+/// How long to wait for a single response.
+const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long to wait between two rounds of queries.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The record types whose values can be compared.
+const SUPPORTED_RECORD_TYPES: [RecordType; 7] = [
+    RecordType::A,
+    RecordType::AAAA,
+    RecordType::CNAME,
+    RecordType::MX,
+    RecordType::NS,
+    RecordType::SRV,
+    RecordType::TXT,
+];
+
+/// Checks whether records are served by the authoritative nameservers of a zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropagationChecker {
+    nameservers: Vec<SocketAddr>,
+    query_timeout: Duration,
+    interval: Duration,
+}
+
+impl PropagationChecker {
+    /// Creates a checker querying the given nameserver addresses.
+    ///
+    /// Besides the real authoritative nameservers, this can be any server answering DNS queries, e.g. a local stub server in tests.
+    pub fn new(nameservers: impl IntoIterator<Item = SocketAddr>) -> Self {
+        Self {
+            nameservers: nameservers.into_iter().collect(),
+            query_timeout: DEFAULT_QUERY_TIMEOUT,
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+
+    /// Creates a checker for the authoritative nameservers of the zone, looked up using the recursive `resolver`.
+    ///
+    /// The NS records of the zone are resolved to their IPv4 addresses, as IPv6 connectivity cannot be taken for granted.
+    pub async fn discover(zone: &str, resolver: SocketAddr) -> Result<Self, PropagationError> {
+        let checker = Self::new([resolver]);
+        let names = checker.resolve(resolver, zone, RecordType::NS).await?;
+
+        let mut nameservers = Vec::new();
+        for name in names {
+            let RecordData::NS(name) = name else {
+                continue;
+            };
+            for address in checker.resolve(resolver, &name, RecordType::A).await? {
+                if let RecordData::A(ip) = address {
+                    let address = SocketAddr::new(IpAddr::V4(ip), DNS_PORT);
+                    if !nameservers.contains(&address) {
+                        nameservers.push(address);
+                    }
+                }
+            }
+        }
+
+        if nameservers.is_empty() {
+            return Err(PropagationError::NoNameservers);
+        }
+        Ok(Self {
+            nameservers,
+            ..checker
+        })
+    }
+
+    /// Sets how long to wait for the response of a single nameserver. Defaults to 3 seconds.
+    pub fn with_query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = timeout;
+        self
+    }
+
+    /// Sets how long [`PropagationChecker::wait`] waits between two rounds of queries. Defaults to 5 seconds.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the addresses of the queried nameservers.
+    pub fn nameservers(&self) -> &[SocketAddr] {
+        &self.nameservers
+    }
+
+    /// Queries a single nameserver for the records of the given fully qualified name and type, without recursion.
+    ///
+    /// Returns an empty list if the name or the records do not exist.
+    pub async fn query(
+        &self,
+        nameserver: SocketAddr,
+        name: &str,
+        typ: RecordType,
+    ) -> Result<Vec<RecordData>, PropagationError> {
+        self.lookup(nameserver, name, typ, false).await
+    }
+
+    /// Returns the nameservers that do not serve the record with the given fully qualified name and value yet.
+    ///
+    /// Nameservers that cannot be reached or answer with an error are considered pending as well.
+    pub async fn pending(
+        &self,
+        name: &str,
+        data: &RecordData,
+    ) -> Result<Vec<SocketAddr>, PropagationError> {
+        let typ = supported_type(data)?;
+        if self.nameservers.is_empty() {
+            return Err(PropagationError::NoNameservers);
+        }
+        self.pending_among(&self.nameservers, name, typ, data).await
+    }
+
+    /// Waits until every nameserver serves the record with the given fully qualified name and value.
+    ///
+    /// Nameservers are polled at the configured interval; those already serving the record are not queried again.
+    pub async fn wait(
+        &self,
+        name: &str,
+        data: &RecordData,
+        timeout: Duration,
+    ) -> Result<(), PropagationError> {
+        let typ = supported_type(data)?;
+        if self.nameservers.is_empty() {
+            return Err(PropagationError::NoNameservers);
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut pending = self.nameservers.clone();
+        loop {
+            pending = self.pending_among(&pending, name, typ, data).await?;
+
+            if pending.is_empty() {
+                return Ok(());
+            }
+            if Instant::now() + self.interval > deadline {
+                return Err(PropagationError::Timeout { pending });
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Returns the given nameservers not serving the record yet.
+    async fn pending_among(
+        &self,
+        nameservers: &[SocketAddr],
+        name: &str,
+        typ: RecordType,
+        data: &RecordData,
+    ) -> Result<Vec<SocketAddr>, PropagationError> {
+        let mut pending = Vec::new();
+        for &nameserver in nameservers {
+            match self.query(nameserver, name, typ).await {
+                Ok(records) if records.iter().any(|r| same_value(r, data)) => {}
+                Ok(_) | Err(PropagationError::Query { .. }) => pending.push(nameserver),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Asks a recursive resolver for the records of the given name and type.
+    async fn resolve(
+        &self,
+        resolver: SocketAddr,
+        name: &str,
+        typ: RecordType,
+    ) -> Result<Vec<RecordData>, PropagationError> {
+        self.lookup(resolver, name, typ, true).await
+    }
+
+    async fn lookup(
+        &self,
+        server: SocketAddr,
+        name: &str,
+        typ: RecordType,
+        recursive: bool,
+    ) -> Result<Vec<RecordData>, PropagationError> {
+        let qname = DomainName::from_dotted(name).ok_or(PropagationError::InvalidName)?;
+        let id = next_id();
+        let query = encode_query(id, &qname, typ, recursive);
+
+        let exchange = async {
+            let response = exchange_udp(server, &query, id).await?;
+            if response.truncated {
+                exchange_tcp(server, &query, id).await
+            } else {
+                Ok(response)
+            }
+        };
+        let response = tokio::time::timeout(self.query_timeout, exchange)
+            .await
+            .map_err(|_| PropagationError::Query {
+                nameserver: server,
+                cause: QueryError::Timeout,
+            })?
+            .map_err(|cause| PropagationError::Query {
+                nameserver: server,
+                cause,
+            })?;
+
+        let owner = qname.to_dotted().to_ascii_lowercase();
+        Ok(response
+            .answers
+            .into_iter()
+            .filter(|answer| answer.name == owner && answer.typ == typ.as_u16())
+            .map(|answer| answer.data)
+            .collect())
+    }
+}
+
+#[cfg(feature = "acme")]
+impl crate::acme::PropagationCheck for PropagationChecker {
+    async fn is_visible(&self, name: &str, value: &str) -> bool {
+        let data = RecordData::TXT(value.to_string());
+        self.pending(name, &data)
+            .await
+            .is_ok_and(|pending| pending.is_empty())
+    }
+}
+
+/// Represents an error that occured when checking the propagation of a record.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Error)]
+pub enum PropagationError {
+    /// Indicates that the name is not a valid domain name.
+    #[error("the name is not a valid domain name")]
+    InvalidName,
+
+    /// Indicates that the values of the record type cannot be compared.
+    #[error("the record type is not supported")]
+    UnsupportedType,
+
+    /// Indicates that no nameservers were given or found for the zone.
+    #[error("no nameservers to query")]
+    NoNameservers,
+
+    /// Indicates that a nameserver did not answer a query successfully.
+    #[error("the query to {nameserver} failed: {cause}")]
+    Query {
+        nameserver: SocketAddr,
+        cause: QueryError,
+    },
+
+    /// Indicates that some nameservers did not serve the record in time.
+    #[error("the record did not propagate to {} nameservers in time", pending.len())]
+    Timeout { pending: Vec<SocketAddr> },
+}
+
+/// Represents the reason a single query failed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+pub enum QueryError {
+    /// Indicates that the nameserver could not be reached.
+    #[error("the nameserver could not be reached: {0}")]
+    Io(io::ErrorKind),
+
+    /// Indicates that the nameserver did not respond in time.
+    #[error("the nameserver did not respond in time")]
+    Timeout,
+
+    /// Indicates that the response could not be parsed.
+    #[error("the response is malformed")]
+    Malformed,
+
+    /// Indicates that the nameserver answered with an error response code, e.g. 2 (SERVFAIL) or 5 (REFUSED).
+    #[error("the nameserver answered with response code {0}")]
+    ResponseCode(u8),
+}
+
+impl ClassifyError for PropagationError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            PropagationError::InvalidName | PropagationError::UnsupportedType => {
+                ErrorKind::Validation
+            }
+            PropagationError::NoNameservers => ErrorKind::NotFound,
+            PropagationError::Query { cause, .. } => cause.kind(),
+            PropagationError::Timeout { .. } => ErrorKind::Transient,
+        }
+    }
+}
+
+impl ClassifyError for QueryError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            QueryError::Io(_) | QueryError::Timeout => ErrorKind::Transient,
+            QueryError::Malformed => ErrorKind::Other,
+            // SERVFAIL
+            QueryError::ResponseCode(2) => ErrorKind::Transient,
+            // REFUSED
+            QueryError::ResponseCode(5) => ErrorKind::Unauthorized,
+            QueryError::ResponseCode(_) => ErrorKind::Other,
+        }
+    }
+}
+
+impl From<Malformed> for QueryError {
+    fn from(_: Malformed) -> Self {
+        QueryError::Malformed
+    }
+}
+
+impl From<io::Error> for QueryError {
+    fn from(err: io::Error) -> Self {
+        QueryError::Io(err.kind())
+    }
+}
+
+/// Returns the record type of the data if its values can be compared.
+fn supported_type(data: &RecordData) -> Result<RecordType, PropagationError> {
+    RecordType::from_str(data.get_type())
+        .filter(|typ| SUPPORTED_RECORD_TYPES.contains(typ))
+        .ok_or(PropagationError::UnsupportedType)
+}
+
+async fn exchange_udp(server: SocketAddr, query: &[u8], id: u16) -> Result<Response, QueryError> {
+    let local: SocketAddr = match server {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(server).await?;
+    socket.send(query).await?;
+
+    let mut buf = vec![0u8; UDP_PAYLOAD_SIZE as usize];
+    loop {
+        let len = socket.recv(&mut buf).await?;
+        // Stray datagrams with another ID are ignored
+        if len >= 2 && buf[..2] == id.to_be_bytes() {
+            return decode_response(&buf[..len], id);
+        }
+    }
+}
+
+async fn exchange_tcp(server: SocketAddr, query: &[u8], id: u16) -> Result<Response, QueryError> {
+    let mut stream = TcpStream::connect(server).await?;
+
+    // Messages over TCP are prefixed with their length (RFC 1035 §4.2.2)
+    let mut message = (query.len() as u16).to_be_bytes().to_vec();
+    message.extend_from_slice(query);
+    stream.write_all(&message).await?;
+
+    let len = stream.read_u16().await?;
+    let mut buf = vec![0u8; len as usize];
+    stream.read_exact(&mut buf).await?;
+    decode_response(&buf, id)
+}
+
+/// Encodes a query for the name and type, advertising a larger UDP payload size using EDNS(0).
+fn encode_query(id: u16, name: &DomainName, typ: RecordType, recursive: bool) -> Vec<u8> {
+    let flags: u16 = if recursive { 0x0100 } else { 0 };
+
+    let mut query = wire::header(id, flags, [1, 0, 0, 1]);
+    wire::push_question(&mut query, name, typ.as_u16(), RecordClass::IN.as_u16());
+    // OPT pseudo-record: root owner, payload size as class, no extended flags or options
+    wire::push_record(
+        &mut query,
+        &DomainName::default(),
+        OPT_TYPE,
+        UDP_PAYLOAD_SIZE,
+        0,
+        &[],
+    );
+    query
+}
+
+/// The parts of a response relevant for comparing records.
+struct Response {
+    truncated: bool,
+    answers: Vec<Answer>,
+}
+
+/// A resource record of the answer section.
+struct Answer {
+    /// The owner name in lowercase dotted notation, without trailing dot.
+    name: String,
+    typ: u16,
+    data: RecordData,
+}
+
+/// Decodes the response to the query with the given ID.
+fn decode_response(msg: &[u8], id: u16) -> Result<Response, QueryError> {
+    let message = Message::parse(msg)?;
+    if message.id != id || message.flags & 0x8000 == 0 {
+        // Not a response to the query
+        return Err(QueryError::Malformed);
+    }
+    let truncated = message.flags & 0x0200 != 0;
+    match message.rcode() {
+        0 => {}
+        NXDOMAIN => {
+            return Ok(Response {
+                truncated,
+                answers: Vec::new(),
+            })
+        }
+        rcode => return Err(QueryError::ResponseCode(rcode)),
+    }
+
+    let answers = message
+        .answers
+        .iter()
+        .map(|record| {
+            Ok(Answer {
+                data: record.data(msg)?,
+                name: record.name.clone(),
+                typ: record.typ,
+            })
+        })
+        .collect::<Result<_, Malformed>>()?;
+
+    Ok(Response { truncated, answers })
+}
@@ -0,0 +1,454 @@
+//! Declarative zone reconciliation.
+//!
+//! Instead of issuing individual record operations, the desired state of a zone is described as a list of [`Record`]s.
+//! A [`Reconciler`] compares it to the records currently present in the zone and computes a [`Plan`] of creates, updates and deletes,
+//! which can be reviewed before it is applied through [`CreateRecord`] and [`DeleteRecord`], or additionally [`UpdateRecord`] to replace records in place.
+//!
+//! The IDs of desired records are ignored, so they can be written by hand or deserialized from a file (with the `serde` feature enabled).
+//!
+//! # Ownership
+//!
+//! By default, every record of the zone is managed except for the SOA and NS records at the apex, which are usually maintained by the provider.
+//! Further records can be excluded using [`Reconciler::ignore`].
+//! To share a zone with other tools, [`Reconciler::owned_only`] restricts the reconciler to the records it created in an earlier run.
+//!
+//! # Example
+//!
+//! ```
+//! use libdns::reconcile::Reconciler;
+//! use libdns::{CreateRecord, DeleteRecord, Record, RecordData};
+//!
+//! async fn sync<Z: CreateRecord + DeleteRecord>(zone: &Z) -> Result<(), Box<dyn std::error::Error>>
+//! where
+//!     Z::CustomRetrieveError: std::error::Error + 'static,
+//!     Z::CustomCreateError: std::error::Error + 'static,
+//!     Z::CustomDeleteError: std::error::Error + 'static,
+//! {
+//!     let desired = vec![Record {
+//!         id: String::new(),
+//!         host: "www".to_string(),
+//!         data: RecordData::A("10.0.0.1".parse()?),
+//!         ttl: 300,
+//!     }];
+//!
+//!     let reconciler = Reconciler::new().ignore(|record| record.host.starts_with("_acme-challenge"));
+//!     let plan = reconciler.plan_zone(zone, &desired).await?;
+//!     for change in plan.changes() {
+//!         println!("{:?}", change);
+//!     }
+//!     plan.apply(zone).await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::convert::Infallible;
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::{
+    normalize_host, same_content, with_listed_record, ClassifyError, CreateRecord,
+    CreateRecordError, DeleteRecord, DeleteRecordError, ErrorKind, Record, RetrieveRecordError,
+    UpdateRecord, UpdateRecordError, Zone,
+};
+
+/// A predicate selecting records to be left alone by a [`Reconciler`].
+type RecordFilter = Box<dyn Fn(&Record) -> bool + Send + Sync>;
+
+/// Computes [`Plan`]s transitioning a zone to a desired set of records.
+///
+/// See the [module documentation](self) for the ownership rules.
+pub struct Reconciler {
+    manage_apex_authority: bool,
+    filters: Vec<RecordFilter>,
+    owned: Option<Vec<Record>>,
+}
+
+impl Default for Reconciler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Reconciler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reconciler")
+            .field("manage_apex_authority", &self.manage_apex_authority)
+            .field("filters", &self.filters.len())
+            .field("owned", &self.owned)
+            .finish()
+    }
+}
+
+impl Reconciler {
+    /// Creates a reconciler managing every record except for the SOA and NS records at the apex.
+    pub fn new() -> Self {
+        Self {
+            manage_apex_authority: false,
+            filters: Vec::new(),
+            owned: None,
+        }
+    }
+
+    /// Also manages the SOA and NS records at the apex of the zone.
+    pub fn manage_apex_authority(mut self) -> Self {
+        self.manage_apex_authority = true;
+        self
+    }
+
+    /// Leaves all records matching the given filter alone, both on the desired and the existing side.
+    ///
+    /// Filters receive records with hosts as they are reported by the provider or given by the caller, so they may be relative or fully qualified.
+    pub fn ignore<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Record) -> bool + Send + Sync + 'static,
+    {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Only updates and deletes records previously created by the reconciler.
+    ///
+    /// `owned` are the records returned by an earlier [`Plan::apply`]. They are matched by host and value, as some providers reassign record IDs.
+    /// Records that already exist but are not owned are never touched, even if they are part of the desired state.
+    pub fn owned_only(mut self, owned: impl IntoIterator<Item = Record>) -> Self {
+        self.owned = Some(owned.into_iter().collect());
+        self
+    }
+
+    /// Returns whether the reconciler is allowed to touch the given existing record.
+    fn manages(&self, record: &Record, domain: &str) -> bool {
+        !self.ignores(record, domain)
+            && self.owned.as_ref().is_none_or(|owned| {
+                owned
+                    .iter()
+                    .any(|o| same_content(o, &record.host, &record.data, domain))
+            })
+    }
+
+    /// Returns whether the record is excluded by the apex rule or a filter.
+    fn ignores(&self, record: &Record, domain: &str) -> bool {
+        let apex_authority = normalize_host(&record.host, domain) == "@"
+            && ["SOA", "NS"]
+                .iter()
+                .any(|typ| record.data.get_type().eq_ignore_ascii_case(typ));
+
+        (apex_authority && !self.manage_apex_authority)
+            || self.filters.iter().any(|filter| filter(record))
+    }
+
+    /// Computes the plan transitioning the `existing` records of the zone for `domain` to the `desired` ones.
+    ///
+    /// Within a record set (host and type), matching values are kept, values with a different TTL are updated and remaining values are paired up as updates before falling back to creates and deletes.
+    pub fn plan(&self, domain: &str, existing: &[Record], desired: &[Record]) -> Plan {
+        let mut managed: Vec<&Record> = existing
+            .iter()
+            .filter(|r| self.manages(r, domain))
+            .collect();
+        let unmanaged: Vec<&Record> = existing
+            .iter()
+            .filter(|r| !self.manages(r, domain))
+            .collect();
+
+        let mut plan = Plan::default();
+        let mut leftovers = Vec::new();
+        for wanted in desired.iter().filter(|r| !self.ignores(r, domain)) {
+            if plan
+                .unchanged
+                .iter()
+                .chain(plan.changes.iter().filter_map(PlannedChange::target))
+                .chain(leftovers.iter().copied())
+                .any(|r| same_content(r, &wanted.host, &wanted.data, domain))
+            {
+                // Duplicate of a desired record already handled
+                continue;
+            }
+
+            if let Some(position) = managed
+                .iter()
+                .position(|r| same_content(r, &wanted.host, &wanted.data, domain))
+            {
+                let found = managed.remove(position);
+                if found.ttl == wanted.ttl {
+                    plan.unchanged.push(found.clone());
+                } else {
+                    plan.changes.push(PlannedChange::Update {
+                        from: found.clone(),
+                        to: wanted.clone(),
+                    });
+                }
+            } else if !unmanaged
+                .iter()
+                .any(|r| same_content(r, &wanted.host, &wanted.data, domain))
+            {
+                leftovers.push(wanted);
+            }
+        }
+
+        // Pair up the remaining values of each record set as updates
+        for wanted in leftovers {
+            let host = normalize_host(&wanted.host, domain);
+            let typ = wanted.data.get_type();
+            let stale = managed.iter().position(|r| {
+                normalize_host(&r.host, domain) == host
+                    && r.data.get_type().eq_ignore_ascii_case(typ)
+            });
+
+            plan.changes.push(match stale {
+                Some(position) => PlannedChange::Update {
+                    from: managed.remove(position).clone(),
+                    to: wanted.clone(),
+                },
+                None => PlannedChange::Create(wanted.clone()),
+            });
+        }
+
+        plan.changes
+            .extend(managed.into_iter().cloned().map(PlannedChange::Delete));
+        plan
+    }
+
+    /// Retrieves the records of the zone and computes the plan transitioning them to the `desired` ones.
+    pub async fn plan_zone<Z: Zone>(
+        &self,
+        zone: &Z,
+        desired: &[Record],
+    ) -> Result<Plan, RetrieveRecordError<Z::CustomRetrieveError>> {
+        let existing = zone.list_records().await?;
+        Ok(self.plan(zone.domain(), &existing, desired))
+    }
+}
+
+/// A single change of a [`Plan`].
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum PlannedChange {
+    /// Creates the desired record.
+    Create(Record),
+
+    /// Replaces an existing record with the desired one.
+    Update { from: Record, to: Record },
+
+    /// Deletes an existing record that is not part of the desired state.
+    Delete(Record),
+}
+
+impl PlannedChange {
+    /// Returns the desired record this change results in, if any.
+    fn target(&self) -> Option<&Record> {
+        match self {
+            PlannedChange::Create(record) | PlannedChange::Update { to: record, .. } => {
+                Some(record)
+            }
+            PlannedChange::Delete(_) => None,
+        }
+    }
+}
+
+/// The changes required to reconcile a zone, as computed by a [`Reconciler`].
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Plan {
+    changes: Vec<PlannedChange>,
+    unchanged: Vec<Record>,
+}
+
+impl Plan {
+    /// Returns the planned changes.
+    pub fn changes(&self) -> &[PlannedChange] {
+        &self.changes
+    }
+
+    /// Returns the managed records that already match the desired state.
+    pub fn unchanged(&self) -> &[Record] {
+        &self.unchanged
+    }
+
+    /// Returns whether the zone already matches the desired state.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Applies the plan to the zone it was computed for.
+    ///
+    /// New records are created before the records they replace are deleted, so a record set does not become empty in between.
+    /// A record whose value is kept but whose TTL changes is deleted before it is recreated, as providers commonly reject duplicate values.
+    /// Zones supporting [`UpdateRecord`] should be reconciled using [`Plan::apply_updates`] instead, which replaces records in place.
+    ///
+    /// Returns every record the reconciler now owns, i.e. the unchanged records and the created ones, which can be passed to [`Reconciler::owned_only`] in the next run.
+    pub async fn apply<Z>(
+        &self,
+        zone: &Z,
+    ) -> Result<
+        Vec<Record>,
+        ApplyPlanError<
+            Z::CustomRetrieveError,
+            Z::CustomCreateError,
+            Infallible,
+            Z::CustomDeleteError,
+        >,
+    >
+    where
+        Z: CreateRecord + DeleteRecord,
+    {
+        let domain = zone.domain();
+        let mut owned = self.unchanged.clone();
+        let mut recreated = Vec::new();
+        let mut stale = Vec::new();
+        for change in &self.changes {
+            match change {
+                PlannedChange::Create(record) => owned.push(create(zone, record).await?),
+                PlannedChange::Update { from, to }
+                    if same_content(to, &from.host, &from.data, domain) =>
+                {
+                    recreated.push((from, to))
+                }
+                PlannedChange::Update { from, to } => {
+                    owned.push(create(zone, to).await?);
+                    stale.push(from);
+                }
+                PlannedChange::Delete(record) => stale.push(record),
+            }
+        }
+        let mut listing = None;
+        for (from, to) in recreated {
+            delete_current(zone, &mut listing, from).await?;
+            owned.push(create(zone, to).await?);
+        }
+        let mut listing = None;
+        for record in stale {
+            delete_current(zone, &mut listing, record).await?;
+        }
+
+        Ok(owned)
+    }
+
+    /// Applies the plan to the zone it was computed for, replacing records in place using [`UpdateRecord::update_record`].
+    ///
+    /// Updates and creates are applied before stale records are deleted.
+    /// Returns every record the reconciler now owns, like [`Plan::apply`].
+    pub async fn apply_updates<Z>(
+        &self,
+        zone: &Z,
+    ) -> Result<
+        Vec<Record>,
+        ApplyPlanError<
+            Z::CustomRetrieveError,
+            Z::CustomCreateError,
+            Z::CustomUpdateError,
+            Z::CustomDeleteError,
+        >,
+    >
+    where
+        Z: CreateRecord + UpdateRecord + DeleteRecord,
+    {
+        let mut owned = self.unchanged.clone();
+        let mut stale = Vec::new();
+        let mut listing = None;
+        for change in &self.changes {
+            match change {
+                PlannedChange::Create(record) => owned.push(create(zone, record).await?),
+                PlannedChange::Update { from, to } => {
+                    let updated = with_listed_record(
+                        zone,
+                        &mut listing,
+                        &from.host,
+                        &from.data,
+                        ApplyPlanError::Retrieve,
+                        |current| async move {
+                            zone.update_record(&current.id, &to.host, &to.data, to.ttl)
+                                .await
+                                .map_err(ApplyPlanError::Update)
+                        },
+                    )
+                    .await?;
+                    match updated {
+                        Some(record) => owned.push(record),
+                        // The record vanished since planning, so there is nothing left to replace
+                        None => owned.push(create(zone, to).await?),
+                    }
+                }
+                PlannedChange::Delete(record) => stale.push(record),
+            }
+        }
+        let mut listing = None;
+        for record in stale {
+            delete_current(zone, &mut listing, record).await?;
+        }
+
+        Ok(owned)
+    }
+}
+
+/// Creates the desired record.
+async fn create<Z: CreateRecord, U, D>(
+    zone: &Z,
+    record: &Record,
+) -> Result<Record, ApplyPlanError<Z::CustomRetrieveError, Z::CustomCreateError, U, D>> {
+    zone.create_record(&record.host, &record.data, record.ttl)
+        .await
+        .map_err(ApplyPlanError::Create)
+}
+
+/// Deletes the current version of an existing record, skipping it if it no longer exists.
+async fn delete_current<Z, U>(
+    zone: &Z,
+    listing: &mut Option<Vec<Record>>,
+    record: &Record,
+) -> Result<(), ApplyPlanError<Z::CustomRetrieveError, Z::CustomCreateError, U, Z::CustomDeleteError>>
+where
+    Z: CreateRecord + DeleteRecord,
+    U: ClassifyError,
+{
+    with_listed_record(
+        zone,
+        listing,
+        &record.host,
+        &record.data,
+        ApplyPlanError::Retrieve,
+        |current| async move {
+            zone.delete_record(&current.id)
+                .await
+                .map_err(ApplyPlanError::Delete)
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// Represents an error that occured when applying a [`Plan`].
+///
+/// The variant indicates which underlying operation failed. As a plan consists of multiple requests, some changes may already have been applied.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+pub enum ApplyPlanError<R, C, U, D> {
+    /// Indicates that the records of the zone could not be retrieved to look up a record to be replaced or deleted.
+    #[error(transparent)]
+    Retrieve(RetrieveRecordError<R>),
+
+    /// Indicates that a record could not be created.
+    #[error(transparent)]
+    Create(CreateRecordError<C>),
+
+    /// Indicates that a record could not be updated.
+    #[error(transparent)]
+    Update(UpdateRecordError<U>),
+
+    /// Indicates that a record could not be deleted.
+    #[error(transparent)]
+    Delete(DeleteRecordError<D>),
+}
+
+impl<R, C, U, D> ClassifyError for ApplyPlanError<R, C, U, D>
+where
+    R: ClassifyError,
+    C: ClassifyError,
+    U: ClassifyError,
+    D: ClassifyError,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            ApplyPlanError::Retrieve(e) => e.kind(),
+            ApplyPlanError::Create(e) => e.kind(),
+            ApplyPlanError::Update(e) => e.kind(),
+            ApplyPlanError::Delete(e) => e.kind(),
+        }
+    }
+}
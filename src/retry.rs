@@ -0,0 +1,313 @@
+//! Retrying requests that failed transiently.
+//!
+//! The HTTP-based providers retry requests according to a [`RetryPolicy`], configured using the `with_retry_policy` method of the provider or its API client.
+//! Whether a failed request is retried depends on whether it may have been processed:
+//!
+//! - Requests rejected because of rate limiting (HTTP 429 or a throttle code of the provider, like Tencent Cloud's `RequestLimitExceeded`)
+//!   and requests that could not be sent because the connection failed were not processed, so they are always retried.
+//! - Requests that timed out or failed with HTTP 500, 502, 503 or 504 may have been processed.
+//!   They are only retried for idempotent calls (retrievals, updates replacing a record and deletions), unless [`RetryPolicy::retry_non_idempotent`] is enabled.
+//!
+//! The delay before a retry grows exponentially and is randomized by up to half of it, so that clients throttled at the same time do not retry in lockstep.
+//! A `Retry-After` header sent by the provider takes precedence. If it asks for a longer delay than the maximum backoff, the error is returned instead of waiting.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #[cfg(feature = "hetzner")]
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use std::time::Duration;
+//!
+//! use libdns::hetzner::HetznerProvider;
+//! use libdns::retry::RetryPolicy;
+//!
+//! let policy = RetryPolicy::new()
+//!     .with_max_retries(5)
+//!     .with_backoff(Duration::from_secs(1), Duration::from_secs(60));
+//! let provider = HetznerProvider::new("your-api-token")?.with_retry_policy(policy);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+
+use crate::{ClassifyError, ErrorKind};
+
+/// Describes when and how often failed requests are retried.
+///
+/// The default policy retries up to 3 times, starting with a delay of 500 milliseconds and doubling it up to 30 seconds.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates the default policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a policy that never retries.
+    pub fn disabled() -> Self {
+        Self::default().with_max_retries(0)
+    }
+
+    /// Sets how often a request is retried at most.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry and the maximum delay it grows to.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Sets whether delays are randomized, enabled by default.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets whether calls that are not idempotent, like record creation, are retried after failures that leave it unknown whether they were processed.
+    ///
+    /// Disabled by default, as such a retry may create a record twice. Rate-limited requests are retried regardless.
+    pub fn retry_non_idempotent(mut self, enabled: bool) -> Self {
+        self.retry_non_idempotent = enabled;
+        self
+    }
+
+    /// Returns how often a request is retried at most.
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Returns the delay before the retry with the given zero-based index, before jitter is applied.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(1 << retry.min(31))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+
+    /// Returns the delay before the given retry, or [`None`] if the request should not be retried.
+    fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if retry >= self.max_retries {
+            return None;
+        }
+        if let Some(retry_after) = retry_after {
+            return (retry_after <= self.max_backoff).then_some(retry_after);
+        }
+
+        let backoff = self.backoff(retry);
+        if !self.jitter {
+            return Some(backoff);
+        }
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u32(retry);
+        let fraction = (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64;
+        Some(backoff.mul_f64(1.0 - fraction / 2.0))
+    }
+}
+
+/// Represents a failed attempt that may succeed when retried.
+pub(crate) enum Transient {
+    /// The request was rejected without being processed, e.g. because of rate limiting.
+    Rejected(Option<Duration>),
+
+    /// It is unknown whether the request was processed.
+    Failed(Option<Duration>),
+}
+
+/// Calls `attempt` until it succeeds, `classify` does not consider its error transient, or the policy gives up.
+pub(crate) async fn run<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    idempotent: bool,
+    mut attempt: F,
+    classify: impl Fn(&E) -> Option<Transient>,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut retry = 0;
+    loop {
+        let err = match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        let delay = match classify(&err) {
+            Some(Transient::Rejected(retry_after)) => policy.delay(retry, retry_after),
+            Some(Transient::Failed(retry_after)) if idempotent || policy.retry_non_idempotent => {
+                policy.delay(retry, retry_after)
+            }
+            _ => None,
+        };
+        match delay {
+            Some(delay) => {
+                tokio::time::sleep(delay).await;
+                retry += 1;
+            }
+            None => return Err(err),
+        }
+    }
+}
+
+/// Classifies a request error, returning [`None`] for errors that will not go away by retrying.
+pub(crate) fn classify_request_error(err: &reqwest::Error) -> Option<Transient> {
+    if err.is_connect() {
+        Some(Transient::Rejected(None))
+    } else if err.is_timeout() {
+        Some(Transient::Failed(None))
+    } else {
+        None
+    }
+}
+
+/// Classifies request errors by their HTTP status code, or as transient if the connection failed or timed out.
+impl ClassifyError for reqwest::Error {
+    fn kind(&self) -> ErrorKind {
+        let Some(status) = self.status() else {
+            return if self.is_connect() || self.is_timeout() {
+                ErrorKind::Transient
+            } else {
+                ErrorKind::Other
+            };
+        };
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ErrorKind::Unauthorized,
+            StatusCode::NOT_FOUND => ErrorKind::NotFound,
+            StatusCode::CONFLICT => ErrorKind::AlreadyExists,
+            StatusCode::TOO_MANY_REQUESTS => ErrorKind::RateLimited,
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => ErrorKind::Validation,
+            StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => ErrorKind::Transient,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// A failed attempt of [`RequestBuilderExt::send_with_retry`].
+enum Attempt {
+    Error(reqwest::Error),
+    Status(Response),
+}
+
+/// Sending HTTP requests according to a [`RetryPolicy`].
+pub(crate) trait RequestBuilderExt {
+    /// Sends the request, retrying it on connection failures, timeouts and transient status codes.
+    ///
+    /// When the policy gives up on a transient status code, the last response is returned for the caller to handle like any other.
+    fn send_with_retry(
+        self,
+        policy: &RetryPolicy,
+        idempotent: bool,
+    ) -> impl Future<Output = Result<Response, reqwest::Error>> + Send;
+}
+
+impl RequestBuilderExt for RequestBuilder {
+    async fn send_with_retry(
+        self,
+        policy: &RetryPolicy,
+        idempotent: bool,
+    ) -> Result<Response, reqwest::Error> {
+        let attempt = || {
+            // Request bodies of the clients are always buffered, so the request can be cloned
+            let request = self.try_clone().expect("request body is not a stream");
+            async move {
+                let response = request.send().await.map_err(Attempt::Error)?;
+                match response.status() {
+                    StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::INTERNAL_SERVER_ERROR
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT => Err(Attempt::Status(response)),
+                    _ => Ok(response),
+                }
+            }
+        };
+        let classify = |attempt: &Attempt| match attempt {
+            Attempt::Error(err) => classify_request_error(err),
+            Attempt::Status(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                Some(Transient::Rejected(retry_after(response.headers())))
+            }
+            Attempt::Status(response) => Some(Transient::Failed(retry_after(response.headers()))),
+        };
+
+        match run(policy, idempotent, attempt, classify).await {
+            Ok(response) | Err(Attempt::Status(response)) => Ok(response),
+            Err(Attempt::Error(err)) => Err(err),
+        }
+    }
+}
+
+/// Parses the `Retry-After` header, given either in seconds or as an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = parse_http_date(value)?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Parses an HTTP date in the preferred format of RFC 9110, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [_, day, month, year, time, "GMT"] = parts[..] else {
+        return None;
+    };
+    let day: i64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|m| *m == month)? as i64 + 1;
+    let year: i64 = year.parse().ok()?;
+    let mut time = time.split(':').map(|part| part.parse::<i64>().ok());
+    let (Some(Some(hour)), Some(Some(minute)), Some(Some(second)), None) =
+        (time.next(), time.next(), time.next(), time.next())
+    else {
+        return None;
+    };
+
+    // Days since the epoch of the proleptic Gregorian calendar date
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    let seconds = days * 86400 + hour * 3600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(seconds).ok()?))
+}
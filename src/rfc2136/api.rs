@@ -0,0 +1,573 @@
+//! Low-level client for DNS UPDATE (RFC 2136) and zone transfers (RFC 5936), signed using TSIG (RFC 8945).
+//!
+//! All messages are exchanged over TCP, as zone transfers require it and update responses are small.
+
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::types::{DomainName, RecordClass, RecordType};
+use crate::wire::{self, base64_decode, next_id, Malformed, Message, Reader};
+use crate::{ClassifyError, ErrorKind, RecordData};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The type code of the TSIG meta-record.
+const TSIG_TYPE: u16 = 250;
+
+/// The type code of a full zone transfer query.
+const AXFR_TYPE: u16 = 252;
+
+/// The opcode of a dynamic update, in position of the header flags.
+const UPDATE_OPCODE: u16 = 5 << 11;
+
+/// The name of the only supported TSIG algorithm.
+const ALGORITHM: &str = "hmac-sha256";
+
+/// The permitted difference between the clocks of client and server, as recommended by RFC 8945 §10.
+const FUDGE: u16 = 300;
+
+/// The TSIG error for a signature outside the permitted time window (RFC 8945 §3).
+const BADTIME: u16 = 18;
+
+/// The response code for a name that does not exist (RFC 1035 §4.1.1).
+const NXDOMAIN: u8 = 3;
+
+/// The response code of a refused request (RFC 1035 §4.1.1).
+const REFUSED: u8 = 5;
+
+/// The response code of a server that is not authoritative for the zone (RFC 2136 §2.2).
+const NOTAUTH: u8 = 9;
+
+/// The number of consecutive unsigned responses permitted within a zone transfer (RFC 8945 §5.3.1).
+const MAX_UNSIGNED: usize = 99;
+
+/// How long to wait for the connection and for every single response.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A shared secret used to sign messages with HMAC-SHA256.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TsigKey {
+    name: DomainName,
+    secret: Vec<u8>,
+}
+
+impl TsigKey {
+    /// Creates a key from its name and raw secret.
+    ///
+    /// Returns `None` if the name is not a valid domain name.
+    pub fn new(name: &str, secret: &[u8]) -> Option<Self> {
+        Some(Self {
+            // Key names are compared and signed in canonical (lowercase) form
+            name: DomainName::from_dotted(&name.to_ascii_lowercase())?,
+            secret: secret.to_vec(),
+        })
+    }
+
+    /// Creates a key from its name and base64-encoded secret, as found in BIND `key` statements and Knot `key` sections.
+    ///
+    /// Returns `None` if the name is not a valid domain name or the secret is not valid base64.
+    pub fn from_base64(name: &str, secret: &str) -> Option<Self> {
+        Self::new(name, &base64_decode(secret)?)
+    }
+
+    /// Returns the name of the key.
+    pub fn name(&self) -> String {
+        self.name.to_dotted()
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.secret).expect("HMAC can take key of any size")
+    }
+}
+
+impl fmt::Debug for TsigKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TsigKey")
+            .field("name", &self.name)
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+
+/// A resource record received in a zone transfer.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct ResourceRecord {
+    /// The fully qualified owner name in lowercase, without trailing dot.
+    pub name: String,
+    pub ttl: u32,
+    pub data: RecordData,
+}
+
+/// A single change of a dynamic update.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum Update {
+    /// Adds the record to its record set. Adding a record that already exists only changes the TTL of the record set.
+    Add {
+        name: String,
+        ttl: u32,
+        data: RecordData,
+    },
+    /// Deletes the record from its record set. Deleting a record that does not exist is silently ignored by the server.
+    Delete { name: String, data: RecordData },
+}
+
+/// Client for a primary nameserver accepting dynamic updates and zone transfers.
+#[derive(Debug, Clone)]
+pub struct Client {
+    server: SocketAddr,
+    key: Option<TsigKey>,
+    timeout: Duration,
+}
+
+impl Client {
+    /// Creates a client for the server, signing all messages with the key if one is given.
+    pub fn new(server: SocketAddr, key: Option<TsigKey>) -> Self {
+        Self {
+            server,
+            key,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets how long to wait for the connection and for every single response (10 seconds by default).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns whether the server is authoritative for the zone, i.e. answers for its SOA record.
+    pub async fn is_authoritative(&self, zone: &str) -> Result<bool, Rfc2136Error> {
+        let zone_name = domain_name(zone)?;
+        let id = next_id();
+        let mut query = wire::header(id, 0, [1, 0, 0, 0]);
+        wire::push_question(
+            &mut query,
+            &zone_name,
+            RecordType::SOA.as_u16(),
+            RecordClass::IN.as_u16(),
+        );
+
+        let mut connection = self.connect().await?;
+        let mut signer = connection.send(query, self.key.as_ref()).await?;
+        let (_, message) = connection.receive(id, signer.as_mut()).await?;
+
+        match message.rcode() {
+            0 => {}
+            NXDOMAIN | REFUSED | NOTAUTH => return Ok(false),
+            rcode => return Err(Rfc2136Error::ResponseCode(rcode)),
+        }
+
+        // Authoritative answer holding the SOA record at the zone apex
+        let zone = zone_name.to_dotted().to_ascii_lowercase();
+        Ok(message.flags & 0x0400 != 0
+            && message
+                .answers
+                .iter()
+                .any(|record| record.typ == RecordType::SOA.as_u16() && record.name == zone))
+    }
+
+    /// Retrieves all records of the zone using a full zone transfer (AXFR).
+    ///
+    /// The SOA record is returned once, as the first record.
+    pub async fn transfer(&self, zone: &str) -> Result<Vec<ResourceRecord>, Rfc2136Error> {
+        let zone_name = domain_name(zone)?;
+        let id = next_id();
+        let mut query = wire::header(id, 0, [1, 0, 0, 0]);
+        wire::push_question(&mut query, &zone_name, AXFR_TYPE, RecordClass::IN.as_u16());
+
+        let mut connection = self.connect().await?;
+        let mut signer = connection.send(query, self.key.as_ref()).await?;
+
+        let mut records: Vec<ResourceRecord> = Vec::new();
+        loop {
+            let (response, message) = connection.receive(id, signer.as_mut()).await?;
+            match message.rcode() {
+                0 => {}
+                rcode => return Err(Rfc2136Error::ResponseCode(rcode)),
+            }
+            if message.answers.is_empty() {
+                return Err(Rfc2136Error::Malformed);
+            }
+
+            for record in &message.answers {
+                if record.class != RecordClass::IN.as_u16() {
+                    continue;
+                }
+                let data = record.data(&response)?;
+                let is_soa = record.typ == RecordType::SOA.as_u16();
+
+                if records.is_empty() && !is_soa {
+                    // A transfer starts with the SOA record
+                    return Err(Rfc2136Error::Malformed);
+                }
+                if is_soa && !records.is_empty() {
+                    // The SOA record is repeated at the end of the transfer
+                    if signer.as_ref().is_some_and(|signer| signer.unsigned > 0) {
+                        return Err(Rfc2136Error::Unsigned);
+                    }
+                    return Ok(records);
+                }
+
+                records.push(ResourceRecord {
+                    name: record.name.clone(),
+                    ttl: record.ttl,
+                    data,
+                });
+            }
+        }
+    }
+
+    /// Applies the updates to the zone in a single UPDATE message.
+    ///
+    /// The server applies either all updates or none of them (RFC 2136 §3.4).
+    pub async fn update(&self, zone: &str, updates: &[Update]) -> Result<(), Rfc2136Error> {
+        let zone_name = domain_name(zone)?;
+        let id = next_id();
+        let count = u16::try_from(updates.len()).map_err(|_| Rfc2136Error::InvalidData)?;
+        let mut message = wire::header(id, UPDATE_OPCODE, [1, 0, count, 0]);
+        wire::push_question(
+            &mut message,
+            &zone_name,
+            RecordType::SOA.as_u16(),
+            RecordClass::IN.as_u16(),
+        );
+
+        for update in updates {
+            let (name, class, ttl, data) = match update {
+                Update::Add { name, ttl, data } => (name, RecordClass::IN, *ttl, data),
+                Update::Delete { name, data } => (name, RecordClass::NONE, 0, data),
+            };
+            let (typ, rdata) = wire::encode_rdata(data).ok_or(Rfc2136Error::InvalidData)?;
+            wire::push_record(
+                &mut message,
+                &domain_name(name)?,
+                typ,
+                class.as_u16(),
+                ttl,
+                &rdata,
+            );
+        }
+
+        let mut connection = self.connect().await?;
+        let mut signer = connection.send(message, self.key.as_ref()).await?;
+        let (_, message) = connection.receive(id, signer.as_mut()).await?;
+
+        match message.rcode() {
+            0 => Ok(()),
+            rcode => Err(Rfc2136Error::ResponseCode(rcode)),
+        }
+    }
+
+    async fn connect(&self) -> Result<Connection, Rfc2136Error> {
+        let stream = tokio::time::timeout(self.timeout, TcpStream::connect(self.server))
+            .await
+            .map_err(|_| Rfc2136Error::Timeout)??;
+        Ok(Connection {
+            stream,
+            timeout: self.timeout,
+        })
+    }
+}
+
+/// A TCP connection to the server.
+struct Connection {
+    stream: TcpStream,
+    timeout: Duration,
+}
+
+impl Connection {
+    /// Sends the message, signing it with the key if one is given.
+    ///
+    /// Returns the state needed to verify the responses of a signed message.
+    async fn send(
+        &mut self,
+        mut message: Vec<u8>,
+        key: Option<&TsigKey>,
+    ) -> Result<Option<Signer>, Rfc2136Error> {
+        let signer = key.map(|key| Signer::sign(key, &mut message));
+
+        // Messages over TCP are prefixed with their length (RFC 1035 §4.2.2)
+        let mut framed = (message.len() as u16).to_be_bytes().to_vec();
+        framed.extend_from_slice(&message);
+        self.stream.write_all(&framed).await?;
+
+        Ok(signer)
+    }
+
+    /// Receives the next response to the message with the given ID, verifying its signature if the message was signed.
+    async fn receive(
+        &mut self,
+        id: u16,
+        signer: Option<&mut Signer>,
+    ) -> Result<(Vec<u8>, Message), Rfc2136Error> {
+        let read = async {
+            let len = self.stream.read_u16().await?;
+            let mut buf = vec![0u8; len as usize];
+            self.stream.read_exact(&mut buf).await?;
+            Ok::<_, io::Error>(buf)
+        };
+        let response = tokio::time::timeout(self.timeout, read)
+            .await
+            .map_err(|_| Rfc2136Error::Timeout)??;
+
+        let message = Message::parse(&response)?;
+        if message.id != id || message.flags & 0x8000 == 0 {
+            // Not a response to the message
+            return Err(Rfc2136Error::Malformed);
+        }
+
+        if let Some(signer) = signer {
+            signer.verify(&response, &message)?;
+        }
+        Ok((response, message))
+    }
+}
+
+/// Signs a message and verifies the signatures of its responses (RFC 8945 §5).
+struct Signer {
+    key: TsigKey,
+    /// The MAC of the request or of the last signed response.
+    prior_mac: Vec<u8>,
+    /// The unsigned responses received since the last signed one, which are covered by the next signature.
+    pending: Vec<u8>,
+    /// The number of unsigned responses since the last signed one.
+    unsigned: usize,
+    /// Whether a signed response was received yet.
+    verified_first: bool,
+}
+
+impl Signer {
+    /// Appends a TSIG record to the message.
+    fn sign(key: &TsigKey, message: &mut Vec<u8>) -> Self {
+        let id = u16::from_be_bytes([message[0], message[1]]);
+        let time = now();
+
+        let mut mac = key.mac();
+        mac.update(message);
+        mac.update(&tsig_variables(key, time, FUDGE, 0, &[]));
+        let digest = mac.finalize().into_bytes().to_vec();
+
+        let mut rdata = algorithm_name().as_wire_bytes().to_vec();
+        rdata.extend_from_slice(&time_bytes_of(time));
+        rdata.extend_from_slice(&FUDGE.to_be_bytes());
+        rdata.extend_from_slice(&(digest.len() as u16).to_be_bytes());
+        rdata.extend_from_slice(&digest);
+        rdata.extend_from_slice(&id.to_be_bytes());
+        // No error, no other data
+        rdata.extend_from_slice(&[0, 0, 0, 0]);
+
+        wire::push_record(
+            message,
+            &key.name,
+            TSIG_TYPE,
+            RecordClass::ANY.as_u16(),
+            0,
+            &rdata,
+        );
+        let arcount = u16::from_be_bytes([message[10], message[11]]) + 1;
+        message[10..12].copy_from_slice(&arcount.to_be_bytes());
+
+        Self {
+            key: key.clone(),
+            prior_mac: digest,
+            pending: Vec::new(),
+            unsigned: 0,
+            verified_first: false,
+        }
+    }
+
+    /// Verifies the TSIG record of a response.
+    ///
+    /// Within a zone transfer, responses after the first may be unsigned as long as a later one is signed (RFC 8945 §5.3.1).
+    fn verify(&mut self, response: &[u8], message: &Message) -> Result<(), Rfc2136Error> {
+        let Some(tsig) = message
+            .additional
+            .last()
+            .filter(|record| record.typ == TSIG_TYPE)
+        else {
+            if !self.verified_first || self.unsigned >= MAX_UNSIGNED {
+                return Err(Rfc2136Error::Unsigned);
+            }
+            self.pending.extend_from_slice(response);
+            self.unsigned += 1;
+            return Ok(());
+        };
+
+        let mut reader = Reader {
+            pos: tsig.rdata.start,
+            ..Reader::new(response)
+        };
+        let algorithm = reader.name()?;
+        let mut time = [0u8; 8];
+        time[2..].copy_from_slice(reader.bytes(6)?);
+        let time = u64::from_be_bytes(time);
+        let fudge = reader.u16()?;
+        let mac_len = reader.u16()? as usize;
+        let received_mac = reader.bytes(mac_len)?;
+        let original_id = reader.u16()?;
+        let error = reader.u16()?;
+        let other_len = reader.u16()? as usize;
+        let other = reader.bytes(other_len)?;
+        if reader.pos != tsig.rdata.end {
+            return Err(Rfc2136Error::Malformed);
+        }
+
+        if error != 0 {
+            return Err(Rfc2136Error::Tsig(error));
+        }
+        if tsig.name != self.key.name.to_dotted() || algorithm != ALGORITHM {
+            return Err(Rfc2136Error::BadSignature);
+        }
+
+        // The response as it was before the TSIG record was added
+        let mut unsigned = response[..tsig.start].to_vec();
+        unsigned[..2].copy_from_slice(&original_id.to_be_bytes());
+        let arcount = u16::from_be_bytes([unsigned[10], unsigned[11]]) - 1;
+        unsigned[10..12].copy_from_slice(&arcount.to_be_bytes());
+
+        let mut mac = self.key.mac();
+        mac.update(&(self.prior_mac.len() as u16).to_be_bytes());
+        mac.update(&self.prior_mac);
+        mac.update(&self.pending);
+        mac.update(&unsigned);
+        if self.verified_first {
+            // Subsequent messages only cover the timers (RFC 8945 §5.3.1)
+            mac.update(&time_bytes_of(time));
+            mac.update(&fudge.to_be_bytes());
+        } else {
+            mac.update(&tsig_variables(&self.key, time, fudge, error, other));
+        }
+        mac.verify_slice(received_mac)
+            .map_err(|_| Rfc2136Error::BadSignature)?;
+
+        if now().abs_diff(time) > u64::from(fudge) {
+            return Err(Rfc2136Error::Tsig(BADTIME));
+        }
+
+        self.prior_mac = received_mac.to_vec();
+        self.pending.clear();
+        self.unsigned = 0;
+        self.verified_first = true;
+        Ok(())
+    }
+}
+
+/// Represents an error that occured when communicating with the server.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+pub enum Rfc2136Error {
+    /// Indicates that the server could not be reached or closed the connection.
+    #[error("the server could not be reached: {0}")]
+    Io(io::ErrorKind),
+
+    /// Indicates that the server did not respond in time.
+    #[error("the server did not respond in time")]
+    Timeout,
+
+    /// Indicates that a response could not be parsed.
+    #[error("the response is malformed")]
+    Malformed,
+
+    /// Indicates that a zone or record name is not a valid domain name.
+    #[error("the name is not a valid domain name")]
+    InvalidName,
+
+    /// Indicates that record data cannot be encoded.
+    #[error("the record data cannot be encoded")]
+    InvalidData,
+
+    /// Indicates that the server answered with an error response code, e.g. 5 (REFUSED) or 9 (NOTAUTH).
+    #[error("the server answered with response code {0}")]
+    ResponseCode(u8),
+
+    /// Indicates that the server rejected the signature of the request, e.g. with 16 (BADSIG) or 17 (BADKEY).
+    #[error("the server rejected the signature with TSIG error {0}")]
+    Tsig(u16),
+
+    /// Indicates that the signature of a response does not match the key.
+    #[error("the signature of the response is invalid")]
+    BadSignature,
+
+    /// Indicates that a response to a signed request is not signed.
+    #[error("the response is not signed")]
+    Unsigned,
+}
+
+impl ClassifyError for Rfc2136Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Rfc2136Error::Io(_) | Rfc2136Error::Timeout => ErrorKind::Transient,
+            Rfc2136Error::InvalidName | Rfc2136Error::InvalidData => ErrorKind::Validation,
+            Rfc2136Error::ResponseCode(rcode) => match rcode {
+                // SERVFAIL
+                2 => ErrorKind::Transient,
+                // NXDOMAIN, NXRRSET
+                3 | 8 => ErrorKind::NotFound,
+                // REFUSED, NOTAUTH
+                5 | 9 => ErrorKind::Unauthorized,
+                // YXDOMAIN, YXRRSET
+                6 | 7 => ErrorKind::AlreadyExists,
+                // FORMERR, NOTZONE
+                1 | 10 => ErrorKind::Validation,
+                _ => ErrorKind::Other,
+            },
+            Rfc2136Error::Tsig(_) => ErrorKind::Unauthorized,
+            Rfc2136Error::Malformed | Rfc2136Error::BadSignature | Rfc2136Error::Unsigned => {
+                ErrorKind::Other
+            }
+        }
+    }
+}
+
+impl From<Malformed> for Rfc2136Error {
+    fn from(_: Malformed) -> Self {
+        Rfc2136Error::Malformed
+    }
+}
+
+impl From<io::Error> for Rfc2136Error {
+    fn from(err: io::Error) -> Self {
+        Rfc2136Error::Io(err.kind())
+    }
+}
+
+fn domain_name(name: &str) -> Result<DomainName, Rfc2136Error> {
+    DomainName::from_dotted(name).ok_or(Rfc2136Error::InvalidName)
+}
+
+fn algorithm_name() -> DomainName {
+    DomainName::from_dotted(ALGORITHM).expect("algorithm name is valid")
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Encodes a time as the 48-bit unsigned integer used by TSIG.
+fn time_bytes_of(time: u64) -> [u8; 6] {
+    time.to_be_bytes()[2..].try_into().unwrap()
+}
+
+/// Returns the TSIG variables covered by the MAC of a request or of the first response (RFC 8945 §4.3.3).
+fn tsig_variables(key: &TsigKey, time: u64, fudge: u16, error: u16, other: &[u8]) -> Vec<u8> {
+    let mut variables = key.name.as_wire_bytes().to_vec();
+    variables.extend_from_slice(&RecordClass::ANY.as_u16().to_be_bytes());
+    variables.extend_from_slice(&0u32.to_be_bytes());
+    variables.extend_from_slice(algorithm_name().as_wire_bytes());
+    variables.extend_from_slice(&time_bytes_of(time));
+    variables.extend_from_slice(&fudge.to_be_bytes());
+    variables.extend_from_slice(&error.to_be_bytes());
+    variables.extend_from_slice(&(other.len() as u16).to_be_bytes());
+    variables.extend_from_slice(other);
+    variables
+}
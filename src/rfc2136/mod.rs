@@ -0,0 +1,530 @@
+//! RFC 2136 dynamic update provider implementation.
+//!
+//! This module provides a [`Provider`] implementation for any authoritative nameserver accepting dynamic updates (RFC 2136),
+//! such as [BIND](https://www.isc.org/bind/), [Knot DNS](https://www.knot-dns.cz/) or PowerDNS.
+//! Records are read using full zone transfers (AXFR) and changed using UPDATE messages, both over TCP.
+//!
+//! # Authentication
+//!
+//! Messages are signed using TSIG (RFC 8945) with an HMAC-SHA256 key shared with the server, e.g. generated using `tsig-keygen` (BIND) or `keymgr -t` (Knot).
+//! The server has to allow both updates and zone transfers for the key.
+//! Responses are verified against the same key, so a forged answer is reported as [`api::Rfc2136Error::BadSignature`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use libdns::rfc2136::{api::TsigKey, Rfc2136Provider};
+//! use libdns::{CreateRecord, Provider, RecordData};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let key = TsigKey::from_base64("update-key", "c2VjcmV0IGtleSBzaGFyZWQgd2l0aCB0aGUgc2VydmVy")
+//!     .ok_or("invalid key")?;
+//! let provider = Rfc2136Provider::new("192.0.2.53:53".parse()?, Some(key));
+//!
+//! let zone = provider.get_zone("example.com").await?;
+//! zone.create_record("www", &RecordData::A("10.0.0.1".parse()?), 3600)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Zone IDs
+//!
+//! Zones are identified by their domain name, e.g. `example.com`.
+//! The protocol cannot enumerate the zones of a server, so [`Provider::list_zones`] returns the zones configured using [`Rfc2136Provider::with_zones`].
+//!
+//! # Record IDs
+//!
+//! DNS records have no IDs of their own. Records are identified by their content in the format `{host}/{type}/{value}`,
+//! e.g. `www/A/10.0.0.1`, so updating a record changes its ID.
+//!
+//! # Atomicity
+//!
+//! Updating a record deletes the old and adds the new record in the same UPDATE message, and [`ApplyChanges`] sends the whole changeset as one message.
+//! The server applies either all changes of a message or none of them.
+//!
+//! Updated and deleted records are looked up using a zone transfer first to report missing records as not found.
+//! A record deleted by someone else between the lookup and the update is silently ignored by the server.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "ddns")]
+use crate::ddns::DynamicDns;
+use crate::dynamic::{DynProvider, DynProviderAdapter};
+use crate::migrate::MigrationTarget;
+use crate::types::{to_ascii_name, MAX_TTL};
+use crate::wire::{self, Reader};
+use crate::{
+    normalize_host, AppendRecords, ApplyChanges, ApplyChangesError, Capabilities, ChangeError,
+    CreateRecord, CreateRecordError, DeleteRecord, DeleteRecordError, DeleteRecords, Provider,
+    Record, RecordChange, RecordData, RetrieveRecordError, RetrieveZoneError, SetRecords,
+    UpdateRecord, UpdateRecordError, Zone,
+};
+
+use api::Rfc2136Error;
+
+pub mod api;
+
+/// The record types that can be written using dynamic updates.
+const SUPPORTED_RECORD_TYPES: &[&str] = &["A", "AAAA", "CNAME", "MX", "NS", "PTR", "SRV", "TXT"];
+
+/// The response code of a request the server could not interpret (RFC 1035 §4.1.1).
+const FORMERR: u8 = 1;
+
+/// The response code of a refused request (RFC 1035 §4.1.1).
+const REFUSED: u8 = 5;
+
+/// The response code of a request the server is not authorized for (RFC 8945 §5.2).
+const NOTAUTH: u8 = 9;
+
+/// The response code of a name outside the zone of an update (RFC 2136 §2.2).
+const NOTZONE: u8 = 10;
+
+/// Provider for a primary nameserver accepting dynamic updates.
+#[derive(Debug, Clone)]
+pub struct Rfc2136Provider {
+    api_client: Arc<api::Client>,
+    zones: Vec<String>,
+}
+
+impl Rfc2136Provider {
+    /// Creates a provider for the nameserver at the given address, signing all messages with the key if one is given.
+    ///
+    /// Unsigned messages are only accepted by servers allowing updates by IP address, which is discouraged.
+    pub fn new(server: SocketAddr, key: Option<api::TsigKey>) -> Self {
+        Self {
+            api_client: Arc::new(api::Client::new(server, key)),
+            zones: Vec::new(),
+        }
+    }
+
+    /// Sets the zones returned by [`Provider::list_zones`].
+    pub fn with_zones(mut self, zones: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.zones = zones
+            .into_iter()
+            .map(|zone| zone_domain(zone.as_ref()))
+            .collect();
+        self
+    }
+
+    /// Sets how long to wait for the connection and for every single response (10 seconds by default).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.api_client = Arc::new(self.api_client.as_ref().clone().with_timeout(timeout));
+        self
+    }
+
+    fn zone(&self, domain: String) -> Rfc2136Zone {
+        Rfc2136Zone {
+            api_client: self.api_client.clone(),
+            domain,
+        }
+    }
+}
+
+impl From<Rfc2136Provider> for Box<dyn DynProvider> {
+    fn from(provider: Rfc2136Provider) -> Self {
+        Box::new(
+            DynProviderAdapter::new(provider)
+                .with_create_record()
+                .with_update_record()
+                .with_delete_record(),
+        )
+    }
+}
+
+impl Provider for Rfc2136Provider {
+    type Zone = Rfc2136Zone;
+    type CustomRetrieveError = Rfc2136Error;
+
+    /// Returns the zones configured using [`Rfc2136Provider::with_zones`], without contacting the server.
+    async fn list_zones(
+        &self,
+    ) -> Result<Vec<Self::Zone>, RetrieveZoneError<Self::CustomRetrieveError>> {
+        Ok(self
+            .zones
+            .iter()
+            .map(|domain| self.zone(domain.clone()))
+            .collect())
+    }
+
+    /// Returns the zone if the server is authoritative for it.
+    async fn get_zone(
+        &self,
+        zone_id: &str,
+    ) -> Result<Self::Zone, RetrieveZoneError<Self::CustomRetrieveError>> {
+        let zone_id: &str = &to_ascii_name(zone_id).ok_or(RetrieveZoneError::NotFound)?;
+        let domain = zone_domain(zone_id);
+        let authoritative = self
+            .api_client
+            .is_authoritative(&domain)
+            .await
+            .map_err(|err| match &err {
+                Rfc2136Error::InvalidName => RetrieveZoneError::NotFound,
+                _ if is_unauthorized(&err) => RetrieveZoneError::Unauthorized,
+                _ => RetrieveZoneError::Custom(err),
+            })?;
+
+        if !authoritative {
+            return Err(RetrieveZoneError::NotFound);
+        }
+        Ok(self.zone(domain))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
+}
+
+/// Represents a zone served by the nameserver.
+#[derive(Debug, Clone)]
+pub struct Rfc2136Zone {
+    api_client: Arc<api::Client>,
+    domain: String,
+}
+
+impl Rfc2136Zone {
+    /// Resolves a host relative to this zone to the fully qualified name, without trailing dot.
+    fn record_name(&self, host: &str) -> String {
+        match normalize_host(host, &self.domain).as_str() {
+            "@" => self.domain.clone(),
+            relative if !host.ends_with('.') => format!("{}.{}", relative, self.domain),
+            _ => host.trim_end_matches('.').to_string(),
+        }
+    }
+
+    /// Creates the record for the given fully qualified name, deriving its ID.
+    fn record(&self, name: &str, ttl: u32, data: RecordData) -> Record {
+        let host = normalize_host(name, &self.domain);
+        Record {
+            id: format!("{}/{}/{}", host, data.get_type(), data.get_value()),
+            host,
+            data,
+            ttl: u64::from(ttl),
+        }
+    }
+
+    /// Returns the record adding the data at the host results in, as it will be reported by zone transfers.
+    fn new_record(
+        &self,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, CreateRecordError<Rfc2136Error>> {
+        if !SUPPORTED_RECORD_TYPES.contains(&data.get_type()) {
+            return Err(CreateRecordError::UnsupportedType);
+        }
+        let ttl = u32::try_from(ttl)
+            .ok()
+            .filter(|ttl| *ttl <= MAX_TTL)
+            .ok_or(CreateRecordError::InvalidRecord)?;
+        let data = canonical(data).ok_or(CreateRecordError::InvalidRecord)?;
+
+        Ok(self.record(&self.record_name(host), ttl, data))
+    }
+
+    /// Looks up a record by its ID using a zone transfer.
+    async fn find_record(
+        &self,
+        record_id: &str,
+    ) -> Result<Record, RetrieveRecordError<Rfc2136Error>> {
+        self.list_records()
+            .await?
+            .into_iter()
+            .find(|record| record.id == record_id)
+            .ok_or(RetrieveRecordError::NotFound)
+    }
+
+    fn add(&self, record: &Record) -> api::Update {
+        api::Update::Add {
+            name: self.record_name(&record.host),
+            // Records are only built from TTLs that fit
+            ttl: record.ttl as u32,
+            data: record.data.clone(),
+        }
+    }
+
+    fn delete(&self, record: &Record) -> api::Update {
+        api::Update::Delete {
+            name: self.record_name(&record.host),
+            data: record.data.clone(),
+        }
+    }
+}
+
+impl Zone for Rfc2136Zone {
+    type CustomRetrieveError = Rfc2136Error;
+
+    fn id(&self) -> &str {
+        &self.domain
+    }
+
+    fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    async fn list_records(
+        &self,
+    ) -> Result<Vec<Record>, RetrieveRecordError<Self::CustomRetrieveError>> {
+        let records = self
+            .api_client
+            .transfer(&self.domain)
+            .await
+            .map_err(|err| match &err {
+                _ if is_unauthorized(&err) => RetrieveRecordError::Unauthorized,
+                _ => RetrieveRecordError::Custom(err),
+            })?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| self.record(&record.name, record.ttl, record.data))
+            .collect())
+    }
+
+    async fn get_record(
+        &self,
+        record_id: &str,
+    ) -> Result<Record, RetrieveRecordError<Self::CustomRetrieveError>> {
+        self.find_record(record_id).await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
+}
+
+impl CreateRecord for Rfc2136Zone {
+    type CustomCreateError = Rfc2136Error;
+
+    /// Adds the record to its record set.
+    ///
+    /// Adding a record that already exists succeeds without creating a duplicate, but sets the TTL of the whole record set.
+    async fn create_record(
+        &self,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, CreateRecordError<Self::CustomCreateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(CreateRecordError::InvalidRecord)?;
+        let record = self.new_record(host, data, ttl)?;
+
+        self.api_client
+            .update(&self.domain, &[self.add(&record)])
+            .await
+            .map_err(|err| match &err {
+                _ if is_unauthorized(&err) => CreateRecordError::Unauthorized,
+                _ if is_invalid(&err) => CreateRecordError::InvalidRecord,
+                _ => CreateRecordError::Custom(err),
+            })?;
+
+        Ok(record)
+    }
+}
+
+impl UpdateRecord for Rfc2136Zone {
+    type CustomUpdateError = Rfc2136Error;
+
+    async fn update_record(
+        &self,
+        record_id: &str,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, UpdateRecordError<Self::CustomUpdateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(UpdateRecordError::InvalidRecord)?;
+        let record = self.new_record(host, data, ttl).map_err(|err| match err {
+            CreateRecordError::Unauthorized => UpdateRecordError::Unauthorized,
+            CreateRecordError::UnsupportedType => UpdateRecordError::UnsupportedType,
+            CreateRecordError::InvalidRecord => UpdateRecordError::InvalidRecord,
+            CreateRecordError::Custom(e) => UpdateRecordError::Custom(e),
+        })?;
+        let existing = self.find_record(record_id).await.map_err(|err| match err {
+            RetrieveRecordError::Unauthorized => UpdateRecordError::Unauthorized,
+            RetrieveRecordError::NotFound => UpdateRecordError::NotFound,
+            RetrieveRecordError::Custom(e) => UpdateRecordError::Custom(e),
+        })?;
+
+        self.api_client
+            .update(&self.domain, &[self.delete(&existing), self.add(&record)])
+            .await
+            .map_err(|err| match &err {
+                _ if is_unauthorized(&err) => UpdateRecordError::Unauthorized,
+                _ if is_invalid(&err) => UpdateRecordError::InvalidRecord,
+                _ => UpdateRecordError::Custom(err),
+            })?;
+
+        Ok(record)
+    }
+}
+
+impl DeleteRecord for Rfc2136Zone {
+    type CustomDeleteError = Rfc2136Error;
+
+    async fn delete_record(
+        &self,
+        record_id: &str,
+    ) -> Result<(), DeleteRecordError<Self::CustomDeleteError>> {
+        let existing = self.find_record(record_id).await.map_err(|err| match err {
+            RetrieveRecordError::Unauthorized => DeleteRecordError::Unauthorized,
+            RetrieveRecordError::NotFound => DeleteRecordError::NotFound,
+            RetrieveRecordError::Custom(e) => DeleteRecordError::Custom(e),
+        })?;
+
+        self.api_client
+            .update(&self.domain, &[self.delete(&existing)])
+            .await
+            .map_err(|err| match &err {
+                _ if is_unauthorized(&err) => DeleteRecordError::Unauthorized,
+                _ => DeleteRecordError::Custom(err),
+            })
+    }
+}
+
+impl ApplyChanges for Rfc2136Zone {
+    type CustomApplyError = Rfc2136Error;
+
+    async fn apply_changes(
+        &self,
+        changes: &[RecordChange],
+    ) -> Result<Vec<Record>, ApplyChangesError<Self::CustomApplyError>> {
+        let references_records = changes
+            .iter()
+            .any(|change| !matches!(change, RecordChange::Create { .. }));
+        let records = if references_records {
+            self.list_records().await.map_err(|err| ApplyChangesError {
+                index: None,
+                rolled_back: true,
+                cause: err.into(),
+            })?
+        } else {
+            Vec::new()
+        };
+
+        let mut updates = Vec::with_capacity(changes.len());
+        let mut results = Vec::new();
+        for (index, change) in changes.iter().enumerate() {
+            let failed = |cause: ChangeError<Rfc2136Error>| ApplyChangesError {
+                index: Some(index),
+                rolled_back: true,
+                cause,
+            };
+            let existing = |record_id: &str| {
+                records
+                    .iter()
+                    .find(|record| record.id == record_id)
+                    .ok_or_else(|| failed(ChangeError::NotFound))
+            };
+
+            match change {
+                RecordChange::Create { host, data, ttl } => {
+                    let record = self
+                        .new_record(host, data, *ttl)
+                        .map_err(|err| failed(err.into()))?;
+                    updates.push(self.add(&record));
+                    results.push(record);
+                }
+                RecordChange::Update {
+                    record_id,
+                    host,
+                    data,
+                    ttl,
+                } => {
+                    let before = existing(record_id)?;
+                    let after = self
+                        .new_record(host, data, *ttl)
+                        .map_err(|err| failed(err.into()))?;
+                    updates.push(self.delete(before));
+                    updates.push(self.add(&after));
+                    results.push(after);
+                }
+                RecordChange::Delete { record_id } => {
+                    updates.push(self.delete(existing(record_id)?));
+                }
+            }
+        }
+
+        if updates.is_empty() {
+            return Ok(results);
+        }
+        self.api_client
+            .update(&self.domain, &updates)
+            .await
+            .map_err(|err| ApplyChangesError {
+                index: None,
+                // The server rejects the whole message, but a lost response leaves the outcome unknown
+                rolled_back: matches!(
+                    err,
+                    Rfc2136Error::ResponseCode(_)
+                        | Rfc2136Error::Tsig(_)
+                        | Rfc2136Error::InvalidName
+                        | Rfc2136Error::InvalidData
+                ),
+                cause: match &err {
+                    _ if is_unauthorized(&err) => ChangeError::Unauthorized,
+                    _ if is_invalid(&err) => ChangeError::InvalidRecord,
+                    _ => ChangeError::Custom(err),
+                },
+            })?;
+
+        Ok(results)
+    }
+}
+
+impl AppendRecords for Rfc2136Zone {}
+
+impl SetRecords for Rfc2136Zone {}
+
+impl DeleteRecords for Rfc2136Zone {}
+
+#[cfg(feature = "ddns")]
+impl DynamicDns for Rfc2136Zone {}
+
+/// Describes what the provider and all of its zones support.
+fn capabilities() -> Capabilities {
+    Capabilities {
+        // Only the configured zones are listed
+        list_zones: true,
+        create_zone: false,
+        delete_zone: false,
+        create_record: true,
+        update_record: true,
+        delete_record: true,
+        // The old record is deleted and the new one added in the same UPDATE message
+        atomic_update: true,
+        ttl_range: 0..=MAX_TTL as u64,
+        ..Capabilities::default()
+    }
+    .with_record_types(SUPPORTED_RECORD_TYPES)
+}
+
+impl MigrationTarget for Rfc2136Zone {}
+
+/// Returns the zone domain in lowercase, without trailing dot.
+fn zone_domain(zone: &str) -> String {
+    zone.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Returns the data as it is decoded from the wire, e.g. with lowercase names and TXT values without quotes.
+fn canonical(data: &RecordData) -> Option<RecordData> {
+    let (typ, rdata) = wire::encode_rdata(data)?;
+    wire::decode_rdata(&mut Reader::new(&rdata), typ, rdata.len()).ok()
+}
+
+/// Returns whether the server refused the request or did not accept its signature.
+fn is_unauthorized(err: &Rfc2136Error) -> bool {
+    matches!(
+        err,
+        Rfc2136Error::Tsig(_) | Rfc2136Error::ResponseCode(REFUSED | NOTAUTH)
+    )
+}
+
+/// Returns whether the records of an update could not be encoded or were rejected by the server.
+fn is_invalid(err: &Rfc2136Error) -> bool {
+    matches!(
+        err,
+        Rfc2136Error::InvalidName
+            | Rfc2136Error::InvalidData
+            | Rfc2136Error::ResponseCode(FORMERR | NOTZONE)
+    )
+}
@@ -7,6 +7,9 @@
 use reqwest::Client as HttpClient;
 use serde::Deserialize;
 
+use crate::retry::{RequestBuilderExt, RetryPolicy};
+use crate::{ClassifyError, ErrorKind};
+
 /// The default port for Technitium DNS Server web interface.
 pub const DEFAULT_PORT: u16 = 5380;
 
@@ -33,6 +36,7 @@ pub struct Client {
     http_client: HttpClient,
     base_url: String,
     token: String,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
@@ -45,12 +49,23 @@ impl Client {
     /// - Logging in via `/api/user/login`
     /// - Creating a non-expiring API token via `/api/user/createToken`
     pub fn new(base_url: &str, token: &str) -> Result<Self, reqwest::Error> {
-        let http_client = HttpClient::builder().build()?;
-        Ok(Self {
+        let http_client = Self::http_client_builder().build()?;
+        Ok(Self::from_http_client(http_client, base_url, token))
+    }
+
+    /// Returns the builder of the HTTP client used unless a preconfigured one is given.
+    pub(crate) fn http_client_builder() -> reqwest::ClientBuilder {
+        HttpClient::builder()
+    }
+
+    /// Creates a new API client sending its requests using the given HTTP client.
+    pub(crate) fn from_http_client(http_client: HttpClient, base_url: &str, token: &str) -> Self {
+        Self {
             http_client,
             base_url: base_url.trim_end_matches('/').to_string(),
             token: token.to_string(),
-        })
+            retry_policy: RetryPolicy::default(),
+        }
     }
 
     /// Creates a new API client by logging in with username and password.
@@ -58,7 +73,9 @@ impl Client {
     /// This will create a session token that expires after 30 minutes of inactivity.
     /// For long-running applications, consider using a non-expiring API token instead.
     pub async fn login(base_url: &str, username: &str, password: &str) -> Result<Self, ApiError> {
-        let http_client = HttpClient::builder().build().map_err(ApiError::Request)?;
+        let http_client = Self::http_client_builder()
+            .build()
+            .map_err(ApiError::Request)?;
         let base_url = base_url.trim_end_matches('/').to_string();
 
         let response: LoginResponse = http_client
@@ -78,6 +95,7 @@ impl Client {
                 http_client,
                 base_url,
                 token: response.token.ok_or(ApiError::MissingToken)?,
+                retry_policy: RetryPolicy::default(),
             }),
             ApiStatus::InvalidToken => Err(ApiError::InvalidToken),
             ApiStatus::TwoFactorRequired => Err(ApiError::TwoFactorRequired),
@@ -87,6 +105,18 @@ impl Client {
         }
     }
 
+    /// Replaces the HTTP client, e.g. with one using custom timeouts, proxies or root certificates.
+    pub fn with_http_client(mut self, http_client: HttpClient) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
     fn url_with_token(&self, path: &str) -> String {
         if path.contains('?') {
             format!("{}{}&token={}", self.base_url, path, self.token)
@@ -100,7 +130,7 @@ impl Client {
         let response: ApiResponse<ZonesResponse> = self
             .http_client
             .get(self.url_with_token("/api/zones/list"))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(ApiError::Request)?
             .json()
@@ -117,7 +147,7 @@ impl Client {
         let response: ApiResponse<ZoneOptionsResponse> = self
             .http_client
             .get(self.url_with_token(&format!("/api/zones/options/get?zone={}", zone)))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(ApiError::Request)?
             .json()
@@ -132,7 +162,7 @@ impl Client {
         let response: ApiResponse<CreateZoneResponse> = self
             .http_client
             .get(self.url_with_token(&format!("/api/zones/create?zone={}&type=Primary", zone)))
-            .send()
+            .send_with_retry(&self.retry_policy, false)
             .await
             .map_err(ApiError::Request)?
             .json()
@@ -147,7 +177,7 @@ impl Client {
         let response: EmptyApiResponse = self
             .http_client
             .get(self.url_with_token(&format!("/api/zones/delete?zone={}", zone)))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(ApiError::Request)?
             .json()
@@ -162,7 +192,7 @@ impl Client {
         let response: EmptyApiResponse = self
             .http_client
             .get(self.url_with_token(&format!("/api/zones/enable?zone={}", zone)))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(ApiError::Request)?
             .json()
@@ -177,7 +207,7 @@ impl Client {
         let response: EmptyApiResponse = self
             .http_client
             .get(self.url_with_token(&format!("/api/zones/disable?zone={}", zone)))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(ApiError::Request)?
             .json()
@@ -195,7 +225,7 @@ impl Client {
                 "/api/zones/records/get?domain={}&zone={}&listZone=true",
                 zone, zone
             )))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(ApiError::Request)?
             .json()
@@ -213,7 +243,7 @@ impl Client {
                 "/api/zones/records/get?domain={}&zone={}",
                 domain, zone
             )))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(ApiError::Request)?
             .json()
@@ -296,6 +326,11 @@ impl Client {
             RecordParams::DNAME { dname } => {
                 url.push_str(&format!("&dname={}", dname));
             }
+            RecordParams::SSHFP { .. } | RecordParams::TLSA { .. } | RecordParams::SVCB { .. } => {
+                for (key, value) in record_params.query_pairs() {
+                    url.push_str(&format!("&{}={}", key, url_encode(&value)));
+                }
+            }
             RecordParams::Other { value } => {
                 url.push_str(&format!("&rdata={}", url_encode(value)));
             }
@@ -304,7 +339,57 @@ impl Client {
         let response: ApiResponse<AddRecordResponse> = self
             .http_client
             .get(self.url_with_token(&url))
-            .send()
+            .send_with_retry(&self.retry_policy, false)
+            .await
+            .map_err(ApiError::Request)?
+            .json()
+            .await
+            .map_err(ApiError::Request)?;
+
+        response.into_result()
+    }
+
+    /// Updates an existing DNS record in place.
+    ///
+    /// The record is identified by `domain`, `record_type` and `old_params`; it is
+    /// moved to `new_domain` and its data is replaced with `new_params`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_record(
+        &self,
+        zone: &str,
+        domain: &str,
+        new_domain: &str,
+        record_type: &str,
+        ttl: u64,
+        old_params: &RecordParams,
+        new_params: &RecordParams,
+    ) -> Result<UpdateRecordResponse, ApiError> {
+        let mut url = format!(
+            "/api/zones/records/update?domain={}&newDomain={}&zone={}&type={}&ttl={}",
+            domain, new_domain, zone, record_type, ttl
+        );
+
+        // CNAME and DNAME records are unique per name, so only the new value is sent
+        if !matches!(
+            old_params,
+            RecordParams::CNAME { .. } | RecordParams::DNAME { .. }
+        ) {
+            for (key, value) in old_params.query_pairs() {
+                url.push_str(&format!("&{}={}", key, url_encode(&value)));
+            }
+        }
+        for (key, value) in new_params.query_pairs() {
+            let key = match new_params {
+                RecordParams::CNAME { .. } | RecordParams::DNAME { .. } => key.to_string(),
+                _ => format!("new{}{}", key[..1].to_uppercase(), &key[1..]),
+            };
+            url.push_str(&format!("&{}={}", key, url_encode(&value)));
+        }
+
+        let response: ApiResponse<UpdateRecordResponse> = self
+            .http_client
+            .get(self.url_with_token(&url))
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(ApiError::Request)?
             .json()
@@ -386,6 +471,11 @@ impl Client {
             RecordParams::DNAME { dname } => {
                 url.push_str(&format!("&dname={}", dname));
             }
+            RecordParams::SSHFP { .. } | RecordParams::TLSA { .. } | RecordParams::SVCB { .. } => {
+                for (key, value) in record_params.query_pairs() {
+                    url.push_str(&format!("&{}={}", key, url_encode(&value)));
+                }
+            }
             RecordParams::Other { value } => {
                 url.push_str(&format!("&rdata={}", url_encode(value)));
             }
@@ -394,7 +484,7 @@ impl Client {
         let response: EmptyApiResponse = self
             .http_client
             .get(self.url_with_token(&url))
-            .send()
+            .send_with_retry(&self.retry_policy, true)
             .await
             .map_err(ApiError::Request)?
             .json()
@@ -447,6 +537,23 @@ pub enum RecordParams {
         digest_type: String,
         digest: String,
     },
+    SSHFP {
+        algorithm: String,
+        fingerprint_type: String,
+        fingerprint: String,
+    },
+    TLSA {
+        certificate_usage: String,
+        selector: String,
+        matching_type: String,
+        certificate_association_data: String,
+    },
+    SVCB {
+        svc_priority: u16,
+        svc_target_name: String,
+        /// Parameters as alternating keys and values separated by `|`.
+        svc_params: String,
+    },
     DNAME {
         dname: String,
     },
@@ -455,6 +562,94 @@ pub enum RecordParams {
     },
 }
 
+impl RecordParams {
+    /// Returns the query parameter names and values identifying the record data.
+    pub fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        match self {
+            RecordParams::A { ip_address } | RecordParams::AAAA { ip_address } => {
+                vec![("ipAddress", ip_address.clone())]
+            }
+            RecordParams::CNAME { cname } => vec![("cname", cname.clone())],
+            RecordParams::MX {
+                preference,
+                exchange,
+            } => vec![
+                ("preference", preference.to_string()),
+                ("exchange", exchange.clone()),
+            ],
+            RecordParams::NS { name_server } => vec![("nameServer", name_server.clone())],
+            RecordParams::TXT { text } => vec![("text", text.clone())],
+            RecordParams::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => vec![
+                ("priority", priority.to_string()),
+                ("weight", weight.to_string()),
+                ("port", port.to_string()),
+                ("target", target.clone()),
+            ],
+            RecordParams::PTR { ptr_name } => vec![("ptrName", ptr_name.clone())],
+            RecordParams::CAA { flags, tag, value } => vec![
+                ("flags", flags.to_string()),
+                ("tag", tag.clone()),
+                ("value", value.clone()),
+            ],
+            RecordParams::DS {
+                key_tag,
+                algorithm,
+                digest_type,
+                digest,
+            } => vec![
+                ("keyTag", key_tag.to_string()),
+                ("algorithm", algorithm.clone()),
+                ("digestType", digest_type.clone()),
+                ("digest", digest.clone()),
+            ],
+            RecordParams::SSHFP {
+                algorithm,
+                fingerprint_type,
+                fingerprint,
+            } => vec![
+                ("sshfpAlgorithm", algorithm.clone()),
+                ("sshfpFingerprintType", fingerprint_type.clone()),
+                ("sshfpFingerprint", fingerprint.clone()),
+            ],
+            RecordParams::TLSA {
+                certificate_usage,
+                selector,
+                matching_type,
+                certificate_association_data,
+            } => vec![
+                ("tlsaCertificateUsage", certificate_usage.clone()),
+                ("tlsaSelector", selector.clone()),
+                ("tlsaMatchingType", matching_type.clone()),
+                (
+                    "tlsaCertificateAssociationData",
+                    certificate_association_data.clone(),
+                ),
+            ],
+            RecordParams::SVCB {
+                svc_priority,
+                svc_target_name,
+                svc_params,
+            } => {
+                let mut pairs = vec![
+                    ("svcPriority", svc_priority.to_string()),
+                    ("svcTargetName", svc_target_name.clone()),
+                ];
+                if !svc_params.is_empty() {
+                    pairs.push(("svcParams", svc_params.clone()));
+                }
+                pairs
+            }
+            RecordParams::DNAME { dname } => vec![("dname", dname.clone())],
+            RecordParams::Other { value } => vec![("rdata", value.clone())],
+        }
+    }
+}
+
 /// API response status as documented by Technitium.
 ///
 /// The `status` property can have the following values:
@@ -503,6 +698,31 @@ pub enum ApiError {
     InvalidRecord,
 }
 
+impl ClassifyError for ApiError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            ApiError::Request(e) => e.kind(),
+            // The API only reports error messages, see `classify_error_message`
+            ApiError::ApiStatus(msg) => {
+                let msg_lower = msg.to_lowercase();
+                if msg_lower.contains("already exists") {
+                    ErrorKind::AlreadyExists
+                } else if msg_lower.contains("invalid") {
+                    ErrorKind::Validation
+                } else {
+                    ErrorKind::Other
+                }
+            }
+            ApiError::MissingToken => ErrorKind::Other,
+            ApiError::InvalidToken | ApiError::TwoFactorRequired | ApiError::Unauthorized => {
+                ErrorKind::Unauthorized
+            }
+            ApiError::NotFound => ErrorKind::NotFound,
+            ApiError::InvalidDomainName | ApiError::InvalidRecord => ErrorKind::Validation,
+        }
+    }
+}
+
 /// Generic API response wrapper.
 #[derive(Debug, Deserialize)]
 pub struct ApiResponse<T> {
@@ -721,6 +941,30 @@ pub enum RecordData {
         digest_type: String,
         digest: String,
     },
+    SSHFP {
+        algorithm: String,
+        #[serde(rename = "fingerprintType")]
+        fingerprint_type: String,
+        fingerprint: String,
+    },
+    TLSA {
+        #[serde(rename = "certificateUsage")]
+        certificate_usage: String,
+        selector: String,
+        #[serde(rename = "matchingType")]
+        matching_type: String,
+        #[serde(rename = "certificateAssociationData")]
+        certificate_association_data: String,
+    },
+    /// SVCB or HTTPS record data.
+    SVCB {
+        #[serde(rename = "svcPriority")]
+        svc_priority: u16,
+        #[serde(rename = "svcTargetName")]
+        svc_target_name: String,
+        #[serde(rename = "svcParams", default)]
+        svc_params: serde_json::Map<String, serde_json::Value>,
+    },
     DNAME {
         dname: String,
     },
@@ -767,6 +1011,41 @@ impl RecordData {
                 digest_type,
                 digest,
             } => format!("{} {} {} {}", key_tag, algorithm, digest_type, digest),
+            RecordData::SSHFP {
+                algorithm,
+                fingerprint_type,
+                fingerprint,
+            } => format!("{} {} {}", algorithm, fingerprint_type, fingerprint),
+            RecordData::TLSA {
+                certificate_usage,
+                selector,
+                matching_type,
+                certificate_association_data,
+            } => format!(
+                "{} {} {} {}",
+                certificate_usage, selector, matching_type, certificate_association_data
+            ),
+            RecordData::SVCB {
+                svc_priority,
+                svc_target_name,
+                svc_params,
+            } => {
+                let target = match svc_target_name.as_str() {
+                    "" => ".",
+                    target => target,
+                };
+                let params: Vec<String> = svc_params
+                    .iter()
+                    .map(|(key, value)| match value.as_str() {
+                        Some("") => key.clone(),
+                        Some(value) => format!("{}={}", key, value),
+                        None => format!("{}={}", key, value),
+                    })
+                    .collect();
+                format!("{} {} {}", svc_priority, target, params.join(" "))
+                    .trim_end()
+                    .to_string()
+            }
             RecordData::DNAME { dname } => dname.clone(),
             RecordData::Other(v) => v.to_string(),
         }
@@ -825,6 +1104,44 @@ impl RecordData {
                 digest_type: digest_type.clone(),
                 digest: digest.clone(),
             },
+            RecordData::SSHFP {
+                algorithm,
+                fingerprint_type,
+                fingerprint,
+            } => RecordParams::SSHFP {
+                algorithm: algorithm.clone(),
+                fingerprint_type: fingerprint_type.clone(),
+                fingerprint: fingerprint.clone(),
+            },
+            RecordData::TLSA {
+                certificate_usage,
+                selector,
+                matching_type,
+                certificate_association_data,
+            } => RecordParams::TLSA {
+                certificate_usage: certificate_usage.clone(),
+                selector: selector.clone(),
+                matching_type: matching_type.clone(),
+                certificate_association_data: certificate_association_data.clone(),
+            },
+            RecordData::SVCB {
+                svc_priority,
+                svc_target_name,
+                svc_params,
+            } => RecordParams::SVCB {
+                svc_priority: *svc_priority,
+                svc_target_name: svc_target_name.clone(),
+                svc_params: svc_params
+                    .iter()
+                    .flat_map(|(key, value)| {
+                        let value = value
+                            .as_str()
+                            .map_or_else(|| value.to_string(), String::from);
+                        [key.clone(), value]
+                    })
+                    .collect::<Vec<_>>()
+                    .join("|"),
+            },
             RecordData::DNAME { dname } => RecordParams::DNAME {
                 dname: dname.clone(),
             },
@@ -842,3 +1159,11 @@ pub struct AddRecordResponse {
     #[serde(rename = "addedRecord")]
     pub added_record: Record,
 }
+
+/// Update record response.
+#[derive(Debug, Deserialize)]
+pub struct UpdateRecordResponse {
+    pub zone: ZoneInfo,
+    #[serde(rename = "updatedRecord")]
+    pub updated_record: Record,
+}
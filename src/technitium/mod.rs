@@ -39,14 +39,25 @@
 //!
 //! Technitium doesn't provide unique record IDs. Instead, records are identified by a combination
 //! of domain name, record type, and record data. This implementation generates a composite ID
-//! in the format `{domain}:{type}:{data_hash}` for compatibility with the generic Record interface.
+//! in the format `{domain}:{type}:{fingerprint}` for compatibility with the generic Record interface,
+//! where the last part is the record's [`Fingerprint`], so IDs stay valid across restarts and Rust releases.
 
 use std::sync::Arc;
-
+use std::time::Duration;
+
+#[cfg(feature = "ddns")]
+use crate::ddns::DynamicDns;
+use crate::dynamic::{DynProvider, DynProviderAdapter};
+use crate::http::HttpOptions;
+use crate::migrate::MigrationTarget;
+use crate::retry::RetryPolicy;
+use crate::types::{to_ascii_name, MAX_TTL};
 use crate::{
+    apply_changes_sequentially, AppendRecords, ApplyChanges, ApplyChangesError, Capabilities,
     CreateRecord, CreateRecordError, CreateZone, CreateZoneError, DeleteRecord, DeleteRecordError,
-    DeleteZone, DeleteZoneError, Provider, Record, RecordData, RetrieveRecordError,
-    RetrieveZoneError, Zone,
+    DeleteRecords, DeleteZone, DeleteZoneError, Fingerprint, Provider, Record, RecordChange,
+    RecordData, RetrieveRecordError, RetrieveZoneError, SetRecords, UpdateRecord,
+    UpdateRecordError, Zone,
 };
 
 pub mod api;
@@ -57,6 +68,56 @@ const SUPPORTED_RECORD_TYPES: &[&str] = &[
     "HTTPS", "URI", "CAA", "ANAME", "FWD", "APP",
 ];
 
+/// DNSSEC algorithm mnemonics as named by Technitium (RFC 8624 §3.1).
+const DNSSEC_ALGORITHMS: &[(&str, u8)] = &[
+    ("RSAMD5", 1),
+    ("DSA", 3),
+    ("RSASHA1", 5),
+    ("DSA_NSEC3_SHA1", 6),
+    ("RSASHA1_NSEC3_SHA1", 7),
+    ("RSASHA256", 8),
+    ("RSASHA512", 10),
+    ("ECC_GOST", 12),
+    ("ECDSAP256SHA256", 13),
+    ("ECDSAP384SHA384", 14),
+    ("ED25519", 15),
+    ("ED448", 16),
+];
+
+/// DS digest type mnemonics as named by Technitium (RFC 8624 §3.3).
+const DS_DIGEST_TYPES: &[(&str, u8)] = &[
+    ("SHA1", 1),
+    ("SHA256", 2),
+    ("GOST_R_34_11_94", 3),
+    ("SHA384", 4),
+];
+
+/// SSHFP algorithm mnemonics as named by Technitium.
+const SSHFP_ALGORITHMS: &[(&str, u8)] = &[
+    ("RSA", 1),
+    ("DSA", 2),
+    ("ECDSA", 3),
+    ("ED25519", 4),
+    ("ED448", 6),
+];
+
+/// SSHFP fingerprint type mnemonics as named by Technitium.
+const SSHFP_FINGERPRINT_TYPES: &[(&str, u8)] = &[("SHA1", 1), ("SHA256", 2)];
+
+/// TLSA certificate usage mnemonics as named by Technitium (RFC 7218).
+const TLSA_CERTIFICATE_USAGES: &[(&str, u8)] = &[
+    ("PKIX_TA", 0),
+    ("PKIX_EE", 1),
+    ("DANE_TA", 2),
+    ("DANE_EE", 3),
+];
+
+/// TLSA selector mnemonics as named by Technitium (RFC 7218).
+const TLSA_SELECTORS: &[(&str, u8)] = &[("CERT", 0), ("SPKI", 1)];
+
+/// TLSA matching type mnemonics as named by Technitium (RFC 7218).
+const TLSA_MATCHING_TYPES: &[(&str, u8)] = &[("FULL", 0), ("SHA2_256", 1), ("SHA2_512", 2)];
+
 /// Technitium DNS Server provider.
 ///
 /// This provider implements DNS zone and record management for Technitium DNS Server.
@@ -90,10 +151,30 @@ impl TechnitiumProvider {
     /// let provider = TechnitiumProvider::new("http://localhost:5380", "my-api-token").unwrap();
     /// ```
     pub fn new(base_url: &str, token: &str) -> Result<Self, reqwest::Error> {
-        let api_client = api::Client::new(base_url, token)?;
-        Ok(Self {
-            api_client: Arc::new(api_client),
-        })
+        Self::builder(base_url, token).build()
+    }
+
+    /// Returns a builder to configure the HTTP client and retry policy of the provider.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use libdns::technitium::TechnitiumProvider;
+    ///
+    /// let provider = TechnitiumProvider::builder("https://dns.example.com", "my-api-token")
+    ///     .with_timeout(Duration::from_secs(10))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(base_url: &str, token: &str) -> TechnitiumProviderBuilder {
+        TechnitiumProviderBuilder {
+            base_url: base_url.to_string(),
+            token: token.to_string(),
+            http: HttpOptions::default(),
+            retry_policy: RetryPolicy::default(),
+        }
     }
 
     /// Creates a new Technitium DNS provider by logging in with username and password.
@@ -116,6 +197,88 @@ impl TechnitiumProvider {
             api_client: Arc::new(api_client),
         })
     }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        let api_client = self.api_client.as_ref().clone().with_retry_policy(policy);
+        Self {
+            api_client: Arc::new(api_client),
+        }
+    }
+}
+
+/// Builder for a [`TechnitiumProvider`], created using [`TechnitiumProvider::builder`].
+#[derive(Clone)]
+pub struct TechnitiumProviderBuilder {
+    base_url: String,
+    token: String,
+    http: HttpOptions,
+    retry_policy: RetryPolicy,
+}
+
+impl TechnitiumProviderBuilder {
+    /// Sends all requests using the given HTTP client, e.g. one with custom root certificates or connection pool limits.
+    ///
+    /// The timeout, proxy and user agent set on the builder are ignored in that case.
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http.client = Some(client);
+        self
+    }
+
+    /// Sets the timeout of every request, unlimited by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends all requests through the given proxy.
+    pub fn with_proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.http.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets the `User-Agent` header of every request.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.http.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Creates the provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn build(self) -> Result<TechnitiumProvider, reqwest::Error> {
+        let http_client = self.http.build(api::Client::http_client_builder())?;
+        let api_client = api::Client::from_http_client(http_client, &self.base_url, &self.token)
+            .with_retry_policy(self.retry_policy);
+        Ok(TechnitiumProvider {
+            api_client: Arc::new(api_client),
+        })
+    }
+}
+
+impl From<TechnitiumProvider> for Box<dyn DynProvider> {
+    fn from(provider: TechnitiumProvider) -> Self {
+        Box::new(
+            DynProviderAdapter::new(provider)
+                .with_create_zone()
+                .with_delete_zone()
+                .with_create_record()
+                .with_update_record()
+                .with_delete_record(),
+        )
+    }
 }
 
 impl Provider for TechnitiumProvider {
@@ -126,12 +289,15 @@ impl Provider for TechnitiumProvider {
         &self,
         zone_id: &str,
     ) -> Result<Self::Zone, RetrieveZoneError<Self::CustomRetrieveError>> {
+        let zone_id: &str = &to_ascii_name(zone_id).ok_or(RetrieveZoneError::NotFound)?;
         let response = self
             .api_client
             .get_zone(zone_id)
             .await
             .map_err(|err| match &err {
-                api::ApiError::Unauthorized => RetrieveZoneError::Unauthorized,
+                api::ApiError::Unauthorized
+                | api::ApiError::InvalidToken
+                | api::ApiError::TwoFactorRequired => RetrieveZoneError::Unauthorized,
                 api::ApiError::NotFound => RetrieveZoneError::NotFound,
                 _ => RetrieveZoneError::Custom(err),
             })?;
@@ -152,7 +318,9 @@ impl Provider for TechnitiumProvider {
             .list_zones()
             .await
             .map_err(|err| match &err {
-                api::ApiError::Unauthorized => RetrieveZoneError::Unauthorized,
+                api::ApiError::Unauthorized
+                | api::ApiError::InvalidToken
+                | api::ApiError::TwoFactorRequired => RetrieveZoneError::Unauthorized,
                 api::ApiError::NotFound => RetrieveZoneError::NotFound,
                 _ => RetrieveZoneError::Custom(err),
             })?;
@@ -168,6 +336,10 @@ impl Provider for TechnitiumProvider {
             })
             .collect())
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl CreateZone for TechnitiumProvider {
@@ -177,12 +349,15 @@ impl CreateZone for TechnitiumProvider {
         &self,
         domain: &str,
     ) -> Result<Self::Zone, CreateZoneError<Self::CustomCreateError>> {
+        let domain: &str = &to_ascii_name(domain).ok_or(CreateZoneError::InvalidDomainName)?;
         let response = self
             .api_client
             .create_zone(domain)
             .await
             .map_err(|err| match &err {
-                api::ApiError::Unauthorized => CreateZoneError::Unauthorized,
+                api::ApiError::Unauthorized
+                | api::ApiError::InvalidToken
+                | api::ApiError::TwoFactorRequired => CreateZoneError::Unauthorized,
                 api::ApiError::InvalidDomainName => CreateZoneError::InvalidDomainName,
                 _ => CreateZoneError::Custom(err),
             })?;
@@ -207,7 +382,9 @@ impl DeleteZone for TechnitiumProvider {
             .delete_zone(zone_id)
             .await
             .map_err(|err| match &err {
-                api::ApiError::Unauthorized => DeleteZoneError::Unauthorized,
+                api::ApiError::Unauthorized
+                | api::ApiError::InvalidToken
+                | api::ApiError::TwoFactorRequired => DeleteZoneError::Unauthorized,
                 api::ApiError::NotFound => DeleteZoneError::NotFound,
                 _ => DeleteZoneError::Custom(err),
             })
@@ -243,6 +420,17 @@ impl TechnitiumZone {
     pub async fn disable(&self) -> Result<(), api::ApiError> {
         self.api_client.disable_zone(&self.name).await
     }
+
+    /// Resolves a host relative to this zone to the fully qualified domain Technitium expects.
+    fn record_domain(&self, host: &str) -> String {
+        if host == "@" || host.is_empty() {
+            self.name.clone()
+        } else if host.ends_with('.') {
+            host.trim_end_matches('.').to_string()
+        } else {
+            format!("{}.{}", host, self.name)
+        }
+    }
 }
 
 impl Zone for TechnitiumZone {
@@ -264,7 +452,9 @@ impl Zone for TechnitiumZone {
                 .list_records(&self.name)
                 .await
                 .map_err(|err| match &err {
-                    api::ApiError::Unauthorized => RetrieveRecordError::Unauthorized,
+                    api::ApiError::Unauthorized
+                    | api::ApiError::InvalidToken
+                    | api::ApiError::TwoFactorRequired => RetrieveRecordError::Unauthorized,
                     api::ApiError::NotFound => RetrieveRecordError::NotFound,
                     _ => RetrieveRecordError::Custom(err),
                 })?;
@@ -276,7 +466,7 @@ impl Zone for TechnitiumZone {
         &self,
         record_id: &str,
     ) -> Result<Record, RetrieveRecordError<Self::CustomRetrieveError>> {
-        // Parse the composite record ID: "domain:type:data_hash"
+        // Parse the composite record ID: "domain:type:fingerprint"
         let parts: Vec<&str> = record_id.splitn(3, ':').collect();
         if parts.len() < 3 {
             return Err(RetrieveRecordError::NotFound);
@@ -284,14 +474,16 @@ impl Zone for TechnitiumZone {
 
         let domain = parts[0];
         let record_type = parts[1];
-        let data_hash = parts[2];
+        let fingerprint = parts[2];
 
         let response = self
             .api_client
             .get_records(&self.name, domain)
             .await
             .map_err(|err| match &err {
-                api::ApiError::Unauthorized => RetrieveRecordError::Unauthorized,
+                api::ApiError::Unauthorized
+                | api::ApiError::InvalidToken
+                | api::ApiError::TwoFactorRequired => RetrieveRecordError::Unauthorized,
                 api::ApiError::NotFound => RetrieveRecordError::NotFound,
                 _ => RetrieveRecordError::Custom(err),
             })?;
@@ -303,10 +495,14 @@ impl Zone for TechnitiumZone {
             .map(Record::from)
             .find(|r| {
                 r.data.get_type() == record_type
-                    && format!("{:x}", calculate_hash(&r.data.get_value())) == data_hash
+                    && Fingerprint::new(domain, &r.data).to_string() == fingerprint
             })
             .ok_or(RetrieveRecordError::NotFound)
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl CreateRecord for TechnitiumZone {
@@ -318,26 +514,23 @@ impl CreateRecord for TechnitiumZone {
         data: &RecordData,
         ttl: u64,
     ) -> Result<Record, CreateRecordError<Self::CustomCreateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(CreateRecordError::InvalidRecord)?;
         let typ = data.get_type();
-        if !SUPPORTED_RECORD_TYPES.iter().any(|r| *r == typ) {
+        if !SUPPORTED_RECORD_TYPES.contains(&typ) {
             return Err(CreateRecordError::UnsupportedType);
         }
 
         let record_params = record_data_to_params(data);
-        let domain = if host == "@" || host.is_empty() {
-            self.name.clone()
-        } else if host.ends_with('.') {
-            host.trim_end_matches('.').to_string()
-        } else {
-            format!("{}.{}", host, self.name)
-        };
+        let domain = self.record_domain(host);
 
         let response = self
             .api_client
             .add_record(&self.name, &domain, typ, ttl, &record_params)
             .await
             .map_err(|err| match &err {
-                api::ApiError::Unauthorized => CreateRecordError::Unauthorized,
+                api::ApiError::Unauthorized
+                | api::ApiError::InvalidToken
+                | api::ApiError::TwoFactorRequired => CreateRecordError::Unauthorized,
                 api::ApiError::InvalidRecord => CreateRecordError::InvalidRecord,
                 _ => CreateRecordError::Custom(err),
             })?;
@@ -346,6 +539,74 @@ impl CreateRecord for TechnitiumZone {
     }
 }
 
+impl UpdateRecord for TechnitiumZone {
+    type CustomUpdateError = api::ApiError;
+
+    async fn update_record(
+        &self,
+        record_id: &str,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, UpdateRecordError<Self::CustomUpdateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(UpdateRecordError::InvalidRecord)?;
+        let typ = data.get_type();
+        if !SUPPORTED_RECORD_TYPES.contains(&typ) {
+            return Err(UpdateRecordError::UnsupportedType);
+        }
+
+        let record = self.get_record(record_id).await.map_err(|err| match err {
+            RetrieveRecordError::Unauthorized => UpdateRecordError::Unauthorized,
+            RetrieveRecordError::NotFound => UpdateRecordError::NotFound,
+            RetrieveRecordError::Custom(e) => UpdateRecordError::Custom(e),
+        })?;
+
+        // Technitium cannot change the type of a record, so fall back to create-then-delete
+        if record.data.get_type() != typ {
+            let created = self
+                .create_record(host, data, ttl)
+                .await
+                .map_err(|err| match err {
+                    CreateRecordError::Unauthorized => UpdateRecordError::Unauthorized,
+                    CreateRecordError::UnsupportedType => UpdateRecordError::UnsupportedType,
+                    CreateRecordError::InvalidRecord => UpdateRecordError::InvalidRecord,
+                    CreateRecordError::Custom(e) => UpdateRecordError::Custom(e),
+                })?;
+            self.delete_record(record_id)
+                .await
+                .map_err(|err| match err {
+                    DeleteRecordError::Unauthorized => UpdateRecordError::Unauthorized,
+                    DeleteRecordError::NotFound => UpdateRecordError::NotFound,
+                    DeleteRecordError::Custom(e) => UpdateRecordError::Custom(e),
+                })?;
+            return Ok(created);
+        }
+
+        let response = self
+            .api_client
+            .update_record(
+                &self.name,
+                &record.host,
+                &self.record_domain(host),
+                typ,
+                ttl,
+                &record_data_to_params(&record.data),
+                &record_data_to_params(data),
+            )
+            .await
+            .map_err(|err| match &err {
+                api::ApiError::Unauthorized
+                | api::ApiError::InvalidToken
+                | api::ApiError::TwoFactorRequired => UpdateRecordError::Unauthorized,
+                api::ApiError::NotFound => UpdateRecordError::NotFound,
+                api::ApiError::InvalidRecord => UpdateRecordError::InvalidRecord,
+                _ => UpdateRecordError::Custom(err),
+            })?;
+
+        Ok(Record::from(response.updated_record))
+    }
+}
+
 impl DeleteRecord for TechnitiumZone {
     type CustomDeleteError = api::ApiError;
 
@@ -371,7 +632,9 @@ impl DeleteRecord for TechnitiumZone {
             )
             .await
             .map_err(|err| match &err {
-                api::ApiError::Unauthorized => DeleteRecordError::Unauthorized,
+                api::ApiError::Unauthorized
+                | api::ApiError::InvalidToken
+                | api::ApiError::TwoFactorRequired => DeleteRecordError::Unauthorized,
                 api::ApiError::NotFound => DeleteRecordError::NotFound,
                 _ => DeleteRecordError::Custom(err),
             })
@@ -380,11 +643,11 @@ impl DeleteRecord for TechnitiumZone {
 
 impl From<api::Record> for Record {
     fn from(record: api::Record) -> Self {
-        let data = RecordData::from_raw(&record.record_type, &record.rdata.to_value_string());
-        let data_hash = format!("{:x}", calculate_hash(&data.get_value()));
+        let data = record_data(&record.record_type, &record.rdata);
+        let fingerprint = Fingerprint::new(&record.name, &data);
 
         Record {
-            id: format!("{}:{}:{}", record.name, record.record_type, data_hash),
+            id: format!("{}:{}:{}", record.name, record.record_type, fingerprint),
             host: record.name,
             data,
             ttl: record.ttl,
@@ -392,6 +655,72 @@ impl From<api::Record> for Record {
     }
 }
 
+/// Converts API record data to a generic RecordData, resolving the mnemonics Technitium uses for numeric fields.
+fn record_data(typ: &str, rdata: &api::RecordData) -> RecordData {
+    let data = match rdata {
+        api::RecordData::DS {
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+        } => mnemonic_code(algorithm, DNSSEC_ALGORITHMS)
+            .zip(mnemonic_code(digest_type, DS_DIGEST_TYPES))
+            .map(|(algorithm, digest_type)| RecordData::DS {
+                key_tag: *key_tag,
+                algorithm,
+                digest_type,
+                digest: digest.clone(),
+            }),
+        api::RecordData::SSHFP {
+            algorithm,
+            fingerprint_type,
+            fingerprint,
+        } => mnemonic_code(algorithm, SSHFP_ALGORITHMS)
+            .zip(mnemonic_code(fingerprint_type, SSHFP_FINGERPRINT_TYPES))
+            .map(|(algorithm, fingerprint_type)| RecordData::SSHFP {
+                algorithm,
+                fingerprint_type,
+                fingerprint: fingerprint.clone(),
+            }),
+        api::RecordData::TLSA {
+            certificate_usage,
+            selector,
+            matching_type,
+            certificate_association_data,
+        } => mnemonic_code(certificate_usage, TLSA_CERTIFICATE_USAGES)
+            .zip(mnemonic_code(selector, TLSA_SELECTORS))
+            .zip(mnemonic_code(matching_type, TLSA_MATCHING_TYPES))
+            .map(|((usage, selector), matching_type)| RecordData::TLSA {
+                usage,
+                selector,
+                matching_type,
+                certificate: certificate_association_data.clone(),
+            }),
+        _ => None,
+    };
+
+    data.unwrap_or_else(|| RecordData::from_raw(typ, &rdata.to_value_string()))
+}
+
+/// Returns the code of a mnemonic such as `DANE-EE`, which may also be given as a number.
+fn mnemonic_code(value: &str, mnemonics: &[(&str, u8)]) -> Option<u8> {
+    let name = value.replace('-', "_");
+    value.parse().ok().or_else(|| {
+        mnemonics
+            .iter()
+            .find(|(mnemonic, _)| mnemonic.eq_ignore_ascii_case(&name))
+            .map(|(_, code)| *code)
+    })
+}
+
+/// Returns the mnemonic of a code, or the code itself if it has none.
+fn mnemonic(code: u8, mnemonics: &[(&str, u8)]) -> String {
+    mnemonics
+        .iter()
+        .find(|(_, c)| *c == code)
+        .map_or_else(|| code.to_string(), |(mnemonic, _)| mnemonic.to_string())
+}
+
 /// Converts a generic RecordData to API-specific RecordParams.
 fn record_data_to_params(data: &RecordData) -> api::RecordParams {
     match data {
@@ -426,16 +755,111 @@ fn record_data_to_params(data: &RecordData) -> api::RecordParams {
             port: *port,
             target: target.clone(),
         },
+        RecordData::PTR(ptr_name) => api::RecordParams::PTR {
+            ptr_name: ptr_name.clone(),
+        },
+        RecordData::CAA { flags, tag, value } => api::RecordParams::CAA {
+            flags: *flags,
+            tag: tag.clone(),
+            value: value.clone(),
+        },
+        RecordData::DS {
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+        } => api::RecordParams::DS {
+            key_tag: *key_tag,
+            algorithm: mnemonic(*algorithm, DNSSEC_ALGORITHMS),
+            digest_type: mnemonic(*digest_type, DS_DIGEST_TYPES),
+            digest: digest.clone(),
+        },
+        RecordData::SSHFP {
+            algorithm,
+            fingerprint_type,
+            fingerprint,
+        } => api::RecordParams::SSHFP {
+            algorithm: mnemonic(*algorithm, SSHFP_ALGORITHMS),
+            fingerprint_type: mnemonic(*fingerprint_type, SSHFP_FINGERPRINT_TYPES),
+            fingerprint: fingerprint.clone(),
+        },
+        RecordData::TLSA {
+            usage,
+            selector,
+            matching_type,
+            certificate,
+        } => api::RecordParams::TLSA {
+            certificate_usage: mnemonic(*usage, TLSA_CERTIFICATE_USAGES),
+            selector: mnemonic(*selector, TLSA_SELECTORS),
+            matching_type: mnemonic(*matching_type, TLSA_MATCHING_TYPES),
+            certificate_association_data: certificate.clone(),
+        },
+        RecordData::HTTPS {
+            priority,
+            target,
+            params,
+        }
+        | RecordData::SVCB {
+            priority,
+            target,
+            params,
+        } => api::RecordParams::SVCB {
+            svc_priority: *priority,
+            svc_target_name: target.clone(),
+            svc_params: params
+                .split_whitespace()
+                .flat_map(|param| {
+                    let (key, value) = param.split_once('=').unwrap_or((param, ""));
+                    [key, value.trim_matches('"')]
+                })
+                .collect::<Vec<_>>()
+                .join("|"),
+        },
+        RecordData::SOA { .. } | RecordData::NAPTR { .. } => api::RecordParams::Other {
+            value: data.get_value(),
+        },
         RecordData::Other { value, .. } => api::RecordParams::Other {
             value: value.clone(),
         },
     }
 }
 
-/// Simple hash function for generating record IDs.
-fn calculate_hash(s: &str) -> u64 {
-    use std::hash::{Hash, Hasher};
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    s.hash(&mut hasher);
-    hasher.finish()
+impl ApplyChanges for TechnitiumZone {
+    type CustomApplyError = api::ApiError;
+
+    async fn apply_changes(
+        &self,
+        changes: &[RecordChange],
+    ) -> Result<Vec<Record>, ApplyChangesError<Self::CustomApplyError>> {
+        // No batch endpoint, so changes are applied one by one with best-effort rollback
+        apply_changes_sequentially(self, changes).await
+    }
+}
+
+impl AppendRecords for TechnitiumZone {}
+
+impl SetRecords for TechnitiumZone {}
+
+impl DeleteRecords for TechnitiumZone {}
+
+#[cfg(feature = "ddns")]
+impl DynamicDns for TechnitiumZone {}
+
+/// Describes what the provider and all of its zones support.
+fn capabilities() -> Capabilities {
+    Capabilities {
+        list_zones: true,
+        create_zone: true,
+        delete_zone: true,
+        create_record: true,
+        update_record: true,
+        delete_record: true,
+        // Changing the type of a record adds the new record before deleting the old one
+        atomic_update: false,
+        ttl_range: 0..=MAX_TTL as u64,
+        ..Capabilities::default()
+    }
+    .with_record_types(SUPPORTED_RECORD_TYPES)
 }
+
+impl MigrationTarget for TechnitiumZone {}
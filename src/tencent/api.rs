@@ -10,13 +10,16 @@
 //! - [Data Types](https://www.tencentcloud.com/document/api/1157/49043)
 
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
+use crate::retry::{self, classify_request_error, RetryPolicy, Transient};
+use crate::{ClassifyError, ErrorKind};
+
 /// The Tencent Cloud DNSPod API endpoint.
-const TENCENT_API_HOST: &str = "dnspod.intl.tencentcloudapi.com";
-const TENCENT_API_URL: &str = "https://dnspod.intl.tencentcloudapi.com";
+pub(crate) const TENCENT_API_URL: &str = "https://dnspod.intl.tencentcloudapi.com";
 
 /// Service name for signature calculation.
 const SERVICE: &str = "dnspod";
@@ -57,6 +60,35 @@ impl std::fmt::Display for ApiError {
     }
 }
 
+impl ClassifyError for TencentError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            TencentError::Api(api_err) => {
+                // Codes are hierarchical, e.g. `InvalidParameter.DomainExists`
+                let code = api_err.code.as_str();
+                let (category, detail) = code.split_once('.').unwrap_or((code, ""));
+                match category {
+                    "AuthFailure" | "UnauthorizedOperation" => ErrorKind::Unauthorized,
+                    "RequestLimitExceeded" => ErrorKind::RateLimited,
+                    "LimitExceeded" => ErrorKind::QuotaExceeded,
+                    "InternalError" => ErrorKind::Transient,
+                    "ResourceNotFound" => ErrorKind::NotFound,
+                    _ if detail.contains("NotExist") || detail == "RecordIdInvalid" => {
+                        ErrorKind::NotFound
+                    }
+                    _ if detail.contains("Exist") => ErrorKind::AlreadyExists,
+                    "InvalidParameter" | "InvalidParameterValue" | "MissingParameter" => {
+                        ErrorKind::Validation
+                    }
+                    _ => ErrorKind::Other,
+                }
+            }
+            TencentError::Request(e) => e.kind(),
+            TencentError::Serialization(_) => ErrorKind::Other,
+        }
+    }
+}
+
 /// Raw API response that can contain either an error or success data.
 /// We first deserialize to this to check for errors before deserializing the actual data.
 #[derive(Debug, Deserialize)]
@@ -350,6 +382,7 @@ fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
 fn generate_signature(
     secret_id: &str,
     secret_key: &str,
+    host: &str,
     timestamp: u64,
     _action: &str,
     payload: &str,
@@ -359,7 +392,7 @@ fn generate_signature(
     let canonical_uri = "/";
     let canonical_query_string = "";
     let content_type = "application/json; charset=utf-8";
-    let canonical_headers = format!("content-type:{}\nhost:{}\n", content_type, TENCENT_API_HOST);
+    let canonical_headers = format!("content-type:{}\nhost:{}\n", content_type, host);
     let signed_headers = "content-type;host";
     let hashed_request_payload = sha256_hex(payload);
 
@@ -402,15 +435,57 @@ fn generate_signature(
     (authorization, date)
 }
 
+/// A failed attempt of [`Client::request_once`].
+struct Attempt {
+    error: TencentError,
+    /// The delay requested by the `Retry-After` header of an error response.
+    retry_after: Option<Duration>,
+}
+
+impl<E: Into<TencentError>> From<E> for Attempt {
+    fn from(err: E) -> Self {
+        Self {
+            error: err.into(),
+            retry_after: None,
+        }
+    }
+}
+
+/// Classifies a failed request attempt for [`retry::run`].
+fn classify_error(attempt: &Attempt) -> Option<Transient> {
+    match &attempt.error {
+        TencentError::Api(err) if err.code.starts_with("RequestLimitExceeded") => {
+            Some(Transient::Rejected(None))
+        }
+        TencentError::Api(err) if err.code.starts_with("InternalError") => {
+            Some(Transient::Failed(None))
+        }
+        TencentError::Request(err) => match err.status() {
+            Some(StatusCode::TOO_MANY_REQUESTS) => Some(Transient::Rejected(attempt.retry_after)),
+            Some(status) if status.is_server_error() => {
+                Some(Transient::Failed(attempt.retry_after))
+            }
+            Some(_) => None,
+            None => classify_request_error(err),
+        },
+        _ => None,
+    }
+}
+
 // =============================================================================
 // API Client
 // =============================================================================
 
 /// Tencent Cloud DNSPod API client.
+#[derive(Clone)]
 pub struct Client {
     http_client: reqwest::Client,
     secret_id: String,
     secret_key: String,
+    base_url: String,
+    /// The host of the base URL, which is part of the signature.
+    host: String,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
@@ -424,17 +499,88 @@ impl Client {
         secret_id: &str,
         secret_key: &str,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let http_client = reqwest::Client::builder().build()?;
+        Self::with_base_url(secret_id, secret_key, TENCENT_API_URL)
+    }
+
+    /// Creates a new Tencent Cloud API client with a custom base URL.
+    ///
+    /// This is primarily useful for testing with mock servers.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_id` - Tencent Cloud SecretId from API key management
+    /// * `secret_key` - Tencent Cloud SecretKey from API key management
+    /// * `base_url` - Custom base URL for the API
+    pub fn with_base_url(
+        secret_id: &str,
+        secret_key: &str,
+        base_url: &str,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let http_client = Self::http_client_builder().build()?;
+        Self::from_http_client(http_client, secret_id, secret_key, base_url)
+    }
+
+    /// Returns the builder of the HTTP client used unless a preconfigured one is given.
+    pub(crate) fn http_client_builder() -> reqwest::ClientBuilder {
+        reqwest::Client::builder()
+    }
+
+    /// Creates a new Tencent Cloud API client sending its requests using the given HTTP client.
+    pub(crate) fn from_http_client(
+        http_client: reqwest::Client,
+        secret_id: &str,
+        secret_key: &str,
+        base_url: &str,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let url = reqwest::Url::parse(base_url)?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(format!("base URL has no host: {}", base_url).into()),
+        };
 
         Ok(Self {
             http_client,
             secret_id: secret_id.to_string(),
             secret_key: secret_key.to_string(),
+            base_url: base_url.to_string(),
+            host,
+            retry_policy: RetryPolicy::default(),
         })
     }
 
-    /// Makes a signed API request.
+    /// Replaces the HTTP client, e.g. with one using custom timeouts, proxies or root certificates.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Makes a signed API request, retrying it according to the retry policy.
     async fn request<Req, Resp>(&self, action: &str, request: &Req) -> Result<Resp, TencentError>
+    where
+        Req: Serialize,
+        Resp: for<'de> Deserialize<'de>,
+    {
+        // Modifications replace the whole record, so only creations are not idempotent
+        let idempotent = !action.starts_with("Create");
+        retry::run(
+            &self.retry_policy,
+            idempotent,
+            || self.request_once(action, request),
+            classify_error,
+        )
+        .await
+        .map_err(|attempt| attempt.error)
+    }
+
+    /// Makes a single signed API request; every attempt is signed with the current time.
+    async fn request_once<Req, Resp>(&self, action: &str, request: &Req) -> Result<Resp, Attempt>
     where
         Req: Serialize,
         Resp: for<'de> Deserialize<'de>,
@@ -448,6 +594,7 @@ impl Client {
         let (authorization, _date) = generate_signature(
             &self.secret_id,
             &self.secret_key,
+            &self.host,
             timestamp,
             action,
             &payload,
@@ -458,7 +605,7 @@ impl Client {
             CONTENT_TYPE,
             HeaderValue::from_static("application/json; charset=utf-8"),
         );
-        headers.insert("Host", HeaderValue::from_static(TENCENT_API_HOST));
+        headers.insert("Host", HeaderValue::from_str(&self.host).unwrap());
         headers.insert(
             "Authorization",
             HeaderValue::from_str(&authorization).unwrap(),
@@ -472,18 +619,25 @@ impl Client {
 
         let response = self
             .http_client
-            .post(TENCENT_API_URL)
+            .post(&self.base_url)
             .headers(headers)
             .body(payload)
             .send()
             .await?;
 
+        if let Err(error) = response.error_for_status_ref() {
+            return Err(Attempt {
+                error: error.into(),
+                retry_after: retry::retry_after(response.headers()),
+            });
+        }
+
         // First, get the raw JSON to check for errors
         let raw: RawApiResponse = response.json().await?;
 
         // Check if the response contains an Error field
         if let Ok(error_resp) = serde_json::from_value::<ErrorResponse>(raw.response.clone()) {
-            return Err(TencentError::Api(error_resp.error));
+            return Err(TencentError::Api(error_resp.error).into());
         }
 
         // No error, deserialize the success response
@@ -614,6 +768,7 @@ impl Client {
     }
 
     /// Creates a new record.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_record(
         &self,
         domain: &str,
@@ -658,6 +813,7 @@ impl Client {
     }
 
     /// Modifies an existing record.
+    #[allow(clippy::too_many_arguments)]
     pub async fn modify_record(
         &self,
         domain: &str,
@@ -805,6 +961,13 @@ fn parse_record_data(
                 })
             }
         }
+        "PTR" | "CAA" | "HTTPS" | "SVCB" => match RecordData::from_raw(record_type, value) {
+            RecordData::Other { .. } => Err(RecordConversionError {
+                record_type: record_type.to_string(),
+                reason: "invalid record value",
+            }),
+            data => Ok(data),
+        },
         _ => Ok(RecordData::Other {
             typ: record_type.to_string(),
             value: value.to_string(),
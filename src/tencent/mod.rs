@@ -49,17 +49,28 @@ pub mod api;
 
 use std::error::Error as StdErr;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub use api::{ApiError, Client, RecordConversionError, TencentError};
 
+#[cfg(feature = "ddns")]
+use crate::ddns::DynamicDns;
+use crate::dynamic::{DynProvider, DynProviderAdapter};
+use crate::http::HttpOptions;
+use crate::migrate::MigrationTarget;
+use crate::retry::RetryPolicy;
+use crate::types::to_ascii_name;
 use crate::{
+    apply_changes_sequentially, AppendRecords, ApplyChanges, ApplyChangesError, Capabilities,
     CreateRecord, CreateRecordError, CreateZone, CreateZoneError, DeleteRecord, DeleteRecordError,
-    DeleteZone, DeleteZoneError, Provider, Record, RecordData, RetrieveRecordError,
-    RetrieveZoneError, Zone,
+    DeleteRecords, DeleteZone, DeleteZoneError, Provider, Record, RecordChange, RecordData,
+    RetrieveRecordError, RetrieveZoneError, SetRecords, UpdateRecord, UpdateRecordError, Zone,
 };
 
 /// Supported DNS record types for Tencent Cloud DNSPod.
-const SUPPORTED_RECORD_TYPES: &[&str] = &["A", "AAAA", "CNAME", "MX", "NS", "TXT", "SRV"];
+const SUPPORTED_RECORD_TYPES: &[&str] = &[
+    "A", "AAAA", "CNAME", "MX", "NS", "TXT", "SRV", "PTR", "CAA", "HTTPS", "SVCB",
+];
 
 /// Tencent Cloud DNSPod provider.
 ///
@@ -99,13 +110,148 @@ impl TencentProvider {
     /// let provider = TencentProvider::new("secret_id", "secret_key").unwrap();
     /// ```
     pub fn new(secret_id: &str, secret_key: &str) -> Result<Self, Box<dyn StdErr + Send + Sync>> {
-        let api_client = Client::new(secret_id, secret_key)?;
-        Ok(Self {
+        Self::builder(secret_id, secret_key).build()
+    }
+
+    /// Creates a new Tencent Cloud DNSPod provider with a custom base URL.
+    ///
+    /// This is primarily useful for testing with mock servers.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_id` - Tencent Cloud SecretId
+    /// * `secret_key` - Tencent Cloud SecretKey
+    /// * `base_url` - Custom base URL for the API
+    pub fn with_base_url(
+        secret_id: &str,
+        secret_key: &str,
+        base_url: &str,
+    ) -> Result<Self, Box<dyn StdErr + Send + Sync>> {
+        Self::builder(secret_id, secret_key)
+            .with_base_url(base_url)
+            .build()
+    }
+
+    /// Returns a builder to configure the HTTP client, API base URL and retry policy of the provider.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use libdns::tencent::TencentProvider;
+    ///
+    /// let provider = TencentProvider::builder("secret_id", "secret_key")
+    ///     .with_timeout(Duration::from_secs(10))
+    ///     .with_user_agent("my-app/1.0")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(secret_id: &str, secret_key: &str) -> TencentProviderBuilder {
+        TencentProviderBuilder {
+            secret_id: secret_id.to_string(),
+            secret_key: secret_key.to_string(),
+            base_url: api::TENCENT_API_URL.to_string(),
+            http: HttpOptions::default(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        let api_client = self.api_client.as_ref().clone().with_retry_policy(policy);
+        Self {
+            api_client: Arc::new(api_client),
+        }
+    }
+}
+
+/// Builder for a [`TencentProvider`], created using [`TencentProvider::builder`].
+#[derive(Clone)]
+pub struct TencentProviderBuilder {
+    secret_id: String,
+    secret_key: String,
+    base_url: String,
+    http: HttpOptions,
+    retry_policy: RetryPolicy,
+}
+
+impl TencentProviderBuilder {
+    /// Sets a custom API base URL, e.g. of a mock server.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Sends all requests using the given HTTP client, e.g. one with custom root certificates or connection pool limits.
+    ///
+    /// The timeout, proxy and user agent set on the builder are ignored in that case.
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http.client = Some(client);
+        self
+    }
+
+    /// Sets the timeout of every request, unlimited by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends all requests through the given proxy.
+    pub fn with_proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.http.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets the `User-Agent` header of every request.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.http.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the policy for retrying rate-limited and transiently failed requests.
+    ///
+    /// See the [`retry`](crate::retry) module for which requests are retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Creates the provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created or the base URL is invalid.
+    pub fn build(self) -> Result<TencentProvider, Box<dyn StdErr + Send + Sync>> {
+        let http_client = self.http.build(Client::http_client_builder())?;
+        let api_client = Client::from_http_client(
+            http_client,
+            &self.secret_id,
+            &self.secret_key,
+            &self.base_url,
+        )?
+        .with_retry_policy(self.retry_policy);
+        Ok(TencentProvider {
             api_client: Arc::new(api_client),
         })
     }
 }
 
+impl From<TencentProvider> for Box<dyn DynProvider> {
+    fn from(provider: TencentProvider) -> Self {
+        Box::new(
+            DynProviderAdapter::new(provider)
+                .with_create_zone()
+                .with_delete_zone()
+                .with_create_record()
+                .with_update_record()
+                .with_delete_record(),
+        )
+    }
+}
+
 impl Provider for TencentProvider {
     type Zone = TencentZone;
     type CustomRetrieveError = TencentError;
@@ -114,6 +260,7 @@ impl Provider for TencentProvider {
         &self,
         zone_id: &str,
     ) -> Result<Self::Zone, RetrieveZoneError<Self::CustomRetrieveError>> {
+        let zone_id: &str = &to_ascii_name(zone_id).ok_or(RetrieveZoneError::NotFound)?;
         // zone_id can be either a domain ID (numeric) or domain name
         let response = if zone_id.chars().all(|c| c.is_ascii_digit()) {
             let domain_id: u64 = zone_id.parse().map_err(|_| RetrieveZoneError::NotFound)?;
@@ -186,6 +333,10 @@ impl Provider for TencentProvider {
 
         Ok(zones)
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl CreateZone for TencentProvider {
@@ -195,6 +346,7 @@ impl CreateZone for TencentProvider {
         &self,
         domain: &str,
     ) -> Result<Self::Zone, CreateZoneError<Self::CustomCreateError>> {
+        let domain: &str = &to_ascii_name(domain).ok_or(CreateZoneError::InvalidDomainName)?;
         let response = self
             .api_client
             .create_domain(domain)
@@ -338,6 +490,10 @@ impl Zone for TencentZone {
 
         Record::try_from(response.record_info).map_err(|_| RetrieveRecordError::NotFound)
     }
+
+    fn capabilities(&self) -> Capabilities {
+        capabilities()
+    }
 }
 
 impl CreateRecord for TencentZone {
@@ -349,6 +505,7 @@ impl CreateRecord for TencentZone {
         data: &RecordData,
         ttl: u64,
     ) -> Result<Record, CreateRecordError<Self::CustomCreateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(CreateRecordError::InvalidRecord)?;
         let typ = data.get_type();
         if !SUPPORTED_RECORD_TYPES.contains(&typ) {
             return Err(CreateRecordError::UnsupportedType);
@@ -396,6 +553,70 @@ impl CreateRecord for TencentZone {
     }
 }
 
+impl UpdateRecord for TencentZone {
+    type CustomUpdateError = TencentError;
+
+    async fn update_record(
+        &self,
+        record_id: &str,
+        host: &str,
+        data: &RecordData,
+        ttl: u64,
+    ) -> Result<Record, UpdateRecordError<Self::CustomUpdateError>> {
+        let host: &str = &to_ascii_name(host).ok_or(UpdateRecordError::InvalidRecord)?;
+        let record_id_num: u64 = record_id.parse().map_err(|_| UpdateRecordError::NotFound)?;
+
+        let typ = data.get_type();
+        if !SUPPORTED_RECORD_TYPES.contains(&typ) {
+            return Err(UpdateRecordError::UnsupportedType);
+        }
+
+        let mx = match data {
+            RecordData::MX { priority, .. } => Some(*priority),
+            _ => None,
+        };
+
+        let value = data.get_api_value();
+
+        let response = self
+            .api_client
+            .modify_record(
+                &self.repr.name,
+                record_id_num,
+                host,
+                typ,
+                "默认", // Default line for Tencent Cloud
+                &value,
+                mx,
+                Some(ttl),
+            )
+            .await
+            .map_err(|err| match &err {
+                TencentError::Api(api_err) => match api_err.code.as_str() {
+                    "AuthFailure"
+                    | "AuthFailure.SecretIdNotFound"
+                    | "AuthFailure.SignatureFailure" => UpdateRecordError::Unauthorized,
+                    "InvalidParameter.RecordIdInvalid" | "ResourceNotFound.NoDataOfRecord" => {
+                        UpdateRecordError::NotFound
+                    }
+                    "InvalidParameter.RecordTypeInvalid" => UpdateRecordError::UnsupportedType,
+                    "InvalidParameter.SubDomainInvalid"
+                    | "InvalidParameter.RecordValueInvalid"
+                    | "InvalidParameter.MXInvalid" => UpdateRecordError::InvalidRecord,
+                    _ => UpdateRecordError::Custom(err),
+                },
+                _ => UpdateRecordError::Custom(err),
+            })?;
+
+        Ok(Record {
+            id: response.record_id.to_string(),
+            host: host.to_string(),
+            data: data.clone(),
+            ttl,
+        })
+    }
+}
+
 impl DeleteRecord for TencentZone {
     type CustomDeleteError = TencentError;
 
@@ -424,3 +645,43 @@ impl DeleteRecord for TencentZone {
         Ok(())
     }
 }
+
+impl ApplyChanges for TencentZone {
+    type CustomApplyError = TencentError;
+
+    async fn apply_changes(
+        &self,
+        changes: &[RecordChange],
+    ) -> Result<Vec<Record>, ApplyChangesError<Self::CustomApplyError>> {
+        // No batch endpoint, so changes are applied one by one with best-effort rollback
+        apply_changes_sequentially(self, changes).await
+    }
+}
+
+impl AppendRecords for TencentZone {}
+
+impl SetRecords for TencentZone {}
+
+impl DeleteRecords for TencentZone {}
+
+#[cfg(feature = "ddns")]
+impl DynamicDns for TencentZone {}
+
+/// Describes what the provider and all of its zones support.
+fn capabilities() -> Capabilities {
+    Capabilities {
+        list_zones: true,
+        create_zone: true,
+        delete_zone: true,
+        create_record: true,
+        update_record: true,
+        delete_record: true,
+        atomic_update: true,
+        // The lower bound depends on the plan of the domain (600 seconds on the free plan)
+        ttl_range: 1..=604800,
+        ..Capabilities::default()
+    }
+    .with_record_types(SUPPORTED_RECORD_TYPES)
+}
+
+impl MigrationTarget for TencentZone {}
@@ -0,0 +1,542 @@
+//! Conformance suite for provider implementations.
+//!
+//! A [`ConformanceSuite`] runs a provider through a fixed list of [`Scenario`]s covering the behavior the traits of this crate promise,
+//! and reports every deviation instead of stopping at the first one:
+//!
+//! - A created zone can be retrieved by its ID and is listed.
+//! - Retrieving an unknown zone fails with `NotFound`.
+//! - A record of every sample type can be created, retrieved by its ID, listed and deleted, and is gone afterwards.
+//!   Hosts are compared after normalization, so providers may report them relative to the zone or fully qualified.
+//!   Types rejected as unsupported are reported as such and are not a deviation.
+//! - A record created with a fully qualified host ends up at the same name as one created with a relative host.
+//! - Retrieving or deleting a deleted record fails with `NotFound`.
+//!
+//! The suite is meant for the tests of provider implementations, both against mock servers and, with disposable domains, against the real API.
+//! Records are created below a dedicated host (see [`ConformanceSuite::with_host`]) and deleted again; a zone created by [`ConformanceSuite::run`] is left in place
+//! and its ID returned in the [`ConformanceReport`] for cleanup.
+//!
+//! # Example
+//!
+//! ```
+//! use libdns::testing::ConformanceSuite;
+//! use libdns::{CreateRecord, CreateZone, DeleteRecord};
+//!
+//! async fn check<P>(provider: &P)
+//! where
+//!     P: CreateZone,
+//!     P::Zone: CreateRecord + DeleteRecord,
+//! {
+//!     let report = ConformanceSuite::new("conformance.example.com").run(provider).await;
+//!     assert!(report.is_conformant(), "{}", report);
+//!     println!("supported types: {:?}", report.supported_types());
+//! }
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{
+    normalize_host, same_value, CreateRecord, CreateRecordError, CreateZone, DeleteRecord,
+    DeleteRecordError, Provider, Record, RecordData, RetrieveRecordError, RetrieveZoneError, Zone,
+};
+
+/// A check run by the [`ConformanceSuite`].
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum Scenario {
+    /// A zone is created, then retrieved by its ID and found in the list of zones.
+    ZoneRoundtrip,
+
+    /// Retrieving a zone by an unknown ID fails with `NotFound`.
+    MissingZone,
+
+    /// A record of the given type is created, retrieved, listed and deleted.
+    RecordRoundtrip(String),
+
+    /// A record created with a fully qualified host is reported at the same name as with a relative host.
+    HostForms,
+
+    /// Retrieving and deleting a deleted record fails with `NotFound`.
+    MissingRecord,
+}
+
+impl Display for Scenario {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Scenario::ZoneRoundtrip => write!(f, "zone roundtrip"),
+            Scenario::MissingZone => write!(f, "missing zone"),
+            Scenario::RecordRoundtrip(typ) => write!(f, "record roundtrip ({})", typ),
+            Scenario::HostForms => write!(f, "host forms"),
+            Scenario::MissingRecord => write!(f, "missing record"),
+        }
+    }
+}
+
+/// Represents the outcome of a single [`Scenario`].
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum Outcome {
+    /// The provider behaved as expected.
+    Passed,
+
+    /// The provider rejected the record type as unsupported.
+    Unsupported,
+
+    /// The scenario could not run because a step it depends on failed or no record type was supported.
+    Skipped,
+
+    /// The provider deviated from the expected behavior in the described ways.
+    Deviated(Vec<String>),
+}
+
+impl Outcome {
+    fn from_deviations(deviations: Vec<String>) -> Self {
+        if deviations.is_empty() {
+            Outcome::Passed
+        } else {
+            Outcome::Deviated(deviations)
+        }
+    }
+}
+
+/// The outcome of a single [`Scenario`] of a run.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct ScenarioResult {
+    pub scenario: Scenario,
+    pub outcome: Outcome,
+}
+
+/// The result of a [`ConformanceSuite`] run.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ConformanceReport {
+    /// The ID of the zone created by [`ConformanceSuite::run`], if any.
+    pub zone_id: Option<String>,
+    /// The outcome of every scenario, in the order they ran.
+    pub results: Vec<ScenarioResult>,
+}
+
+impl ConformanceReport {
+    /// Returns whether no scenario deviated from the expected behavior.
+    pub fn is_conformant(&self) -> bool {
+        self.deviations().is_empty()
+    }
+
+    /// Returns every deviation along with the scenario it occurred in.
+    pub fn deviations(&self) -> Vec<(&Scenario, &str)> {
+        self.results
+            .iter()
+            .flat_map(|result| match &result.outcome {
+                Outcome::Deviated(deviations) => deviations
+                    .iter()
+                    .map(|deviation| (&result.scenario, deviation.as_str()))
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Returns the record types the provider accepted, in the order of the samples.
+    pub fn supported_types(&self) -> Vec<&str> {
+        self.results
+            .iter()
+            .filter_map(|result| match (&result.scenario, &result.outcome) {
+                (Scenario::RecordRoundtrip(_), Outcome::Unsupported | Outcome::Skipped) => None,
+                (Scenario::RecordRoundtrip(typ), _) => Some(typ.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Display for ConformanceReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            match &result.outcome {
+                Outcome::Passed => writeln!(f, "{}: passed", result.scenario)?,
+                Outcome::Unsupported => writeln!(f, "{}: unsupported", result.scenario)?,
+                Outcome::Skipped => writeln!(f, "{}: skipped", result.scenario)?,
+                Outcome::Deviated(deviations) => {
+                    writeln!(f, "{}: deviated", result.scenario)?;
+                    for deviation in deviations {
+                        writeln!(f, "  - {}", deviation)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs providers and zones through the conformance [`Scenario`]s.
+///
+/// See the [module documentation](self) for what is checked.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ConformanceSuite {
+    domain: String,
+    host: String,
+    ttl: u64,
+    samples: Vec<RecordData>,
+    missing_zone_id: String,
+}
+
+impl ConformanceSuite {
+    /// Creates a suite for the zone of the given domain, using the default samples of the A, AAAA, CNAME, MX, SRV and TXT types.
+    pub fn new(domain: &str) -> Self {
+        Self {
+            domain: domain.trim_end_matches('.').to_ascii_lowercase(),
+            host: "libdns-conformance".to_string(),
+            ttl: 3600,
+            samples: vec![
+                RecordData::A(Ipv4Addr::new(192, 0, 2, 1)),
+                RecordData::AAAA(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+                RecordData::CNAME("target.example.net".to_string()),
+                RecordData::MX {
+                    priority: 10,
+                    mail_server: "mail.example.net".to_string(),
+                },
+                RecordData::SRV {
+                    priority: 10,
+                    weight: 5,
+                    port: 5060,
+                    target: "sip.example.net".to_string(),
+                },
+                RecordData::TXT("libdns conformance".to_string()),
+            ],
+            missing_zone_id: "libdns-conformance-missing".to_string(),
+        }
+    }
+
+    /// Sets the host below which records are created, `libdns-conformance` by default.
+    /// Existing records below it may be modified or deleted by a run.
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self
+    }
+
+    /// Sets the TTL of the created records, 3600 seconds by default.
+    pub fn with_ttl(mut self, ttl: u64) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Replaces the sample records, one of which is created per [`Scenario::RecordRoundtrip`].
+    pub fn with_samples(mut self, samples: impl IntoIterator<Item = RecordData>) -> Self {
+        self.samples = samples.into_iter().collect();
+        self
+    }
+
+    /// Sets the zone ID used for [`Scenario::MissingZone`], for providers rejecting the default one as malformed.
+    pub fn with_missing_zone_id(mut self, zone_id: &str) -> Self {
+        self.missing_zone_id = zone_id.to_string();
+        self
+    }
+
+    /// Creates the zone of the suite's domain and runs every scenario against the provider.
+    ///
+    /// The domain must not exist at the provider yet. If the zone cannot be created, the record scenarios are skipped.
+    pub async fn run<P>(&self, provider: &P) -> ConformanceReport
+    where
+        P: CreateZone,
+        P::Zone: CreateRecord + DeleteRecord,
+    {
+        let (outcome, zone) = self.zone_roundtrip(provider).await;
+        let mut results = vec![
+            ScenarioResult {
+                scenario: Scenario::ZoneRoundtrip,
+                outcome,
+            },
+            ScenarioResult {
+                scenario: Scenario::MissingZone,
+                outcome: self.missing_zone(provider).await,
+            },
+        ];
+
+        match &zone {
+            Some(zone) => results.extend(self.run_zone(zone).await.results),
+            None => {
+                results.extend(
+                    self.record_scenarios()
+                        .into_iter()
+                        .map(|scenario| ScenarioResult {
+                            scenario,
+                            outcome: Outcome::Skipped,
+                        }),
+                )
+            }
+        }
+
+        ConformanceReport {
+            zone_id: zone.map(|zone| zone.id().to_string()),
+            results,
+        }
+    }
+
+    /// Runs the record scenarios against an existing zone, for providers that cannot create zones.
+    pub async fn run_zone<Z: CreateRecord + DeleteRecord>(&self, zone: &Z) -> ConformanceReport {
+        let mut results = Vec::new();
+        let mut supported = None;
+        let mut deleted_id = None;
+        for data in &self.samples {
+            let (outcome, id) = self
+                .record_roundtrip(zone, &self.sample_host(data), data)
+                .await;
+            if outcome != Outcome::Unsupported && supported.is_none() {
+                supported = Some(data);
+            }
+            if deleted_id.is_none() {
+                deleted_id = id;
+            }
+            results.push(ScenarioResult {
+                scenario: Scenario::RecordRoundtrip(data.get_type().to_string()),
+                outcome,
+            });
+        }
+
+        let outcome = match supported {
+            Some(data) => self.host_forms(zone, data).await,
+            None => Outcome::Skipped,
+        };
+        results.push(ScenarioResult {
+            scenario: Scenario::HostForms,
+            outcome,
+        });
+
+        let outcome = match deleted_id {
+            Some(id) => missing_record(zone, &id).await,
+            None => Outcome::Skipped,
+        };
+        results.push(ScenarioResult {
+            scenario: Scenario::MissingRecord,
+            outcome,
+        });
+
+        ConformanceReport {
+            zone_id: None,
+            results,
+        }
+    }
+
+    fn record_scenarios(&self) -> Vec<Scenario> {
+        let mut scenarios: Vec<Scenario> = self
+            .samples
+            .iter()
+            .map(|data| Scenario::RecordRoundtrip(data.get_type().to_string()))
+            .collect();
+        scenarios.push(Scenario::HostForms);
+        scenarios.push(Scenario::MissingRecord);
+        scenarios
+    }
+
+    /// Returns the relative host a sample is created at; SRV records need service and protocol labels.
+    fn sample_host(&self, data: &RecordData) -> String {
+        match data {
+            RecordData::SRV { .. } => format!("_libdns._tcp.{}", self.host),
+            _ => format!("{}.{}", data.get_type().to_ascii_lowercase(), self.host),
+        }
+    }
+
+    async fn zone_roundtrip<P: CreateZone>(&self, provider: &P) -> (Outcome, Option<P::Zone>) {
+        let zone = match provider.create_zone(&self.domain).await {
+            Ok(zone) => zone,
+            Err(err) => {
+                let deviation = format!("create_zone failed: {:?}", err);
+                return (Outcome::Deviated(vec![deviation]), None);
+            }
+        };
+
+        let mut deviations = Vec::new();
+        if !same_domain(zone.domain(), &self.domain) {
+            deviations.push(format!(
+                "create_zone returned domain {:?} instead of {:?}",
+                zone.domain(),
+                self.domain
+            ));
+        }
+        match provider.get_zone(zone.id()).await {
+            Ok(fetched) if !same_domain(fetched.domain(), &self.domain) => {
+                deviations.push(format!(
+                    "get_zone returned domain {:?} instead of {:?}",
+                    fetched.domain(),
+                    self.domain
+                ));
+            }
+            Ok(_) => {}
+            Err(err) => deviations.push(format!("get_zone failed: {:?}", err)),
+        }
+        match provider.list_zones().await {
+            Ok(zones) if !zones.iter().any(|listed| listed.id() == zone.id()) => {
+                deviations.push("list_zones did not include the created zone".to_string());
+            }
+            Ok(_) => {}
+            Err(err) => deviations.push(format!("list_zones failed: {:?}", err)),
+        }
+
+        (Outcome::from_deviations(deviations), Some(zone))
+    }
+
+    async fn missing_zone<P: Provider>(&self, provider: &P) -> Outcome {
+        match provider.get_zone(&self.missing_zone_id).await {
+            Err(RetrieveZoneError::NotFound) => Outcome::Passed,
+            Ok(_) => Outcome::Deviated(vec![format!(
+                "get_zone returned a zone for the unknown ID {:?}",
+                self.missing_zone_id
+            )]),
+            Err(err) => Outcome::Deviated(vec![format!(
+                "get_zone returned {:?} instead of NotFound",
+                err
+            )]),
+        }
+    }
+
+    /// Runs a record through its lifecycle, returning the ID it had if it was deleted.
+    async fn record_roundtrip<Z: CreateRecord + DeleteRecord>(
+        &self,
+        zone: &Z,
+        host: &str,
+        data: &RecordData,
+    ) -> (Outcome, Option<String>) {
+        let created = match zone.create_record(host, data, self.ttl).await {
+            Ok(created) => created,
+            Err(CreateRecordError::UnsupportedType) => return (Outcome::Unsupported, None),
+            Err(err) => {
+                let deviation = format!("create_record failed: {:?}", err);
+                return (Outcome::Deviated(vec![deviation]), None);
+            }
+        };
+
+        let mut deviations = Vec::new();
+        self.check_record("create_record", zone, &created, host, data, &mut deviations);
+        match zone.get_record(&created.id).await {
+            Ok(fetched) => {
+                if fetched.id != created.id {
+                    deviations.push(format!(
+                        "get_record returned ID {:?} instead of {:?}",
+                        fetched.id, created.id
+                    ));
+                }
+                self.check_record("get_record", zone, &fetched, host, data, &mut deviations);
+            }
+            Err(err) => deviations.push(format!("get_record failed: {:?}", err)),
+        }
+        match zone.list_records().await {
+            Ok(records) => match records.iter().find(|record| record.id == created.id) {
+                Some(listed) => {
+                    self.check_record("list_records", zone, listed, host, data, &mut deviations)
+                }
+                None => deviations.push("list_records did not include the created record".into()),
+            },
+            Err(err) => deviations.push(format!("list_records failed: {:?}", err)),
+        }
+
+        if let Err(err) = zone.delete_record(&created.id).await {
+            deviations.push(format!("delete_record failed: {:?}", err));
+            return (Outcome::Deviated(deviations), None);
+        }
+        match zone.get_record(&created.id).await {
+            Err(RetrieveRecordError::NotFound) => {}
+            Ok(_) => deviations.push("get_record returned the deleted record".to_string()),
+            Err(err) => deviations.push(format!(
+                "get_record returned {:?} instead of NotFound for the deleted record",
+                err
+            )),
+        }
+        match zone.list_records().await {
+            Ok(records) if records.iter().any(|record| record.id == created.id) => {
+                deviations.push("list_records included the deleted record".to_string());
+            }
+            Ok(_) => {}
+            Err(err) => deviations.push(format!("list_records failed: {:?}", err)),
+        }
+
+        (Outcome::from_deviations(deviations), Some(created.id))
+    }
+
+    async fn host_forms<Z: CreateRecord + DeleteRecord>(
+        &self,
+        zone: &Z,
+        data: &RecordData,
+    ) -> Outcome {
+        let host = self.sample_host(data);
+        let fqdn = format!("{}.{}.", host, zone.domain().trim_end_matches('.'));
+
+        let created = match zone.create_record(&fqdn, data, self.ttl).await {
+            Ok(created) => created,
+            Err(err) => {
+                return Outcome::Deviated(vec![format!(
+                    "create_record failed for the fully qualified host {:?}: {:?}",
+                    fqdn, err
+                )])
+            }
+        };
+
+        let mut deviations = Vec::new();
+        self.check_record(
+            "create_record",
+            zone,
+            &created,
+            &host,
+            data,
+            &mut deviations,
+        );
+        if let Err(err) = zone.delete_record(&created.id).await {
+            deviations.push(format!("delete_record failed: {:?}", err));
+        }
+        Outcome::from_deviations(deviations)
+    }
+
+    /// Compares a record returned by `operation` to the one that was created.
+    fn check_record<Z: Zone>(
+        &self,
+        operation: &str,
+        zone: &Z,
+        record: &Record,
+        host: &str,
+        data: &RecordData,
+        deviations: &mut Vec<String>,
+    ) {
+        let expected = normalize_host(host, zone.domain());
+        if normalize_host(&record.host, zone.domain()) != expected {
+            deviations.push(format!(
+                "{} returned host {:?} instead of {:?}",
+                operation, record.host, expected
+            ));
+        }
+        if !same_value(&record.data, data) {
+            deviations.push(format!(
+                "{} returned {} {:?} instead of {} {:?}",
+                operation,
+                record.data.get_type(),
+                record.data.get_value(),
+                data.get_type(),
+                data.get_value()
+            ));
+        }
+        if record.ttl != self.ttl {
+            deviations.push(format!(
+                "{} returned TTL {} instead of {}",
+                operation, record.ttl, self.ttl
+            ));
+        }
+    }
+}
+
+async fn missing_record<Z: DeleteRecord>(zone: &Z, record_id: &str) -> Outcome {
+    let mut deviations = Vec::new();
+    match zone.get_record(record_id).await {
+        Err(RetrieveRecordError::NotFound) => {}
+        Ok(_) => deviations.push("get_record returned a deleted record".to_string()),
+        Err(err) => deviations.push(format!("get_record returned {:?} instead of NotFound", err)),
+    }
+    match zone.delete_record(record_id).await {
+        Err(DeleteRecordError::NotFound) => {}
+        Ok(()) => deviations.push("delete_record succeeded for a deleted record".to_string()),
+        Err(err) => deviations.push(format!(
+            "delete_record returned {:?} instead of NotFound",
+            err
+        )),
+    }
+    Outcome::from_deviations(deviations)
+}
+
+fn same_domain(a: &str, b: &str) -> bool {
+    a.trim_end_matches('.')
+        .eq_ignore_ascii_case(b.trim_end_matches('.'))
+}
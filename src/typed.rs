@@ -0,0 +1,330 @@
+//! Typed records built on the RFC types of [`crate::types`].
+//!
+//! [`Record`] carries hosts and names as strings and TTLs as `u64`, exactly as providers report them.
+//! [`TypedRecord`] holds a fully qualified owner [`DomainName`], a [`Ttl`] within the limit of RFC 2181 §8 and [`TypedRecordData`]
+//! whose names are [`DomainName`]s, so malformed names and TTLs are rejected when the record is built instead of by the provider.
+//!
+//! [`TypedRecords`] creates and updates typed records in every zone supporting record creation and updates.
+//! Before any request is sent, it also checks the record type and TTL against the [`Zone::capabilities`].
+//!
+//! Typed records convert from and to the existing [`Record`] using [`TypedRecord::from_record`] and [`TypedRecord::to_record`],
+//! and their data using [`TryFrom<RecordData>`] and [`From<TypedRecordData>`].
+//!
+//! # Example
+//!
+//! ```
+//! use libdns::typed::{TypedRecord, TypedRecordData};
+//! use libdns::types::{DomainName, MxData, Ttl};
+//! use libdns::{Record, RecordData};
+//!
+//! let record = TypedRecord::new(
+//!     DomainName::from_dotted("example.com").unwrap(),
+//!     Ttl::ONE_HOUR,
+//!     TypedRecordData::MX(MxData::new(10, DomainName::from_dotted("mail.example.com").unwrap())),
+//! );
+//!
+//! let untyped = record.to_record("example.com")?;
+//! assert_eq!(untyped.host, "@");
+//! assert_eq!(untyped.data, RecordData::MX { priority: 10, mail_server: "mail.example.com".to_string() });
+//! assert_eq!(TypedRecord::from_record(&untyped, "example.com")?, record);
+//!
+//! // TTLs above 2^31 - 1 seconds are rejected
+//! let invalid = Record { ttl: 1 << 31, ..untyped };
+//! assert!(TypedRecord::from_record(&invalid, "example.com").is_err());
+//! # Ok::<(), libdns::typed::TypedRecordError>(())
+//! ```
+
+use std::future::Future;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use thiserror::Error;
+
+use crate::types::{DomainName, MxData, RecordType, SoaData, SrvData, Ttl};
+use crate::{
+    normalize_host, ClassifyError, CreateRecord, CreateRecordError, ErrorKind, Record, RecordData,
+    UpdateRecord, UpdateRecordError, Zone,
+};
+
+/// A DNS record with a validated owner name, TTL and data.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct TypedRecord {
+    /// The provider-specific ID, empty for records that were not created yet.
+    pub id: String,
+    /// The fully qualified owner name.
+    pub name: DomainName,
+    pub ttl: Ttl,
+    pub data: TypedRecordData,
+}
+
+impl TypedRecord {
+    /// Creates a record that was not created yet, i.e. without an ID.
+    pub fn new(name: DomainName, ttl: Ttl, data: TypedRecordData) -> Self {
+        Self {
+            id: String::new(),
+            name,
+            ttl,
+            data,
+        }
+    }
+
+    /// Converts a record of the zone of the given domain, whose host may be relative to the domain or fully qualified.
+    pub fn from_record(record: &Record, domain: &str) -> Result<Self, TypedRecordError> {
+        let domain = domain.trim_end_matches('.');
+        let name = match normalize_host(&record.host, domain).as_str() {
+            "@" => domain.to_string(),
+            relative => format!("{}.{}", relative, domain),
+        };
+
+        Ok(Self {
+            id: record.id.clone(),
+            name: domain_name(&name)?,
+            ttl: u32::try_from(record.ttl)
+                .ok()
+                .and_then(Ttl::try_new)
+                .ok_or(TypedRecordError::InvalidTtl(record.ttl))?,
+            data: TypedRecordData::try_from(record.data.clone())?,
+        })
+    }
+
+    /// Converts the record to a [`Record`] whose host is relative to the given domain, using `@` for the apex.
+    /// Fails if the name is not within the domain.
+    pub fn to_record(&self, domain: &str) -> Result<Record, TypedRecordError> {
+        Ok(Record {
+            id: self.id.clone(),
+            host: self.host(domain)?,
+            data: self.data.clone().into(),
+            ttl: self.ttl.as_secs() as u64,
+        })
+    }
+
+    /// Returns the name relative to the given domain, using `@` for the apex.
+    fn host(&self, domain: &str) -> Result<String, TypedRecordError> {
+        DomainName::from_unicode(domain)
+            .and_then(|zone| self.name.relative_to(&zone))
+            .ok_or_else(|| TypedRecordError::OutsideZone {
+                name: self.name.to_dotted(),
+                zone: domain.trim_end_matches('.').to_string(),
+            })
+    }
+}
+
+/// Represents the data of a [`TypedRecord`].
+///
+/// Types with a representation in [`crate::types`] use it; all others are kept as [`RecordData`], with the names within validated nonetheless.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum TypedRecordData {
+    A(Ipv4Addr),
+    AAAA(Ipv6Addr),
+    CNAME(DomainName),
+    NS(DomainName),
+    PTR(DomainName),
+    MX(MxData),
+    SRV(SrvData),
+    SOA(Box<SoaData>),
+    TXT(String),
+    Other(RecordData),
+}
+
+impl TypedRecordData {
+    /// Returns the record type, or `None` for an [`Other`](Self::Other) type unknown to [`RecordType`].
+    pub fn record_type(&self) -> Option<RecordType> {
+        match self {
+            TypedRecordData::A(_) => Some(RecordType::A),
+            TypedRecordData::AAAA(_) => Some(RecordType::AAAA),
+            TypedRecordData::CNAME(_) => Some(RecordType::CNAME),
+            TypedRecordData::NS(_) => Some(RecordType::NS),
+            TypedRecordData::PTR(_) => Some(RecordType::PTR),
+            TypedRecordData::MX(_) => Some(RecordType::MX),
+            TypedRecordData::SRV(_) => Some(RecordType::SRV),
+            TypedRecordData::SOA(_) => Some(RecordType::SOA),
+            TypedRecordData::TXT(_) => Some(RecordType::TXT),
+            TypedRecordData::Other(data) => RecordType::from_str(data.get_type()),
+        }
+    }
+}
+
+impl TryFrom<RecordData> for TypedRecordData {
+    type Error = TypedRecordError;
+
+    fn try_from(data: RecordData) -> Result<Self, Self::Error> {
+        // The root name stands for the owner name or no replacement
+        let root_or_name = |name: &str| match name {
+            "." => Ok(()),
+            name => domain_name(name).map(|_| ()),
+        };
+
+        Ok(match data {
+            RecordData::A(addr) => TypedRecordData::A(addr),
+            RecordData::AAAA(addr) => TypedRecordData::AAAA(addr),
+            RecordData::CNAME(name) => TypedRecordData::CNAME(domain_name(&name)?),
+            RecordData::NS(name) => TypedRecordData::NS(domain_name(&name)?),
+            RecordData::PTR(name) => TypedRecordData::PTR(domain_name(&name)?),
+            RecordData::MX {
+                priority,
+                mail_server,
+            } => TypedRecordData::MX(MxData::new(priority, domain_name(&mail_server)?)),
+            RecordData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => TypedRecordData::SRV(SrvData::new(priority, weight, port, domain_name(&target)?)),
+            RecordData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => TypedRecordData::SOA(Box::new(SoaData {
+                mname: domain_name(&mname)?,
+                rname: domain_name(&rname)?,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            })),
+            RecordData::TXT(text) => TypedRecordData::TXT(text),
+            RecordData::HTTPS { ref target, .. } | RecordData::SVCB { ref target, .. } => {
+                root_or_name(target)?;
+                TypedRecordData::Other(data)
+            }
+            RecordData::NAPTR {
+                ref replacement, ..
+            } => {
+                root_or_name(replacement)?;
+                TypedRecordData::Other(data)
+            }
+            other => TypedRecordData::Other(other),
+        })
+    }
+}
+
+impl From<TypedRecordData> for RecordData {
+    fn from(data: TypedRecordData) -> Self {
+        match data {
+            TypedRecordData::A(addr) => RecordData::A(addr),
+            TypedRecordData::AAAA(addr) => RecordData::AAAA(addr),
+            TypedRecordData::CNAME(name) => RecordData::CNAME(name.to_dotted()),
+            TypedRecordData::NS(name) => RecordData::NS(name.to_dotted()),
+            TypedRecordData::PTR(name) => RecordData::PTR(name.to_dotted()),
+            TypedRecordData::MX(mx) => RecordData::MX {
+                priority: mx.priority,
+                mail_server: mx.exchange.to_dotted(),
+            },
+            TypedRecordData::SRV(srv) => RecordData::SRV {
+                priority: srv.priority,
+                weight: srv.weight,
+                port: srv.port,
+                target: srv.target.to_dotted(),
+            },
+            TypedRecordData::SOA(soa) => RecordData::SOA {
+                mname: soa.mname.to_dotted(),
+                rname: soa.rname.to_dotted(),
+                serial: soa.serial,
+                refresh: soa.refresh,
+                retry: soa.retry,
+                expire: soa.expire,
+                minimum: soa.minimum,
+            },
+            TypedRecordData::TXT(text) => RecordData::TXT(text),
+            TypedRecordData::Other(data) => data,
+        }
+    }
+}
+
+/// Parses a non-empty domain name, converting Unicode labels to ASCII.
+fn domain_name(name: &str) -> Result<DomainName, TypedRecordError> {
+    DomainName::from_unicode(name)
+        .filter(|name| !name.is_root())
+        .ok_or_else(|| TypedRecordError::InvalidName(name.to_string()))
+}
+
+/// Represents an error that occured when converting a [`Record`] or [`RecordData`] to its typed counterpart or back.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Error)]
+pub enum TypedRecordError {
+    /// Indicates an empty name, an empty or too long label or a name longer than 255 octets.
+    #[error("invalid domain name `{0}`")]
+    InvalidName(String),
+
+    /// Indicates a TTL above the maximum of 2^31 - 1 seconds (RFC 2181 §8).
+    #[error("the TTL {0} exceeds the maximum of 2^31 - 1 seconds")]
+    InvalidTtl(u64),
+
+    /// Indicates a record name that is not within the zone.
+    #[error("the name `{name}` is not within the zone `{zone}`")]
+    OutsideZone { name: String, zone: String },
+}
+
+impl ClassifyError for TypedRecordError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Validation
+    }
+}
+
+/// Represents a [`Zone`] that creates and updates [`TypedRecord`]s.
+///
+/// It is implemented for every zone supporting [`CreateRecord`] and [`UpdateRecord`].
+/// Records of a type the zone does not support or with a TTL outside of its range (see [`Zone::capabilities`])
+/// and records outside of the zone are rejected before any request is sent.
+pub trait TypedRecords: CreateRecord + UpdateRecord {
+    /// Creates the record and returns it as reported by the provider.
+    fn create_typed_record(
+        &self,
+        record: &TypedRecord,
+    ) -> impl Future<Output = Result<Record, CreateRecordError<Self::CustomCreateError>>> + Send
+    {
+        async move {
+            let record = match check(self, record) {
+                Ok(record) => record,
+                Err(Rejection::UnsupportedType) => return Err(CreateRecordError::UnsupportedType),
+                Err(Rejection::InvalidRecord) => return Err(CreateRecordError::InvalidRecord),
+            };
+            self.create_record(&record.host, &record.data, record.ttl)
+                .await
+        }
+    }
+
+    /// Replaces the host, value and TTL of the record with the ID of the given record and returns it as reported by the provider.
+    fn update_typed_record(
+        &self,
+        record: &TypedRecord,
+    ) -> impl Future<Output = Result<Record, UpdateRecordError<Self::CustomUpdateError>>> + Send
+    {
+        async move {
+            let record = match check(self, record) {
+                Ok(record) => record,
+                Err(Rejection::UnsupportedType) => return Err(UpdateRecordError::UnsupportedType),
+                Err(Rejection::InvalidRecord) => return Err(UpdateRecordError::InvalidRecord),
+            };
+            self.update_record(&record.id, &record.host, &record.data, record.ttl)
+                .await
+        }
+    }
+}
+
+impl<Z: CreateRecord + UpdateRecord> TypedRecords for Z {}
+
+/// The reason a record was rejected before sending it.
+enum Rejection {
+    UnsupportedType,
+    InvalidRecord,
+}
+
+/// Converts the record for the zone after checking it against the zone's capabilities.
+fn check(zone: &(impl Zone + ?Sized), record: &TypedRecord) -> Result<Record, Rejection> {
+    let capabilities = zone.capabilities();
+    let record = record
+        .to_record(zone.domain())
+        .map_err(|_| Rejection::InvalidRecord)?;
+
+    if !capabilities.supports_type(record.data.get_type()) {
+        return Err(Rejection::UnsupportedType);
+    }
+    if !capabilities.supports_ttl(record.ttl) {
+        return Err(Rejection::InvalidRecord);
+    }
+    Ok(record)
+}
@@ -4,6 +4,9 @@
 //! - RFC 1035: Domain Names - Implementation and Specification
 //! - RFC 2181: Clarifications to the DNS Specification
 //! - RFC 2782: A DNS RR for specifying the location of services (DNS SRV)
+//! - RFC 4034 §6.1: Canonical DNS Name Order
+//! - RFC 4343: Domain Name System (DNS) Case Insensitivity Clarification
+//! - RFC 5891 and UTS #46: Internationalized Domain Names in Applications (IDNA)
 //!
 //! All types in this module are designed to be:
 //! - Fixed size (no heap allocation where possible)
@@ -23,7 +26,12 @@
 //! | Weight (SRV) | 16-bit unsigned | RFC 2782 |
 //! | Port (SRV) | 16-bit unsigned | RFC 2782 |
 
+use core::cmp::Ordering;
 use core::fmt;
+use core::hash::{Hash, Hasher};
+use std::borrow::Cow;
+
+use idna::AsciiDenyList;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -87,8 +95,10 @@ impl fmt::Display for Environment {
 /// Labels are limited to 63 octets (RFC 1035 §2.3.4).
 /// The high-order two bits of the length octet must be zero.
 ///
+/// Labels are compared case-insensitively (RFC 4343) and ordered as in the canonical order of RFC 4034 §6.1.
+///
 /// This is a fixed-size, Copy type with no heap allocation.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy)]
 #[repr(C)]
 pub struct Label {
     /// Length of the label (1-63).
@@ -153,6 +163,38 @@ impl Label {
     }
 }
 
+impl PartialEq for Label {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes().eq_ignore_ascii_case(other.as_bytes())
+    }
+}
+
+impl Eq for Label {}
+
+impl Hash for Label {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_lowercase(self.as_bytes(), state);
+    }
+}
+
+impl PartialOrd for Label {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Label {
+    /// Compares the labels as octet strings with uppercase ASCII letters treated as lowercase (RFC 4034 §6.1).
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lowercase = |label: &Self| {
+            let mut bytes = label.data;
+            bytes.make_ascii_lowercase();
+            bytes
+        };
+        lowercase(self)[..self.len()].cmp(&lowercase(other)[..other.len()])
+    }
+}
+
 impl fmt::Debug for Label {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.as_str() {
@@ -185,9 +227,12 @@ impl Default for Label {
 /// Domain names are limited to 255 octets total (RFC 1035 §2.3.4).
 /// This includes the length octets for each label and the terminating zero.
 ///
+/// Names are compared case-insensitively (RFC 4343) and ordered in the canonical order of RFC 4034 §6.1,
+/// i.e. by their labels from right to left.
+///
 /// This is a fixed-size type with no heap allocation.
 /// It's too large to be Copy (256 bytes), but implements Clone.
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone)]
 #[repr(C)]
 pub struct DomainName {
     /// Length of the domain name in wire format.
@@ -199,6 +244,8 @@ pub struct DomainName {
 impl DomainName {
     /// Creates a new domain name from a dotted string (e.g., "example.com").
     ///
+    /// Labels are stored as given, so names with Unicode labels should be created with
+    /// [`DomainName::from_unicode`] instead.
     /// Returns `None` if the domain name is invalid or too long.
     pub fn from_dotted(s: &str) -> Option<Self> {
         if s.is_empty() {
@@ -240,6 +287,43 @@ impl DomainName {
         })
     }
 
+    /// Creates a new domain name from a dotted string that may contain Unicode labels (e.g., "münchen.de"),
+    /// which are converted to ASCII as described in [`DomainName::to_ascii`].
+    ///
+    /// Returns `None` if the domain name is invalid or too long in ASCII form.
+    pub fn from_unicode(s: &str) -> Option<Self> {
+        Self::from_dotted(&to_ascii_name(s)?)
+    }
+
+    /// Returns the domain name with its Unicode labels converted to ASCII (IDNA 2008 / UTS #46),
+    /// e.g. "xn--mnchen-3ya.de" for "münchen.de".
+    ///
+    /// Names containing Unicode labels are mapped as UTS #46 specifies, which includes lowercasing them.
+    /// Names consisting of ASCII labels only are returned unchanged.
+    /// Returns `None` if the name is not a valid internationalized domain name or too long in ASCII form.
+    pub fn to_ascii(&self) -> Option<Self> {
+        Self::from_unicode(&self.to_dotted())
+    }
+
+    /// Returns the domain name in dotted notation with its ASCII-compatible labels converted to Unicode,
+    /// e.g. "münchen.de" for "xn--mnchen-3ya.de".
+    ///
+    /// Labels that are no valid Punycode of a Unicode label are kept as is.
+    pub fn to_unicode(&self) -> String {
+        self.to_dotted()
+            .split('.')
+            .map(|label| {
+                label
+                    .get(..4)
+                    .filter(|prefix| prefix.eq_ignore_ascii_case("xn--"))
+                    .and_then(|_| idna::punycode::decode_to_string(&label[4..]))
+                    .filter(|decoded| !decoded.is_ascii())
+                    .unwrap_or_else(|| label.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
     /// Returns the domain name in dotted notation.
     pub fn to_dotted(&self) -> String {
         let mut result = String::with_capacity(self.len as usize);
@@ -282,6 +366,154 @@ impl DomainName {
     pub const fn is_root(&self) -> bool {
         self.len == 1 && self.data[0] == 0
     }
+
+    /// Returns the labels from left to right, without the empty root label.
+    pub fn labels(&self) -> impl Iterator<Item = Label> + '_ {
+        self.label_offsets().map(|offset| self.label_at(offset))
+    }
+
+    /// Returns the number of labels, without the empty root label.
+    pub fn label_count(&self) -> usize {
+        self.label_offsets().count()
+    }
+
+    /// Returns the name without its leftmost label, or `None` for the root domain.
+    pub fn parent(&self) -> Option<Self> {
+        if self.is_root() {
+            return None;
+        }
+        Self::from_wire_parts(&[&self.as_wire_bytes()[1 + self.data[0] as usize..]])
+    }
+
+    /// Returns whether the name equals the zone or is below it.
+    pub fn is_subdomain_of(&self, zone: &DomainName) -> bool {
+        let bytes = self.as_wire_bytes();
+        let Some(offset) = bytes.len().checked_sub(zone.wire_len()) else {
+            return false;
+        };
+
+        // The zone must start at a label boundary, which includes the root label
+        (offset == bytes.len() - 1 || self.label_offsets().any(|start| start == offset))
+            && bytes[offset..].eq_ignore_ascii_case(zone.as_wire_bytes())
+    }
+
+    /// Returns the name with the label added to its left, or `None` if the label is empty or the name would be too long.
+    pub fn prepend(&self, label: &Label) -> Option<Self> {
+        if label.is_empty() {
+            return None;
+        }
+        Self::from_wire_parts(&[&[label.len() as u8], label.as_bytes(), self.as_wire_bytes()])
+    }
+
+    /// Returns the name with the labels of `suffix` added to its right, or `None` if the name would be too long.
+    pub fn append(&self, suffix: &DomainName) -> Option<Self> {
+        Self::from_wire_parts(&[
+            &self.as_wire_bytes()[..self.wire_len() - 1],
+            suffix.as_wire_bytes(),
+        ])
+    }
+
+    /// Returns the name relative to the zone in dotted notation, using `@` for the zone apex,
+    /// or `None` if the name is not within the zone (see [`DomainName::is_subdomain_of`]).
+    pub fn relative_to(&self, zone: &DomainName) -> Option<String> {
+        if !self.is_subdomain_of(zone) {
+            return None;
+        }
+
+        let count = self.label_count() - zone.label_count();
+        if count == 0 {
+            return Some("@".to_string());
+        }
+        let labels: Vec<_> = self
+            .labels()
+            .take(count)
+            .map(|label| String::from_utf8_lossy(label.as_bytes()).into_owned())
+            .collect();
+        Some(labels.join("."))
+    }
+
+    /// Creates a domain name from the concatenation of wire-format parts, which must end with the root label.
+    fn from_wire_parts(parts: &[&[u8]]) -> Option<Self> {
+        let len: usize = parts.iter().map(|part| part.len()).sum();
+        if len > MAX_DOMAIN_LEN {
+            return None;
+        }
+
+        let mut data = [0u8; MAX_DOMAIN_LEN];
+        let mut pos = 0;
+        for part in parts {
+            data[pos..pos + part.len()].copy_from_slice(part);
+            pos += part.len();
+        }
+
+        Some(Self {
+            len: len as u8,
+            data,
+        })
+    }
+
+    /// Returns the offsets of the labels within the wire format, from left to right.
+    fn label_offsets(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut pos = 0;
+        std::iter::from_fn(move || {
+            let offset = pos;
+            match self.data[offset] as usize {
+                0 => None,
+                len => {
+                    pos += 1 + len;
+                    Some(offset)
+                }
+            }
+        })
+    }
+
+    /// Returns the label starting at the offset.
+    fn label_at(&self, offset: usize) -> Label {
+        let len = self.data[offset] as usize;
+        Label::new(&self.data[offset + 1..offset + 1 + len]).expect("Labels are at most 63 octets")
+    }
+}
+
+impl PartialEq for DomainName {
+    fn eq(&self, other: &Self) -> bool {
+        // Length octets are below 64, so only the letters of labels are affected
+        self.as_wire_bytes()
+            .eq_ignore_ascii_case(other.as_wire_bytes())
+    }
+}
+
+impl Eq for DomainName {}
+
+impl Hash for DomainName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_lowercase(self.as_wire_bytes(), state);
+    }
+}
+
+impl PartialOrd for DomainName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DomainName {
+    /// Compares the names by their labels from right to left, where a name sorts before the names below it (RFC 4034 §6.1).
+    fn cmp(&self, other: &Self) -> Ordering {
+        /// Returns the labels from right to left. A name has at most 127 labels.
+        fn reversed(name: &DomainName) -> impl Iterator<Item = Label> + '_ {
+            let mut offsets = [0u8; MAX_DOMAIN_LEN / 2];
+            let mut count = 0;
+            for offset in name.label_offsets() {
+                offsets[count] = offset as u8;
+                count += 1;
+            }
+            (0..count)
+                .rev()
+                .map(move |i| name.label_at(offsets[i] as usize))
+        }
+
+        reversed(self).cmp(reversed(other))
+    }
 }
 
 impl fmt::Debug for DomainName {
@@ -305,6 +537,25 @@ impl Default for DomainName {
     }
 }
 
+/// Hashes the bytes with uppercase ASCII letters treated as lowercase, matching case-insensitive equality.
+fn hash_lowercase<H: Hasher>(bytes: &[u8], state: &mut H) {
+    state.write_usize(bytes.len());
+    for byte in bytes {
+        state.write_u8(byte.to_ascii_lowercase());
+    }
+}
+
+/// Converts the Unicode labels of a dotted name to ASCII (see [`DomainName::to_ascii`]), keeping ASCII names unchanged.
+///
+/// Names may be relative, e.g. hosts like `_acme-challenge.büro` or `@`, and keep their trailing dot.
+pub(crate) fn to_ascii_name(name: &str) -> Option<Cow<'_, str>> {
+    if name.is_ascii() {
+        return Some(Cow::Borrowed(name));
+    }
+    // Underscores and wildcards are common in hosts, so no ASCII characters are rejected
+    idna::domain_to_ascii_cow(name.as_bytes(), AsciiDenyList::EMPTY).ok()
+}
+
 /// DNS Time To Live value.
 ///
 /// Per RFC 2181 §8, TTL is an unsigned 32-bit integer with a maximum
@@ -412,10 +663,20 @@ pub enum RecordType {
     AAAA = 28,
     /// Server selection (RFC 2782).
     SRV = 33,
+    /// Naming authority pointer (RFC 3403).
+    NAPTR = 35,
     /// Delegation signer (RFC 4034).
     DS = 43,
+    /// SSH key fingerprint (RFC 4255).
+    SSHFP = 44,
     /// DNSKEY (RFC 4034).
     DNSKEY = 48,
+    /// TLS certificate association (RFC 6698).
+    TLSA = 52,
+    /// General purpose service binding (RFC 9460).
+    SVCB = 64,
+    /// Service binding for HTTPS (RFC 9460).
+    HTTPS = 65,
     /// Certification Authority Authorization (RFC 8659).
     CAA = 257,
 }
@@ -434,14 +695,20 @@ impl RecordType {
             16 => Some(Self::TXT),
             28 => Some(Self::AAAA),
             33 => Some(Self::SRV),
+            35 => Some(Self::NAPTR),
             43 => Some(Self::DS),
+            44 => Some(Self::SSHFP),
             48 => Some(Self::DNSKEY),
+            52 => Some(Self::TLSA),
+            64 => Some(Self::SVCB),
+            65 => Some(Self::HTTPS),
             257 => Some(Self::CAA),
             _ => None,
         }
     }
 
     /// Creates a RecordType from a string.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_uppercase().as_str() {
             "A" => Some(Self::A),
@@ -454,8 +721,13 @@ impl RecordType {
             "TXT" => Some(Self::TXT),
             "AAAA" => Some(Self::AAAA),
             "SRV" => Some(Self::SRV),
+            "NAPTR" => Some(Self::NAPTR),
             "DS" => Some(Self::DS),
+            "SSHFP" => Some(Self::SSHFP),
             "DNSKEY" => Some(Self::DNSKEY),
+            "TLSA" => Some(Self::TLSA),
+            "SVCB" => Some(Self::SVCB),
+            "HTTPS" => Some(Self::HTTPS),
             "CAA" => Some(Self::CAA),
             _ => None,
         }
@@ -480,8 +752,13 @@ impl RecordType {
             Self::TXT => "TXT",
             Self::AAAA => "AAAA",
             Self::SRV => "SRV",
+            Self::NAPTR => "NAPTR",
             Self::DS => "DS",
+            Self::SSHFP => "SSHFP",
             Self::DNSKEY => "DNSKEY",
+            Self::TLSA => "TLSA",
+            Self::SVCB => "SVCB",
+            Self::HTTPS => "HTTPS",
             Self::CAA => "CAA",
         }
     }
@@ -509,6 +786,10 @@ pub enum RecordClass {
     CH = 3,
     /// Hesiod (RFC 1035).
     HS = 4,
+    /// Meta class for deleting individual records in dynamic updates (RFC 2136 §2.4).
+    NONE = 254,
+    /// Meta class matching any class (RFC 1035 §3.2.5).
+    ANY = 255,
 }
 
 impl RecordClass {
@@ -519,6 +800,8 @@ impl RecordClass {
             2 => Some(Self::CS),
             3 => Some(Self::CH),
             4 => Some(Self::HS),
+            254 => Some(Self::NONE),
+            255 => Some(Self::ANY),
             _ => None,
         }
     }
@@ -537,6 +820,8 @@ impl fmt::Display for RecordClass {
             Self::CS => write!(f, "CS"),
             Self::CH => write!(f, "CH"),
             Self::HS => write!(f, "HS"),
+            Self::NONE => write!(f, "NONE"),
+            Self::ANY => write!(f, "ANY"),
         }
     }
 }
@@ -0,0 +1,941 @@
+//! Encoding and decoding resource records in the DNS wire format (RFC 1035 §4).
+//!
+//! [`ResourceRecord`] holds the owner name, class, TTL and data of a record and converts it from and to the wire format,
+//! e.g. to compute DNSSEC digests or to build zone transfers and dynamic updates.
+//! The data of all [`RecordData`] variants is encoded, including the service parameters of HTTPS and SVCB records (RFC 9460 §2.2).
+//! Data of other types is encoded from and decoded to the generic `\# length hex` form of RFC 3597 §5.
+//!
+//! Names are written uncompressed, but compressed names (RFC 1035 §4.1.4) are followed when decoding records within a message.
+//!
+//! # Example
+//!
+//! ```
+//! use libdns::types::{DomainName, Ttl};
+//! use libdns::wire::ResourceRecord;
+//! use libdns::RecordData;
+//!
+//! let record = ResourceRecord::new(
+//!     DomainName::from_dotted("example.com").unwrap(),
+//!     Ttl::ONE_HOUR,
+//!     RecordData::MX { priority: 10, mail_server: "mail.example.com".to_string() },
+//! );
+//!
+//! let bytes = record.to_wire()?;
+//! assert_eq!(ResourceRecord::from_wire(&bytes)?, record);
+//! # Ok::<(), libdns::wire::WireError>(())
+//! ```
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::Range;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use thiserror::Error;
+
+use crate::types::{DomainName, RecordClass, RecordType, Ttl};
+use crate::{ClassifyError, ErrorKind, RecordData};
+
+/// A resource record (RFC 1035 §4.1.3), whose type is given by its data.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct ResourceRecord {
+    /// The fully qualified owner name.
+    pub name: DomainName,
+    pub class: RecordClass,
+    pub ttl: Ttl,
+    pub data: RecordData,
+}
+
+impl ResourceRecord {
+    /// Creates a record of the [`RecordClass::IN`] class.
+    pub fn new(name: DomainName, ttl: Ttl, data: RecordData) -> Self {
+        Self {
+            name,
+            class: RecordClass::IN,
+            ttl,
+            data,
+        }
+    }
+
+    /// Appends the record in wire format to the buffer, leaving it unchanged on failure.
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), WireError> {
+        let (typ, rdata) = encode_rdata(&self.data).ok_or(WireError::InvalidData)?;
+        if rdata.len() > u16::MAX as usize {
+            return Err(WireError::InvalidData);
+        }
+
+        push_record(
+            buf,
+            &self.name,
+            typ,
+            self.class.as_u16(),
+            self.ttl.as_secs(),
+            &rdata,
+        );
+        Ok(())
+    }
+
+    /// Returns the record in wire format.
+    pub fn to_wire(&self) -> Result<Vec<u8>, WireError> {
+        let mut buf = Vec::new();
+        self.encode(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Decodes the record starting at `offset` within the message, returning it together with the offset following it.
+    ///
+    /// Compressed names may point to any earlier part of the message. Names keep their case and are returned without trailing dot,
+    /// except for the root name of HTTPS, SVCB and NAPTR targets, which is returned as `.`.
+    /// TTLs above the maximum of 2^31 - 1 seconds are treated as zero (RFC 2181 §8).
+    pub fn decode(msg: &[u8], offset: usize) -> Result<(Self, usize), WireError> {
+        let mut reader = Reader {
+            msg,
+            pos: offset,
+            lowercase: false,
+        };
+        let name = DomainName::from_dotted(&reader.name()?).ok_or(WireError::Malformed)?;
+        let typ = reader.u16()?;
+        let class = reader.u16()?;
+        let ttl = reader.u32()?;
+        let end = reader.u16()? as usize + reader.pos;
+        let data = decode_rdata(&mut reader, typ, end)?;
+
+        let record = Self {
+            name,
+            class: RecordClass::from_u16(class).ok_or(WireError::UnknownClass(class))?,
+            ttl: Ttl::try_new(ttl).unwrap_or(Ttl::ZERO),
+            data,
+        };
+        Ok((record, reader.pos))
+    }
+
+    /// Decodes a record that spans all of the bytes.
+    pub fn from_wire(bytes: &[u8]) -> Result<Self, WireError> {
+        match Self::decode(bytes, 0)? {
+            (record, end) if end == bytes.len() => Ok(record),
+            _ => Err(WireError::Malformed),
+        }
+    }
+}
+
+/// Represents an error that occured when encoding or decoding a [`ResourceRecord`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Error)]
+pub enum WireError {
+    /// Indicates truncated input or input that does not follow the wire format.
+    #[error("the record is malformed")]
+    Malformed,
+
+    /// Indicates a class without a [`RecordClass`] variant.
+    #[error("the class {0} is unknown")]
+    UnknownClass(u16),
+
+    /// Indicates data that cannot be encoded, like invalid names, hex digits or service parameters,
+    /// an unknown type or data of a type without a dedicated variant that is not in the generic form.
+    #[error("the record data cannot be encoded")]
+    InvalidData,
+}
+
+impl From<Malformed> for WireError {
+    fn from(_: Malformed) -> Self {
+        WireError::Malformed
+    }
+}
+
+impl ClassifyError for WireError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            WireError::InvalidData => ErrorKind::Validation,
+            WireError::Malformed | WireError::UnknownClass(_) => ErrorKind::Other,
+        }
+    }
+}
+
+/// Indicates that a message or RDATA does not follow the wire format.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct Malformed;
+
+/// Returns a message ID that is hard to guess for off-path attackers and differs between consecutive queries.
+pub(crate) fn next_id() -> u16 {
+    static COUNTER: AtomicU16 = AtomicU16::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    (nanos as u16) ^ ((nanos >> 16) as u16) ^ COUNTER.fetch_add(0x9e37, Ordering::Relaxed)
+}
+
+/// A cursor over a DNS message.
+pub(crate) struct Reader<'a> {
+    pub(crate) msg: &'a [u8],
+    pub(crate) pos: usize,
+    /// Whether names are read in lowercase, as they are compared within this crate.
+    pub(crate) lowercase: bool,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(msg: &'a [u8]) -> Self {
+        Self {
+            msg,
+            pos: 0,
+            lowercase: true,
+        }
+    }
+
+    pub(crate) fn bytes(&mut self, len: usize) -> Result<&'a [u8], Malformed> {
+        let bytes = self.msg.get(self.pos..self.pos + len).ok_or(Malformed)?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub(crate) fn skip(&mut self, len: usize) -> Result<(), Malformed> {
+        self.bytes(len).map(|_| ())
+    }
+
+    /// Reads the remaining bytes up to `end`.
+    pub(crate) fn rest(&mut self, end: usize) -> Result<&'a [u8], Malformed> {
+        self.bytes(end.checked_sub(self.pos).ok_or(Malformed)?)
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, Malformed> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, Malformed> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a possibly compressed domain name (RFC 1035 §4.1.4) as dotted string without trailing dot.
+    pub(crate) fn name(&mut self) -> Result<String, Malformed> {
+        let mut labels: Vec<String> = Vec::new();
+        let mut pos = self.pos;
+        let mut end = None;
+        // Every pointer must point before the previous one, which rules out loops
+        let mut limit = self.pos;
+
+        loop {
+            let len = *self.msg.get(pos).ok_or(Malformed)? as usize;
+            match len {
+                0 => {
+                    end.get_or_insert(pos + 1);
+                    break;
+                }
+                1..=0x3f => {
+                    let label = self.msg.get(pos + 1..pos + 1 + len).ok_or(Malformed)?;
+                    let label = String::from_utf8_lossy(label);
+                    labels.push(if self.lowercase {
+                        label.to_ascii_lowercase()
+                    } else {
+                        label.into_owned()
+                    });
+                    pos += 1 + len;
+                }
+                0xc0..=0xff => {
+                    let low = *self.msg.get(pos + 1).ok_or(Malformed)? as usize;
+                    let target = (len & 0x3f) << 8 | low;
+                    if target >= limit {
+                        return Err(Malformed);
+                    }
+                    end.get_or_insert(pos + 2);
+                    pos = target;
+                    limit = target;
+                }
+                _ => return Err(Malformed),
+            }
+        }
+
+        self.pos = end.unwrap_or(pos);
+        Ok(labels.join("."))
+    }
+
+    /// Reads a target name, which may be the root name written as `.` (RFC 3403 §4.1, RFC 9460 §2.5).
+    pub(crate) fn target(&mut self) -> Result<String, Malformed> {
+        match self.name()? {
+            root if root.is_empty() => Ok(".".to_string()),
+            target => Ok(target),
+        }
+    }
+
+    /// Reads a character string (RFC 1035 §3.3), which is prefixed by its length.
+    pub(crate) fn character_string(&mut self) -> Result<String, Malformed> {
+        let len = self.bytes(1)?[0] as usize;
+        Ok(String::from_utf8_lossy(self.bytes(len)?).into_owned())
+    }
+}
+
+/// The sections of a parsed message, with RDATA left undecoded.
+pub(crate) struct Message {
+    pub(crate) id: u16,
+    pub(crate) flags: u16,
+    pub(crate) answers: Vec<WireRecord>,
+    pub(crate) additional: Vec<WireRecord>,
+}
+
+impl Message {
+    /// Parses a message, skipping the question and authority sections.
+    pub(crate) fn parse(msg: &[u8]) -> Result<Self, Malformed> {
+        let mut reader = Reader::new(msg);
+        let id = reader.u16()?;
+        let flags = reader.u16()?;
+        let qdcount = reader.u16()?;
+        let counts = [reader.u16()?, reader.u16()?, reader.u16()?];
+
+        for _ in 0..qdcount {
+            reader.name()?;
+            reader.skip(4)?;
+        }
+
+        let [answers, authority, additional] = counts.map(|count| {
+            (0..count)
+                .map(|_| WireRecord::parse(&mut reader))
+                .collect::<Result<Vec<_>, _>>()
+        });
+
+        authority?;
+        Ok(Self {
+            id,
+            flags,
+            answers: answers?,
+            additional: additional?,
+        })
+    }
+
+    /// Returns the response code of the header.
+    pub(crate) fn rcode(&self) -> u8 {
+        (self.flags & 0x000f) as u8
+    }
+}
+
+/// A resource record of a parsed message.
+pub(crate) struct WireRecord {
+    /// The offset of the record within the message.
+    pub(crate) start: usize,
+    /// The owner name in lowercase dotted notation, without trailing dot.
+    pub(crate) name: String,
+    pub(crate) typ: u16,
+    pub(crate) class: u16,
+    pub(crate) ttl: u32,
+    /// The location of the RDATA within the message.
+    pub(crate) rdata: Range<usize>,
+}
+
+impl WireRecord {
+    fn parse(reader: &mut Reader<'_>) -> Result<Self, Malformed> {
+        let start = reader.pos;
+        let name = reader.name()?;
+        let typ = reader.u16()?;
+        let class = reader.u16()?;
+        let ttl = reader.u32()?;
+        let len = reader.u16()? as usize;
+        let rdata = reader.pos..reader.pos + len;
+        reader.skip(len)?;
+
+        Ok(Self {
+            start,
+            name,
+            typ,
+            class,
+            ttl,
+            rdata,
+        })
+    }
+
+    /// Decodes the RDATA using [`decode_rdata`].
+    pub(crate) fn data(&self, msg: &[u8]) -> Result<RecordData, Malformed> {
+        let mut reader = Reader {
+            pos: self.rdata.start,
+            ..Reader::new(msg)
+        };
+        decode_rdata(&mut reader, self.typ, self.rdata.end)
+    }
+}
+
+/// Starts a message with the given header fields and section counts (question, answer, authority, additional).
+pub(crate) fn header(id: u16, flags: u16, counts: [u16; 4]) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(512);
+    msg.extend_from_slice(&id.to_be_bytes());
+    msg.extend_from_slice(&flags.to_be_bytes());
+    for count in counts {
+        msg.extend_from_slice(&count.to_be_bytes());
+    }
+    msg
+}
+
+/// Appends an entry of the question section (or the zone section of an update).
+pub(crate) fn push_question(msg: &mut Vec<u8>, name: &DomainName, typ: u16, class: u16) {
+    msg.extend_from_slice(name.as_wire_bytes());
+    msg.extend_from_slice(&typ.to_be_bytes());
+    msg.extend_from_slice(&class.to_be_bytes());
+}
+
+/// Appends a resource record with an uncompressed owner name.
+pub(crate) fn push_record(
+    msg: &mut Vec<u8>,
+    name: &DomainName,
+    typ: u16,
+    class: u16,
+    ttl: u32,
+    rdata: &[u8],
+) {
+    push_question(msg, name, typ, class);
+    msg.extend_from_slice(&ttl.to_be_bytes());
+    msg.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+    msg.extend_from_slice(rdata);
+}
+
+/// Returns the mnemonic of a type code, or the generic `TYPEn` form of RFC 3597 §5 for unknown types.
+pub(crate) fn type_name(typ: u16) -> String {
+    RecordType::from_u16(typ).map_or_else(|| format!("TYPE{}", typ), |t| t.as_str().to_string())
+}
+
+/// Returns the type code of a mnemonic or of the generic `TYPEn` form.
+pub(crate) fn type_code(typ: &str) -> Option<u16> {
+    RecordType::from_str(typ).map(|t| t.as_u16()).or_else(|| {
+        typ.get(..4)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("TYPE"))
+            .and_then(|_| typ[4..].parse().ok())
+    })
+}
+
+/// Decodes the RDATA of a record ending at `end`.
+///
+/// Types without a dedicated [`RecordData`] variant are returned as [`RecordData::Other`]
+/// in the generic `\# length hex` form of RFC 3597 §5.
+pub(crate) fn decode_rdata(
+    reader: &mut Reader<'_>,
+    typ: u16,
+    end: usize,
+) -> Result<RecordData, Malformed> {
+    if end > reader.msg.len() {
+        return Err(Malformed);
+    }
+
+    let data = match RecordType::from_u16(typ) {
+        Some(RecordType::A) => {
+            let octets: [u8; 4] = reader.bytes(4)?.try_into().unwrap();
+            RecordData::A(Ipv4Addr::from(octets))
+        }
+        Some(RecordType::AAAA) => {
+            let octets: [u8; 16] = reader.bytes(16)?.try_into().unwrap();
+            RecordData::AAAA(Ipv6Addr::from(octets))
+        }
+        Some(RecordType::CNAME) => RecordData::CNAME(reader.name()?),
+        Some(RecordType::NS) => RecordData::NS(reader.name()?),
+        Some(RecordType::MX) => RecordData::MX {
+            priority: reader.u16()?,
+            mail_server: reader.name()?,
+        },
+        Some(RecordType::SRV) => RecordData::SRV {
+            priority: reader.u16()?,
+            weight: reader.u16()?,
+            port: reader.u16()?,
+            target: reader.name()?,
+        },
+        Some(RecordType::TXT) => {
+            // Character strings are concatenated, as providers report them
+            let mut text = Vec::new();
+            while reader.pos < end {
+                let len = reader.bytes(1)?[0] as usize;
+                text.extend_from_slice(reader.bytes(len)?);
+            }
+            RecordData::TXT(String::from_utf8_lossy(&text).into_owned())
+        }
+        Some(RecordType::PTR) => RecordData::PTR(reader.name()?),
+        Some(RecordType::SOA) => RecordData::SOA {
+            mname: reader.name()?,
+            rname: reader.name()?,
+            serial: reader.u32()?,
+            refresh: reader.u32()?,
+            retry: reader.u32()?,
+            expire: reader.u32()?,
+            minimum: reader.u32()?,
+        },
+        Some(RecordType::CAA) => {
+            let flags = reader.bytes(1)?[0];
+            let len = reader.bytes(1)?[0] as usize;
+            let tag = String::from_utf8_lossy(reader.bytes(len)?).into_owned();
+            let value = String::from_utf8_lossy(reader.rest(end)?).into_owned();
+            RecordData::CAA { flags, tag, value }
+        }
+        Some(RecordType::TLSA) => {
+            let [usage, selector, matching_type]: [u8; 3] = reader.bytes(3)?.try_into().unwrap();
+            RecordData::TLSA {
+                usage,
+                selector,
+                matching_type,
+                certificate: hex(reader.rest(end)?),
+            }
+        }
+        Some(RecordType::SSHFP) => {
+            let [algorithm, fingerprint_type]: [u8; 2] = reader.bytes(2)?.try_into().unwrap();
+            RecordData::SSHFP {
+                algorithm,
+                fingerprint_type,
+                fingerprint: hex(reader.rest(end)?),
+            }
+        }
+        Some(RecordType::DS) => {
+            let key_tag = reader.u16()?;
+            let [algorithm, digest_type]: [u8; 2] = reader.bytes(2)?.try_into().unwrap();
+            RecordData::DS {
+                key_tag,
+                algorithm,
+                digest_type,
+                digest: hex(reader.rest(end)?),
+            }
+        }
+        Some(RecordType::NAPTR) => RecordData::NAPTR {
+            order: reader.u16()?,
+            preference: reader.u16()?,
+            flags: reader.character_string()?,
+            services: reader.character_string()?,
+            regexp: reader.character_string()?,
+            replacement: reader.target()?,
+        },
+        Some(RecordType::HTTPS) => RecordData::HTTPS {
+            priority: reader.u16()?,
+            target: reader.target()?,
+            params: decode_params(reader, end)?,
+        },
+        Some(RecordType::SVCB) => RecordData::SVCB {
+            priority: reader.u16()?,
+            target: reader.target()?,
+            params: decode_params(reader, end)?,
+        },
+        _ => {
+            let rdata = reader.rest(end)?;
+            RecordData::Other {
+                typ: type_name(typ),
+                value: format!("\\# {} {}", rdata.len(), hex(rdata))
+                    .trim_end()
+                    .to_string(),
+            }
+        }
+    };
+
+    if reader.pos != end {
+        return Err(Malformed);
+    }
+    Ok(data)
+}
+
+/// Encodes the record data, returning its type code and RDATA.
+///
+/// Names are written uncompressed, with Unicode labels converted to ASCII. Returns `None` for types without
+/// a dedicated [`RecordData`] variant, unless the value is in the generic `\# length hex` form.
+pub(crate) fn encode_rdata(data: &RecordData) -> Option<(u16, Vec<u8>)> {
+    let name = |name: &str| DomainName::from_unicode(name).map(|n| n.as_wire_bytes().to_vec());
+    // The root name stands for no replacement or the owner name (RFC 3403 §4.1, RFC 9460 §2.5)
+    let target = |target: &str| match target {
+        "." => Some(vec![0]),
+        target => name(target),
+    };
+
+    let rdata = match data {
+        RecordData::A(ip) => ip.octets().to_vec(),
+        RecordData::AAAA(ip) => ip.octets().to_vec(),
+        RecordData::CNAME(target) | RecordData::NS(target) => name(target)?,
+        RecordData::MX {
+            priority,
+            mail_server,
+        } => [priority.to_be_bytes().to_vec(), name(mail_server)?].concat(),
+        RecordData::SRV {
+            priority,
+            weight,
+            port,
+            target,
+        } => [
+            priority.to_be_bytes().to_vec(),
+            weight.to_be_bytes().to_vec(),
+            port.to_be_bytes().to_vec(),
+            name(target)?,
+        ]
+        .concat(),
+        RecordData::TXT(text) => {
+            let text = text
+                .strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .unwrap_or(text);
+            let mut rdata = Vec::with_capacity(text.len() + text.len() / 255 + 1);
+            // Character strings hold at most 255 bytes (RFC 1035 §3.3)
+            for chunk in text.as_bytes().chunks(255) {
+                rdata.push(chunk.len() as u8);
+                rdata.extend_from_slice(chunk);
+            }
+            if text.is_empty() {
+                rdata.push(0);
+            }
+            rdata
+        }
+        RecordData::PTR(target) => name(target)?,
+        RecordData::SOA {
+            mname,
+            rname,
+            serial,
+            refresh,
+            retry,
+            expire,
+            minimum,
+        } => {
+            let mut rdata = [name(mname)?, name(rname)?].concat();
+            for timer in [serial, refresh, retry, expire, minimum] {
+                rdata.extend_from_slice(&timer.to_be_bytes());
+            }
+            rdata
+        }
+        RecordData::CAA { flags, tag, value } => {
+            let tag = u8::try_from(tag.len())
+                .ok()
+                .map(|len| (len, tag.as_bytes()))?;
+            [&[*flags, tag.0], tag.1, value.as_bytes()].concat()
+        }
+        RecordData::TLSA {
+            usage,
+            selector,
+            matching_type,
+            certificate,
+        } => [vec![*usage, *selector, *matching_type], unhex(certificate)?].concat(),
+        RecordData::SSHFP {
+            algorithm,
+            fingerprint_type,
+            fingerprint,
+        } => [vec![*algorithm, *fingerprint_type], unhex(fingerprint)?].concat(),
+        RecordData::DS {
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+        } => [
+            key_tag.to_be_bytes().to_vec(),
+            vec![*algorithm, *digest_type],
+            unhex(digest)?,
+        ]
+        .concat(),
+        RecordData::NAPTR {
+            order,
+            preference,
+            flags,
+            services,
+            regexp,
+            replacement,
+        } => {
+            let mut rdata = [order.to_be_bytes(), preference.to_be_bytes()].concat();
+            for text in [flags, services, regexp] {
+                rdata.push(u8::try_from(text.len()).ok()?);
+                rdata.extend_from_slice(text.as_bytes());
+            }
+            rdata.extend(target(replacement)?);
+            rdata
+        }
+        RecordData::HTTPS {
+            priority,
+            target: svc_target,
+            params,
+        }
+        | RecordData::SVCB {
+            priority,
+            target: svc_target,
+            params,
+        } => [
+            priority.to_be_bytes().to_vec(),
+            target(svc_target)?,
+            encode_params(params)?,
+        ]
+        .concat(),
+        RecordData::Other { value, .. } => decode_generic(value)?,
+    };
+
+    Some((type_code(data.get_type())?, rdata))
+}
+
+/// Parses the generic `\# length hex` RDATA form of RFC 3597 §5.
+fn decode_generic(value: &str) -> Option<Vec<u8>> {
+    let mut parts = value.split_whitespace();
+    if parts.next()? != "\\#" {
+        return None;
+    }
+    let len: usize = parts.next()?.parse().ok()?;
+    let hex: String = parts.collect();
+    if hex.len() != len * 2 {
+        return None;
+    }
+
+    unhex(&hex)
+}
+
+/// Formats bytes as lowercase hexadecimal digits.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Parses hexadecimal digits into bytes.
+fn unhex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len() / 2)
+        .map(|i| u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok())
+        .collect()
+}
+
+/// The names of the service parameter keys, indexed by their number (RFC 9460 §14.3.2).
+const SVC_PARAM_KEYS: [&str; 9] = [
+    "mandatory",
+    "alpn",
+    "no-default-alpn",
+    "port",
+    "ipv4hint",
+    "ech",
+    "ipv6hint",
+    "dohpath",
+    "ohttp",
+];
+
+/// Returns the number of a service parameter key, which may also be given in the generic `keyN` form.
+fn svc_param_key(name: &str) -> Option<u16> {
+    match SVC_PARAM_KEYS.iter().position(|key| *key == name) {
+        Some(key) => Some(key as u16),
+        None => name.strip_prefix("key")?.parse().ok(),
+    }
+}
+
+/// Returns the name of a service parameter key, or the generic `keyN` form for unknown keys.
+fn svc_param_name(key: u16) -> String {
+    SVC_PARAM_KEYS
+        .get(key as usize)
+        .map_or_else(|| format!("key{}", key), |name| name.to_string())
+}
+
+/// Encodes service parameters in presentation format (RFC 9460 §2.1) as their wire format, ordered by key.
+fn encode_params(params: &str) -> Option<Vec<u8>> {
+    let mut entries = Vec::new();
+    for param in split_params(params)? {
+        let (name, value) = param.split_once('=').unwrap_or((param, ""));
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        let list = || value.split(',').filter(|item| !item.is_empty());
+
+        let key = svc_param_key(name)?;
+        let value = match key {
+            0 => {
+                // Mandatory keys are in strictly increasing order as well (RFC 9460 §8)
+                let mut keys = list().map(svc_param_key).collect::<Option<Vec<_>>>()?;
+                keys.sort();
+                if keys.windows(2).any(|pair| pair[0] == pair[1]) {
+                    return None;
+                }
+                keys.iter().flat_map(|key| key.to_be_bytes()).collect()
+            }
+            1 => list()
+                .map(|id| Some([&[u8::try_from(id.len()).ok()?], id.as_bytes()].concat()))
+                .collect::<Option<Vec<_>>>()?
+                .concat(),
+            2 | 8 if value.is_empty() => Vec::new(),
+            2 | 8 => return None,
+            3 => value.parse::<u16>().ok()?.to_be_bytes().to_vec(),
+            4 => list()
+                .map(|ip| ip.parse::<Ipv4Addr>().ok().map(|ip| ip.octets().to_vec()))
+                .collect::<Option<Vec<_>>>()?
+                .concat(),
+            5 => base64_decode(value)?,
+            6 => list()
+                .map(|ip| ip.parse::<Ipv6Addr>().ok().map(|ip| ip.octets().to_vec()))
+                .collect::<Option<Vec<_>>>()?
+                .concat(),
+            _ => unescape(value)?,
+        };
+        entries.push((key, value));
+    }
+
+    // Keys must be in strictly increasing order (RFC 9460 §2.2)
+    entries.sort_by_key(|(key, _)| *key);
+    if entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+        return None;
+    }
+
+    let mut rdata = Vec::new();
+    for (key, value) in entries {
+        rdata.extend_from_slice(&key.to_be_bytes());
+        rdata.extend_from_slice(&u16::try_from(value.len()).ok()?.to_be_bytes());
+        rdata.extend(value);
+    }
+    Some(rdata)
+}
+
+/// Decodes the service parameters up to `end` in presentation format, omitting the values of parameters without one.
+fn decode_params(reader: &mut Reader<'_>, end: usize) -> Result<String, Malformed> {
+    let mut params = Vec::new();
+    let mut last = None;
+
+    while reader.pos < end {
+        let key = reader.u16()?;
+        if last.is_some_and(|last| key <= last) {
+            return Err(Malformed);
+        }
+        last = Some(key);
+        let len = reader.u16()? as usize;
+        let value = reader.bytes(len)?;
+
+        let value = match key {
+            0 if value.len().is_multiple_of(2) => value
+                .chunks(2)
+                .map(|key| svc_param_name(u16::from_be_bytes([key[0], key[1]])))
+                .collect::<Vec<_>>()
+                .join(","),
+            1 => {
+                let mut ids = Reader::new(value);
+                let mut alpn = Vec::new();
+                while ids.pos < value.len() {
+                    alpn.push(ids.character_string()?);
+                }
+                alpn.join(",")
+            }
+            2 | 8 if value.is_empty() => String::new(),
+            3 if len == 2 => u16::from_be_bytes([value[0], value[1]]).to_string(),
+            4 if len.is_multiple_of(4) => value
+                .chunks(4)
+                .map(|ip| Ipv4Addr::from(<[u8; 4]>::try_from(ip).unwrap()).to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            5 => base64_encode(value),
+            6 if len.is_multiple_of(16) => value
+                .chunks(16)
+                .map(|ip| Ipv6Addr::from(<[u8; 16]>::try_from(ip).unwrap()).to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            0..=8 => return Err(Malformed),
+            _ => escape(value),
+        };
+
+        let name = svc_param_name(key);
+        params.push(if value.is_empty() {
+            name
+        } else {
+            format!("{}={}", name, value)
+        });
+    }
+    Ok(params.join(" "))
+}
+
+/// Splits service parameters at whitespace outside of quotes, returning `None` for unterminated quotes.
+fn split_params(params: &str) -> Option<Vec<&str>> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+
+    let mut chars = params.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c.is_whitespace() && !quoted {
+            tokens.extend(start.take().map(|start| &params[start..i]));
+            continue;
+        }
+        start.get_or_insert(i);
+        match c {
+            '"' => quoted = !quoted,
+            '\\' => {
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+
+    if quoted {
+        return None;
+    }
+    tokens.extend(start.map(|start| &params[start..]));
+    Some(tokens)
+}
+
+/// Escapes bytes that are not printable, as well as quotes and backslashes, in the `\DDD` form of RFC 1035 §5.1.
+fn escape(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            b'"' | b'\\' => format!("\\{:03}", b),
+            b if b.is_ascii_graphic() => (b as char).to_string(),
+            b => format!("\\{:03}", b),
+        })
+        .collect()
+}
+
+/// Resolves the `\DDD` and `\X` escapes of RFC 1035 §5.1.
+fn unescape(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        if b != b'\\' {
+            bytes.push(b);
+            continue;
+        }
+        match rest {
+            [a, b, c, tail @ ..] if [a, b, c].iter().all(|d| d.is_ascii_digit()) => {
+                let code = (a - b'0') as u16 * 100 + (b - b'0') as u16 * 10 + (c - b'0') as u16;
+                bytes.push(u8::try_from(code).ok()?);
+                rest = tail;
+            }
+            [escaped, tail @ ..] => {
+                bytes.push(*escaped);
+                rest = tail;
+            }
+            [] => return None,
+        }
+    }
+    Some(bytes)
+}
+
+/// The alphabet of standard base64 (RFC 4648 §4).
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes as standard base64 with padding (RFC 4648 §4).
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = u32::from_be_bytes([
+            0,
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ]);
+        for i in 0..4 {
+            encoded.push(if i <= chunk.len() {
+                BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char
+            } else {
+                '='
+            });
+        }
+    }
+    encoded
+}
+
+/// Decodes standard base64 (RFC 4648 §4), ignoring whitespace.
+pub(crate) fn base64_decode(encoded: &str) -> Option<Vec<u8>> {
+    let chars: Vec<u8> = encoded
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    let data = chars
+        .strip_suffix(b"==")
+        .or_else(|| chars.strip_suffix(b"="));
+    let data = data.unwrap_or(&chars);
+    if !chars.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let mut decoded = Vec::with_capacity(data.len() * 3 / 4);
+    for chunk in data.chunks(4) {
+        let mut n = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET.iter().position(|a| a == c)? as u32;
+            n |= value << (18 - 6 * i);
+        }
+        let bytes = n.to_be_bytes();
+        decoded.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(decoded)
+}
@@ -0,0 +1,706 @@
+//! RFC 1035 master zone file import and export.
+//!
+//! [`ZoneFile::parse`] reads BIND-style zone files into [`Record`]s and the [`Display`](fmt::Display) implementation of [`ZoneFile`] writes them back.
+//! The following parts of the format (RFC 1035 §5) are supported:
+//!
+//! - `$ORIGIN` and `$TTL` directives (`$INCLUDE` is rejected)
+//! - omitted owner names, TTLs and classes
+//! - relative names, `@` and TTLs with units (e.g. `1h30m`)
+//! - parenthesized RDATA spanning multiple lines and `;` comments
+//! - quoted character strings with `\"`, `\\` and `\DDD` escapes
+//!
+//! Hosts are made relative to the origin passed to [`ZoneFile::parse`] (`@` for the apex), while names outside of it keep their trailing dot.
+//! Domain names within RDATA are fully qualified with a trailing dot.
+//! Multiple TXT character strings are concatenated into a single [`RecordData::TXT`] value and split again on export.
+//! Record types without a dedicated [`RecordData`] variant are kept as [`RecordData::Other`] in presentation format;
+//! use [`ZoneFile::soa`] to retrieve the SOA record as [`SoaData`].
+//!
+//! # Example
+//!
+//! ```
+//! use libdns::zonefile::ZoneFile;
+//!
+//! let zone = ZoneFile::parse(
+//!     r#"
+//! $TTL 1h
+//! @       IN SOA ns1 hostmaster ( 2024010101 7200 3600 1209600 300 )
+//!         IN NS  ns1
+//! www 300 IN A   192.0.2.1
+//! "#,
+//!     "example.com",
+//! )?;
+//!
+//! assert_eq!(zone.records.len(), 3);
+//! assert_eq!(zone.soa().unwrap().serial, 2024010101);
+//! println!("{}", zone);
+//! # Ok::<(), libdns::zonefile::ParseError>(())
+//! ```
+
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::{DomainName, MxData, RecordClass, SoaData, SrvData, Ttl};
+use crate::{ascii_name, normalize_host, Record, RecordData};
+
+/// The maximum length of a single character string (RFC 1035 §3.3).
+const MAX_CHARACTER_STRING_LEN: usize = 255;
+
+/// The records of a single zone, as read from or written to a master file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ZoneFile {
+    /// The domain of the zone, without a trailing dot.
+    pub origin: String,
+    /// The TTL written as `$TTL` directive, if any.
+    pub default_ttl: Option<u64>,
+    /// The records of the zone.
+    pub records: Vec<Record>,
+}
+
+impl ZoneFile {
+    /// Creates a zone file for the given zone domain and records, e.g. as returned by [`Zone::list_records`](crate::Zone::list_records).
+    pub fn new(origin: &str, records: Vec<Record>) -> Self {
+        Self {
+            origin: origin.trim_end_matches('.').to_ascii_lowercase(),
+            default_ttl: None,
+            records,
+        }
+    }
+
+    /// Parses a master file for the zone of the given domain.
+    ///
+    /// The domain is used as the initial `$ORIGIN` and hosts are made relative to it.
+    pub fn parse(input: &str, origin: &str) -> Result<Self, ParseError> {
+        let mut zone = Self::new(origin, Vec::new());
+        let mut current_origin = zone.origin.clone();
+        let mut last_owner: Option<String> = None;
+        let mut last_ttl: Option<u64> = None;
+
+        for entry in tokenize(input)? {
+            let fail = |kind| ParseError {
+                line: entry.line,
+                kind,
+            };
+            let mut tokens = entry.tokens.iter();
+
+            if !entry.blank_owner && entry.tokens[0].text.starts_with('$') {
+                let directive = tokens.next().unwrap().text.to_ascii_uppercase();
+                let argument = tokens
+                    .next()
+                    .ok_or_else(|| fail(ParseErrorKind::MissingArgument(directive.clone())))?;
+                match directive.as_str() {
+                    "$ORIGIN" => {
+                        current_origin =
+                            absolute_name(&argument.text, &current_origin).map_err(fail)?;
+                    }
+                    "$TTL" => zone.default_ttl = Some(parse_ttl(&argument.text).map_err(fail)?),
+                    _ => return Err(fail(ParseErrorKind::UnsupportedDirective(directive))),
+                }
+                continue;
+            }
+
+            let owner = if entry.blank_owner {
+                last_owner
+                    .clone()
+                    .ok_or_else(|| fail(ParseErrorKind::MissingOwner))?
+            } else {
+                absolute_name(&tokens.next().unwrap().text, &current_origin).map_err(fail)?
+            };
+
+            // TTL and class may appear in either order
+            let mut ttl = None;
+            let mut typ = None;
+            for token in tokens.by_ref() {
+                if token.text.starts_with(|c: char| c.is_ascii_digit()) && ttl.is_none() {
+                    ttl = Some(parse_ttl(&token.text).map_err(fail)?);
+                } else if let Some(class) = parse_class(&token.text) {
+                    if class != RecordClass::IN {
+                        return Err(fail(ParseErrorKind::UnsupportedClass(token.text.clone())));
+                    }
+                } else {
+                    typ = Some(token.text.to_ascii_uppercase());
+                    break;
+                }
+            }
+            let typ = typ.ok_or_else(|| fail(ParseErrorKind::MissingType))?;
+
+            let ttl = match ttl {
+                Some(ttl) => {
+                    last_ttl = Some(ttl);
+                    ttl
+                }
+                None => zone
+                    .default_ttl
+                    .or(last_ttl)
+                    .ok_or_else(|| fail(ParseErrorKind::MissingTtl))?,
+            };
+
+            let rdata: Vec<&Token> = tokens.collect();
+            let data = parse_rdata(&typ, &rdata, &current_origin).map_err(fail)?;
+
+            zone.records.push(Record {
+                id: String::new(),
+                host: relative_host(&owner, &zone.origin),
+                data,
+                ttl,
+            });
+            last_owner = Some(owner);
+        }
+
+        Ok(zone)
+    }
+
+    /// Returns the SOA record at the apex of the zone, if present.
+    pub fn soa(&self) -> Option<SoaData> {
+        self.records.iter().find_map(|record| match &record.data {
+            RecordData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } if normalize_host(&record.host, &self.origin) == "@" => Some(SoaData {
+                mname: DomainName::from_dotted(mname)?,
+                rname: DomainName::from_dotted(rname)?,
+                serial: *serial,
+                refresh: *refresh,
+                retry: *retry,
+                expire: *expire,
+                minimum: *minimum,
+            }),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for ZoneFile {
+    /// Writes the zone in master file format, with one record per line in the order of [`ZoneFile::records`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "$ORIGIN {}.", self.origin)?;
+        if let Some(ttl) = self.default_ttl {
+            writeln!(f, "$TTL {}", ttl)?;
+        }
+
+        for record in &self.records {
+            let mut owner = normalize_host(&record.host, &self.origin);
+            // Fully qualified hosts outside of the zone keep their trailing dot
+            if record.host.ends_with('.') && owner == ascii_name(&record.host) {
+                owner.push('.');
+            }
+            let rdata = match &record.data {
+                RecordData::CNAME(name) | RecordData::NS(name) => fqdn(name),
+                RecordData::MX {
+                    priority,
+                    mail_server,
+                } => format!("{} {}", priority, fqdn(mail_server)),
+                RecordData::SRV {
+                    priority,
+                    weight,
+                    port,
+                    target,
+                } => format!("{} {} {} {}", priority, weight, port, fqdn(target)),
+                RecordData::TXT(text) => character_strings(text),
+                other => qualify_names(other).get_value(),
+            };
+
+            writeln!(
+                f,
+                "{}\t{}\tIN\t{}\t{}",
+                owner,
+                record.ttl,
+                record.data.get_type(),
+                rdata
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Represents an error that occured when parsing a zone file using [`ZoneFile::parse`].
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Error)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[error("line {line}: {kind}")]
+pub struct ParseError {
+    /// The line (starting at 1) of the entry that could not be parsed.
+    pub line: usize,
+    /// The reason the entry could not be parsed.
+    pub kind: ParseErrorKind,
+}
+
+/// Represents the reason of a [`ParseError`].
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Error)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ParseErrorKind {
+    /// Indicates a closing parenthesis without an opening one or an entry that is never closed.
+    #[error("unbalanced parentheses")]
+    UnbalancedParentheses,
+
+    /// Indicates a quoted string without a closing quote.
+    #[error("unterminated quoted string")]
+    UnterminatedString,
+
+    /// Indicates a directive other than `$ORIGIN` and `$TTL`.
+    #[error("unsupported directive `{0}`")]
+    UnsupportedDirective(String),
+
+    /// Indicates a directive without its argument.
+    #[error("missing argument for `{0}`")]
+    MissingArgument(String),
+
+    /// Indicates a record with an omitted owner name before any owner name was given.
+    #[error("the record has no owner name")]
+    MissingOwner,
+
+    /// Indicates a record with an omitted TTL before any TTL was given.
+    #[error("the record has no TTL and no default TTL is set")]
+    MissingTtl,
+
+    /// Indicates a malformed or out of range TTL.
+    #[error("invalid TTL `{0}`")]
+    InvalidTtl(String),
+
+    /// Indicates a class other than `IN`.
+    #[error("unsupported class `{0}`")]
+    UnsupportedClass(String),
+
+    /// Indicates a record without a type.
+    #[error("the record has no type")]
+    MissingType,
+
+    /// Indicates a malformed domain name.
+    #[error("invalid domain name `{0}`")]
+    InvalidName(String),
+
+    /// Indicates RDATA not matching the record type.
+    #[error("invalid {typ} record data `{value}`")]
+    InvalidData { typ: String, value: String },
+}
+
+/// A single token of an entry.
+#[derive(Debug)]
+struct Token {
+    /// The token with quotes and escapes of quoted strings removed.
+    text: String,
+    /// Whether the token was a quoted string.
+    quoted: bool,
+}
+
+/// A logical entry of a zone file, which may span multiple lines using parentheses.
+#[derive(Debug)]
+struct Entry {
+    /// The line the entry starts at.
+    line: usize,
+    /// Whether the entry starts with whitespace, i.e. reuses the previous owner name.
+    blank_owner: bool,
+    tokens: Vec<Token>,
+}
+
+/// Splits a zone file into entries, handling comments, quotes and parentheses.
+fn tokenize(input: &str) -> Result<Vec<Entry>, ParseError> {
+    let mut entries = Vec::new();
+    let mut line = 1;
+    let mut depth = 0usize;
+    let mut entry = Entry {
+        line,
+        blank_owner: false,
+        tokens: Vec::new(),
+    };
+    let mut word: Option<String> = None;
+    let mut at_line_start = true;
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if at_line_start && depth == 0 {
+            entry.line = line;
+            entry.blank_owner = c == ' ' || c == '\t';
+        }
+        at_line_start = false;
+
+        match c {
+            '\n' | ' ' | '\t' | '\r' | '(' | ')' | ';' | '"' => {
+                if let Some(text) = word.take() {
+                    entry.tokens.push(Token {
+                        text,
+                        quoted: false,
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        match c {
+            '\n' => {
+                if depth == 0 && !entry.tokens.is_empty() {
+                    entries.push(std::mem::replace(
+                        &mut entry,
+                        Entry {
+                            line,
+                            blank_owner: false,
+                            tokens: Vec::new(),
+                        },
+                    ));
+                }
+                line += 1;
+                at_line_start = true;
+            }
+            ' ' | '\t' | '\r' => {}
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.checked_sub(1).ok_or(ParseError {
+                    line,
+                    kind: ParseErrorKind::UnbalancedParentheses,
+                })?;
+            }
+            ';' => while chars.next_if(|&c| c != '\n').is_some() {},
+            '"' => {
+                let mut bytes = Vec::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => bytes.extend(unescape(&mut chars)),
+                        Some('\n') | None => {
+                            return Err(ParseError {
+                                line,
+                                kind: ParseErrorKind::UnterminatedString,
+                            })
+                        }
+                        Some(c) => bytes.extend(c.encode_utf8(&mut [0; 4]).as_bytes()),
+                    }
+                }
+                entry.tokens.push(Token {
+                    text: String::from_utf8_lossy(&bytes).into_owned(),
+                    quoted: true,
+                });
+            }
+            _ => {
+                let word = word.get_or_insert_with(String::new);
+                word.push(c);
+                // Escaped characters never end a token
+                if c == '\\' {
+                    word.extend(chars.next());
+                }
+            }
+        }
+    }
+
+    if depth != 0 {
+        return Err(ParseError {
+            line: entry.line,
+            kind: ParseErrorKind::UnbalancedParentheses,
+        });
+    }
+    if let Some(text) = word {
+        entry.tokens.push(Token {
+            text,
+            quoted: false,
+        });
+    }
+    if !entry.tokens.is_empty() {
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Decodes the escape sequence following a backslash within a quoted string.
+fn unescape(chars: &mut std::iter::Peekable<std::str::Chars>) -> Vec<u8> {
+    let digits: String = (0..3)
+        .map_while(|_| chars.next_if(|c| c.is_ascii_digit()))
+        .collect();
+
+    match (digits.len(), digits.parse::<u8>()) {
+        (0, _) => chars
+            .next()
+            .map(|c| c.to_string().into_bytes())
+            .unwrap_or_default(),
+        (3, Ok(byte)) => vec![byte],
+        // Not a valid \DDD sequence, so the digits are taken literally
+        _ => digits.into_bytes(),
+    }
+}
+
+/// Parses a TTL given in seconds or with units (`1w2d3h4m5s`).
+fn parse_ttl(s: &str) -> Result<u64, ParseErrorKind> {
+    let invalid = || ParseErrorKind::InvalidTtl(s.to_string());
+
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => 604800,
+            _ => return Err(invalid()),
+        };
+        let value: u64 = number.parse().map_err(|_| invalid())?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        total = number
+            .parse::<u64>()
+            .ok()
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(invalid)?;
+    }
+
+    u32::try_from(total)
+        .ok()
+        .and_then(Ttl::try_new)
+        .map(|ttl| u64::from(ttl.as_secs()))
+        .ok_or_else(invalid)
+}
+
+/// Parses a class mnemonic.
+fn parse_class(s: &str) -> Option<RecordClass> {
+    match s.to_ascii_uppercase().as_str() {
+        "IN" => Some(RecordClass::IN),
+        "CS" => Some(RecordClass::CS),
+        "CH" => Some(RecordClass::CH),
+        "HS" => Some(RecordClass::HS),
+        _ => None,
+    }
+}
+
+/// Resolves a possibly relative name against the origin, returning it without a trailing dot.
+fn absolute_name(name: &str, origin: &str) -> Result<String, ParseErrorKind> {
+    let absolute = if name == "@" {
+        origin.to_string()
+    } else if let Some(name) = name.strip_suffix('.') {
+        name.to_string()
+    } else if origin.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", name, origin)
+    };
+
+    DomainName::from_dotted(&absolute)
+        .map(|_| absolute)
+        .ok_or_else(|| ParseErrorKind::InvalidName(name.to_string()))
+}
+
+/// Returns the host of an owner name relative to the zone, keeping the trailing dot of names outside of it.
+fn relative_host(owner: &str, zone: &str) -> String {
+    let owner = owner.to_ascii_lowercase();
+    if owner == zone {
+        "@".to_string()
+    } else if let Some(relative) = owner.strip_suffix(&format!(".{}", zone)) {
+        relative.to_string()
+    } else {
+        format!("{}.", owner)
+    }
+}
+
+/// Appends the trailing dot to a fully qualified name, if missing.
+fn fqdn(name: &str) -> String {
+    if name.ends_with('.') {
+        name.to_string()
+    } else {
+        format!("{}.", name)
+    }
+}
+
+/// Appends the trailing dot to the names within PTR, SOA, HTTPS, SVCB and NAPTR data.
+fn qualify_names(data: &RecordData) -> RecordData {
+    let mut data = data.clone();
+    match &mut data {
+        RecordData::PTR(name)
+        | RecordData::HTTPS { target: name, .. }
+        | RecordData::SVCB { target: name, .. }
+        | RecordData::NAPTR {
+            replacement: name, ..
+        } => *name = fqdn(name),
+        RecordData::SOA { mname, rname, .. } => {
+            *mname = fqdn(mname);
+            *rname = fqdn(rname);
+        }
+        _ => {}
+    }
+    data
+}
+
+/// Formats text as quoted character strings of at most 255 bytes each.
+fn character_strings(text: &str) -> String {
+    // Some providers report TXT values including their quotes
+    let text = text
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(text);
+
+    let mut strings = vec![String::from("\"")];
+    let mut len = 0;
+    for c in text.chars() {
+        if len + c.len_utf8() > MAX_CHARACTER_STRING_LEN {
+            strings.last_mut().unwrap().push('"');
+            strings.push(String::from("\""));
+            len = 0;
+        }
+
+        let current = strings.last_mut().unwrap();
+        if c == '"' || c == '\\' {
+            current.push('\\');
+        }
+        current.push(c);
+        len += c.len_utf8();
+    }
+    strings.last_mut().unwrap().push('"');
+
+    strings.join(" ")
+}
+
+/// Parses the RDATA of a record into its [`RecordData`].
+fn parse_rdata(typ: &str, rdata: &[&Token], origin: &str) -> Result<RecordData, ParseErrorKind> {
+    let value = rdata
+        .iter()
+        .map(|token| {
+            if token.quoted {
+                character_strings(&token.text)
+            } else {
+                token.text.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let invalid = || ParseErrorKind::InvalidData {
+        typ: typ.to_string(),
+        value: value.clone(),
+    };
+    let single = || match rdata {
+        [token] => Ok(token.text.as_str()),
+        _ => Err(invalid()),
+    };
+    let name = |token: &str| absolute_name(token, origin).map(|name| fqdn(&name));
+    let number = |token: &str| token.parse::<u16>().map_err(|_| invalid());
+
+    Ok(match typ {
+        "A" => RecordData::A(Ipv4Addr::from_str(single()?).map_err(|_| invalid())?),
+        "AAAA" => RecordData::AAAA(Ipv6Addr::from_str(single()?).map_err(|_| invalid())?),
+        "CNAME" => RecordData::CNAME(name(single()?)?),
+        "NS" => RecordData::NS(name(single()?)?),
+        "MX" => {
+            let [priority, exchange] = rdata else {
+                return Err(invalid());
+            };
+            let exchange = name(&exchange.text)?;
+            let mx = MxData::new(
+                number(&priority.text)?,
+                DomainName::from_dotted(&exchange).ok_or_else(invalid)?,
+            );
+            RecordData::MX {
+                priority: mx.priority,
+                mail_server: exchange,
+            }
+        }
+        "SRV" => {
+            let [priority, weight, port, target] = rdata else {
+                return Err(invalid());
+            };
+            let target = name(&target.text)?;
+            let srv = SrvData::new(
+                number(&priority.text)?,
+                number(&weight.text)?,
+                number(&port.text)?,
+                DomainName::from_dotted(&target).ok_or_else(invalid)?,
+            );
+            RecordData::SRV {
+                priority: srv.priority,
+                weight: srv.weight,
+                port: srv.port,
+                target,
+            }
+        }
+        "TXT" if !rdata.is_empty() => {
+            RecordData::TXT(rdata.iter().map(|token| token.text.as_str()).collect())
+        }
+        "PTR" => RecordData::PTR(name(single()?)?),
+        "SOA" => {
+            let soa = parse_soa(rdata, origin)?;
+            RecordData::SOA {
+                mname: fqdn(&soa.mname.to_dotted()),
+                rname: fqdn(&soa.rname.to_dotted()),
+                serial: soa.serial,
+                refresh: soa.refresh,
+                retry: soa.retry,
+                expire: soa.expire,
+                minimum: soa.minimum,
+            }
+        }
+        "CAA" | "TLSA" | "SSHFP" | "DS" | "HTTPS" | "SVCB" | "NAPTR" => {
+            let mut data = RecordData::from_raw(typ, &value);
+            match &mut data {
+                // The root name stands for the owner name or no replacement
+                RecordData::HTTPS { target, .. }
+                | RecordData::SVCB { target, .. }
+                | RecordData::NAPTR {
+                    replacement: target,
+                    ..
+                } if target != "." => *target = name(target)?,
+                RecordData::Other { .. } => return Err(invalid()),
+                _ => {}
+            }
+            data
+        }
+        "TXT" => return Err(invalid()),
+        _ => RecordData::Other {
+            typ: typ.to_string(),
+            value,
+        },
+    })
+}
+
+/// Parses the RDATA of an SOA record.
+fn parse_soa(rdata: &[&Token], origin: &str) -> Result<SoaData, ParseErrorKind> {
+    let invalid = || ParseErrorKind::InvalidData {
+        typ: "SOA".to_string(),
+        value: rdata
+            .iter()
+            .map(|t| t.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    let [mname, rname, serial, timers @ ..] = rdata else {
+        return Err(invalid());
+    };
+    let [refresh, retry, expire, minimum] = timers else {
+        return Err(invalid());
+    };
+    let name = |token: &Token| {
+        absolute_name(&token.text, origin)
+            .ok()
+            .and_then(|name| DomainName::from_dotted(&name))
+            .ok_or_else(invalid)
+    };
+    let timer = |token: &Token| {
+        parse_ttl(&token.text)
+            .ok()
+            .and_then(|secs| u32::try_from(secs).ok())
+            .ok_or_else(invalid)
+    };
+
+    Ok(SoaData {
+        mname: name(mname)?,
+        rname: name(rname)?,
+        serial: serial.text.parse().map_err(|_| invalid())?,
+        refresh: timer(refresh)?,
+        retry: timer(retry)?,
+        expire: timer(expire)?,
+        minimum: timer(minimum)?,
+    })
+}
@@ -8,6 +8,7 @@ use wiremock::MockServer;
 /// Sets up a new mock server for testing.
 ///
 /// This is the standard way to create a mock server in tests.
+#[allow(dead_code)]
 pub async fn setup_mock_server() -> MockServer {
     MockServer::start().await
 }
@@ -253,3 +254,494 @@ pub mod hetzner {
         })
     }
 }
+
+/// Tencent Cloud-specific mock helpers.
+#[cfg(feature = "tencent")]
+pub mod tencent {
+    use serde_json::{json, Value};
+
+    /// Standard test credentials used in mock tests.
+    pub const SECRET_ID: &str = "AKIDtest";
+    pub const SECRET_KEY: &str = "test-secret-key";
+
+    /// Request ID returned in every mock response.
+    pub const REQUEST_ID: &str = "6ef60bec-0242-43af-bb20-270359fb54a7";
+
+    /// Creates a mock DescribeDomainList response.
+    pub fn mock_domain_list_response(domains: Vec<(u64, &str)>) -> Value {
+        json!({
+            "Response": {
+                "DomainList": domains.iter().map(|(id, name)| json!({
+                    "DomainId": id,
+                    "Name": name,
+                    "Status": "ENABLE",
+                    "TTL": 600,
+                    "RecordCount": 2
+                })).collect::<Vec<_>>(),
+                "DomainCountInfo": {
+                    "DomainTotal": domains.len(),
+                    "AllTotal": domains.len()
+                },
+                "RequestId": REQUEST_ID
+            }
+        })
+    }
+
+    /// Creates a mock DescribeDomain response.
+    pub fn mock_domain_response(id: u64, name: &str) -> Value {
+        json!({
+            "Response": {
+                "DomainInfo": {
+                    "DomainId": id,
+                    "Domain": name,
+                    "Status": "ENABLE",
+                    "TTL": 600,
+                    "RecordCount": 2
+                },
+                "RequestId": REQUEST_ID
+            }
+        })
+    }
+
+    /// Creates a mock DescribeRecordList response.
+    ///
+    /// MX records get a priority of 10.
+    pub fn mock_record_list_response(records: Vec<(u64, &str, &str, &str, u64)>) -> Value {
+        json!({
+            "Response": {
+                "RecordList": records.iter().map(|(id, name, record_type, value, ttl)| json!({
+                    "RecordId": id,
+                    "Name": name,
+                    "Type": record_type,
+                    "Value": value,
+                    "TTL": ttl,
+                    "Line": "默认",
+                    "Status": "ENABLE",
+                    "MX": if *record_type == "MX" { 10 } else { 0 }
+                })).collect::<Vec<_>>(),
+                "RecordCountInfo": {
+                    "TotalCount": records.len()
+                },
+                "RequestId": REQUEST_ID
+            }
+        })
+    }
+
+    /// Creates a mock DescribeRecord response.
+    pub fn mock_record_response(
+        id: u64,
+        sub_domain: &str,
+        record_type: &str,
+        value: &str,
+        ttl: u64,
+    ) -> Value {
+        json!({
+            "Response": {
+                "RecordInfo": {
+                    "Id": id,
+                    "SubDomain": sub_domain,
+                    "RecordType": record_type,
+                    "Value": value,
+                    "TTL": ttl,
+                    "MX": if record_type == "MX" { 10 } else { 0 },
+                    "Enabled": 1
+                },
+                "RequestId": REQUEST_ID
+            }
+        })
+    }
+
+    /// Creates a mock CreateRecord or ModifyRecord response.
+    pub fn mock_record_id_response(id: u64) -> Value {
+        json!({
+            "Response": {
+                "RecordId": id,
+                "RequestId": REQUEST_ID
+            }
+        })
+    }
+
+    /// Creates a mock response carrying nothing but the request ID.
+    pub fn mock_empty_response() -> Value {
+        json!({
+            "Response": {
+                "RequestId": REQUEST_ID
+            }
+        })
+    }
+
+    /// Creates a mock error response.
+    pub fn mock_error_response(code: &str, message: &str) -> Value {
+        json!({
+            "Response": {
+                "Error": {
+                    "Code": code,
+                    "Message": message
+                },
+                "RequestId": REQUEST_ID
+            }
+        })
+    }
+}
+
+/// DNSPod-specific mock helpers.
+#[cfg(feature = "dnspod")]
+pub mod dnspod {
+    use serde_json::{json, Value};
+
+    /// Standard test token used in mock tests.
+    pub const LOGIN_TOKEN: &str = "12345,test-token";
+
+    /// The User-Agent derived from [`client_config`].
+    pub const USER_AGENT: &str = "libdns-tests/1.0.0 (tests@example.com)";
+
+    /// Returns the client configuration used in mock tests.
+    pub fn client_config() -> libdns::dnspod::ClientConfig {
+        libdns::dnspod::ClientConfig::new("libdns-tests", "1.0.0", "tests@example.com")
+    }
+
+    /// Creates a mock status object.
+    pub fn mock_status(code: &str, message: &str) -> Value {
+        json!({
+            "code": code,
+            "message": message,
+            "created_at": "2024-01-01 00:00:00"
+        })
+    }
+
+    /// Creates a mock Domain.List response.
+    pub fn mock_domain_list_response(domains: Vec<(u64, &str)>) -> Value {
+        json!({
+            "status": mock_status("1", "Action completed successful"),
+            "info": {
+                "domain_total": domains.len(),
+                "all_total": domains.len(),
+                "mine_total": domains.len(),
+                "share_total": 0
+            },
+            "domains": domains.iter().map(|(id, name)| json!({
+                "id": id,
+                "name": name,
+                "grade": "DP_Free",
+                "status": "enable",
+                "ext_status": "",
+                "records": "2",
+                "ttl": "600",
+                "is_vip": "no"
+            })).collect::<Vec<_>>()
+        })
+    }
+
+    /// Creates a mock Domain.Info response.
+    pub fn mock_domain_response(id: u64, name: &str, ttl: u64) -> Value {
+        json!({
+            "status": mock_status("1", "Action completed successful"),
+            "domain": {
+                "id": id.to_string(),
+                "name": name,
+                "punycode": name,
+                "grade": "DP_Free",
+                "status": "enable",
+                "ttl": ttl.to_string(),
+                "records": "2",
+                "owner": "tests@example.com"
+            }
+        })
+    }
+
+    /// Creates a mock Record.List response.
+    pub fn mock_record_list_response(
+        domain_id: u64,
+        records: Vec<(u64, &str, &str, &str, u64)>,
+    ) -> Value {
+        json!({
+            "status": mock_status("1", "Action completed successful"),
+            "domain": {
+                "id": domain_id,
+                "name": "example.com",
+                "punycode": "example.com",
+                "grade": "DP_Free"
+            },
+            "info": {
+                "sub_domains": records.len().to_string(),
+                "record_total": records.len().to_string()
+            },
+            "records": records.iter().map(|(id, name, record_type, value, ttl)| json!({
+                "id": id.to_string(),
+                "name": name,
+                "line": "默认",
+                "type": record_type,
+                "ttl": ttl.to_string(),
+                "value": value,
+                "mx": "0",
+                "enabled": "1",
+                "status": "enable",
+                "monitor_status": "",
+                "remark": "",
+                "updated_on": "2024-01-01 00:00:00",
+                "hold": "no"
+            })).collect::<Vec<_>>()
+        })
+    }
+
+    /// Creates a mock Record.Info response.
+    pub fn mock_record_response(
+        domain_id: u64,
+        id: u64,
+        sub_domain: &str,
+        record_type: &str,
+        value: &str,
+        ttl: u64,
+    ) -> Value {
+        json!({
+            "status": mock_status("1", "Action completed successful"),
+            "domain": {
+                "id": domain_id.to_string(),
+                "domain": "example.com",
+                "domain_grade": "DP_Free"
+            },
+            "record": {
+                "id": id.to_string(),
+                "sub_domain": sub_domain,
+                "record_type": record_type,
+                "record_line": "默认",
+                "value": value,
+                "mx": "0",
+                "ttl": ttl.to_string(),
+                "enabled": "1",
+                "updated_on": "2024-01-01 00:00:00",
+                "domain_id": domain_id.to_string()
+            }
+        })
+    }
+
+    /// Creates a mock Record.Create, Record.Modify or Record.Ddns response.
+    pub fn mock_record_id_response(id: u64, name: &str) -> Value {
+        json!({
+            "status": mock_status("1", "Action completed successful"),
+            "record": {
+                "id": id,
+                "name": name,
+                "status": "enable"
+            }
+        })
+    }
+
+    /// Creates a mock response carrying nothing but a successful status.
+    pub fn mock_success_response() -> Value {
+        json!({
+            "status": mock_status("1", "Action completed successful")
+        })
+    }
+
+    /// Creates a mock error response.
+    pub fn mock_error_response(code: &str, message: &str) -> Value {
+        json!({
+            "status": mock_status(code, message)
+        })
+    }
+}
+
+/// Technitium DNS Server-specific mock helpers.
+#[cfg(feature = "technitium-dns")]
+pub mod technitium {
+    use serde_json::{json, Value};
+
+    /// Standard test token used in mock tests.
+    pub const TOKEN: &str = "test-token";
+
+    /// Creates a mock zones list response.
+    pub fn mock_zones_response(zones: Vec<(&str, &str)>) -> Value {
+        json!({
+            "status": "ok",
+            "response": {
+                "zones": zones.iter().map(|(name, zone_type)| json!({
+                    "name": name,
+                    "type": zone_type,
+                    "internal": false,
+                    "dnssecStatus": "Unsigned",
+                    "soaSerial": 1,
+                    "disabled": false,
+                    "lastModified": "2024-01-01T00:00:00Z"
+                })).collect::<Vec<_>>()
+            }
+        })
+    }
+
+    /// Creates a mock zone options response.
+    pub fn mock_zone_response(name: &str) -> Value {
+        json!({
+            "status": "ok",
+            "response": {
+                "name": name,
+                "type": "Primary",
+                "internal": false,
+                "dnssecStatus": "Unsigned",
+                "disabled": false
+            }
+        })
+    }
+
+    /// Creates a mock records response from `(name, type, ttl, rData)` tuples.
+    pub fn mock_records_response(zone: &str, records: Vec<(&str, &str, u64, Value)>) -> Value {
+        json!({
+            "status": "ok",
+            "response": {
+                "zone": {
+                    "name": zone,
+                    "type": "Primary",
+                    "disabled": false
+                },
+                "records": records.into_iter().map(|(name, record_type, ttl, rdata)| json!({
+                    "disabled": false,
+                    "name": name,
+                    "type": record_type,
+                    "ttl": ttl,
+                    "rData": rdata,
+                    "dnssecStatus": "Unknown"
+                })).collect::<Vec<_>>()
+            }
+        })
+    }
+
+    /// Creates a mock add or update record response.
+    pub fn mock_record_change_response(
+        zone: &str,
+        key: &str,
+        name: &str,
+        record_type: &str,
+        ttl: u64,
+        rdata: Value,
+    ) -> Value {
+        json!({
+            "status": "ok",
+            "response": {
+                "zone": {
+                    "name": zone,
+                    "type": "Primary",
+                    "disabled": false
+                },
+                key: {
+                    "disabled": false,
+                    "name": name,
+                    "type": record_type,
+                    "ttl": ttl,
+                    "rData": rdata
+                }
+            }
+        })
+    }
+
+    /// Creates a mock response without response data.
+    pub fn mock_ok_response() -> Value {
+        json!({ "status": "ok" })
+    }
+
+    /// Creates a mock error response.
+    pub fn mock_error_response(message: &str) -> Value {
+        json!({
+            "status": "error",
+            "errorMessage": message,
+            "stackTrace": "at DnsServerCore.WebServiceZonesApi"
+        })
+    }
+
+    /// Creates a mock response for an invalid or expired token.
+    pub fn mock_invalid_token_response() -> Value {
+        json!({
+            "status": "invalid-token",
+            "errorMessage": "Invalid token or session expired."
+        })
+    }
+}
+
+/// Namecheap-specific mock helpers.
+#[cfg(feature = "namecheap")]
+pub mod namecheap {
+    /// The path of the XML API endpoint.
+    pub const API_PATH: &str = "/xml.response";
+
+    /// Returns the client configuration used in mock tests.
+    pub fn client_config() -> libdns::namecheap::ClientConfig {
+        libdns::namecheap::ClientConfig::sandbox("test-user", "test-key", "127.0.0.1")
+    }
+
+    /// Wraps a command response in the Namecheap response envelope.
+    pub fn mock_response(command: &str, body: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<ApiResponse Status="OK" xmlns="http://api.namecheap.com/xml.response">
+  <Errors />
+  <Warnings />
+  <RequestedCommand>{}</RequestedCommand>
+  <CommandResponse Type="{}">
+    {}
+  </CommandResponse>
+  <Server>WEB1-SANDBOX1</Server>
+  <GMTTimeDifference>--5:00</GMTTimeDifference>
+  <ExecutionTime>0.011</ExecutionTime>
+</ApiResponse>"#,
+            command.to_lowercase(),
+            command,
+            body
+        )
+    }
+
+    /// Creates a mock getHosts response from `(id, name, type, address, ttl)` tuples.
+    ///
+    /// Addresses are inserted as they are, so they must already be XML-escaped.
+    pub fn mock_get_hosts_response(
+        domain: &str,
+        hosts: Vec<(&str, &str, &str, &str, u64)>,
+    ) -> String {
+        let hosts = hosts
+            .iter()
+            .map(|(id, name, record_type, address, ttl)| {
+                format!(
+                    r#"<host HostId="{}" Name="{}" Type="{}" Address="{}" MXPref="10" TTL="{}" AssociatedAppTitle="" FriendlyName="" IsActive="true" IsDDNSEnabled="false" />"#,
+                    id, name, record_type, address, ttl
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n      ");
+
+        mock_response(
+            "namecheap.domains.dns.getHosts",
+            &format!(
+                r#"<DomainDNSGetHostsResult Domain="{}" EmailType="FWD" IsUsingOurDNS="true">
+      {}
+    </DomainDNSGetHostsResult>"#,
+                domain, hosts
+            ),
+        )
+    }
+
+    /// Creates a mock setHosts response.
+    pub fn mock_set_hosts_response(domain: &str) -> String {
+        mock_response(
+            "namecheap.domains.dns.setHosts",
+            &format!(
+                r#"<DomainDNSSetHostsResult Domain="{}" IsSuccess="true"><Warnings /></DomainDNSSetHostsResult>"#,
+                domain
+            ),
+        )
+    }
+
+    /// Creates a mock error response.
+    pub fn mock_error_response(number: &str, message: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<ApiResponse Status="ERROR" xmlns="http://api.namecheap.com/xml.response">
+  <Errors>
+    <Error Number="{}">{}</Error>
+  </Errors>
+  <Warnings />
+  <RequestedCommand />
+  <Server>WEB1-SANDBOX1</Server>
+  <GMTTimeDifference>--5:00</GMTTimeDifference>
+  <ExecutionTime>0</ExecutionTime>
+</ApiResponse>"#,
+            number, message
+        )
+    }
+}
@@ -76,7 +76,7 @@ fn get_test_config() -> Option<TestConfig> {
 }
 
 /// Helper to get the test zone.
-async fn get_test_zone(config: &TestConfig) -> impl Zone + CreateRecord + DeleteRecord + '_ {
+async fn get_test_zone(config: &TestConfig) -> impl CreateRecord + DeleteRecord + '_ {
     let zones = config
         .provider
         .list_zones()
@@ -86,10 +86,7 @@ async fn get_test_zone(config: &TestConfig) -> impl Zone + CreateRecord + Delete
     zones
         .into_iter()
         .find(|z| z.domain() == config.domain)
-        .expect(&format!(
-            "Test domain '{}' not found in account",
-            config.domain
-        ))
+        .unwrap_or_else(|| panic!("Test domain '{}' not found in account", config.domain))
 }
 
 /// Clean up any existing test records for a given host.
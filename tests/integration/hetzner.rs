@@ -72,7 +72,7 @@ fn get_test_config() -> Option<TestConfig> {
 }
 
 /// Helper to get the test zone.
-async fn get_test_zone(config: &TestConfig) -> impl Zone + CreateRecord + DeleteRecord + '_ {
+async fn get_test_zone(config: &TestConfig) -> impl CreateRecord + DeleteRecord + '_ {
     let zones = config
         .provider
         .list_zones()
@@ -82,10 +82,7 @@ async fn get_test_zone(config: &TestConfig) -> impl Zone + CreateRecord + Delete
     zones
         .into_iter()
         .find(|z| z.domain() == config.domain)
-        .expect(&format!(
-            "Test domain '{}' not found in account",
-            config.domain
-        ))
+        .unwrap_or_else(|| panic!("Test domain '{}' not found in account", config.domain))
 }
 
 /// Clean up any existing test records for a given host.
@@ -567,6 +564,35 @@ async fn test_zone_create_delete() {
     println!("  Verified zone is deleted");
 }
 
+/// Run the conformance suite against a newly created zone.
+/// WARNING: This test creates real zones - use with caution!
+#[cfg(feature = "testing")]
+#[tokio::test]
+#[ignore = "requires HETZNER credentials - creates real zones"]
+async fn test_conformance() {
+    let provider =
+        get_test_provider().expect("HETZNER credentials not found. Set HETZNER_API_TOKEN in .env");
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let test_domain = format!("libdns-conformance-{}.example", timestamp);
+
+    let report = libdns::testing::ConformanceSuite::new(&test_domain)
+        .run(&provider)
+        .await;
+    println!("{}", report);
+
+    if let Some(zone_id) = &report.zone_id {
+        provider
+            .delete_zone(zone_id)
+            .await
+            .expect("Failed to delete zone");
+    }
+    assert!(report.is_conformant(), "{}", report);
+}
+
 // =============================================================================
 // Error Handling Tests
 // =============================================================================
@@ -6,14 +6,17 @@ use crate::common::cloudflare::*;
 use crate::common::setup_mock_server;
 
 use libdns::cloudflare::CloudflareProvider;
+use libdns::dynamic::{DynError, DynProvider};
+use libdns::reconcile::Reconciler;
 use libdns::{
-    CreateRecord, CreateRecordError, DeleteRecord, DeleteRecordError, Provider, RecordData,
-    RetrieveRecordError, RetrieveZoneError, Zone,
+    ApplyChanges, ChangeError, ClassifyError, CreateRecord, CreateRecordError, DeleteRecord,
+    DeleteRecordError, DeleteRecords, ErrorKind, Provider, Record, RecordChange, RecordData,
+    RetrieveRecordError, RetrieveZoneError, SetRecords, UpdateRecord, UpdateRecordError, Zone,
 };
 use proptest::prelude::*;
 use serde_json::json;
 use std::net::Ipv4Addr;
-use wiremock::matchers::{header, method, path, path_regex, query_param};
+use wiremock::matchers::{body_partial_json, header, method, path, path_regex, query_param};
 use wiremock::{Mock, ResponseTemplate};
 
 // =============================================================================
@@ -361,6 +364,165 @@ async fn test_create_record_success() {
     assert_eq!(record.id, NEW_RECORD_ID);
 }
 
+#[tokio::test]
+async fn test_create_record_structured_data() {
+    let server = setup_mock_server().await;
+    mount_zone_lookup(&server).await;
+
+    // CAA records are sent as structured data and reported in presentation format
+    Mock::given(method("POST"))
+        .and(path(format!("/zones/{}/dns_records", ZONE_ID_1)))
+        .and(body_partial_json(json!({
+            "type": "CAA",
+            "data": {"flags": 0, "tag": "issue", "value": "letsencrypt.org"}
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_response(
+                NEW_RECORD_ID,
+                ZONE_ID_1,
+                "example.com",
+                "example.com",
+                "CAA",
+                "0 issue \"letsencrypt.org\"",
+                300,
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+
+    let caa = RecordData::CAA {
+        flags: 0,
+        tag: "issue".to_string(),
+        value: "letsencrypt.org".to_string(),
+    };
+    let record = zone
+        .create_record("@", &caa, 300)
+        .await
+        .expect("Failed to create record");
+
+    assert_eq!(record.host, "@");
+    assert_eq!(record.data, caa);
+}
+
+#[tokio::test]
+async fn test_internationalized_names() {
+    let server = setup_mock_server().await;
+
+    // Unicode zone names and hosts are sent in ASCII form
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "xn--mnchen-3ya.de"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_zones_list_response(vec![(
+                ZONE_ID_1,
+                "xn--mnchen-3ya.de",
+            )])),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("/zones/{}/dns_records", ZONE_ID_1)))
+        .and(body_partial_json(
+            json!({"name": "xn--bcher-kva.xn--mnchen-3ya.de"}),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_response(
+                NEW_RECORD_ID,
+                ZONE_ID_1,
+                "xn--mnchen-3ya.de",
+                "xn--bcher-kva.xn--mnchen-3ya.de",
+                "A",
+                "10.0.0.1",
+                300,
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+    let zone = provider
+        .get_zone("münchen.de")
+        .await
+        .expect("Failed to get zone");
+    let record = zone
+        .create_record("bücher", &RecordData::A(Ipv4Addr::new(10, 0, 0, 1)), 300)
+        .await
+        .expect("Failed to create record");
+
+    assert_eq!(record.host, "xn--bcher-kva");
+}
+
+#[tokio::test]
+async fn test_internationalized_names_in_batch() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "xn--mnchen-3ya.de"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_zones_list_response(vec![(
+                ZONE_ID_1,
+                "xn--mnchen-3ya.de",
+            )])),
+        )
+        .mount(&server)
+        .await;
+
+    // Hosts of batched changes are sent in ASCII form as well
+    let record = mock_record_response(
+        NEW_RECORD_ID,
+        ZONE_ID_1,
+        "xn--mnchen-3ya.de",
+        "xn--bcher-kva.xn--mnchen-3ya.de",
+        "A",
+        "10.0.0.1",
+        300,
+    )["result"]
+        .clone();
+    Mock::given(method("POST"))
+        .and(path(format!("/zones/{}/dns_records/batch", ZONE_ID_1)))
+        .and(body_partial_json(json!({
+            "posts": [{"name": "xn--bcher-kva.xn--mnchen-3ya.de"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "errors": [],
+            "messages": [],
+            "result": {"posts": [record]}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+    let zone = provider
+        .get_zone("münchen.de")
+        .await
+        .expect("Failed to get zone");
+    let records = zone
+        .apply_changes(&[RecordChange::Create {
+            host: "bücher".to_string(),
+            data: RecordData::A(Ipv4Addr::new(10, 0, 0, 1)),
+            ttl: 300,
+        }])
+        .await
+        .expect("Failed to apply changes");
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].host, "xn--bcher-kva");
+}
+
 #[tokio::test]
 async fn test_create_record_unsupported_type() {
     let server = setup_mock_server().await;
@@ -400,6 +562,147 @@ async fn test_create_record_unsupported_type() {
     assert!(matches!(result, Err(CreateRecordError::UnsupportedType)));
 }
 
+#[tokio::test]
+async fn test_create_record_already_exists() {
+    let server = setup_mock_server().await;
+    mount_zone_lookup(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path(format!("/zones/{}/dns_records", ZONE_ID_1)))
+        .respond_with(
+            ResponseTemplate::new(400)
+                .set_body_json(mock_error_response(81057, "Record already exists.")),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let err = zone
+        .create_record("test", &RecordData::A(Ipv4Addr::new(10, 0, 0, 1)), 300)
+        .await
+        .expect_err("Creation should fail");
+
+    // The code is kept instead of being flattened into InvalidRecord
+    assert!(matches!(err, CreateRecordError::Custom(_)));
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+}
+
+#[tokio::test]
+async fn test_update_record_success() {
+    let server = setup_mock_server().await;
+
+    // Zone lookup
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_zones_list_response(vec![(ZONE_ID_1, "example.com")])),
+        )
+        .mount(&server)
+        .await;
+
+    // Update record
+    Mock::given(method("PATCH"))
+        .and(path(format!(
+            "/zones/{}/dns_records/{}",
+            ZONE_ID_1, RECORD_ID_1
+        )))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_response(
+                RECORD_ID_1,
+                ZONE_ID_1,
+                "example.com",
+                "www.example.com",
+                "A",
+                "10.0.0.2",
+                600,
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let record = zone
+        .update_record(
+            RECORD_ID_1,
+            "www",
+            &RecordData::A(Ipv4Addr::new(10, 0, 0, 2)),
+            600,
+        )
+        .await
+        .expect("Failed to update record");
+
+    // The record keeps its ID across the update
+    assert_eq!(record.id, RECORD_ID_1);
+    assert_eq!(record.data, RecordData::A(Ipv4Addr::new(10, 0, 0, 2)));
+    assert_eq!(record.ttl, 600);
+}
+
+#[tokio::test]
+async fn test_update_record_not_found() {
+    let server = setup_mock_server().await;
+
+    // Zone lookup
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_zones_list_response(vec![(ZONE_ID_1, "example.com")])),
+        )
+        .mount(&server)
+        .await;
+
+    // Update non-existent record
+    let nonexistent_record = "00000000000000000000000000000000";
+    Mock::given(method("PATCH"))
+        .and(path(format!(
+            "/zones/{}/dns_records/{}",
+            ZONE_ID_1, nonexistent_record
+        )))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(404)
+                .set_body_json(mock_error_response(81044, "Record not found")),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let result = zone
+        .update_record(
+            nonexistent_record,
+            "www",
+            &RecordData::A(Ipv4Addr::new(10, 0, 0, 2)),
+            300,
+        )
+        .await;
+
+    assert!(matches!(result, Err(UpdateRecordError::NotFound)));
+}
+
 #[tokio::test]
 async fn test_delete_record_success() {
     let server = setup_mock_server().await;
@@ -482,6 +785,639 @@ async fn test_delete_record_not_found() {
     assert!(matches!(result, Err(DeleteRecordError::NotFound)));
 }
 
+// =============================================================================
+// Record Set Tests
+// =============================================================================
+
+/// Mounts a zone containing an apex record and two `www` A records.
+async fn mount_record_set_zone(server: &wiremock::MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_zones_list_response(vec![(ZONE_ID_1, "example.com")])),
+        )
+        .mount(server)
+        .await;
+
+    record_set_listing().mount(server).await;
+}
+
+/// A listing of the records of the zone mounted by [`mount_record_set_zone`].
+fn record_set_listing() -> Mock {
+    Mock::given(method("GET"))
+        .and(path(format!("/zones/{}/dns_records", ZONE_ID_1)))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_records_list_response(vec![
+                (
+                    NEW_RECORD_ID,
+                    ZONE_ID_1,
+                    "example.com",
+                    "example.com",
+                    "A",
+                    "192.168.1.1",
+                    300,
+                ),
+                (
+                    RECORD_ID_1,
+                    ZONE_ID_1,
+                    "example.com",
+                    "www.example.com",
+                    "A",
+                    "10.0.0.1",
+                    300,
+                ),
+                (
+                    RECORD_ID_2,
+                    ZONE_ID_1,
+                    "example.com",
+                    "www.example.com",
+                    "A",
+                    "10.0.0.2",
+                    300,
+                ),
+            ])),
+        )
+}
+
+fn www_a_record(ip: Ipv4Addr) -> Record {
+    Record {
+        id: String::new(),
+        host: "www".to_string(),
+        data: RecordData::A(ip),
+        ttl: 300,
+    }
+}
+
+#[tokio::test]
+async fn test_set_records_replaces_rrset() {
+    let server = setup_mock_server().await;
+    mount_record_set_zone(&server).await;
+
+    // Only the missing value is created
+    Mock::given(method("POST"))
+        .and(path(format!("/zones/{}/dns_records", ZONE_ID_1)))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_response(
+                "cccccccccccccccccccccccccccccccc",
+                ZONE_ID_1,
+                "example.com",
+                "www.example.com",
+                "A",
+                "10.0.0.3",
+                300,
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Only the stale value is deleted; the apex record is left alone
+    Mock::given(method("DELETE"))
+        .and(path(format!(
+            "/zones/{}/dns_records/{}",
+            ZONE_ID_1, RECORD_ID_1
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_delete_response(RECORD_ID_1)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path_regex(r"^/zones/.*/dns_records/.*$"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .with_priority(10)
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let records = zone
+        .set_records(&[
+            www_a_record(Ipv4Addr::new(10, 0, 0, 2)),
+            www_a_record(Ipv4Addr::new(10, 0, 0, 3)),
+        ])
+        .await
+        .expect("Failed to set records");
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].id, RECORD_ID_2);
+    assert_eq!(records[1].data, RecordData::A(Ipv4Addr::new(10, 0, 0, 3)));
+}
+
+#[tokio::test]
+async fn test_delete_records_by_content() {
+    let server = setup_mock_server().await;
+    mount_record_set_zone(&server).await;
+
+    Mock::given(method("DELETE"))
+        .and(path(format!(
+            "/zones/{}/dns_records/{}",
+            ZONE_ID_1, RECORD_ID_1
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_delete_response(RECORD_ID_1)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+
+    // The FQDN and the relative host name address the same record
+    let mut record = www_a_record(Ipv4Addr::new(10, 0, 0, 1));
+    record.host = "www.example.com.".to_string();
+    let deleted = zone
+        .delete_records(&[record, www_a_record(Ipv4Addr::new(10, 0, 0, 9))])
+        .await
+        .expect("Failed to delete records");
+
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].id, RECORD_ID_1);
+}
+
+#[tokio::test]
+async fn test_reconcile_plan_apply() {
+    let server = setup_mock_server().await;
+    mount_record_set_zone(&server).await;
+
+    // The stale value is replaced: deleted first, then created
+    Mock::given(method("DELETE"))
+        .and(path(format!(
+            "/zones/{}/dns_records/{}",
+            ZONE_ID_1, RECORD_ID_1
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_delete_response(RECORD_ID_1)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("/zones/{}/dns_records", ZONE_ID_1)))
+        .and(body_partial_json(json!({"content": "10.0.0.3"})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_response(
+                "cccccccccccccccccccccccccccccccc",
+                ZONE_ID_1,
+                "example.com",
+                "www.example.com",
+                "A",
+                "10.0.0.3",
+                300,
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let mut apex = www_a_record(Ipv4Addr::new(192, 168, 1, 1));
+    apex.host = "@".to_string();
+    let plan = Reconciler::new()
+        .plan_zone(
+            &zone,
+            &[
+                apex,
+                www_a_record(Ipv4Addr::new(10, 0, 0, 2)),
+                www_a_record(Ipv4Addr::new(10, 0, 0, 3)),
+            ],
+        )
+        .await
+        .expect("Failed to plan");
+
+    assert_eq!(plan.changes().len(), 1);
+    let owned = plan.apply(&zone).await.expect("Failed to apply plan");
+
+    assert_eq!(owned.len(), 3);
+    assert_eq!(owned[2].id, "cccccccccccccccccccccccccccccccc");
+}
+
+#[tokio::test]
+async fn test_reconcile_plan_lists_records_once_per_phase() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "example.com"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_zones_list_response(vec![(ZONE_ID_1, "example.com")])),
+        )
+        .mount(&server)
+        .await;
+    // One listing for the plan and one to look up the records deleted by it
+    record_set_listing().expect(2).mount(&server).await;
+    for id in [NEW_RECORD_ID, RECORD_ID_1, RECORD_ID_2] {
+        Mock::given(method("DELETE"))
+            .and(path(format!("/zones/{}/dns_records/{}", ZONE_ID_1, id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(mock_delete_response(id)))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let plan = Reconciler::new()
+        .plan_zone(&zone, &[])
+        .await
+        .expect("Failed to plan");
+
+    assert_eq!(plan.changes().len(), 3);
+    let owned = plan.apply(&zone).await.expect("Failed to apply plan");
+    assert!(owned.is_empty());
+}
+
+#[cfg(feature = "ddns")]
+#[tokio::test]
+async fn test_ddns_sync_repoints_hosts() {
+    use libdns::ddns::{AddressChange, Addresses, DdnsUpdater};
+
+    let server = setup_mock_server().await;
+    mount_record_set_zone(&server).await;
+
+    // The apex points elsewhere and is updated in place
+    Mock::given(method("PATCH"))
+        .and(path(format!(
+            "/zones/{}/dns_records/{}",
+            ZONE_ID_1, NEW_RECORD_ID
+        )))
+        .and(body_partial_json(json!({"content": "10.0.0.2"})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_response(
+                NEW_RECORD_ID,
+                ZONE_ID_1,
+                "example.com",
+                "example.com",
+                "A",
+                "10.0.0.2",
+                120,
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    // www already holds the address, only the other value is removed
+    Mock::given(method("DELETE"))
+        .and(path(format!(
+            "/zones/{}/dns_records/{}",
+            ZONE_ID_1, RECORD_ID_1
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_delete_response(RECORD_ID_1)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    // home has no A record yet
+    Mock::given(method("POST"))
+        .and(path(format!("/zones/{}/dns_records", ZONE_ID_1)))
+        .and(body_partial_json(
+            json!({"name": "home.example.com", "content": "10.0.0.2", "ttl": 120}),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_response(
+                "cccccccccccccccccccccccccccccccc",
+                ZONE_ID_1,
+                "example.com",
+                "home.example.com",
+                "A",
+                "10.0.0.2",
+                120,
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+
+    let addresses = Addresses {
+        ipv4: Some(Ipv4Addr::new(10, 0, 0, 2)),
+        ipv6: None,
+    };
+    let updater =
+        DdnsUpdater::new(&zone, addresses, ["@", "www.example.com", "home"]).with_ttl(120);
+    let changes = updater.update().await.expect("Failed to update");
+
+    assert_eq!(changes.len(), 3);
+    assert!(matches!(&changes[0], AddressChange::Updated { from, .. } if from.id == NEW_RECORD_ID));
+    assert!(matches!(&changes[1], AddressChange::Deleted(record) if record.id == RECORD_ID_1));
+    assert!(
+        matches!(&changes[2], AddressChange::Created(record) if record.id == "cccccccccccccccccccccccccccccccc")
+    );
+}
+
+#[cfg(feature = "ddns")]
+#[tokio::test]
+async fn test_ddns_sync_unchanged_address() {
+    use libdns::ddns::{Addresses, DdnsUpdater};
+
+    let server = setup_mock_server().await;
+    mount_record_set_zone(&server).await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+
+    // No AAAA records exist, but without an IPv6 address they are left alone
+    let addresses = Addresses {
+        ipv4: Some(Ipv4Addr::new(192, 168, 1, 1)),
+        ipv6: None,
+    };
+    let changes = DdnsUpdater::new(&zone, addresses, ["@"])
+        .sync(&addresses)
+        .await
+        .expect("Failed to sync");
+
+    assert!(changes.is_empty());
+}
+
+// =============================================================================
+// Changeset Tests
+// =============================================================================
+
+/// Mounts the zone lookup for `example.com`.
+async fn mount_zone_lookup(server: &wiremock::MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_zones_list_response(vec![(ZONE_ID_1, "example.com")])),
+        )
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_apply_changes_uses_batch() {
+    let server = setup_mock_server().await;
+    mount_zone_lookup(&server).await;
+
+    let record = |id: &str, content: &str| {
+        mock_record_response(
+            id,
+            ZONE_ID_1,
+            "example.com",
+            "www.example.com",
+            "A",
+            content,
+            300,
+        )["result"]
+            .clone()
+    };
+
+    // The whole changeset is sent in a single request
+    Mock::given(method("POST"))
+        .and(path(format!("/zones/{}/dns_records/batch", ZONE_ID_1)))
+        .and(header("Authorization", "Bearer test-token"))
+        .and(body_partial_json(json!({
+            "deletes": [{"id": RECORD_ID_2}],
+            "patches": [{"id": RECORD_ID_1, "content": "10.0.0.2"}],
+            "posts": [{"name": "www.example.com", "content": "10.0.0.3"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "errors": [],
+            "messages": [],
+            "result": {
+                "deletes": [record(RECORD_ID_2, "10.0.0.9")],
+                "patches": [record(RECORD_ID_1, "10.0.0.2")],
+                "posts": [record(NEW_RECORD_ID, "10.0.0.3")]
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let records = zone
+        .apply_changes(&[
+            RecordChange::Create {
+                host: "www".to_string(),
+                data: RecordData::A(Ipv4Addr::new(10, 0, 0, 3)),
+                ttl: 300,
+            },
+            RecordChange::Delete {
+                record_id: RECORD_ID_2.to_string(),
+            },
+            RecordChange::Update {
+                record_id: RECORD_ID_1.to_string(),
+                host: "www".to_string(),
+                data: RecordData::A(Ipv4Addr::new(10, 0, 0, 2)),
+                ttl: 300,
+            },
+        ])
+        .await
+        .expect("Failed to apply changes");
+
+    // Results follow the order of the changeset, not the batch response
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].id, NEW_RECORD_ID);
+    assert_eq!(records[1].id, RECORD_ID_1);
+    assert_eq!(records[1].data, RecordData::A(Ipv4Addr::new(10, 0, 0, 2)));
+}
+
+#[tokio::test]
+async fn test_apply_changes_rejected_batch() {
+    let server = setup_mock_server().await;
+    mount_zone_lookup(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path(format!("/zones/{}/dns_records/batch", ZONE_ID_1)))
+        .respond_with(
+            ResponseTemplate::new(404)
+                .set_body_json(mock_error_response(81044, "Record not found")),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let err = zone
+        .apply_changes(&[RecordChange::Delete {
+            record_id: RECORD_ID_1.to_string(),
+        }])
+        .await
+        .expect_err("Batch should fail");
+
+    assert!(matches!(err.cause, ChangeError::NotFound));
+    assert!(err.rolled_back);
+}
+
+// =============================================================================
+// Dynamic Provider Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_dyn_provider_capabilities() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_zones_list_response(vec![(ZONE_ID_1, "example.com")])),
+        )
+        .mount(&server)
+        .await;
+
+    let provider: Box<dyn DynProvider> =
+        CloudflareProvider::with_base_url("test-token", &server.uri())
+            .expect("Failed to create provider")
+            .into();
+
+    // Cloudflare does not manage zones through this crate
+    assert!(!provider.supports_create_zone());
+    assert!(!provider.supports_delete_zone());
+    let result = provider.create_zone("example.org").await;
+    assert!(matches!(result, Err(DynError::Unsupported)));
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    assert_eq!(zone.id(), ZONE_ID_1);
+    assert!(zone.supports_create_record());
+    assert!(zone.supports_update_record());
+    assert!(zone.supports_delete_record());
+}
+
+#[tokio::test]
+async fn test_dyn_provider_get_record_not_found() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_zones_list_response(vec![(ZONE_ID_1, "example.com")])),
+        )
+        .mount(&server)
+        .await;
+
+    let nonexistent_record = "00000000000000000000000000000000";
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/zones/{}/dns_records/{}",
+            ZONE_ID_1, nonexistent_record
+        )))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(404)
+                .set_body_json(mock_error_response(81044, "Record not found")),
+        )
+        .mount(&server)
+        .await;
+
+    let provider: Box<dyn DynProvider> =
+        CloudflareProvider::with_base_url("test-token", &server.uri())
+            .expect("Failed to create provider")
+            .into();
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let result = zone.get_record(nonexistent_record).await;
+
+    assert!(matches!(result, Err(DynError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_dyn_provider_keeps_error_kind() {
+    let server = setup_mock_server().await;
+    mount_zone_lookup(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path(format!("/zones/{}/dns_records", ZONE_ID_1)))
+        .respond_with(
+            ResponseTemplate::new(400)
+                .set_body_json(mock_error_response(81045, "Record quota exceeded.")),
+        )
+        .mount(&server)
+        .await;
+
+    let provider: Box<dyn DynProvider> =
+        CloudflareProvider::with_base_url("test-token", &server.uri())
+            .expect("Failed to create provider")
+            .into();
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let err = zone
+        .create_record("test", &RecordData::A(Ipv4Addr::new(10, 0, 0, 1)), 300)
+        .await
+        .expect_err("Creation should fail");
+
+    assert!(matches!(err, DynError::Custom(ErrorKind::QuotaExceeded, _)));
+    assert_eq!(err.kind(), ErrorKind::QuotaExceeded);
+}
+
 // =============================================================================
 // Property-based Mock Tests
 // =============================================================================
@@ -0,0 +1,944 @@
+//! Mock-based tests for DNSPod provider.
+//!
+//! These tests use `wiremock` to simulate the DNSPod API (`dnsapi.cn`)
+//! without requiring network access or API credentials.
+//!
+//! # Coverage
+//!
+//! This module provides comprehensive testing for:
+//! - Authentication through the `login_token` form parameter and the required User-Agent
+//! - Zone operations (list, get, create, delete)
+//! - Record operations (list, get, create, update, delete)
+//! - Dynamic DNS updates through `Record.Ddns`
+//! - Pagination of domains and records
+//! - Mapping of numeric status codes, including codes signalling empty results
+//! - JSON edge cases (IDs as strings or integers, missing TTLs and types)
+//!
+//! # API Structure
+//!
+//! Every function is a `POST /{Function}` with a form-encoded body:
+//! - Auth: `login_token={id},{token}&format=json`
+//! - Result: `{"status": {"code": "1", ...}, ...}`, where any code but `1` is an error
+
+use crate::common::dnspod::*;
+use crate::common::setup_mock_server;
+
+use libdns::dnspod::{DnspodError, DnspodProvider, DnspodZone};
+use libdns::retry::RetryPolicy;
+use libdns::{
+    ClassifyError, CreateRecord, CreateRecordError, CreateZone, CreateZoneError, DeleteRecord,
+    DeleteRecordError, DeleteZone, DeleteZoneError, ErrorKind, Provider, RecordData,
+    RetrieveRecordError, RetrieveZoneError, UpdateRecord, UpdateRecordError, Zone,
+};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Creates a provider talking to the mock server without retry delays.
+fn provider(server: &MockServer) -> DnspodProvider {
+    DnspodProvider::builder(LOGIN_TOKEN, &client_config())
+        .with_base_url(&server.uri())
+        .with_retry_policy(
+            RetryPolicy::new().with_backoff(Duration::from_millis(1), Duration::from_millis(10)),
+        )
+        .build()
+        .expect("Failed to create provider")
+}
+
+/// Mounts a Domain.Info response for `example.com` and returns its zone.
+async fn zone(server: &MockServer) -> DnspodZone {
+    Mock::given(method("POST"))
+        .and(path("/Domain.Info"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_response(123, "example.com", 600)),
+        )
+        .mount(server)
+        .await;
+
+    provider(server)
+        .get_zone("123")
+        .await
+        .expect("Failed to get zone")
+}
+
+// =============================================================================
+// Authentication Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_requests_are_authenticated() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/Domain.List"))
+        .and(header("User-Agent", USER_AGENT))
+        .and(body_string_contains(
+            "login_token=12345,test-token&format=json",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_domain_list_response(vec![(123, "example.com")])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 1);
+}
+
+#[tokio::test]
+async fn test_custom_http_client_keeps_user_agent() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/Domain.List"))
+        .and(header("User-Agent", USER_AGENT))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_domain_list_response(vec![])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = DnspodProvider::builder(LOGIN_TOKEN, &client_config())
+        .with_base_url(&server.uri())
+        .with_http_client(reqwest::Client::new())
+        .build()
+        .expect("Failed to create provider");
+
+    provider.list_zones().await.expect("Failed to list zones");
+}
+
+#[tokio::test]
+async fn test_invalid_token_is_unauthorized() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response("-1", "Login failed")),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = provider(&server);
+
+    let result = provider.list_zones().await;
+    assert!(matches!(result, Err(RetrieveZoneError::Unauthorized)));
+
+    let result = provider.get_zone("123").await;
+    assert!(matches!(result, Err(RetrieveZoneError::Unauthorized)));
+}
+
+// =============================================================================
+// Zone Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_list_zones_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/Domain.List"))
+        .and(body_string_contains("&offset=0&length=500"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_list_response(vec![
+                (123, "example.com"),
+                (456, "example.org"),
+            ])),
+        )
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 2);
+    assert_eq!(zones[0].id(), "123");
+    assert_eq!(zones[0].domain(), "example.com");
+    assert_eq!(zones[1].id(), "456");
+    assert_eq!(zones[1].domain(), "example.org");
+}
+
+#[tokio::test]
+async fn test_list_zones_empty() {
+    let server = setup_mock_server().await;
+
+    // Accounts without domains are reported with status code 9
+    Mock::given(method("POST"))
+        .and(path("/Domain.List"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response("9", "No domains")),
+        )
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert!(zones.is_empty());
+}
+
+#[tokio::test]
+async fn test_list_zones_paginates() {
+    let server = setup_mock_server().await;
+
+    let names: Vec<String> = (0..500).map(|i| format!("domain{}.com", i)).collect();
+    Mock::given(method("POST"))
+        .and(path("/Domain.List"))
+        .and(body_string_contains("&offset=0&"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_list_response(
+                names
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| (i as u64, name.as_str()))
+                    .collect(),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    // The second page is empty, which DNSPod reports as an error
+    Mock::given(method("POST"))
+        .and(path("/Domain.List"))
+        .and(body_string_contains("&offset=500&"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response("9", "No domains")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 500);
+}
+
+#[tokio::test]
+async fn test_get_zone_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/Domain.Info"))
+        .and(body_string_contains("&domain_id=123"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_response(123, "example.com", 600)),
+        )
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("123")
+        .await
+        .expect("Failed to get zone");
+    assert_eq!(zone.id(), "123");
+    assert_eq!(zone.domain(), "example.com");
+}
+
+#[tokio::test]
+async fn test_get_zone_not_found() {
+    for code in ["6", "8"] {
+        let server = setup_mock_server().await;
+
+        Mock::given(method("POST"))
+            .and(path("/Domain.Info"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(mock_error_response(code, "Domain id invalid")),
+            )
+            .mount(&server)
+            .await;
+
+        let result = provider(&server).get_zone("999").await;
+        assert!(
+            matches!(result, Err(RetrieveZoneError::NotFound)),
+            "status code {} should map to NotFound",
+            code
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_create_zone_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/Domain.Create"))
+        .and(body_string_contains("&domain=newdomain.com"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": mock_status("1", "Action completed successful"),
+            "domain": {
+                "id": "789",
+                "punycode": "newdomain.com",
+                "domain": "newdomain.com"
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/Domain.Info"))
+        .and(body_string_contains("&domain_id=789"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_response(
+                789,
+                "newdomain.com",
+                600,
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .create_zone("newdomain.com")
+        .await
+        .expect("Failed to create zone");
+    assert_eq!(zone.id(), "789");
+    assert_eq!(zone.domain(), "newdomain.com");
+}
+
+#[tokio::test]
+async fn test_create_zone_errors() {
+    let cases = [
+        ("6", "invalid"),
+        ("7", "invalid"),
+        ("11", "invalid"),
+        ("41", "invalid"),
+        ("12", "unauthorized"),
+        ("13", "custom"),
+    ];
+
+    for (code, expected) in cases {
+        let server = setup_mock_server().await;
+
+        Mock::given(method("POST"))
+            .and(path("/Domain.Create"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(mock_error_response(code, "Rejected")),
+            )
+            .mount(&server)
+            .await;
+
+        let result = provider(&server).create_zone("example.com").await;
+        let matched = match expected {
+            "invalid" => matches!(result, Err(CreateZoneError::InvalidDomainName)),
+            "unauthorized" => matches!(result, Err(CreateZoneError::Unauthorized)),
+            _ => matches!(result, Err(CreateZoneError::Custom(DnspodError::Api(_)))),
+        };
+        assert!(
+            matched,
+            "status code {} should be mapped as {}",
+            code, expected
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_delete_zone_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/Domain.Remove"))
+        .and(body_string_contains("&domain_id=123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_success_response()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    provider(&server)
+        .delete_zone("123")
+        .await
+        .expect("Failed to delete zone");
+}
+
+#[tokio::test]
+async fn test_delete_zone_errors() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/Domain.Remove"))
+        .and(body_string_contains("&domain_id=999"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response("6", "Domain id invalid")),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/Domain.Remove"))
+        .and(body_string_contains("&domain_id=123"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response("7", "Domain locked")),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = provider(&server);
+
+    let result = provider.delete_zone("999").await;
+    assert!(matches!(result, Err(DeleteZoneError::NotFound)));
+
+    let result = provider.delete_zone("123").await;
+    assert!(matches!(result, Err(DeleteZoneError::Unauthorized)));
+}
+
+// =============================================================================
+// Record Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_list_records_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/Record.List"))
+        .and(body_string_contains("&domain_id=123&offset=0&length=500"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_list_response(
+                123,
+                vec![
+                    (1, "@", "A", "192.168.1.1", 600),
+                    (2, "www", "CNAME", "example.com.", 3600),
+                    (3, "@", "TXT", "v=spf1 -all", 600),
+                    (4, "_sip._tcp", "SRV", "10 60 5060 sip.example.com.", 600),
+                    (5, "@", "CAA", "0 issue \"letsencrypt.org\"", 600),
+                ],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+
+    assert_eq!(records.len(), 5);
+    assert_eq!(records[0].id, "1");
+    assert_eq!(records[0].host, "@");
+    assert_eq!(
+        records[0].data,
+        RecordData::A("192.168.1.1".parse().unwrap())
+    );
+    assert_eq!(
+        records[1].data,
+        RecordData::CNAME("example.com.".to_string())
+    );
+    assert_eq!(records[1].ttl, 3600);
+    assert_eq!(records[2].data, RecordData::TXT("v=spf1 -all".to_string()));
+    assert_eq!(
+        records[3].data,
+        RecordData::SRV {
+            priority: 10,
+            weight: 60,
+            port: 5060,
+            target: "sip.example.com.".to_string()
+        }
+    );
+    assert_eq!(
+        records[4].data,
+        RecordData::CAA {
+            flags: 0,
+            tag: "issue".to_string(),
+            value: "letsencrypt.org".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_list_records_empty() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    // Zones without records are reported with status code 10
+    Mock::given(method("POST"))
+        .and(path("/Record.List"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response("10", "No records")),
+        )
+        .mount(&server)
+        .await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+    assert!(records.is_empty());
+}
+
+#[tokio::test]
+async fn test_list_records_paginates() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    let addresses: Vec<String> = (0..500)
+        .map(|i| format!("10.0.{}.{}", i / 256, i % 256))
+        .collect();
+    Mock::given(method("POST"))
+        .and(path("/Record.List"))
+        .and(body_string_contains("&offset=0&"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_list_response(
+                123,
+                addresses
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ip)| (i as u64, "host", "A", ip.as_str(), 600))
+                    .collect(),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/Record.List"))
+        .and(body_string_contains("&offset=500&"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_list_response(
+                123,
+                vec![(500, "last", "A", "10.1.0.0", 600)],
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+    assert_eq!(records.len(), 501);
+    assert_eq!(records[500].host, "last");
+}
+
+#[tokio::test]
+async fn test_list_records_lenient_fields() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    // IDs may be integers, and the TTL and type may be missing
+    Mock::given(method("POST"))
+        .and(path("/Record.List"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": mock_status("1", "Action completed successful"),
+            "records": [
+                {"id": 1, "name": "www", "type": "A", "ttl": 300, "value": "192.168.1.1"},
+                {"id": "2", "name": "mail", "type": "AAAA", "value": "2001:db8::1"},
+                {"id": "3", "name": "legacy", "value": "192.168.1.3"}
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].id, "1");
+    assert_eq!(records[0].ttl, 300);
+    // Records without a TTL inherit the TTL of the domain
+    assert_eq!(records[1].ttl, 600);
+    assert_eq!(
+        records[1].data,
+        RecordData::AAAA("2001:db8::1".parse().unwrap())
+    );
+    // Records without a type are A records
+    assert_eq!(
+        records[2].data,
+        RecordData::A("192.168.1.3".parse().unwrap())
+    );
+}
+
+#[tokio::test]
+async fn test_get_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/Record.Info"))
+        .and(body_string_contains("&domain_id=123&record_id=42"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_response(
+                123,
+                42,
+                "www",
+                "A",
+                "192.168.1.1",
+                300,
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let record = zone.get_record("42").await.expect("Failed to get record");
+    assert_eq!(record.id, "42");
+    assert_eq!(record.host, "www");
+    assert_eq!(record.data, RecordData::A("192.168.1.1".parse().unwrap()));
+    assert_eq!(record.ttl, 300);
+}
+
+#[tokio::test]
+async fn test_get_record_not_found() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/Record.Info"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response("8", "Record id invalid")),
+        )
+        .mount(&server)
+        .await;
+
+    let result = zone.get_record("999").await;
+    assert!(matches!(result, Err(RetrieveRecordError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_create_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    // Values are form-encoded
+    Mock::given(method("POST"))
+        .and(path("/Record.Create"))
+        .and(body_string_contains(
+            "&domain_id=123&sub_domain=_dmarc&record_type=TXT&record_line=default\
+             &value=v%3DDMARC1%3B%20p%3Dnone&ttl=600",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_id_response(42, "_dmarc")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let data = RecordData::TXT("v=DMARC1; p=none".to_string());
+    let record = zone
+        .create_record("_dmarc", &data, 600)
+        .await
+        .expect("Failed to create record");
+
+    assert_eq!(record.id, "42");
+    assert_eq!(record.host, "_dmarc");
+    assert_eq!(record.data, data);
+    assert_eq!(record.ttl, 600);
+}
+
+#[tokio::test]
+async fn test_create_mx_record_sends_priority() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/Record.Create"))
+        .and(body_string_contains("&record_type=MX"))
+        .and(body_string_contains(
+            "&value=mail.example.com&mx=10&ttl=3600",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_record_id_response(43, "@")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let data = RecordData::MX {
+        priority: 10,
+        mail_server: "mail.example.com".to_string(),
+    };
+    let record = zone
+        .create_record("@", &data, 3600)
+        .await
+        .expect("Failed to create record");
+    assert_eq!(record.id, "43");
+}
+
+#[tokio::test]
+async fn test_create_record_unsupported_type() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    let data = RecordData::Other {
+        typ: "PTR".to_string(),
+        value: "example.com".to_string(),
+    };
+    let result = zone.create_record("1", &data, 600).await;
+    assert!(matches!(result, Err(CreateRecordError::UnsupportedType)));
+}
+
+#[tokio::test]
+async fn test_create_record_errors() {
+    let cases = [
+        ("22", "invalid"),
+        ("26", "invalid"),
+        ("30", "invalid"),
+        ("34", "invalid"),
+        ("27", "unsupported"),
+        ("21", "unauthorized"),
+        ("-15", "unauthorized"),
+        ("104", "custom"),
+    ];
+
+    for (code, expected) in cases {
+        let server = setup_mock_server().await;
+        let zone = zone(&server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/Record.Create"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(mock_error_response(code, "Rejected")),
+            )
+            .mount(&server)
+            .await;
+
+        let result = zone
+            .create_record("www", &RecordData::A("192.168.1.1".parse().unwrap()), 600)
+            .await;
+        let matched = match expected {
+            "invalid" => matches!(result, Err(CreateRecordError::InvalidRecord)),
+            "unsupported" => matches!(result, Err(CreateRecordError::UnsupportedType)),
+            "unauthorized" => matches!(result, Err(CreateRecordError::Unauthorized)),
+            _ => matches!(result, Err(CreateRecordError::Custom(_))),
+        };
+        assert!(
+            matched,
+            "status code {} should be mapped as {}",
+            code, expected
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_update_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/Record.Modify"))
+        .and(body_string_contains(
+            "&domain_id=123&record_id=42&sub_domain=www&record_type=AAAA&record_line=default\
+             &value=2001%3Adb8%3A%3A1&ttl=300",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_record_id_response(42, "www")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let data = RecordData::AAAA("2001:db8::1".parse().unwrap());
+    let record = zone
+        .update_record("42", "www", &data, 300)
+        .await
+        .expect("Failed to update record");
+
+    assert_eq!(record.id, "42");
+    assert_eq!(record.data, data);
+    assert_eq!(record.ttl, 300);
+}
+
+#[tokio::test]
+async fn test_update_record_not_found() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/Record.Modify"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response("8", "Record id invalid")),
+        )
+        .mount(&server)
+        .await;
+
+    let result = zone
+        .update_record(
+            "999",
+            "www",
+            &RecordData::A("192.168.1.1".parse().unwrap()),
+            600,
+        )
+        .await;
+    assert!(matches!(result, Err(UpdateRecordError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_delete_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/Record.Remove"))
+        .and(body_string_contains("&domain_id=123&record_id=42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_success_response()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    zone.delete_record("42")
+        .await
+        .expect("Failed to delete record");
+}
+
+#[tokio::test]
+async fn test_delete_record_not_found() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/Record.Remove"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response("8", "Record id invalid")),
+        )
+        .mount(&server)
+        .await;
+
+    let result = zone.delete_record("999").await;
+    assert!(matches!(result, Err(DeleteRecordError::NotFound)));
+}
+
+// =============================================================================
+// Dynamic DNS Tests
+// =============================================================================
+
+#[cfg(feature = "ddns")]
+mod ddns {
+    use super::*;
+    use libdns::ddns::DynamicDns;
+    use libdns::Record;
+
+    fn existing_record() -> Record {
+        Record {
+            id: "42".to_string(),
+            host: "home".to_string(),
+            data: RecordData::A("192.168.1.1".parse().unwrap()),
+            ttl: 600,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_address_uses_ddns_endpoint() {
+        let server = setup_mock_server().await;
+        let zone = zone(&server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/Record.Ddns"))
+            .and(body_string_contains(
+                "&domain_id=123&record_id=42&sub_domain=home&record_line=default&value=203.0.113.7",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(mock_record_id_response(42, "home")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let record = zone
+            .update_address(&existing_record(), "203.0.113.7".parse().unwrap(), 600)
+            .await
+            .expect("Failed to update address");
+        assert_eq!(record.data, RecordData::A("203.0.113.7".parse().unwrap()));
+        assert_eq!(record.ttl, 600);
+    }
+
+    #[tokio::test]
+    async fn test_update_address_with_new_ttl_modifies_record() {
+        let server = setup_mock_server().await;
+        let zone = zone(&server).await;
+
+        // The DDNS endpoint cannot change the TTL
+        Mock::given(method("POST"))
+            .and(path("/Record.Modify"))
+            .and(body_string_contains("&value=203.0.113.7&ttl=60"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(mock_record_id_response(42, "home")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/Record.Ddns"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(mock_record_id_response(42, "home")),
+            )
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let record = zone
+            .update_address(&existing_record(), "203.0.113.7".parse().unwrap(), 60)
+            .await
+            .expect("Failed to update address");
+        assert_eq!(record.ttl, 60);
+    }
+}
+
+// =============================================================================
+// Error Classification Tests
+// =============================================================================
+
+#[test]
+fn test_status_codes_are_classified() {
+    let cases = [
+        ("-1", ErrorKind::Unauthorized),
+        ("-8", ErrorKind::Unauthorized),
+        ("-2", ErrorKind::RateLimited),
+        ("-99", ErrorKind::Transient),
+        ("104", ErrorKind::Other),
+    ];
+
+    for (code, kind) in cases {
+        let err = DnspodError::Api(libdns::dnspod::api::Status {
+            code: code.to_string(),
+            message: "Rejected".to_string(),
+            created_at: None,
+        });
+        assert_eq!(
+            err.kind(),
+            kind,
+            "status code {} should be {:?}",
+            code,
+            kind
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limited_request_is_retried() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/Domain.List"))
+        .respond_with(ResponseTemplate::new(429))
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/Domain.List"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_domain_list_response(vec![(123, "example.com")])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 1);
+}
+
+#[tokio::test]
+async fn test_non_json_response_is_an_error() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/Domain.Info"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html>Bad Gateway</html>"))
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).get_zone("123").await;
+    assert!(matches!(
+        result,
+        Err(RetrieveZoneError::Custom(DnspodError::Parse(_)))
+    ));
+}
@@ -7,8 +7,10 @@
 //!
 //! This module provides comprehensive testing for:
 //! - Zone operations (list, get, create, delete)
-//! - RRSet/Record operations (list, get, create, delete)
+//! - RRSet/Record operations (list, get, create, update, delete)
 //! - Error handling (404, 401, server errors)
+//! - Retries of rate-limited and failed requests
+//! - HTTP client configuration through the builder
 //! - Various record types (A, AAAA, CNAME, MX, TXT, etc.)
 //! - TTL handling
 //! - Pagination
@@ -23,12 +25,18 @@
 use crate::common::hetzner::*;
 use crate::common::setup_mock_server;
 
+use libdns::dynamic::{DynError, DynProvider, DynProviderAdapter};
 use libdns::hetzner::HetznerProvider;
-use libdns::{CreateRecord, CreateZone, DeleteRecord, DeleteZone, Provider, RecordData, Zone};
+use libdns::retry::RetryPolicy;
+use libdns::{
+    AppendRecords, ApplyChanges, ClassifyError, CreateRecord, CreateZone, DeleteRecord, DeleteZone,
+    ErrorKind, Provider, Record, RecordChange, RecordData, UpdateRecord, Zone,
+};
 use proptest::prelude::*;
 use serde_json::json;
 use std::net::Ipv4Addr;
-use wiremock::matchers::{header, method, path};
+use std::time::Duration;
+use wiremock::matchers::{body_json, header, method, path};
 use wiremock::{Mock, ResponseTemplate};
 
 // =============================================================================
@@ -189,6 +197,31 @@ async fn test_create_zone_success() {
     assert_eq!(zone.domain(), "newdomain.com");
 }
 
+#[tokio::test]
+async fn test_create_zone_already_exists() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/zones"))
+        .respond_with(ResponseTemplate::new(409).set_body_json(json!({
+            "error": {
+                "code": "uniqueness_error",
+                "message": "zone already exists"
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let err = provider
+        .create_zone("example.com")
+        .await
+        .expect_err("Creation should fail");
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+}
+
 #[tokio::test]
 async fn test_delete_zone_success() {
     let server = setup_mock_server().await;
@@ -438,7 +471,7 @@ async fn test_create_record_add_to_existing_rrset() {
 }
 
 #[tokio::test]
-async fn test_delete_record_success() {
+async fn test_update_record_same_rrset() {
     let server = setup_mock_server().await;
 
     Mock::given(method("GET"))
@@ -452,11 +485,98 @@ async fn test_delete_record_success() {
         .mount(&server)
         .await;
 
-    // Delete record uses remove_records action endpoint
+    Mock::given(method("GET"))
+        .and(path("/zones/123/rrsets/test/A"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_rrset_response(
+                123,
+                "test",
+                "A",
+                300,
+                vec!["10.0.0.1", "10.0.0.3"],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    // Value changes within an RRSet are written back with a single set_records action
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets/test/A/actions/set_records"))
+        .and(header("Authorization", "Bearer test-token"))
+        .and(body_json(json!({
+            "records": [{"value": "10.0.0.2"}, {"value": "10.0.0.3"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(5, "success")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let record = zone
+        .update_record(
+            "test/A/10.0.0.1",
+            "test",
+            &RecordData::A(Ipv4Addr::new(10, 0, 0, 2)),
+            300,
+        )
+        .await
+        .expect("Failed to update record");
+
+    assert_eq!(record.id, "test/A/10.0.0.2");
+    assert_eq!(record.data, RecordData::A(Ipv4Addr::new(10, 0, 0, 2)));
+}
+
+#[tokio::test]
+async fn test_update_record_rename() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zone_response(
+            123,
+            "example.com",
+            3600,
+        )))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/123/rrsets/test/A"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_rrset_response(
+                123,
+                "test",
+                "A",
+                300,
+                vec!["10.0.0.1"],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    // Moving a record to another name adds it there before removing the old one
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets/www/A/actions/add_records"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(6, "success")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
     Mock::given(method("POST"))
         .and(path("/zones/123/rrsets/test/A/actions/remove_records"))
         .and(header("Authorization", "Bearer test-token"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(3, "success")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(7, "success")))
+        .expect(1)
         .mount(&server)
         .await;
 
@@ -467,15 +587,84 @@ async fn test_delete_record_success() {
         .get_zone("example.com")
         .await
         .expect("Failed to get zone");
+    let record = zone
+        .update_record(
+            "test/A/10.0.0.1",
+            "www",
+            &RecordData::A(Ipv4Addr::new(10, 0, 0, 1)),
+            300,
+        )
+        .await
+        .expect("Failed to update record");
 
-    // Delete record by its ID format
-    zone.delete_record("test/A/10.0.0.1")
+    assert_eq!(record.id, "www/A/10.0.0.1");
+    assert_eq!(record.host, "www");
+}
+
+#[tokio::test]
+async fn test_append_records_groups_by_rrset() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zone_response(
+            123,
+            "example.com",
+            3600,
+        )))
+        .mount(&server)
+        .await;
+
+    // Both A values are added to the RRSet in a single action
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets/test/A/actions/add_records"))
+        .and(header("Authorization", "Bearer test-token"))
+        .and(body_json(json!({
+            "records": [{"value": "10.0.0.1"}, {"value": "10.0.0.2"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(8, "success")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets/@/TXT/actions/add_records"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(9, "success")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
         .await
-        .expect("Failed to delete record");
+        .expect("Failed to get zone");
+    let record = |host: &str, data: RecordData| Record {
+        id: String::new(),
+        host: host.to_string(),
+        data,
+        ttl: 3600,
+    };
+    let created = zone
+        .append_records(&[
+            record("test", RecordData::A(Ipv4Addr::new(10, 0, 0, 1))),
+            record("example.com", RecordData::TXT("hello".to_string())),
+            record("test", RecordData::A(Ipv4Addr::new(10, 0, 0, 2))),
+        ])
+        .await
+        .expect("Failed to append records");
+
+    assert_eq!(created.len(), 3);
+    assert_eq!(created[0].id, "test/A/10.0.0.1");
+    assert_eq!(created[1].id, "@/TXT/\"hello\"");
 }
 
 #[tokio::test]
-async fn test_delete_last_record_removes_rrset() {
+async fn test_apply_changes_writes_rrsets_once() {
     let server = setup_mock_server().await;
 
     Mock::given(method("GET"))
@@ -489,12 +678,38 @@ async fn test_delete_last_record_removes_rrset() {
         .mount(&server)
         .await;
 
-    // Delete last record also uses remove_records action endpoint
-    // (The API automatically cleans up empty RRSets)
+    Mock::given(method("GET"))
+        .and(path("/zones/123/rrsets"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_rrsets_response(
+                123,
+                vec![("www", "A", 3600, vec!["10.0.0.1", "10.0.0.2"])],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    // Updating one value and adding another results in a single set_records action
     Mock::given(method("POST"))
-        .and(path("/zones/123/rrsets/test/A/actions/remove_records"))
+        .and(path("/zones/123/rrsets/www/A/actions/set_records"))
         .and(header("Authorization", "Bearer test-token"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(4, "success")))
+        .and(body_json(json!({
+            "records": [{"value": "10.0.0.3"}, {"value": "10.0.0.4"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(10, "success")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // A record for a new name creates its RRSet
+    let mut created = mock_rrset_response(123, "mail", "A", 3600, vec!["10.0.0.5"]);
+    created["action"] = mock_action_response(11, "success")["action"].clone();
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(created))
+        .expect(1)
         .mount(&server)
         .await;
 
@@ -505,10 +720,624 @@ async fn test_delete_last_record_removes_rrset() {
         .get_zone("example.com")
         .await
         .expect("Failed to get zone");
+    let a = |ip: [u8; 4]| RecordData::A(Ipv4Addr::from(ip));
+    let records = zone
+        .apply_changes(&[
+            RecordChange::Update {
+                record_id: "www/A/10.0.0.1".to_string(),
+                host: "www".to_string(),
+                data: a([10, 0, 0, 3]),
+                ttl: 3600,
+            },
+            RecordChange::Delete {
+                record_id: "www/A/10.0.0.2".to_string(),
+            },
+            RecordChange::Create {
+                host: "www".to_string(),
+                data: a([10, 0, 0, 4]),
+                ttl: 3600,
+            },
+            RecordChange::Create {
+                host: "mail.example.com".to_string(),
+                data: a([10, 0, 0, 5]),
+                ttl: 3600,
+            },
+        ])
+        .await
+        .expect("Failed to apply changes");
 
-    zone.delete_record("test/A/10.0.0.1")
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].id, "www/A/10.0.0.3");
+    assert_eq!(records[2].id, "mail/A/10.0.0.5");
+}
+
+#[tokio::test]
+async fn test_apply_changes_restores_failing_rrset() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zone_response(
+            123,
+            "example.com",
+            3600,
+        )))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/123/rrsets"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_rrsets_response(
+                123,
+                vec![("www", "A", 3600, vec!["10.0.0.1"])],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    // The new value is written, but changing the TTL afterwards fails
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets/www/A/actions/set_records"))
+        .and(body_json(json!({"records": [{"value": "10.0.0.2"}]})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(10, "success")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets/www/A/actions/change_ttl"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // The old value is restored, the TTL never changed
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets/www/A/actions/set_records"))
+        .and(body_json(json!({"records": [{"value": "10.0.0.1"}]})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(11, "success")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::builder("test-token")
+        .with_base_url(&server.uri())
+        .with_retry_policy(RetryPolicy::disabled())
+        .build()
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
         .await
-        .expect("Failed to delete record");
+        .expect("Failed to get zone");
+    let err = zone
+        .apply_changes(&[RecordChange::Update {
+            record_id: "www/A/10.0.0.1".to_string(),
+            host: "www".to_string(),
+            data: RecordData::A(Ipv4Addr::new(10, 0, 0, 2)),
+            ttl: 600,
+        }])
+        .await
+        .unwrap_err();
+
+    assert!(err.rolled_back);
+    assert!(err.index.is_none());
+}
+
+#[cfg(feature = "acme")]
+#[tokio::test]
+async fn test_acme_challenges_keep_foreign_values() {
+    use libdns::acme::{Dns01Challenge, Dns01Solver};
+
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zone_response(
+            123,
+            "example.com",
+            3600,
+        )))
+        .mount(&server)
+        .await;
+
+    // Apex and wildcard challenges share the same name
+    Mock::given(method("POST"))
+        .and(path(
+            "/zones/123/rrsets/_acme-challenge/TXT/actions/add_records",
+        ))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(1, "success")))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    // Another client has published its own challenge at the same name
+    Mock::given(method("GET"))
+        .and(path("/zones/123/rrsets"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_rrsets_response(
+                123,
+                vec![(
+                    "_acme-challenge",
+                    "TXT",
+                    60,
+                    vec![
+                        "\"apex-digest\"",
+                        "\"foreign-digest\"",
+                        "\"wildcard-digest\"",
+                    ],
+                )],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path(
+            "/zones/123/rrsets/_acme-challenge/TXT/actions/remove_records",
+        ))
+        .and(body_json(
+            json!({"records": [{"value": "\"apex-digest\""}]}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(2, "success")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(
+            "/zones/123/rrsets/_acme-challenge/TXT/actions/remove_records",
+        ))
+        .and(body_json(
+            json!({"records": [{"value": "\"wildcard-digest\""}]}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(3, "success")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let challenges = [
+        Dns01Challenge::from_digest("example.com", "apex-digest"),
+        Dns01Challenge::from_digest("*.example.com", "wildcard-digest"),
+    ];
+    let solver = Dns01Solver::new(&zone);
+
+    solver
+        .present(&challenges)
+        .await
+        .expect("Failed to present challenges");
+    solver
+        .cleanup(&challenges)
+        .await
+        .expect("Failed to clean up challenges");
+}
+
+#[tokio::test]
+async fn test_delete_record_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zone_response(
+            123,
+            "example.com",
+            3600,
+        )))
+        .mount(&server)
+        .await;
+
+    // Delete record uses remove_records action endpoint
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets/test/A/actions/remove_records"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(3, "success")))
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+
+    // Delete record by its ID format
+    zone.delete_record("test/A/10.0.0.1")
+        .await
+        .expect("Failed to delete record");
+}
+
+#[tokio::test]
+async fn test_delete_last_record_removes_rrset() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zone_response(
+            123,
+            "example.com",
+            3600,
+        )))
+        .mount(&server)
+        .await;
+
+    // Delete last record also uses remove_records action endpoint
+    // (The API automatically cleans up empty RRSets)
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets/test/A/actions/remove_records"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(4, "success")))
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+
+    zone.delete_record("test/A/10.0.0.1")
+        .await
+        .expect("Failed to delete record");
+}
+
+// =============================================================================
+// Builder Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_builder_sets_user_agent() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(header("User-Agent", "my-app/1.0"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zones_response(vec![])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::builder("test-token")
+        .with_base_url(&server.uri())
+        .with_user_agent("my-app/1.0")
+        .build()
+        .expect("Failed to create provider");
+
+    provider.list_zones().await.expect("Failed to list zones");
+}
+
+#[tokio::test]
+async fn test_builder_with_http_client_keeps_authorization() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(header("Authorization", "Bearer test-token"))
+        .and(header("X-Custom", "yes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zones_response(vec![])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("X-Custom", "yes".parse().unwrap());
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+
+    let provider = HetznerProvider::builder("test-token")
+        .with_base_url(&server.uri())
+        .with_http_client(client)
+        .build()
+        .expect("Failed to create provider");
+
+    provider.list_zones().await.expect("Failed to list zones");
+}
+
+#[tokio::test]
+async fn test_builder_timeout_is_applied() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_zones_response(vec![]))
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::builder("test-token")
+        .with_base_url(&server.uri())
+        .with_timeout(Duration::from_millis(50))
+        .with_retry_policy(RetryPolicy::disabled())
+        .build()
+        .expect("Failed to create provider");
+
+    provider
+        .list_zones()
+        .await
+        .expect_err("Listing should time out");
+}
+
+// =============================================================================
+// Retry Tests
+// =============================================================================
+
+/// A policy retrying without noticeable delays.
+fn fast_retries() -> RetryPolicy {
+    RetryPolicy::new().with_backoff(Duration::from_millis(1), Duration::from_millis(10))
+}
+
+#[tokio::test]
+async fn test_rate_limited_request_is_retried() {
+    let server = setup_mock_server().await;
+
+    // Retry-After is given in seconds first, then as an HTTP date in the past
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "Sun, 06 Nov 1994 08:49:37 GMT"),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_zones_response(vec![(
+                123,
+                "example.com",
+                3600,
+            )])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    let zones = provider.list_zones().await.expect("Failed to list zones");
+    assert_eq!(zones.len(), 1);
+}
+
+#[tokio::test]
+async fn test_retries_are_limited() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/example.com"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider")
+        .with_retry_policy(fast_retries().with_max_retries(2));
+
+    let err = provider
+        .get_zone("example.com")
+        .await
+        .expect_err("Retrieval should fail");
+    assert_eq!(err.kind(), ErrorKind::Transient);
+}
+
+#[tokio::test]
+async fn test_exhausted_rate_limit_is_classified() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .respond_with(ResponseTemplate::new(429))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider")
+        .with_retry_policy(fast_retries().with_max_retries(1));
+
+    let err = provider
+        .list_zones()
+        .await
+        .expect_err("Listing should fail");
+    assert_eq!(err.kind(), ErrorKind::RateLimited);
+    assert!(err.kind().is_retryable());
+}
+
+#[tokio::test]
+async fn test_non_idempotent_request_is_only_retried_when_rate_limited() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/zones"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/zones"))
+        .respond_with(ResponseTemplate::new(429))
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/zones"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+            "zone": {
+                "id": 789,
+                "name": "newdomain.com",
+                "mode": "primary",
+                "ttl": 3600,
+                "status": "pending",
+                "record_count": 0
+            },
+            "action": {
+                "id": 1,
+                "command": "create_zone",
+                "status": "running",
+                "progress": 0
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider")
+        .with_retry_policy(fast_retries());
+
+    // The zone may have been created before the server failed
+    assert!(provider.create_zone("newdomain.com").await.is_err());
+    // A rate-limited request was not processed
+    let zone = provider
+        .create_zone("newdomain.com")
+        .await
+        .expect("Failed to create zone");
+    assert_eq!(zone.id(), "789");
+}
+
+#[tokio::test]
+async fn test_long_retry_after_is_not_awaited() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3600"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+
+    assert!(provider.list_zones().await.is_err());
+}
+
+// =============================================================================
+// Dynamic Provider Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_dyn_provider_create_record() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zone_response(
+            123,
+            "example.com",
+            3600,
+        )))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/zones/123/rrsets/test/A/actions/add_records"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_action_response(1, "success")))
+        .mount(&server)
+        .await;
+
+    let provider: Box<dyn DynProvider> =
+        HetznerProvider::with_base_url("test-token", &server.uri())
+            .expect("Failed to create provider")
+            .into();
+
+    assert!(provider.supports_create_zone());
+    assert!(provider.supports_delete_zone());
+
+    let capabilities = provider.capabilities();
+    assert!(capabilities.create_zone && capabilities.create_record);
+    assert!(capabilities.update_record && !capabilities.atomic_update);
+    assert_eq!(capabilities.clamp_ttl(1), 60);
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let record = zone
+        .create_record("test", &RecordData::A(Ipv4Addr::new(10, 0, 0, 1)), 300)
+        .await
+        .expect("Failed to create record");
+
+    assert_eq!(record.id, "test/A/10.0.0.1");
+}
+
+#[tokio::test]
+async fn test_dyn_provider_adapter_without_capabilities() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/example.com"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zone_response(
+            123,
+            "example.com",
+            3600,
+        )))
+        .mount(&server)
+        .await;
+
+    let provider = HetznerProvider::with_base_url("test-token", &server.uri())
+        .expect("Failed to create provider");
+    // Only retrieval is enabled unless capabilities are opted into
+    let provider: Box<dyn DynProvider> = Box::new(DynProviderAdapter::new(provider));
+
+    assert!(!provider.supports_create_zone());
+    assert!(!provider.supports_delete_zone());
+
+    let zone = provider
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    assert!(!zone.supports_create_record());
+    assert!(!zone.supports_update_record());
+    assert!(!zone.supports_delete_record());
+
+    let capabilities = zone.capabilities();
+    assert!(!capabilities.create_record && !capabilities.delete_record);
+    assert!(capabilities.supports_type("CAA"));
+
+    let result = zone.delete_record("test/A/10.0.0.1").await;
+    assert!(matches!(result, Err(DynError::Unsupported)));
 }
 
 // =============================================================================
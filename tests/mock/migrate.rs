@@ -0,0 +1,146 @@
+//! Mock-based tests for migrating zones between providers.
+//!
+//! Records are read from a mocked Hetzner zone and written to a mocked Cloudflare zone.
+
+use crate::common::{cloudflare, hetzner, setup_mock_server};
+
+use libdns::cloudflare::CloudflareProvider;
+use libdns::hetzner::HetznerProvider;
+use libdns::migrate::{migrate_zone, Adjustment, MigrationOutcome, SkipReason};
+use libdns::Provider;
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path, query_param};
+use wiremock::{Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn test_migrate_hetzner_to_cloudflare() {
+    let source_server = setup_mock_server().await;
+    let target_server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones/example.com"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(hetzner::mock_zone_response(
+                123,
+                "example.com",
+                3600,
+            )),
+        )
+        .mount(&source_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/zones/123/rrsets"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(hetzner::mock_rrsets_response(
+                123,
+                vec![
+                    ("@", "NS", 3600, vec!["hydrogen.ns.hetzner.com."]),
+                    ("www", "A", 30, vec!["10.0.0.1"]),
+                    ("@", "HINFO", 3600, vec!["\"x86\" \"linux\""]),
+                    ("@", "TXT", 3600, vec!["\"v=spf1 -all\""]),
+                ],
+            )),
+        )
+        .mount(&source_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "example.com"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(
+            cloudflare::mock_zones_list_response(vec![(cloudflare::ZONE_ID_1, "example.com")]),
+        ))
+        .mount(&target_server)
+        .await;
+
+    // The TTL below Cloudflare's minimum is raised
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/zones/{}/dns_records",
+            cloudflare::ZONE_ID_1
+        )))
+        .and(body_partial_json(json!({"type": "A", "ttl": 60})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(cloudflare::mock_record_response(
+                cloudflare::RECORD_ID_1,
+                cloudflare::ZONE_ID_1,
+                "example.com",
+                "www.example.com",
+                "A",
+                "10.0.0.1",
+                60,
+            )),
+        )
+        .expect(1)
+        .mount(&target_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/zones/{}/dns_records",
+            cloudflare::ZONE_ID_1
+        )))
+        .and(body_partial_json(json!({"type": "TXT"})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(cloudflare::mock_record_response(
+                cloudflare::RECORD_ID_2,
+                cloudflare::ZONE_ID_1,
+                "example.com",
+                "example.com",
+                "TXT",
+                "\"v=spf1 -all\"",
+                3600,
+            )),
+        )
+        .expect(1)
+        .mount(&target_server)
+        .await;
+
+    let source = HetznerProvider::with_base_url("test-token", &source_server.uri())
+        .expect("Failed to create provider")
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get source zone");
+    let target = CloudflareProvider::with_base_url("test-token", &target_server.uri())
+        .expect("Failed to create provider")
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get target zone");
+
+    let report = migrate_zone(&source, &target)
+        .await
+        .expect("Failed to migrate zone");
+
+    assert_eq!(report.entries.len(), 4);
+    assert_eq!(report.copied(), 2);
+    assert_eq!(report.skipped(), 2);
+
+    let outcome = |typ: &str| {
+        &report
+            .entries
+            .iter()
+            .find(|e| e.source.data.get_type() == typ)
+            .expect("Missing report entry")
+            .outcome
+    };
+    assert!(matches!(
+        outcome("NS"),
+        MigrationOutcome::Skipped(SkipReason::ApexAuthority)
+    ));
+    assert!(matches!(
+        outcome("HINFO"),
+        MigrationOutcome::Skipped(SkipReason::UnsupportedType)
+    ));
+    let MigrationOutcome::Copied {
+        record,
+        adjustments,
+    } = outcome("A")
+    else {
+        panic!("A record was not copied");
+    };
+    assert_eq!(record.id, cloudflare::RECORD_ID_1);
+    assert_eq!(adjustments, &vec![Adjustment::Ttl { from: 30, to: 60 }]);
+    assert!(matches!(
+        outcome("TXT"),
+        MigrationOutcome::Copied { adjustments, .. } if adjustments.is_empty()
+    ));
+}
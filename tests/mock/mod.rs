@@ -6,5 +6,26 @@
 #[cfg(feature = "cloudflare")]
 pub mod cloudflare;
 
+#[cfg(feature = "dnspod")]
+pub mod dnspod;
+
 #[cfg(feature = "hetzner")]
 pub mod hetzner;
+
+#[cfg(all(feature = "cloudflare", feature = "hetzner"))]
+pub mod migrate;
+
+#[cfg(feature = "namecheap")]
+pub mod namecheap;
+
+#[cfg(feature = "propagation")]
+pub mod propagation;
+
+#[cfg(feature = "rfc2136")]
+pub mod rfc2136;
+
+#[cfg(feature = "technitium-dns")]
+pub mod technitium;
+
+#[cfg(feature = "tencent")]
+pub mod tencent;
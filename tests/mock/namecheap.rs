@@ -0,0 +1,1001 @@
+//! Mock-based tests for Namecheap provider.
+//!
+//! These tests use `wiremock` to simulate the Namecheap XML API (`api.namecheap.com/xml.response`)
+//! without requiring network access, API credentials or a whitelisted IP address.
+//!
+//! # Coverage
+//!
+//! This module provides comprehensive testing for:
+//! - Authentication through the `ApiUser`, `ApiKey`, `UserName` and `ClientIp` query parameters
+//! - Zone retrieval, including domains with two-part TLDs
+//! - Record operations (list, get, create, update, delete), which replace all hosts at once
+//! - Host IDs being reassigned by every `setHosts` call
+//! - Mapping of numeric error codes
+//! - XML edge cases (escaped attributes and messages, missing attributes, malformed documents)
+//!
+//! # API Structure
+//!
+//! Every command is a `GET /xml.response` with query parameters:
+//! - Auth: `ApiUser`, `ApiKey`, `UserName` and `ClientIp`
+//! - Command: `Command=namecheap.domains.dns.getHosts` with `SLD` and `TLD`
+//! - Result: `<ApiResponse Status="OK">` or `<ApiResponse Status="ERROR">` with `<Errors>`
+
+use crate::common::namecheap::*;
+use crate::common::setup_mock_server;
+
+use libdns::namecheap::{NamecheapError, NamecheapProvider, NamecheapZone};
+use libdns::retry::RetryPolicy;
+use libdns::{
+    ApplyChanges, ChangeError, ClassifyError, CreateRecord, CreateRecordError, DeleteRecord,
+    DeleteRecordError, ErrorKind, Provider, RecordChange, RecordData, RetrieveRecordError,
+    RetrieveZoneError, UpdateRecord, UpdateRecordError, Zone,
+};
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockBuilder, MockServer, ResponseTemplate};
+
+const GET_HOSTS: &str = "namecheap.domains.dns.getHosts";
+const SET_HOSTS: &str = "namecheap.domains.dns.setHosts";
+
+/// Creates a provider talking to the mock server without retry delays.
+fn provider(server: &MockServer) -> NamecheapProvider {
+    NamecheapProvider::builder(client_config())
+        .with_base_url(&format!("{}{}", server.uri(), API_PATH))
+        .with_retry_policy(
+            RetryPolicy::new().with_backoff(Duration::from_millis(1), Duration::from_millis(10)),
+        )
+        .build()
+        .expect("Failed to create provider")
+}
+
+/// Starts a mock for the given command on `example.com`.
+fn command(name: &str) -> MockBuilder {
+    Mock::given(method("GET"))
+        .and(path(API_PATH))
+        .and(query_param("Command", name))
+        .and(query_param("SLD", "example"))
+        .and(query_param("TLD", "com"))
+}
+
+/// The hosts of `example.com` before any changes.
+fn initial_hosts() -> String {
+    mock_get_hosts_response(
+        "example.com",
+        vec![
+            ("101", "@", "A", "192.168.1.1", 1800),
+            ("102", "www", "CNAME", "example.com.", 1800),
+        ],
+    )
+}
+
+/// Mounts a getHosts response answering only the next `times` requests.
+///
+/// Later mocks with the default priority answer once these are used up, which models
+/// the hosts changing after a setHosts call.
+async fn mount_hosts_before_change(server: &MockServer, body: String, times: u64) {
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .up_to_n_times(times)
+        .expect(times)
+        .with_priority(1)
+        .mount(server)
+        .await;
+}
+
+/// Retrieves the zone of `example.com`, which requires a single getHosts request.
+async fn zone(server: &MockServer) -> NamecheapZone {
+    mount_hosts_before_change(server, initial_hosts(), 1).await;
+
+    provider(server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone")
+}
+
+// =============================================================================
+// Authentication Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_requests_are_authenticated() {
+    let server = setup_mock_server().await;
+
+    command(GET_HOSTS)
+        .and(query_param("ApiUser", "test-user"))
+        .and(query_param("ApiKey", "test-key"))
+        .and(query_param("UserName", "test-user"))
+        .and(query_param("ClientIp", "127.0.0.1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(initial_hosts()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+}
+
+#[tokio::test]
+async fn test_unauthorized_error_codes() {
+    for code in ["1010102", "1011102", "1030408"] {
+        let server = setup_mock_server().await;
+
+        command(GET_HOSTS)
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(mock_error_response(code, "Parameter APIKey is invalid")),
+            )
+            .mount(&server)
+            .await;
+
+        let result = provider(&server).get_zone("example.com").await;
+        assert!(
+            matches!(result, Err(RetrieveZoneError::Unauthorized)),
+            "error {} should map to Unauthorized",
+            code
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_ip_not_whitelisted() {
+    let server = setup_mock_server().await;
+
+    command(GET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_error_response(
+                "1011150",
+                "Invalid request IP: 127.0.0.1",
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let err = provider(&server)
+        .get_zone("example.com")
+        .await
+        .err()
+        .expect("Retrieval should fail");
+    assert!(matches!(
+        &err,
+        RetrieveZoneError::Custom(NamecheapError::Api(api)) if api.code == "1011150"
+    ));
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+}
+
+// =============================================================================
+// Zone Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_get_zone_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    assert_eq!(zone.id(), "example.com");
+    assert_eq!(zone.domain(), "example.com");
+    assert_eq!(zone.sld(), "example");
+    assert_eq!(zone.tld(), "com");
+}
+
+#[tokio::test]
+async fn test_get_zone_two_part_tld() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path(API_PATH))
+        .and(query_param("Command", GET_HOSTS))
+        .and(query_param("SLD", "example"))
+        .and(query_param("TLD", "co.uk"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(mock_get_hosts_response("example.co.uk", vec![])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.co.uk")
+        .await
+        .expect("Failed to get zone");
+    assert_eq!(zone.sld(), "example");
+    assert_eq!(zone.tld(), "co.uk");
+}
+
+#[tokio::test]
+async fn test_get_zone_not_found() {
+    for code in ["2019166", "2016166"] {
+        let server = setup_mock_server().await;
+
+        command(GET_HOSTS)
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(mock_error_response(code, "Domain not found")),
+            )
+            .mount(&server)
+            .await;
+
+        let result = provider(&server).get_zone("example.com").await;
+        assert!(
+            matches!(result, Err(RetrieveZoneError::NotFound)),
+            "error {} should map to NotFound",
+            code
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_get_zone_invalid_domain() {
+    let server = setup_mock_server().await;
+
+    let result = provider(&server).get_zone("localhost").await;
+    assert!(matches!(
+        result,
+        Err(RetrieveZoneError::Custom(NamecheapError::Parse(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_get_zone_not_using_namecheap_dns() {
+    let server = setup_mock_server().await;
+
+    let body = mock_get_hosts_response("example.com", vec![])
+        .replace(r#"IsUsingOurDNS="true""#, r#"IsUsingOurDNS="false""#);
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).get_zone("example.com").await;
+    assert!(matches!(
+        result,
+        Err(RetrieveZoneError::Custom(NamecheapError::Api(api))) if api.code == "2030288"
+    ));
+}
+
+#[tokio::test]
+async fn test_list_zones_is_empty() {
+    let server = setup_mock_server().await;
+
+    // Listing zones is not supported, so no request is made
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert!(zones.is_empty());
+}
+
+#[tokio::test]
+async fn test_capabilities() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    let capabilities = provider(&server).capabilities();
+    assert_eq!(zone.capabilities(), capabilities);
+    assert!(!capabilities.list_zones);
+    assert!(!capabilities.create_zone && !capabilities.delete_zone);
+    assert!(capabilities.supports_type("CAA"));
+    assert!(!capabilities.supports_type("SRV"));
+    assert_eq!(capabilities.ttl_range, 60..=60000);
+}
+
+// =============================================================================
+// Record Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_list_records_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    command(GET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_get_hosts_response(
+                "example.com",
+                vec![
+                    ("101", "@", "A", "192.168.1.1", 1800),
+                    ("102", "www", "CNAME", "example.com.", 3600),
+                    ("103", "@", "MX", "mail.example.com.", 1800),
+                    ("104", "ipv6", "AAAA", "2001:db8::1", 300),
+                    (
+                        "105",
+                        "@",
+                        "CAA",
+                        "0 issue &quot;letsencrypt.org&quot;",
+                        1800,
+                    ),
+                ],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+
+    assert_eq!(records.len(), 5);
+    assert_eq!(records[0].id, "101");
+    // Hosts are fully qualified
+    assert_eq!(records[0].host, "example.com");
+    assert_eq!(
+        records[0].data,
+        RecordData::A("192.168.1.1".parse().unwrap())
+    );
+    assert_eq!(records[1].host, "www.example.com");
+    assert_eq!(
+        records[1].data,
+        RecordData::CNAME("example.com.".to_string())
+    );
+    assert_eq!(records[1].ttl, 3600);
+    // The priority of MX records is kept in a separate attribute
+    assert_eq!(
+        records[2].data,
+        RecordData::MX {
+            priority: 10,
+            mail_server: "mail.example.com.".to_string()
+        }
+    );
+    assert_eq!(
+        records[3].data,
+        RecordData::AAAA("2001:db8::1".parse().unwrap())
+    );
+    assert_eq!(
+        records[4].data,
+        RecordData::CAA {
+            flags: 0,
+            tag: "issue".to_string(),
+            value: "letsencrypt.org".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_list_records_escaped_attributes() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    command(GET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_get_hosts_response(
+                "example.com",
+                vec![
+                    ("101", "@", "TXT", "&quot;v=spf1 -all&quot;", 1800),
+                    (
+                        "102",
+                        "go",
+                        "URL",
+                        "https://example.net/?a=1&amp;b=&lt;2&gt;",
+                        1800,
+                    ),
+                    (
+                        "103",
+                        "_dmarc",
+                        "TXT",
+                        "v=DMARC1; p=none; rua=mailto:a&#64;example.com",
+                        1800,
+                    ),
+                ],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+
+    assert_eq!(
+        records[0].data,
+        RecordData::TXT("\"v=spf1 -all\"".to_string())
+    );
+    assert_eq!(
+        records[1].data,
+        RecordData::Other {
+            typ: "URL".to_string(),
+            value: "https://example.net/?a=1&b=<2>".to_string()
+        }
+    );
+    assert_eq!(
+        records[2].data,
+        RecordData::TXT("v=DMARC1; p=none; rua=mailto:a@example.com".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_list_records_missing_attributes() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    // Element names are matched case-insensitively and a missing or invalid TTL falls back to the default
+    let body = mock_response(
+        GET_HOSTS,
+        r#"<DomainDNSGetHostsResult Domain="example.com" IsUsingOurDNS="true">
+      <Host HostId="101" Name="@" Type="A" Address="192.168.1.1" />
+      <host HostId="102" Name="www" Type="A" Address="192.168.1.2" TTL="automatic" />
+    </DomainDNSGetHostsResult>"#,
+    );
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].ttl, 1800);
+    assert_eq!(records[1].ttl, 1800);
+    assert_eq!(records[1].host, "www.example.com");
+}
+
+#[tokio::test]
+async fn test_list_records_empty() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    command(GET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(mock_get_hosts_response("example.com", vec![])),
+        )
+        .mount(&server)
+        .await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+    assert!(records.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(initial_hosts()))
+        .mount(&server)
+        .await;
+
+    let record = zone.get_record("102").await.expect("Failed to get record");
+    assert_eq!(record.id, "102");
+    assert_eq!(record.host, "www.example.com");
+    assert_eq!(record.data, RecordData::CNAME("example.com.".to_string()));
+
+    let result = zone.get_record("999").await;
+    assert!(matches!(result, Err(RetrieveRecordError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_create_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    mount_hosts_before_change(&server, initial_hosts(), 1).await;
+    // All existing hosts are sent along with the new one
+    command(SET_HOSTS)
+        .and(query_param("HostName1", "@"))
+        .and(query_param("RecordType1", "A"))
+        .and(query_param("Address1", "192.168.1.1"))
+        .and(query_param("HostName2", "www"))
+        .and(query_param("RecordType2", "CNAME"))
+        .and(query_param("HostName3", "mail"))
+        .and(query_param("RecordType3", "MX"))
+        .and(query_param("Address3", "mx.example.net"))
+        .and(query_param("MXPref3", "20"))
+        .and(query_param("TTL3", "3600"))
+        .and(query_param_is_missing("HostName4"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_set_hosts_response("example.com")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    // Saving assigns new IDs to all hosts
+    command(GET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_get_hosts_response(
+                "example.com",
+                vec![
+                    ("201", "@", "A", "192.168.1.1", 1800),
+                    ("202", "www", "CNAME", "example.com.", 1800),
+                    ("203", "mail", "MX", "mx.example.net", 3600),
+                ],
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let record = zone
+        .create_record(
+            "mail",
+            &RecordData::MX {
+                priority: 20,
+                mail_server: "mx.example.net".to_string(),
+            },
+            3600,
+        )
+        .await
+        .expect("Failed to create record");
+
+    assert_eq!(record.id, "203");
+    assert_eq!(record.host, "mail.example.com");
+    assert_eq!(record.ttl, 3600);
+}
+
+#[tokio::test]
+async fn test_create_record_clamps_ttl() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    mount_hosts_before_change(&server, mock_get_hosts_response("example.com", vec![]), 1).await;
+    command(SET_HOSTS)
+        .and(query_param("HostName1", "www"))
+        .and(query_param("TTL1", "60"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_set_hosts_response("example.com")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    command(GET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_get_hosts_response(
+                "example.com",
+                vec![("201", "www", "A", "192.168.1.2", 60)],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let record = zone
+        .create_record("www", &RecordData::A("192.168.1.2".parse().unwrap()), 1)
+        .await
+        .expect("Failed to create record");
+    assert_eq!(record.ttl, 60);
+}
+
+#[tokio::test]
+async fn test_create_record_unsupported_type() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    let data = RecordData::SRV {
+        priority: 10,
+        weight: 60,
+        port: 5060,
+        target: "sip.example.com".to_string(),
+    };
+    let result = zone.create_record("_sip._tcp", &data, 1800).await;
+    assert!(matches!(result, Err(CreateRecordError::UnsupportedType)));
+}
+
+#[tokio::test]
+async fn test_create_record_rejected() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(initial_hosts()))
+        .mount(&server)
+        .await;
+    command(SET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_error_response(
+                "2050900",
+                "Invalid Address for &apos;CNAME&apos; record &amp; host &quot;www&quot;",
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let result = zone
+        .create_record("www", &RecordData::A("192.168.1.2".parse().unwrap()), 1800)
+        .await;
+
+    // Messages are unescaped, including entities in between text
+    assert!(matches!(
+        result,
+        Err(CreateRecordError::Custom(NamecheapError::Api(api)))
+            if api.code == "2050900"
+                && api.message == "Invalid Address for 'CNAME' record & host \"www\""
+    ));
+}
+
+#[tokio::test]
+async fn test_update_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    mount_hosts_before_change(&server, initial_hosts(), 1).await;
+    // The record is replaced in place
+    command(SET_HOSTS)
+        .and(query_param("HostName1", "@"))
+        .and(query_param("HostName2", "www"))
+        .and(query_param("RecordType2", "A"))
+        .and(query_param("Address2", "192.168.1.2"))
+        .and(query_param("TTL2", "300"))
+        .and(query_param_is_missing("HostName3"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_set_hosts_response("example.com")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    command(GET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_get_hosts_response(
+                "example.com",
+                vec![
+                    ("201", "@", "A", "192.168.1.1", 1800),
+                    ("202", "www", "A", "192.168.1.2", 300),
+                ],
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let record = zone
+        .update_record(
+            "102",
+            "www",
+            &RecordData::A("192.168.1.2".parse().unwrap()),
+            300,
+        )
+        .await
+        .expect("Failed to update record");
+
+    // The ID changed with the save
+    assert_eq!(record.id, "202");
+    assert_eq!(record.data, RecordData::A("192.168.1.2".parse().unwrap()));
+    assert_eq!(record.ttl, 300);
+}
+
+#[tokio::test]
+async fn test_update_record_not_found() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(initial_hosts()))
+        .mount(&server)
+        .await;
+    command(SET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_set_hosts_response("example.com")),
+        )
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let result = zone
+        .update_record(
+            "999",
+            "www",
+            &RecordData::A("192.168.1.2".parse().unwrap()),
+            300,
+        )
+        .await;
+    assert!(matches!(result, Err(UpdateRecordError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_delete_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(initial_hosts()))
+        .mount(&server)
+        .await;
+    // Only the remaining hosts are saved
+    command(SET_HOSTS)
+        .and(query_param("HostName1", "@"))
+        .and(query_param("RecordType1", "A"))
+        .and(query_param_is_missing("HostName2"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_set_hosts_response("example.com")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    zone.delete_record("102")
+        .await
+        .expect("Failed to delete record");
+}
+
+#[tokio::test]
+async fn test_delete_record_not_found() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(initial_hosts()))
+        .mount(&server)
+        .await;
+    command(SET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_set_hosts_response("example.com")),
+        )
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let result = zone.delete_record("999").await;
+    assert!(matches!(result, Err(DeleteRecordError::NotFound)));
+}
+
+#[cfg(feature = "acme")]
+#[tokio::test]
+async fn test_acme_cleanup_with_reassigned_host_ids() {
+    use libdns::acme::{Dns01Challenge, Dns01Solver};
+
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    // Apex and wildcard challenges share the same name; the cleanup listing and the two lookups of the first deletion see these IDs
+    mount_hosts_before_change(
+        &server,
+        mock_get_hosts_response(
+            "example.com",
+            vec![
+                ("101", "@", "A", "192.168.1.1", 1800),
+                ("102", "_acme-challenge", "TXT", "apex-digest", 60),
+                ("103", "_acme-challenge", "TXT", "wildcard-digest", 60),
+            ],
+        ),
+        3,
+    )
+    .await;
+    command(SET_HOSTS)
+        .and(query_param("Address2", "wildcard-digest"))
+        .and(query_param_is_missing("HostName3"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_set_hosts_response("example.com")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // The save reassigned all host IDs
+    command(GET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_get_hosts_response(
+                "example.com",
+                vec![
+                    ("201", "@", "A", "192.168.1.1", 1800),
+                    ("202", "_acme-challenge", "TXT", "wildcard-digest", 60),
+                ],
+            )),
+        )
+        .mount(&server)
+        .await;
+    command(SET_HOSTS)
+        .and(query_param("HostName1", "@"))
+        .and(query_param_is_missing("HostName2"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_set_hosts_response("example.com")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let challenges = [
+        Dns01Challenge::from_digest("example.com", "apex-digest"),
+        Dns01Challenge::from_digest("*.example.com", "wildcard-digest"),
+    ];
+    Dns01Solver::new(&zone)
+        .cleanup(&challenges)
+        .await
+        .expect("Failed to clean up challenges");
+}
+
+#[cfg(feature = "ddns")]
+#[tokio::test]
+async fn test_ddns_sync_with_reassigned_host_ids() {
+    use libdns::ddns::{AddressChange, Addresses, DdnsUpdater};
+    use std::net::Ipv4Addr;
+
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    // The listing and the lookup before the update see these IDs
+    mount_hosts_before_change(
+        &server,
+        mock_get_hosts_response(
+            "example.com",
+            vec![
+                ("101", "@", "A", "192.168.1.1", 1800),
+                ("102", "www", "A", "10.0.0.1", 300),
+                ("103", "www", "A", "10.0.0.2", 300),
+            ],
+        ),
+        2,
+    )
+    .await;
+    command(SET_HOSTS)
+        .and(query_param("Address2", "10.0.0.9"))
+        .and(query_param("Address3", "10.0.0.2"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_set_hosts_response("example.com")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // The update reassigned all host IDs
+    command(GET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_get_hosts_response(
+                "example.com",
+                vec![
+                    ("201", "@", "A", "192.168.1.1", 1800),
+                    ("202", "www", "A", "10.0.0.9", 300),
+                    ("203", "www", "A", "10.0.0.2", 300),
+                ],
+            )),
+        )
+        .mount(&server)
+        .await;
+    command(SET_HOSTS)
+        .and(query_param("Address2", "10.0.0.9"))
+        .and(query_param_is_missing("HostName3"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(mock_set_hosts_response("example.com")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let addresses = Addresses {
+        ipv4: Some(Ipv4Addr::new(10, 0, 0, 9)),
+        ipv6: None,
+    };
+    let changes = DdnsUpdater::new(&zone, addresses, ["www"])
+        .with_ttl(300)
+        .update()
+        .await
+        .expect("Failed to update");
+
+    assert_eq!(changes.len(), 2);
+    assert!(
+        matches!(&changes[0], AddressChange::Updated { from, to } if from.id == "102" && to.id == "202")
+    );
+    assert!(matches!(&changes[1], AddressChange::Deleted(record) if record.id == "203"));
+}
+
+// =============================================================================
+// Error Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_error_without_details_is_parse_error() {
+    let server = setup_mock_server().await;
+
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<ApiResponse Status="ERROR" xmlns="http://api.namecheap.com/xml.response">
+  <Errors />
+</ApiResponse>"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).get_zone("example.com").await;
+    assert!(matches!(
+        result,
+        Err(RetrieveZoneError::Custom(NamecheapError::Parse(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_malformed_xml_is_parse_error() {
+    let server = setup_mock_server().await;
+
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<ApiResponse Status="OK"><CommandResponse><host HostId="1"></ApiResponse>"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).get_zone("example.com").await;
+    assert!(matches!(
+        result,
+        Err(RetrieveZoneError::Custom(NamecheapError::Parse(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_rate_limit_error_is_classified() {
+    let server = setup_mock_server().await;
+
+    command(GET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(mock_error_response("500000", "Too many requests")),
+        )
+        .mount(&server)
+        .await;
+
+    let err = provider(&server)
+        .get_zone("example.com")
+        .await
+        .err()
+        .expect("Retrieval should fail");
+    assert_eq!(err.kind(), ErrorKind::RateLimited);
+}
+
+#[tokio::test]
+async fn test_unavailable_server_is_retried() {
+    let server = setup_mock_server().await;
+
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(initial_hosts()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+}
+
+#[tokio::test]
+async fn test_apply_changes_rollback_state() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    command(GET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string(initial_hosts()))
+        .mount(&server)
+        .await;
+    // The first save is rejected, the response of the second one is unreadable
+    command(SET_HOSTS)
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(mock_error_response("2050900", "Invalid Address")),
+        )
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    command(SET_HOSTS)
+        .respond_with(ResponseTemplate::new(200).set_body_string("<ApiResponse"))
+        .mount(&server)
+        .await;
+
+    let changes = [RecordChange::Delete {
+        record_id: "102".to_string(),
+    }];
+
+    // A rejected save leaves the hosts untouched
+    let err = zone.apply_changes(&changes).await.unwrap_err();
+    assert!(err.rolled_back);
+    assert!(matches!(
+        err.cause,
+        ChangeError::Custom(NamecheapError::Api(_))
+    ));
+
+    // Without a readable response, the hosts may have been saved
+    let err = zone.apply_changes(&changes).await.unwrap_err();
+    assert!(!err.rolled_back);
+    assert!(matches!(
+        err.cause,
+        ChangeError::Custom(NamecheapError::Parse(_))
+    ));
+}
@@ -0,0 +1,253 @@
+//! Tests for the propagation checker against local UDP stub nameservers.
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use libdns::propagation::{PropagationChecker, PropagationError};
+use libdns::types::RecordType;
+use libdns::RecordData;
+use tokio::net::UdpSocket;
+
+/// The records served by a stub nameserver as owner, type and RDATA.
+type StubRecords = Arc<Mutex<Vec<(String, RecordType, Vec<u8>)>>>;
+
+/// Starts a nameserver answering queries from the given records, with NXDOMAIN for unknown names.
+async fn start_stub_server(records: StubRecords) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind stub server");
+    let address = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buf = [0u8; 512];
+        loop {
+            let Ok((len, peer)) = socket.recv_from(&mut buf).await else {
+                return;
+            };
+            let response = answer(&buf[..len], &records.lock().unwrap());
+            let _ = socket.send_to(&response, peer).await;
+        }
+    });
+
+    address
+}
+
+/// Builds the response to a query, pointing the owner of every answer at the question name.
+fn answer(query: &[u8], records: &[(String, RecordType, Vec<u8>)]) -> Vec<u8> {
+    let mut pos = 12;
+    let mut labels = Vec::new();
+    while query[pos] != 0 {
+        let len = query[pos] as usize;
+        labels.push(String::from_utf8_lossy(&query[pos + 1..pos + 1 + len]).to_lowercase());
+        pos += 1 + len;
+    }
+    let name = labels.join(".");
+    let typ = u16::from_be_bytes([query[pos + 1], query[pos + 2]]);
+    let question = &query[12..pos + 5];
+
+    let known = records.iter().any(|(owner, _, _)| *owner == name);
+    let matching: Vec<&Vec<u8>> = records
+        .iter()
+        .filter(|(owner, t, _)| *owner == name && t.as_u16() == typ)
+        .map(|(_, _, rdata)| rdata)
+        .collect();
+
+    let flags: u16 = if known { 0x8400 } else { 0x8403 };
+    let mut response = query[..2].to_vec();
+    response.extend_from_slice(&flags.to_be_bytes());
+    for count in [1u16, matching.len() as u16, 0, 0] {
+        response.extend_from_slice(&count.to_be_bytes());
+    }
+    response.extend_from_slice(question);
+    for rdata in matching {
+        response.extend_from_slice(&[0xc0, 0x0c]);
+        response.extend_from_slice(&typ.to_be_bytes());
+        response.extend_from_slice(&[0, 1, 0, 0, 0, 60]);
+        response.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        response.extend_from_slice(rdata);
+    }
+    response
+}
+
+/// Encodes a TXT RDATA of a single character string.
+fn txt_rdata(text: &str) -> Vec<u8> {
+    let mut rdata = vec![text.len() as u8];
+    rdata.extend_from_slice(text.as_bytes());
+    rdata
+}
+
+/// Encodes a domain name without compression.
+fn name_rdata(name: &str) -> Vec<u8> {
+    let mut rdata = Vec::new();
+    for label in name.split('.') {
+        rdata.push(label.len() as u8);
+        rdata.extend_from_slice(label.as_bytes());
+    }
+    rdata.push(0);
+    rdata
+}
+
+fn checker(nameservers: Vec<SocketAddr>) -> PropagationChecker {
+    PropagationChecker::new(nameservers)
+        .with_query_timeout(Duration::from_millis(500))
+        .with_interval(Duration::from_millis(50))
+}
+
+#[tokio::test]
+async fn test_query_decodes_records() {
+    let records = StubRecords::default();
+    records.lock().unwrap().extend([
+        (
+            "www.example.com".to_string(),
+            RecordType::A,
+            vec![10, 0, 0, 1],
+        ),
+        (
+            "www.example.com".to_string(),
+            RecordType::TXT,
+            [txt_rdata("hello "), txt_rdata("world")].concat(),
+        ),
+        (
+            "example.com".to_string(),
+            RecordType::MX,
+            [vec![0, 10], name_rdata("mail.example.com")].concat(),
+        ),
+    ]);
+    let server = start_stub_server(records).await;
+    let checker = checker(vec![server]);
+
+    let a = checker
+        .query(server, "WWW.example.com.", RecordType::A)
+        .await
+        .expect("Failed to query A");
+    assert_eq!(a, vec![RecordData::A(Ipv4Addr::new(10, 0, 0, 1))]);
+
+    let txt = checker
+        .query(server, "www.example.com", RecordType::TXT)
+        .await
+        .expect("Failed to query TXT");
+    assert_eq!(txt, vec![RecordData::TXT("hello world".to_string())]);
+
+    let mx = checker
+        .query(server, "example.com", RecordType::MX)
+        .await
+        .expect("Failed to query MX");
+    assert_eq!(
+        mx,
+        vec![RecordData::MX {
+            priority: 10,
+            mail_server: "mail.example.com".to_string(),
+        }]
+    );
+
+    // NXDOMAIN is an empty answer
+    let missing = checker
+        .query(server, "missing.example.com", RecordType::A)
+        .await
+        .expect("Failed to query missing name");
+    assert!(missing.is_empty());
+}
+
+#[tokio::test]
+async fn test_wait_for_lagging_nameserver() {
+    let name = "_acme-challenge.example.com";
+    let value = "challenge-value";
+
+    let fast = StubRecords::default();
+    fast.lock()
+        .unwrap()
+        .push((name.to_string(), RecordType::TXT, txt_rdata(value)));
+    let slow = StubRecords::default();
+
+    let servers = vec![
+        start_stub_server(fast).await,
+        start_stub_server(slow.clone()).await,
+    ];
+    let checker = checker(servers.clone());
+    let data = RecordData::TXT(value.to_string());
+
+    assert_eq!(
+        checker.pending(name, &data).await.unwrap(),
+        vec![servers[1]]
+    );
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        slow.lock()
+            .unwrap()
+            .push((name.to_string(), RecordType::TXT, txt_rdata(value)));
+    });
+    checker
+        .wait(name, &data, Duration::from_secs(5))
+        .await
+        .expect("Record did not propagate");
+}
+
+#[tokio::test]
+async fn test_wait_timeout_reports_pending() {
+    let records = StubRecords::default();
+    records.lock().unwrap().push((
+        "www.example.com".to_string(),
+        RecordType::A,
+        vec![10, 0, 0, 1],
+    ));
+    let server = start_stub_server(records).await;
+    // Nothing listens on this port, so the query times out
+    let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let unreachable = silent.local_addr().unwrap();
+
+    let checker = checker(vec![server, unreachable]);
+    let data = RecordData::A(Ipv4Addr::new(10, 0, 0, 2));
+    let err = checker
+        .wait("www.example.com", &data, Duration::from_millis(200))
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        PropagationError::Timeout {
+            pending: vec![server, unreachable]
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_discover_nameservers() {
+    let records = StubRecords::default();
+    records.lock().unwrap().extend([
+        (
+            "example.com".to_string(),
+            RecordType::NS,
+            name_rdata("ns1.example.net"),
+        ),
+        (
+            "example.com".to_string(),
+            RecordType::NS,
+            name_rdata("ns2.example.net"),
+        ),
+        (
+            "ns1.example.net".to_string(),
+            RecordType::A,
+            vec![192, 0, 2, 1],
+        ),
+        (
+            "ns2.example.net".to_string(),
+            RecordType::A,
+            vec![192, 0, 2, 2],
+        ),
+    ]);
+    let resolver = start_stub_server(records).await;
+
+    let checker = PropagationChecker::discover("example.com", resolver)
+        .await
+        .expect("Failed to discover nameservers");
+
+    assert_eq!(
+        checker.nameservers(),
+        [
+            "192.0.2.1:53".parse().unwrap(),
+            "192.0.2.2:53".parse().unwrap()
+        ]
+    );
+}
@@ -0,0 +1,555 @@
+//! Tests for the RFC 2136 provider against a local TCP stub nameserver verifying and signing TSIG.
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use libdns::rfc2136::api::TsigKey;
+use libdns::rfc2136::{Rfc2136Provider, Rfc2136Zone};
+use libdns::{
+    ApplyChanges, CreateRecord, DeleteRecord, DeleteRecordError, Provider, RecordChange,
+    RecordData, RetrieveRecordError, RetrieveZoneError, UpdateRecord, Zone,
+};
+use sha2::Sha256;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const ZONE: &str = "example.com";
+const KEY_NAME: &str = "update-key";
+const SECRET: &[u8] = b"a secret shared with the server!";
+
+const SOA: u16 = 6;
+const TSIG: u16 = 250;
+const AXFR: u16 = 252;
+
+/// A record served by the stub nameserver.
+#[derive(Debug, Clone, PartialEq)]
+struct StubRecord {
+    name: String,
+    typ: u16,
+    ttl: u32,
+    rdata: Vec<u8>,
+}
+
+/// The zone and the number of UPDATE messages received by a stub nameserver.
+#[derive(Clone, Default)]
+struct Stub {
+    records: Arc<Mutex<Vec<StubRecord>>>,
+    updates: Arc<AtomicUsize>,
+}
+
+impl Stub {
+    fn with_records(records: Vec<StubRecord>) -> Self {
+        let stub = Self::default();
+        *stub.records.lock().unwrap() = records;
+        stub
+    }
+
+    fn contains(&self, name: &str, typ: u16, rdata: &[u8]) -> bool {
+        self.records
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|r| r.name == name && r.typ == typ && r.rdata == rdata)
+    }
+}
+
+/// Starts a nameserver for `example.com` accepting messages signed with the test key.
+async fn start_stub_server(stub: Stub) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind stub server");
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                return;
+            };
+            let stub = stub.clone();
+            tokio::spawn(serve(stream, stub));
+        }
+    });
+
+    address
+}
+
+async fn serve(mut stream: TcpStream, stub: Stub) {
+    loop {
+        let Ok(len) = stream.read_u16().await else {
+            return;
+        };
+        let mut request = vec![0u8; len as usize];
+        if stream.read_exact(&mut request).await.is_err() {
+            return;
+        }
+        for response in handle(&request, &stub) {
+            let mut framed = (response.len() as u16).to_be_bytes().to_vec();
+            framed.extend_from_slice(&response);
+            if stream.write_all(&framed).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Returns the responses to a request, the zone transfer split over three messages of which the second is unsigned.
+fn handle(request: &[u8], stub: &Stub) -> Vec<Vec<u8>> {
+    let id = u16::from_be_bytes([request[0], request[1]]);
+    let opcode = (request[2] >> 3) & 0x0f;
+    let (qname, mut pos) = read_name(request, 12);
+    let qtype = u16::from_be_bytes([request[pos], request[pos + 1]]);
+    pos += 4;
+    let question = request[12..pos].to_vec();
+
+    // The TSIG record is the only additional record
+    let update_count = u16::from_be_bytes([request[8], request[9]]);
+    let mut updates = Vec::new();
+    for _ in 0..update_count {
+        let (record, class, next) = read_record(request, pos);
+        updates.push((record, class));
+        pos = next;
+    }
+    let Some(request_mac) = verify_request(request, pos) else {
+        // BADSIG, unsigned
+        let mut response = header(id, 0x8000 | (opcode as u16) << 11 | 9, [1, 0, 0, 1]);
+        response.extend_from_slice(&question);
+        response.extend_from_slice(&tsig_record(id, now(), &[], 16));
+        return vec![response];
+    };
+
+    let mut signer = ResponseSigner::new(request_mac);
+    match opcode {
+        5 => {
+            stub.updates.fetch_add(1, Ordering::SeqCst);
+            let mut records = stub.records.lock().unwrap();
+            for (record, class) in updates {
+                let exists = records.iter().position(|r| {
+                    r.name == record.name && r.typ == record.typ && r.rdata == record.rdata
+                });
+                match (class, exists) {
+                    (1, None) => records.push(record),
+                    (1, Some(position)) => records[position].ttl = record.ttl,
+                    (254, Some(position)) => {
+                        records.remove(position);
+                    }
+                    _ => {}
+                }
+            }
+            let mut response = header(id, 0xa800, [1, 0, 0, 0]);
+            response.extend_from_slice(&question);
+            vec![signer.sign(response)]
+        }
+        _ if qname != ZONE => {
+            let mut response = header(id, 0x8005, [1, 0, 0, 0]);
+            response.extend_from_slice(&question);
+            vec![signer.sign(response)]
+        }
+        _ if qtype == SOA => {
+            let records = stub.records.lock().unwrap();
+            let mut response = header(id, 0x8400, [1, 1, 0, 0]);
+            response.extend_from_slice(&question);
+            response.extend_from_slice(&encode_record(&records[0]));
+            vec![signer.sign(response)]
+        }
+        _ if qtype == AXFR => {
+            let records = stub.records.lock().unwrap();
+            let mut all: Vec<&StubRecord> = records.iter().collect();
+            all.push(&records[0]);
+            let third = all.len().div_ceil(3);
+
+            all.chunks(third)
+                .enumerate()
+                .map(|(index, chunk)| {
+                    let mut response = header(id, 0x8400, [1, chunk.len() as u16, 0, 0]);
+                    response.extend_from_slice(&question);
+                    for record in chunk {
+                        response.extend_from_slice(&encode_record(record));
+                    }
+                    if index == 1 {
+                        signer.skip(response)
+                    } else {
+                        signer.sign(response)
+                    }
+                })
+                .collect()
+        }
+        _ => panic!("Unexpected request type {}", qtype),
+    }
+}
+
+/// Verifies the TSIG record at `pos` and returns the request MAC.
+fn verify_request(request: &[u8], pos: usize) -> Option<Vec<u8>> {
+    let (key, pos) = read_name(request, pos);
+    let typ = u16::from_be_bytes([request[pos], request[pos + 1]]);
+    if key != KEY_NAME || typ != TSIG {
+        return None;
+    }
+    let (_, rdata_start) = read_name(request, pos + 10);
+    let time = &request[rdata_start..rdata_start + 6];
+    let mac_len = u16::from_be_bytes([request[rdata_start + 8], request[rdata_start + 9]]) as usize;
+    let mac = &request[rdata_start + 10..rdata_start + 10 + mac_len];
+
+    let tsig_start = pos - wire_name(KEY_NAME).len();
+    let mut unsigned = request[..tsig_start].to_vec();
+    unsigned[11] -= 1;
+
+    let mut hmac = Hmac::<Sha256>::new_from_slice(SECRET).unwrap();
+    hmac.update(&unsigned);
+    hmac.update(&variables(time));
+    hmac.verify_slice(mac).ok()?;
+    Some(mac.to_vec())
+}
+
+/// Signs the responses to a request as described in RFC 8945 §5.3.
+struct ResponseSigner {
+    prior_mac: Vec<u8>,
+    unsigned: Vec<u8>,
+    first: bool,
+}
+
+impl ResponseSigner {
+    fn new(request_mac: Vec<u8>) -> Self {
+        Self {
+            prior_mac: request_mac,
+            unsigned: Vec::new(),
+            first: true,
+        }
+    }
+
+    fn sign(&mut self, mut response: Vec<u8>) -> Vec<u8> {
+        let id = u16::from_be_bytes([response[0], response[1]]);
+        let time = now();
+
+        let mut hmac = Hmac::<Sha256>::new_from_slice(SECRET).unwrap();
+        hmac.update(&(self.prior_mac.len() as u16).to_be_bytes());
+        hmac.update(&self.prior_mac);
+        hmac.update(&self.unsigned);
+        hmac.update(&response);
+        if self.first {
+            hmac.update(&variables(&time));
+        } else {
+            hmac.update(&time);
+            hmac.update(&300u16.to_be_bytes());
+        }
+        let mac = hmac.finalize().into_bytes().to_vec();
+
+        response.extend_from_slice(&tsig_record(id, time, &mac, 0));
+        response[11] += 1;
+        self.prior_mac = mac;
+        self.unsigned.clear();
+        self.first = false;
+        response
+    }
+
+    fn skip(&mut self, response: Vec<u8>) -> Vec<u8> {
+        self.unsigned.extend_from_slice(&response);
+        response
+    }
+}
+
+fn now() -> [u8; 6] {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    secs.to_be_bytes()[2..].try_into().unwrap()
+}
+
+/// The TSIG variables of a request or first response without error or other data.
+fn variables(time: &[u8]) -> Vec<u8> {
+    let mut variables = wire_name(KEY_NAME);
+    variables.extend_from_slice(&[0, 255, 0, 0, 0, 0]);
+    variables.extend_from_slice(&wire_name("hmac-sha256"));
+    variables.extend_from_slice(time);
+    variables.extend_from_slice(&[1, 44, 0, 0, 0, 0]);
+    variables
+}
+
+fn tsig_record(id: u16, time: [u8; 6], mac: &[u8], error: u16) -> Vec<u8> {
+    let mut rdata = wire_name("hmac-sha256");
+    rdata.extend_from_slice(&time);
+    rdata.extend_from_slice(&300u16.to_be_bytes());
+    rdata.extend_from_slice(&(mac.len() as u16).to_be_bytes());
+    rdata.extend_from_slice(mac);
+    rdata.extend_from_slice(&id.to_be_bytes());
+    rdata.extend_from_slice(&error.to_be_bytes());
+    rdata.extend_from_slice(&[0, 0]);
+
+    let mut record = wire_name(KEY_NAME);
+    record.extend_from_slice(&TSIG.to_be_bytes());
+    record.extend_from_slice(&[0, 255, 0, 0, 0, 0]);
+    record.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+    record.extend_from_slice(&rdata);
+    record
+}
+
+fn header(id: u16, flags: u16, counts: [u16; 4]) -> Vec<u8> {
+    let mut header = id.to_be_bytes().to_vec();
+    header.extend_from_slice(&flags.to_be_bytes());
+    for count in counts {
+        header.extend_from_slice(&count.to_be_bytes());
+    }
+    header
+}
+
+fn encode_record(record: &StubRecord) -> Vec<u8> {
+    let mut encoded = wire_name(&record.name);
+    encoded.extend_from_slice(&record.typ.to_be_bytes());
+    encoded.extend_from_slice(&1u16.to_be_bytes());
+    encoded.extend_from_slice(&record.ttl.to_be_bytes());
+    encoded.extend_from_slice(&(record.rdata.len() as u16).to_be_bytes());
+    encoded.extend_from_slice(&record.rdata);
+    encoded
+}
+
+/// Reads a resource record with an uncompressed owner, returning it with its class and the position after it.
+fn read_record(msg: &[u8], pos: usize) -> (StubRecord, u16, usize) {
+    let (name, pos) = read_name(msg, pos);
+    let field = |offset: usize| u16::from_be_bytes([msg[pos + offset], msg[pos + offset + 1]]);
+    let ttl = u32::from_be_bytes(msg[pos + 4..pos + 8].try_into().unwrap());
+    let len = field(8) as usize;
+    let record = StubRecord {
+        name,
+        typ: field(0),
+        ttl,
+        rdata: msg[pos + 10..pos + 10 + len].to_vec(),
+    };
+    (record, field(2), pos + 10 + len)
+}
+
+/// Reads an uncompressed name, returning it and the position after it.
+fn read_name(msg: &[u8], mut pos: usize) -> (String, usize) {
+    let mut labels = Vec::new();
+    while msg[pos] != 0 {
+        let len = msg[pos] as usize;
+        labels.push(String::from_utf8_lossy(&msg[pos + 1..pos + 1 + len]).to_lowercase());
+        pos += 1 + len;
+    }
+    (labels.join("."), pos + 1)
+}
+
+fn wire_name(name: &str) -> Vec<u8> {
+    let mut wire = Vec::new();
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        wire.push(label.len() as u8);
+        wire.extend_from_slice(label.as_bytes());
+    }
+    wire.push(0);
+    wire
+}
+
+fn record(name: &str, typ: u16, rdata: Vec<u8>) -> StubRecord {
+    StubRecord {
+        name: name.to_string(),
+        typ,
+        ttl: 3600,
+        rdata,
+    }
+}
+
+fn example_zone() -> Vec<StubRecord> {
+    let soa = [
+        wire_name("ns1.example.com"),
+        wire_name("hostmaster.example.com"),
+        [1u32, 7200, 900, 1209600, 300]
+            .iter()
+            .flat_map(|timer| timer.to_be_bytes())
+            .collect(),
+    ]
+    .concat();
+
+    vec![
+        record(ZONE, SOA, soa),
+        record(ZONE, 2, wire_name("ns1.example.com")),
+        record("www.example.com", 1, vec![10, 0, 0, 1]),
+        record(
+            "example.com",
+            15,
+            [vec![0, 10], wire_name("mail.example.com")].concat(),
+        ),
+        record("www.example.com", 16, b"\x0bhello world".to_vec()),
+    ]
+}
+
+fn provider(server: SocketAddr, secret: &[u8]) -> Rfc2136Provider {
+    let key = TsigKey::new(KEY_NAME, secret).unwrap();
+    Rfc2136Provider::new(server, Some(key)).with_timeout(Duration::from_secs(2))
+}
+
+async fn zone(stub: &Stub) -> Rfc2136Zone {
+    let server = start_stub_server(stub.clone()).await;
+    provider(server, SECRET)
+        .get_zone(ZONE)
+        .await
+        .expect("Failed to get zone")
+}
+
+#[tokio::test]
+async fn test_list_records_via_zone_transfer() {
+    let stub = Stub::with_records(example_zone());
+    let zone = zone(&stub).await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+
+    assert_eq!(records.len(), 5);
+    assert_eq!(records[0].host, "@");
+    assert_eq!(records[0].data.get_type(), "SOA");
+    let www = records
+        .iter()
+        .find(|record| record.id == "www/A/10.0.0.1")
+        .expect("A record not listed");
+    assert_eq!(www.host, "www");
+    assert_eq!(www.data, RecordData::A(Ipv4Addr::new(10, 0, 0, 1)));
+    assert_eq!(www.ttl, 3600);
+    assert!(records.iter().any(|record| record.data
+        == RecordData::MX {
+            priority: 10,
+            mail_server: "mail.example.com".to_string(),
+        }));
+    assert!(records
+        .iter()
+        .any(|record| record.data == RecordData::TXT("hello world".to_string())));
+
+    let record = zone
+        .get_record("www/TXT/hello world")
+        .await
+        .expect("Failed to get record");
+    assert_eq!(record.host, "www");
+    assert_eq!(
+        zone.get_record("www/A/10.0.0.9").await.unwrap_err(),
+        RetrieveRecordError::NotFound
+    );
+}
+
+#[tokio::test]
+async fn test_create_update_delete_record() {
+    let stub = Stub::with_records(example_zone());
+    let zone = zone(&stub).await;
+
+    let created = zone
+        .create_record("API", &RecordData::A(Ipv4Addr::new(10, 0, 0, 2)), 300)
+        .await
+        .expect("Failed to create record");
+    assert_eq!(created.id, "api/A/10.0.0.2");
+    assert_eq!(created.host, "api");
+    assert!(stub.contains("api.example.com", 1, &[10, 0, 0, 2]));
+
+    let updated = zone
+        .update_record(
+            &created.id,
+            "api",
+            &RecordData::A(Ipv4Addr::new(10, 0, 0, 3)),
+            600,
+        )
+        .await
+        .expect("Failed to update record");
+    assert_eq!(updated.id, "api/A/10.0.0.3");
+    assert_eq!(updated.ttl, 600);
+    assert!(!stub.contains("api.example.com", 1, &[10, 0, 0, 2]));
+    assert!(stub.contains("api.example.com", 1, &[10, 0, 0, 3]));
+
+    zone.delete_record(&updated.id)
+        .await
+        .expect("Failed to delete record");
+    assert!(!stub.contains("api.example.com", 1, &[10, 0, 0, 3]));
+    assert_eq!(
+        zone.delete_record(&updated.id).await.unwrap_err(),
+        DeleteRecordError::NotFound
+    );
+    // Update and delete are one message each
+    assert_eq!(stub.updates.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_apply_changes_in_single_update() {
+    let stub = Stub::with_records(example_zone());
+    let zone = zone(&stub).await;
+
+    let records = zone
+        .apply_changes(&[
+            RecordChange::Create {
+                host: "_acme-challenge".to_string(),
+                data: RecordData::TXT("\"token\"".to_string()),
+                ttl: 60,
+            },
+            RecordChange::Update {
+                record_id: "www/A/10.0.0.1".to_string(),
+                host: "www".to_string(),
+                data: RecordData::A(Ipv4Addr::new(10, 0, 0, 5)),
+                ttl: 3600,
+            },
+            RecordChange::Delete {
+                record_id: "@/MX/10 mail.example.com".to_string(),
+            },
+        ])
+        .await
+        .expect("Failed to apply changes");
+
+    assert_eq!(records.len(), 2);
+    // Quotes are not part of the value
+    assert_eq!(records[0].id, "_acme-challenge/TXT/token");
+    assert_eq!(records[1].id, "www/A/10.0.0.5");
+    assert_eq!(stub.updates.load(Ordering::SeqCst), 1);
+    assert!(stub.contains("_acme-challenge.example.com", 16, b"\x05token"));
+    assert!(stub.contains("www.example.com", 1, &[10, 0, 0, 5]));
+    assert!(!stub.contains("www.example.com", 1, &[10, 0, 0, 1]));
+    assert!(!stub.records.lock().unwrap().iter().any(|r| r.typ == 15));
+
+    // Unknown records fail the changeset before anything is sent
+    let err = zone
+        .apply_changes(&[
+            RecordChange::Create {
+                host: "new".to_string(),
+                data: RecordData::A(Ipv4Addr::new(10, 0, 0, 6)),
+                ttl: 60,
+            },
+            RecordChange::Delete {
+                record_id: "missing/A/10.0.0.7".to_string(),
+            },
+        ])
+        .await
+        .unwrap_err();
+    assert_eq!(err.index, Some(1));
+    assert!(err.rolled_back);
+    assert_eq!(stub.updates.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_wrong_key_is_unauthorized() {
+    let stub = Stub::with_records(example_zone());
+    let server = start_stub_server(stub.clone()).await;
+    let provider = provider(server, b"not the shared secret");
+
+    assert_eq!(
+        provider.get_zone(ZONE).await.unwrap_err(),
+        RetrieveZoneError::Unauthorized
+    );
+
+    let zone = provider
+        .with_zones([ZONE])
+        .list_zones()
+        .await
+        .unwrap()
+        .remove(0);
+    assert_eq!(
+        zone.list_records().await.unwrap_err(),
+        RetrieveRecordError::Unauthorized
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_zone_not_found() {
+    let stub = Stub::with_records(example_zone());
+    let server = start_stub_server(stub).await;
+
+    assert_eq!(
+        provider(server, SECRET)
+            .get_zone("example.org")
+            .await
+            .unwrap_err(),
+        RetrieveZoneError::NotFound
+    );
+}
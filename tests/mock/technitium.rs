@@ -0,0 +1,1086 @@
+//! Mock-based tests for Technitium DNS Server provider.
+//!
+//! These tests use `wiremock` to simulate the HTTP API of a Technitium DNS Server
+//! without requiring a running server.
+//!
+//! # Coverage
+//!
+//! This module provides comprehensive testing for:
+//! - Authentication through the `token` query parameter
+//! - Zone operations (list, get, create, delete)
+//! - Record operations (list, get, create, update, delete)
+//! - Composite record IDs, which Technitium does not provide itself
+//! - Mapping of `invalid-token`, `2fa-required` and error message statuses
+//! - JSON edge cases (untagged record data, unknown record types, non-JSON bodies)
+//!
+//! # API Structure
+//!
+//! Every call is a `GET /api/{section}/{action}` with query parameters:
+//! - Auth: `token={token}`
+//! - Result: `{"status": "ok", "response": {...}}` or `{"status": "error", "errorMessage": "..."}`
+
+use crate::common::setup_mock_server;
+use crate::common::technitium::*;
+
+use libdns::retry::RetryPolicy;
+use libdns::technitium::{api::ApiError, TechnitiumProvider, TechnitiumZone};
+use libdns::{
+    ClassifyError, CreateRecord, CreateRecordError, CreateZone, CreateZoneError, DeleteRecord,
+    DeleteRecordError, DeleteZone, DeleteZoneError, ErrorKind, Fingerprint, Provider, Record,
+    RecordData, RetrieveRecordError, RetrieveZoneError, UpdateRecord, UpdateRecordError, Zone,
+};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Creates a provider talking to the mock server without retry delays.
+fn provider(server: &MockServer) -> TechnitiumProvider {
+    TechnitiumProvider::builder(&server.uri(), TOKEN)
+        .with_retry_policy(
+            RetryPolicy::new().with_backoff(Duration::from_millis(1), Duration::from_millis(10)),
+        )
+        .build()
+        .expect("Failed to create provider")
+}
+
+/// Mounts a zone options response for `example.com` and returns its zone.
+async fn zone(server: &MockServer) -> TechnitiumZone {
+    Mock::given(method("GET"))
+        .and(path("/api/zones/options/get"))
+        .and(query_param("zone", "example.com"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zone_response("example.com")))
+        .mount(server)
+        .await;
+
+    provider(server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone")
+}
+
+/// Mounts the records of `www.example.com`, used to resolve record IDs.
+async fn mount_www_records(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/get"))
+        .and(query_param("domain", "www.example.com"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_records_response(
+                "example.com",
+                vec![
+                    (
+                        "www.example.com",
+                        "A",
+                        3600,
+                        json!({"ipAddress": "192.168.1.1"}),
+                    ),
+                    (
+                        "www.example.com",
+                        "A",
+                        3600,
+                        json!({"ipAddress": "192.168.1.2"}),
+                    ),
+                ],
+            )),
+        )
+        .mount(server)
+        .await;
+}
+
+/// Returns the record of `www.example.com` with the given address, as listed by the provider.
+async fn www_record(zone: &TechnitiumZone, address: &str) -> Record {
+    zone.get_record(&format!(
+        "www.example.com:A:{}",
+        fingerprint("www.example.com", address)
+    ))
+    .await
+    .expect("Failed to get record")
+}
+
+/// Computes the fingerprint used in the ID of an A record.
+fn fingerprint(name: &str, address: &str) -> Fingerprint {
+    Fingerprint::new(name, &RecordData::A(address.parse().unwrap()))
+}
+
+// =============================================================================
+// Authentication Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_requests_carry_token() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/list"))
+        .and(query_param("token", TOKEN))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_zones_response(vec![("example.com", "Primary")])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 1);
+}
+
+#[tokio::test]
+async fn test_base_url_trailing_slash() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_zones_response(vec![])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = TechnitiumProvider::builder(&format!("{}/", server.uri()), TOKEN)
+        .build()
+        .expect("Failed to create provider");
+    provider.list_zones().await.expect("Failed to list zones");
+}
+
+#[tokio::test]
+async fn test_invalid_token_is_unauthorized() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_invalid_token_response()))
+        .mount(&server)
+        .await;
+
+    let provider = provider(&server);
+
+    let result = provider.list_zones().await;
+    assert!(matches!(result, Err(RetrieveZoneError::Unauthorized)));
+
+    let result = provider.delete_zone("example.com").await;
+    assert!(matches!(result, Err(DeleteZoneError::Unauthorized)));
+}
+
+#[tokio::test]
+async fn test_two_factor_required_is_unauthorized() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "2fa-required",
+            "errorMessage": "A time-based one-time password (TOTP) is required."
+        })))
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).get_zone("example.com").await;
+    assert!(matches!(result, Err(RetrieveZoneError::Unauthorized)));
+}
+
+// =============================================================================
+// Zone Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_list_zones_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/list"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_zones_response(vec![
+                ("example.com", "Primary"),
+                ("example.org", "Secondary"),
+            ])),
+        )
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+
+    assert_eq!(zones.len(), 2);
+    // Zones are identified by their name
+    assert_eq!(zones[0].id(), "example.com");
+    assert_eq!(zones[0].domain(), "example.com");
+    assert_eq!(zones[0].zone_type(), "Primary");
+    assert_eq!(zones[1].domain(), "example.org");
+    assert_eq!(zones[1].zone_type(), "Secondary");
+}
+
+#[tokio::test]
+async fn test_get_zone_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    assert_eq!(zone.id(), "example.com");
+    assert_eq!(zone.zone_type(), "Primary");
+    assert!(!zone.is_disabled());
+}
+
+#[tokio::test]
+async fn test_get_zone_not_found() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/options/get"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_error_response("No such zone was found: missing.com")),
+        )
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).get_zone("missing.com").await;
+    assert!(matches!(result, Err(RetrieveZoneError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_create_zone_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/create"))
+        .and(query_param("zone", "newdomain.com"))
+        .and(query_param("type", "Primary"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "ok",
+            "response": {"domain": "newdomain.com"}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .create_zone("newdomain.com")
+        .await
+        .expect("Failed to create zone");
+    assert_eq!(zone.domain(), "newdomain.com");
+    assert_eq!(zone.zone_type(), "Primary");
+}
+
+#[tokio::test]
+async fn test_create_zone_errors() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/create"))
+        .and(query_param("zone", "invalid..com"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_error_response("Invalid domain name: invalid..com")),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/zones/create"))
+        .and(query_param("zone", "example.com"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_error_response("Zone already exists: example.com")),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = provider(&server);
+
+    let result = provider.create_zone("invalid..com").await;
+    assert!(matches!(result, Err(CreateZoneError::InvalidDomainName)));
+
+    let err = provider
+        .create_zone("example.com")
+        .await
+        .expect_err("Creation should fail");
+    assert!(matches!(
+        &err,
+        CreateZoneError::Custom(ApiError::ApiStatus(msg)) if msg.contains("already exists")
+    ));
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+}
+
+#[tokio::test]
+async fn test_delete_zone_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/delete"))
+        .and(query_param("zone", "example.com"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_ok_response()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    provider(&server)
+        .delete_zone("example.com")
+        .await
+        .expect("Failed to delete zone");
+}
+
+#[tokio::test]
+async fn test_delete_zone_not_found() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/delete"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_error_response("Zone does not exist: missing.com")),
+        )
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).delete_zone("missing.com").await;
+    assert!(matches!(result, Err(DeleteZoneError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_enable_and_disable_zone() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/disable"))
+        .and(query_param("zone", "example.com"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_ok_response()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/zones/enable"))
+        .and(query_param("zone", "example.com"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_ok_response()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    zone.disable().await.expect("Failed to disable zone");
+    zone.enable().await.expect("Failed to enable zone");
+}
+
+// =============================================================================
+// Record Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_list_records_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/get"))
+        .and(query_param("domain", "example.com"))
+        .and(query_param("zone", "example.com"))
+        .and(query_param("listZone", "true"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_records_response(
+                "example.com",
+                vec![
+                    (
+                        "example.com",
+                        "SOA",
+                        900,
+                        json!({
+                            "primaryNameServer": "ns1.example.com",
+                            "responsiblePerson": "hostadmin.example.com",
+                            "serial": 1,
+                            "refresh": 900,
+                            "retry": 300,
+                            "expire": 604800,
+                            "minimum": 900
+                        }),
+                    ),
+                    (
+                        "www.example.com",
+                        "A",
+                        3600,
+                        json!({"ipAddress": "192.168.1.1"}),
+                    ),
+                    (
+                        "www.example.com",
+                        "AAAA",
+                        3600,
+                        json!({"ipAddress": "2001:db8::1"}),
+                    ),
+                    (
+                        "example.com",
+                        "MX",
+                        3600,
+                        json!({"preference": 10, "exchange": "mail.example.com"}),
+                    ),
+                    (
+                        "example.com",
+                        "TXT",
+                        3600,
+                        json!({"text": "v=spf1 -all", "splitText": false}),
+                    ),
+                    (
+                        "_sip._tcp.example.com",
+                        "SRV",
+                        3600,
+                        json!({"priority": 10, "weight": 60, "port": 5060, "target": "sip.example.com"}),
+                    ),
+                ],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+
+    assert_eq!(records.len(), 6);
+    assert_eq!(
+        records[0].data,
+        RecordData::SOA {
+            mname: "ns1.example.com".to_string(),
+            rname: "hostadmin.example.com".to_string(),
+            serial: 1,
+            refresh: 900,
+            retry: 300,
+            expire: 604800,
+            minimum: 900
+        }
+    );
+    // Hosts are fully qualified
+    assert_eq!(records[1].host, "www.example.com");
+    assert_eq!(
+        records[1].data,
+        RecordData::A("192.168.1.1".parse().unwrap())
+    );
+    assert_eq!(records[1].ttl, 3600);
+    // IPv6 addresses share the `ipAddress` field of IPv4 addresses
+    assert_eq!(
+        records[2].data,
+        RecordData::AAAA("2001:db8::1".parse().unwrap())
+    );
+    assert_eq!(
+        records[3].data,
+        RecordData::MX {
+            priority: 10,
+            mail_server: "mail.example.com".to_string()
+        }
+    );
+    assert_eq!(records[4].data, RecordData::TXT("v=spf1 -all".to_string()));
+    assert_eq!(
+        records[5].data,
+        RecordData::SRV {
+            priority: 10,
+            weight: 60,
+            port: 5060,
+            target: "sip.example.com".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_list_records_ids() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/get"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_records_response(
+                "example.com",
+                vec![
+                    (
+                        "www.example.com",
+                        "A",
+                        3600,
+                        json!({"ipAddress": "192.168.1.1"}),
+                    ),
+                    (
+                        "www.example.com",
+                        "A",
+                        3600,
+                        json!({"ipAddress": "192.168.1.2"}),
+                    ),
+                    (
+                        "example.com",
+                        "CAA",
+                        3600,
+                        json!({"flags": 0, "tag": "issue", "value": "letsencrypt.org"}),
+                    ),
+                ],
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let records = zone.list_records().await.expect("Failed to list records");
+
+    // IDs are composed of the name, the type and a hash of the data
+    assert_eq!(
+        records[0].id,
+        format!(
+            "www.example.com:A:{}",
+            fingerprint("www.example.com", "192.168.1.1")
+        )
+    );
+    assert_ne!(records[0].id, records[1].id);
+    assert_eq!(
+        records[2].data,
+        RecordData::CAA {
+            flags: 0,
+            tag: "issue".to_string(),
+            value: "letsencrypt.org".to_string()
+        }
+    );
+
+    // The same data always results in the same ID
+    let again = zone.list_records().await.expect("Failed to list records");
+    assert_eq!(records[2].id, again[2].id);
+}
+
+#[tokio::test]
+async fn test_list_records_missing_response() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/get"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_ok_response()))
+        .mount(&server)
+        .await;
+
+    let result = zone.list_records().await;
+    assert!(matches!(
+        result,
+        Err(RetrieveRecordError::Custom(ApiError::ApiStatus(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_get_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+    mount_www_records(&server).await;
+
+    let record = www_record(&zone, "192.168.1.2").await;
+    assert_eq!(record.host, "www.example.com");
+    assert_eq!(record.data, RecordData::A("192.168.1.2".parse().unwrap()));
+    assert_eq!(record.ttl, 3600);
+}
+
+#[tokio::test]
+async fn test_get_record_not_found() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+    mount_www_records(&server).await;
+
+    // No record with this data
+    let result = zone
+        .get_record(&format!(
+            "www.example.com:A:{}",
+            fingerprint("www.example.com", "10.0.0.1")
+        ))
+        .await;
+    assert!(matches!(result, Err(RetrieveRecordError::NotFound)));
+
+    // No record with this type
+    let result = zone
+        .get_record(&format!(
+            "www.example.com:AAAA:{}",
+            fingerprint("www.example.com", "192.168.1.1")
+        ))
+        .await;
+    assert!(matches!(result, Err(RetrieveRecordError::NotFound)));
+
+    // Malformed IDs are rejected without a request
+    let result = zone.get_record("www.example.com").await;
+    assert!(matches!(result, Err(RetrieveRecordError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_create_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/add"))
+        .and(query_param("domain", "www.example.com"))
+        .and(query_param("zone", "example.com"))
+        .and(query_param("type", "A"))
+        .and(query_param("ttl", "3600"))
+        .and(query_param("ipAddress", "192.168.1.1"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_change_response(
+                "example.com",
+                "addedRecord",
+                "www.example.com",
+                "A",
+                3600,
+                json!({"ipAddress": "192.168.1.1"}),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let record = zone
+        .create_record("www", &RecordData::A("192.168.1.1".parse().unwrap()), 3600)
+        .await
+        .expect("Failed to create record");
+
+    assert_eq!(
+        record.id,
+        format!(
+            "www.example.com:A:{}",
+            fingerprint("www.example.com", "192.168.1.1")
+        )
+    );
+    assert_eq!(record.host, "www.example.com");
+    assert_eq!(record.ttl, 3600);
+}
+
+#[tokio::test]
+async fn test_create_record_hosts() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    // Both the apex and fully qualified hosts are passed as domain names
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/add"))
+        .and(query_param("domain", "example.com"))
+        .and(query_param("type", "TXT"))
+        .and(query_param("text", "v=spf1 include:_spf.example.net ~all"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_change_response(
+                "example.com",
+                "addedRecord",
+                "example.com",
+                "TXT",
+                300,
+                json!({"text": "v=spf1 include:_spf.example.net ~all"}),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/add"))
+        .and(query_param("domain", "mail.example.com"))
+        .and(query_param("type", "MX"))
+        .and(query_param("preference", "10"))
+        .and(query_param("exchange", "mx.example.net"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_change_response(
+                "example.com",
+                "addedRecord",
+                "mail.example.com",
+                "MX",
+                300,
+                json!({"preference": 10, "exchange": "mx.example.net"}),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let txt = RecordData::TXT("v=spf1 include:_spf.example.net ~all".to_string());
+    let record = zone
+        .create_record("@", &txt, 300)
+        .await
+        .expect("Failed to create record");
+    assert_eq!(record.data, txt);
+
+    let mx = RecordData::MX {
+        priority: 10,
+        mail_server: "mx.example.net".to_string(),
+    };
+    let record = zone
+        .create_record("mail.example.com.", &mx, 300)
+        .await
+        .expect("Failed to create record");
+    assert_eq!(record.data, mx);
+}
+
+#[tokio::test]
+async fn test_create_record_mnemonics() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    // Technitium names DS and TLSA fields by their mnemonics
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/add"))
+        .and(query_param("type", "DS"))
+        .and(query_param("keyTag", "2371"))
+        .and(query_param("algorithm", "ECDSAP256SHA256"))
+        .and(query_param("digestType", "SHA256"))
+        .and(query_param("digest", "1f987cc6583e92df0890718c42"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_change_response(
+                "example.com",
+                "addedRecord",
+                "sub.example.com",
+                "DS",
+                3600,
+                json!({
+                    "keyTag": 2371,
+                    "algorithm": "ECDSAP256SHA256",
+                    "digestType": "SHA256",
+                    "digest": "1F987CC6583E92DF0890718C42"
+                }),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/add"))
+        .and(query_param("type", "TLSA"))
+        .and(query_param("tlsaCertificateUsage", "DANE_EE"))
+        .and(query_param("tlsaSelector", "SPKI"))
+        .and(query_param("tlsaMatchingType", "SHA2_256"))
+        .and(query_param(
+            "tlsaCertificateAssociationData",
+            "0c72ac70b745ac19",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_change_response(
+                "example.com",
+                "addedRecord",
+                "_443._tcp.example.com",
+                "TLSA",
+                3600,
+                json!({
+                    "certificateUsage": "DANE-EE",
+                    "selector": "SPKI",
+                    "matchingType": "SHA2-256",
+                    "certificateAssociationData": "0c72ac70b745ac19"
+                }),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let record = zone
+        .create_record(
+            "sub",
+            &RecordData::DS {
+                key_tag: 2371,
+                algorithm: 13,
+                digest_type: 2,
+                digest: "1f987cc6583e92df0890718c42".to_string(),
+            },
+            3600,
+        )
+        .await
+        .expect("Failed to create record");
+    assert_eq!(
+        record.data,
+        RecordData::DS {
+            key_tag: 2371,
+            algorithm: 13,
+            digest_type: 2,
+            digest: "1F987CC6583E92DF0890718C42".to_string()
+        }
+    );
+
+    let tlsa = RecordData::TLSA {
+        usage: 3,
+        selector: 1,
+        matching_type: 1,
+        certificate: "0c72ac70b745ac19".to_string(),
+    };
+    let record = zone
+        .create_record("_443._tcp", &tlsa, 3600)
+        .await
+        .expect("Failed to create record");
+    assert_eq!(record.data, tlsa);
+}
+
+#[tokio::test]
+async fn test_create_record_errors() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/add"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "Cannot add record: record already exists.",
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let err = zone
+        .create_record("www", &RecordData::A("192.168.1.1".parse().unwrap()), 3600)
+        .await
+        .expect_err("Creation should fail");
+    assert!(matches!(
+        err,
+        CreateRecordError::Custom(ApiError::ApiStatus(_))
+    ));
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+
+    let data = RecordData::Other {
+        typ: "SOA".to_string(),
+        value: "ns1.example.com hostadmin.example.com 1 900 300 604800 900".to_string(),
+    };
+    let result = zone.create_record("@", &data, 3600).await;
+    assert!(matches!(result, Err(CreateRecordError::UnsupportedType)));
+}
+
+#[tokio::test]
+async fn test_update_record_in_place() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+    mount_www_records(&server).await;
+
+    // The record is identified by its old data
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/update"))
+        .and(query_param("domain", "www.example.com"))
+        .and(query_param("newDomain", "web.example.com"))
+        .and(query_param("type", "A"))
+        .and(query_param("ttl", "300"))
+        .and(query_param("ipAddress", "192.168.1.2"))
+        .and(query_param("newIpAddress", "10.0.0.2"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_change_response(
+                "example.com",
+                "updatedRecord",
+                "web.example.com",
+                "A",
+                300,
+                json!({"ipAddress": "10.0.0.2"}),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let existing = www_record(&zone, "192.168.1.2").await;
+    let record = zone
+        .update_record(
+            &existing.id,
+            "web",
+            &RecordData::A("10.0.0.2".parse().unwrap()),
+            300,
+        )
+        .await
+        .expect("Failed to update record");
+
+    assert_eq!(
+        record.id,
+        format!(
+            "web.example.com:A:{}",
+            fingerprint("web.example.com", "10.0.0.2")
+        )
+    );
+    assert_eq!(record.ttl, 300);
+}
+
+#[tokio::test]
+async fn test_update_record_type_change() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+    mount_www_records(&server).await;
+
+    // The type cannot be changed, so a new record replaces the old one
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/add"))
+        .and(query_param("domain", "www.example.com"))
+        .and(query_param("type", "CNAME"))
+        .and(query_param("cname", "example.net"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_change_response(
+                "example.com",
+                "addedRecord",
+                "www.example.com",
+                "CNAME",
+                3600,
+                json!({"cname": "example.net"}),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/delete"))
+        .and(query_param("domain", "www.example.com"))
+        .and(query_param("type", "A"))
+        .and(query_param("ipAddress", "192.168.1.1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_ok_response()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/update"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_ok_response()))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let existing = www_record(&zone, "192.168.1.1").await;
+    let record = zone
+        .update_record(
+            &existing.id,
+            "www",
+            &RecordData::CNAME("example.net".to_string()),
+            3600,
+        )
+        .await
+        .expect("Failed to update record");
+    assert_eq!(record.data, RecordData::CNAME("example.net".to_string()));
+}
+
+#[tokio::test]
+async fn test_update_record_not_found() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+    mount_www_records(&server).await;
+
+    let result = zone
+        .update_record(
+            &format!(
+                "www.example.com:A:{}",
+                fingerprint("www.example.com", "10.0.0.1")
+            ),
+            "www",
+            &RecordData::A("10.0.0.2".parse().unwrap()),
+            300,
+        )
+        .await;
+    assert!(matches!(result, Err(UpdateRecordError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_delete_record_success() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+    mount_www_records(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/delete"))
+        .and(query_param("domain", "www.example.com"))
+        .and(query_param("zone", "example.com"))
+        .and(query_param("type", "A"))
+        .and(query_param("ipAddress", "192.168.1.2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_ok_response()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let existing = www_record(&zone, "192.168.1.2").await;
+    zone.delete_record(&existing.id)
+        .await
+        .expect("Failed to delete record");
+}
+
+#[tokio::test]
+async fn test_delete_record_not_found() {
+    let server = setup_mock_server().await;
+    let zone = zone(&server).await;
+    mount_www_records(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/records/delete"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_ok_response()))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let result = zone
+        .delete_record(&format!(
+            "www.example.com:A:{}",
+            fingerprint("www.example.com", "10.0.0.1")
+        ))
+        .await;
+    assert!(matches!(result, Err(DeleteRecordError::NotFound)));
+}
+
+// =============================================================================
+// Error Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_error_messages_are_classified() {
+    let cases = [
+        (
+            "You are not authorized to access this zone.",
+            ErrorKind::Unauthorized,
+        ),
+        ("No such zone was found: example.com", ErrorKind::NotFound),
+        ("Invalid domain name: ex ample.com", ErrorKind::Validation),
+        ("Record already exists.", ErrorKind::AlreadyExists),
+        (
+            "The server encountered an unexpected error.",
+            ErrorKind::Other,
+        ),
+    ];
+
+    for (message, kind) in cases {
+        let server = setup_mock_server().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/zones/list"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(mock_error_response(message)))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server)
+            .list_zones()
+            .await
+            .expect_err("Listing should fail");
+        assert_eq!(
+            err.kind(),
+            kind,
+            "{:?} should be classified as {:?}",
+            message,
+            kind
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_non_json_response_is_an_error() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/list"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html>Login</html>"))
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).list_zones().await;
+    assert!(matches!(
+        result,
+        Err(RetrieveZoneError::Custom(ApiError::Request(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_unavailable_server_is_retried() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/zones/list"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/zones/list"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_zones_response(vec![("example.com", "Primary")])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 1);
+}
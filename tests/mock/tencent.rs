@@ -0,0 +1,1228 @@
+//! Mock-based tests for Tencent Cloud DNSPod provider.
+//!
+//! These tests use `wiremock` to simulate the Tencent Cloud API (`dnspod.intl.tencentcloudapi.com`)
+//! without requiring network access or API credentials.
+//!
+//! # Coverage
+//!
+//! This module provides comprehensive testing for:
+//! - TC3-HMAC-SHA256 signature headers, verified against an independent implementation
+//! - Zone operations (list, get, create, delete)
+//! - Record operations (list, get, create, update, delete)
+//! - Pagination of domains and records
+//! - Mapping of hierarchical error codes (`AuthFailure.*`, `ResourceNotFound.*`, ...)
+//! - Retries of rate-limited and failed requests, reported in the body or by HTTP status
+//! - JSON edge cases (missing lists, unconvertible records, non-JSON bodies)
+//!
+//! # API Structure (Tencent Cloud API 3.0)
+//!
+//! Every action is a `POST /` with a JSON body:
+//! - Action: `X-TC-Action` header, e.g. `DescribeRecordList`
+//! - Auth: `Authorization: TC3-HMAC-SHA256 Credential=..., SignedHeaders=..., Signature=...`
+//! - Result: `{"Response": {...}}`, containing an `Error` object on failure
+
+use crate::common::setup_mock_server;
+use crate::common::tencent::*;
+
+use hmac::{Hmac, Mac};
+use libdns::retry::RetryPolicy;
+use libdns::tencent::{TencentError, TencentProvider};
+use libdns::{
+    ClassifyError, CreateRecord, CreateRecordError, CreateZone, CreateZoneError, DeleteRecord,
+    DeleteRecordError, DeleteZone, DeleteZoneError, ErrorKind, Provider, RecordData,
+    RetrieveRecordError, RetrieveZoneError, UpdateRecord, UpdateRecordError, Zone,
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Match, Mock, MockServer, Request, ResponseTemplate};
+
+/// Creates a provider talking to the mock server without retry delays.
+fn provider(server: &MockServer) -> TencentProvider {
+    TencentProvider::builder(SECRET_ID, SECRET_KEY)
+        .with_base_url(&server.uri())
+        .with_retry_policy(fast_retries())
+        .build()
+        .expect("Failed to create provider")
+}
+
+/// A policy retrying without noticeable delays.
+fn fast_retries() -> RetryPolicy {
+    RetryPolicy::new().with_backoff(Duration::from_millis(1), Duration::from_millis(10))
+}
+
+/// Matches requests for the given API action.
+fn action(name: &'static str) -> impl Match {
+    header("X-TC-Action", name)
+}
+
+/// Mounts a DescribeDomain response for `example.com`.
+async fn mount_zone(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(action("DescribeDomain"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_response(123, "example.com")),
+        )
+        .mount(server)
+        .await;
+}
+
+// =============================================================================
+// Signature Tests
+// =============================================================================
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Matches requests carrying a valid TC3-HMAC-SHA256 signature for the test credentials.
+///
+/// The signature is derived from the received request, following the steps of
+/// <https://www.tencentcloud.com/document/api/1157/49029>.
+struct ValidSignature;
+
+impl Match for ValidSignature {
+    fn matches(&self, request: &Request) -> bool {
+        let get = |name: &str| {
+            request
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let Ok(timestamp) = get("X-TC-Timestamp").parse::<i64>() else {
+            return false;
+        };
+        let date = chrono::DateTime::from_timestamp(timestamp, 0)
+            .unwrap()
+            .format("%Y-%m-%d")
+            .to_string();
+
+        let canonical_request = format!(
+            "POST\n/\n\ncontent-type:{}\nhost:{}\n\ncontent-type;host\n{}",
+            get("Content-Type"),
+            get("Host"),
+            sha256_hex(&request.body)
+        );
+        let credential_scope = format!("{}/dnspod/tc3_request", date);
+        let string_to_sign = format!(
+            "TC3-HMAC-SHA256\n{}\n{}\n{}",
+            timestamp,
+            credential_scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+
+        let secret_date = hmac_sha256(format!("TC3{}", SECRET_KEY).as_bytes(), &date);
+        let secret_service = hmac_sha256(&secret_date, "dnspod");
+        let secret_signing = hmac_sha256(&secret_service, "tc3_request");
+        let signature = hex::encode(hmac_sha256(&secret_signing, &string_to_sign));
+
+        get("Authorization")
+            == format!(
+                "TC3-HMAC-SHA256 Credential={}/{}, SignedHeaders=content-type;host, Signature={}",
+                SECRET_ID, credential_scope, signature
+            )
+    }
+}
+
+#[tokio::test]
+async fn test_requests_are_signed() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(action("DescribeDomainList"))
+        .and(header("X-TC-Version", "2021-03-23"))
+        .and(header("Content-Type", "application/json; charset=utf-8"))
+        .and(ValidSignature)
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_domain_list_response(vec![(123, "example.com")])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 1);
+}
+
+#[tokio::test]
+async fn test_signature_covers_host_of_base_url() {
+    let server = setup_mock_server().await;
+    let host = server.address().to_string();
+
+    Mock::given(method("POST"))
+        .and(header("Host", host.as_str()))
+        .and(ValidSignature)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_response(123, "example.com")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+}
+
+#[tokio::test]
+async fn test_signature_covers_payload() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("CreateRecord"))
+        .and(body_partial_json(json!({
+            "Domain": "example.com",
+            "SubDomain": "txt",
+            "RecordType": "TXT",
+            "RecordLine": "默认",
+            "Value": "quotes \" and unicode ✓",
+            "TTL": 600
+        })))
+        .and(ValidSignature)
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_record_id_response(42)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    zone.create_record(
+        "txt",
+        &RecordData::TXT("quotes \" and unicode ✓".to_string()),
+        600,
+    )
+    .await
+    .expect("Failed to create record");
+}
+
+#[tokio::test]
+async fn test_signature_failure_is_unauthorized() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "AuthFailure.SignatureFailure",
+                "The provided credentials could not be validated.",
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).list_zones().await;
+    assert!(matches!(result, Err(RetrieveZoneError::Unauthorized)));
+}
+
+// =============================================================================
+// Zone Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_list_zones_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(action("DescribeDomainList"))
+        .and(body_partial_json(json!({"Offset": 0, "Limit": 500})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_list_response(vec![
+                (123, "example.com"),
+                (456, "example.org"),
+            ])),
+        )
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 2);
+    assert_eq!(zones[0].domain(), "example.com");
+    assert_eq!(zones[1].domain(), "example.org");
+}
+
+#[tokio::test]
+async fn test_list_zones_missing_domain_list() {
+    let server = setup_mock_server().await;
+
+    // Accounts without domains omit the list entirely
+    Mock::given(method("POST"))
+        .and(action("DescribeDomainList"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "Response": {
+                "DomainCountInfo": {"DomainTotal": 0, "AllTotal": 0},
+                "RequestId": REQUEST_ID
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert!(zones.is_empty());
+}
+
+#[tokio::test]
+async fn test_list_zones_paginates() {
+    let server = setup_mock_server().await;
+
+    let first_page: Vec<(u64, String)> =
+        (0..500).map(|i| (i, format!("domain{}.com", i))).collect();
+    Mock::given(method("POST"))
+        .and(action("DescribeDomainList"))
+        .and(body_partial_json(json!({"Offset": 0, "Limit": 500})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_list_response(
+                first_page
+                    .iter()
+                    .map(|(id, name)| (*id, name.as_str()))
+                    .collect(),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(action("DescribeDomainList"))
+        .and(body_partial_json(json!({"Offset": 500, "Limit": 500})))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_domain_list_response(vec![(500, "last.com")])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 501);
+    assert_eq!(zones[500].domain(), "last.com");
+}
+
+#[tokio::test]
+async fn test_get_zone_by_name() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(action("DescribeDomain"))
+        .and(body_partial_json(json!({"Domain": "example.com"})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_response(123, "example.com")),
+        )
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    assert_eq!(zone.domain(), "example.com");
+    // Records are addressed by domain name, which is therefore used as the ID
+    assert_eq!(zone.id(), "example.com");
+}
+
+#[tokio::test]
+async fn test_get_zone_by_id() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(action("DescribeDomain"))
+        .and(body_partial_json(json!({"DomainId": 123})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_response(123, "example.com")),
+        )
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("123")
+        .await
+        .expect("Failed to get zone");
+    assert_eq!(zone.domain(), "example.com");
+}
+
+#[tokio::test]
+async fn test_get_zone_not_found() {
+    for code in [
+        "InvalidParameterValue.DomainNotExists",
+        "ResourceNotFound.NoDataOfDomain",
+    ] {
+        let server = setup_mock_server().await;
+
+        Mock::given(method("POST"))
+            .and(action("DescribeDomain"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(mock_error_response(code, "The domain does not exist.")),
+            )
+            .mount(&server)
+            .await;
+
+        let result = provider(&server).get_zone("missing.com").await;
+        assert!(
+            matches!(result, Err(RetrieveZoneError::NotFound)),
+            "{} should map to NotFound",
+            code
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_get_zone_unknown_error_is_custom() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(action("DescribeDomain"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "OperationDenied.DomainOwnedByOtherUser",
+                "The domain belongs to another account.",
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let err = match provider(&server).get_zone("example.com").await {
+        Err(RetrieveZoneError::Custom(TencentError::Api(err))) => err,
+        other => panic!("Unexpected result: {:?}", other.map(|z| z.id().to_string())),
+    };
+    assert_eq!(err.code, "OperationDenied.DomainOwnedByOtherUser");
+    assert_eq!(err.message, "The domain belongs to another account.");
+}
+
+#[tokio::test]
+async fn test_create_zone_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(action("CreateDomain"))
+        .and(body_partial_json(json!({"Domain": "newdomain.com"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "Response": {
+                "DomainInfo": {
+                    "Id": 789,
+                    "Domain": "newdomain.com",
+                    "Punycode": "newdomain.com",
+                    "GradeNsList": ["a.dnspod.com", "b.dnspod.com"]
+                },
+                "RequestId": REQUEST_ID
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(action("DescribeDomain"))
+        .and(body_partial_json(json!({"DomainId": 789})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_domain_response(789, "newdomain.com")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .create_zone("newdomain.com")
+        .await
+        .expect("Failed to create zone");
+    assert_eq!(zone.domain(), "newdomain.com");
+}
+
+#[tokio::test]
+async fn test_create_zone_errors() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(action("CreateDomain"))
+        .and(body_partial_json(json!({"Domain": "invalid..com"})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "InvalidParameter.DomainInvalid",
+                "The domain is invalid.",
+            )),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(action("CreateDomain"))
+        .and(body_partial_json(json!({"Domain": "example.com"})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "InvalidParameter.DomainExists",
+                "The domain already exists.",
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = provider(&server);
+
+    let result = provider.create_zone("invalid..com").await;
+    assert!(matches!(result, Err(CreateZoneError::InvalidDomainName)));
+
+    let err = provider
+        .create_zone("example.com")
+        .await
+        .err()
+        .expect("Creation should fail");
+    assert!(matches!(err, CreateZoneError::Custom(_)));
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+}
+
+#[tokio::test]
+async fn test_delete_zone_success() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(action("DeleteDomain"))
+        .and(body_partial_json(json!({"Domain": "example.com"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_empty_response()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    provider(&server)
+        .delete_zone("example.com")
+        .await
+        .expect("Failed to delete zone");
+}
+
+#[tokio::test]
+async fn test_delete_zone_not_found() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(action("DeleteDomain"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "InvalidParameterValue.DomainNotExists",
+                "The domain does not exist.",
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).delete_zone("missing.com").await;
+    assert!(matches!(result, Err(DeleteZoneError::NotFound)));
+}
+
+// =============================================================================
+// Record Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_list_records_success() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("DescribeRecordList"))
+        .and(body_partial_json(
+            json!({"Domain": "example.com", "Offset": 0, "Limit": 500}),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_list_response(vec![
+                (1, "@", "A", "192.168.1.1", 600),
+                (2, "www", "CNAME", "example.com.", 600),
+                (3, "@", "MX", "mail.example.com.", 3600),
+                (4, "@", "TXT", "\"v=spf1 -all\"", 600),
+                (5, "_sip._tcp", "SRV", "10 60 5060 sip.example.com.", 600),
+            ])),
+        )
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let records = zone.list_records().await.expect("Failed to list records");
+
+    assert_eq!(records.len(), 5);
+    assert_eq!(records[0].id, "1");
+    assert_eq!(records[0].host, "@");
+    assert_eq!(
+        records[0].data,
+        RecordData::A("192.168.1.1".parse().unwrap())
+    );
+    // Trailing dots and TXT quotes are stripped
+    assert_eq!(
+        records[1].data,
+        RecordData::CNAME("example.com".to_string())
+    );
+    assert_eq!(
+        records[2].data,
+        RecordData::MX {
+            priority: 10,
+            mail_server: "mail.example.com".to_string()
+        }
+    );
+    assert_eq!(records[2].ttl, 3600);
+    assert_eq!(records[3].data, RecordData::TXT("v=spf1 -all".to_string()));
+    assert_eq!(
+        records[4].data,
+        RecordData::SRV {
+            priority: 10,
+            weight: 60,
+            port: 5060,
+            target: "sip.example.com".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_list_records_paginates() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    let first_page: Vec<(u64, String)> = (0..500)
+        .map(|i| (i, format!("10.0.{}.{}", i / 256, i % 256)))
+        .collect();
+    Mock::given(method("POST"))
+        .and(action("DescribeRecordList"))
+        .and(body_partial_json(json!({"Offset": 0})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_list_response(
+                first_page
+                    .iter()
+                    .map(|(id, ip)| (*id, "host", "A", ip.as_str(), 600))
+                    .collect(),
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(action("DescribeRecordList"))
+        .and(body_partial_json(json!({"Offset": 500})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_list_response(vec![(
+                500, "last", "A", "10.1.0.0", 600,
+            )])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let records = zone.list_records().await.expect("Failed to list records");
+    assert_eq!(records.len(), 501);
+    assert_eq!(records[500].host, "last");
+}
+
+#[tokio::test]
+async fn test_list_records_skips_unconvertible_records() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("DescribeRecordList"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_list_response(vec![
+                (1, "@", "A", "not-an-ip", 600),
+                (2, "@", "SRV", "10 60", 600),
+                (3, "www", "A", "192.168.1.1", 600),
+                (4, "@", "CAA", "0 issue \"letsencrypt.org\"", 600),
+                (5, "@", "SPF", "v=spf1 -all", 600),
+            ])),
+        )
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let records = zone.list_records().await.expect("Failed to list records");
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].host, "www");
+    assert_eq!(
+        records[1].data,
+        RecordData::CAA {
+            flags: 0,
+            tag: "issue".to_string(),
+            value: "letsencrypt.org".to_string()
+        }
+    );
+    // Types without a typed representation are kept verbatim
+    assert_eq!(
+        records[2].data,
+        RecordData::Other {
+            typ: "SPF".to_string(),
+            value: "v=spf1 -all".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_list_records_missing_record_list() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("DescribeRecordList"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "Response": {
+                "RecordList": null,
+                "RecordCountInfo": null,
+                "RequestId": REQUEST_ID
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let records = zone.list_records().await.expect("Failed to list records");
+    assert!(records.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_record_success() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("DescribeRecord"))
+        .and(body_partial_json(
+            json!({"Domain": "example.com", "RecordId": 42}),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_record_response(
+                42,
+                "mail",
+                "MX",
+                "mx.example.com.",
+                3600,
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let record = zone.get_record("42").await.expect("Failed to get record");
+
+    assert_eq!(record.id, "42");
+    assert_eq!(record.host, "mail");
+    assert_eq!(
+        record.data,
+        RecordData::MX {
+            priority: 10,
+            mail_server: "mx.example.com".to_string()
+        }
+    );
+    assert_eq!(record.ttl, 3600);
+}
+
+#[tokio::test]
+async fn test_get_record_not_found() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("DescribeRecord"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "ResourceNotFound.NoDataOfRecord",
+                "The record does not exist.",
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+
+    let result = zone.get_record("42").await;
+    assert!(matches!(result, Err(RetrieveRecordError::NotFound)));
+
+    // Record IDs are numeric, so other IDs are rejected without a request
+    let result = zone.get_record("www/A").await;
+    assert!(matches!(result, Err(RetrieveRecordError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_create_record_success() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("CreateRecord"))
+        .and(body_partial_json(json!({
+            "Domain": "example.com",
+            "SubDomain": "mail",
+            "RecordType": "MX",
+            "Value": "mx.example.com",
+            "MX": 20,
+            "TTL": 3600
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_record_id_response(42)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let data = RecordData::MX {
+        priority: 20,
+        mail_server: "mx.example.com".to_string(),
+    };
+    let record = zone
+        .create_record("mail", &data, 3600)
+        .await
+        .expect("Failed to create record");
+
+    assert_eq!(record.id, "42");
+    assert_eq!(record.host, "mail");
+    assert_eq!(record.data, data);
+    assert_eq!(record.ttl, 3600);
+}
+
+#[tokio::test]
+async fn test_create_record_unsupported_type() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let data = RecordData::Other {
+        typ: "SPF".to_string(),
+        value: "v=spf1 -all".to_string(),
+    };
+
+    let result = zone.create_record("@", &data, 600).await;
+    assert!(matches!(result, Err(CreateRecordError::UnsupportedType)));
+}
+
+#[tokio::test]
+async fn test_create_record_error_codes() {
+    let cases = [
+        ("InvalidParameter.RecordValueInvalid", "invalid"),
+        ("InvalidParameter.SubDomainInvalid", "invalid"),
+        ("InvalidParameter.MXInvalid", "invalid"),
+        ("InvalidParameter.RecordTypeInvalid", "unsupported"),
+        ("AuthFailure.SecretIdNotFound", "unauthorized"),
+        ("UnauthorizedOperation", "custom"),
+    ];
+
+    for (code, expected) in cases {
+        let server = setup_mock_server().await;
+
+        mount_zone(&server).await;
+        Mock::given(method("POST"))
+            .and(action("CreateRecord"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(mock_error_response(code, "Rejected.")),
+            )
+            .mount(&server)
+            .await;
+
+        let zone = provider(&server)
+            .get_zone("example.com")
+            .await
+            .expect("Failed to get zone");
+        let result = zone
+            .create_record("www", &RecordData::A("192.168.1.1".parse().unwrap()), 600)
+            .await;
+
+        let matched = match expected {
+            "invalid" => matches!(result, Err(CreateRecordError::InvalidRecord)),
+            "unsupported" => matches!(result, Err(CreateRecordError::UnsupportedType)),
+            "unauthorized" => matches!(result, Err(CreateRecordError::Unauthorized)),
+            _ => matches!(result, Err(CreateRecordError::Custom(_))),
+        };
+        assert!(matched, "{} should be mapped as {}", code, expected);
+    }
+}
+
+#[tokio::test]
+async fn test_update_record_success() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("ModifyRecord"))
+        .and(body_partial_json(json!({
+            "Domain": "example.com",
+            "RecordId": 42,
+            "SubDomain": "www",
+            "RecordType": "AAAA",
+            "RecordLine": "默认",
+            "Value": "2001:db8::1",
+            "TTL": 300
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_record_id_response(42)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let data = RecordData::AAAA("2001:db8::1".parse().unwrap());
+    let record = zone
+        .update_record("42", "www", &data, 300)
+        .await
+        .expect("Failed to update record");
+
+    assert_eq!(record.id, "42");
+    assert_eq!(record.data, data);
+    assert_eq!(record.ttl, 300);
+}
+
+#[tokio::test]
+async fn test_update_record_not_found() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("ModifyRecord"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "InvalidParameter.RecordIdInvalid",
+                "The record ID is invalid.",
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let result = zone
+        .update_record(
+            "42",
+            "www",
+            &RecordData::A("192.168.1.1".parse().unwrap()),
+            600,
+        )
+        .await;
+    assert!(matches!(result, Err(UpdateRecordError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_delete_record_success() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("DeleteRecord"))
+        .and(body_partial_json(
+            json!({"Domain": "example.com", "RecordId": 42}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_empty_response()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    zone.delete_record("42")
+        .await
+        .expect("Failed to delete record");
+}
+
+#[tokio::test]
+async fn test_delete_record_not_found() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    Mock::given(method("POST"))
+        .and(action("DeleteRecord"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "ResourceNotFound.NoDataOfRecord",
+                "The record does not exist.",
+            )),
+        )
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let result = zone.delete_record("42").await;
+    assert!(matches!(result, Err(DeleteRecordError::NotFound)));
+}
+
+// =============================================================================
+// Error Classification Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_error_codes_are_classified() {
+    let cases = [
+        ("AuthFailure.TokenFailure", ErrorKind::Unauthorized),
+        ("UnauthorizedOperation.NotAllowed", ErrorKind::Unauthorized),
+        ("LimitExceeded.RecordLimit", ErrorKind::QuotaExceeded),
+        ("ResourceNotFound.NoDataOfRecord", ErrorKind::NotFound),
+        ("InvalidParameter.DomainNotExists", ErrorKind::NotFound),
+        ("InvalidParameter.RecordExists", ErrorKind::AlreadyExists),
+        ("InvalidParameter.RecordValueInvalid", ErrorKind::Validation),
+        ("MissingParameter", ErrorKind::Validation),
+        ("FailedOperation.DomainIsLocked", ErrorKind::Other),
+    ];
+
+    for (code, kind) in cases {
+        let server = setup_mock_server().await;
+
+        mount_zone(&server).await;
+        Mock::given(method("POST"))
+            .and(action("DeleteRecord"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(mock_error_response(code, "Rejected.")),
+            )
+            .mount(&server)
+            .await;
+
+        let zone = provider(&server)
+            .get_zone("example.com")
+            .await
+            .expect("Failed to get zone");
+        let err = zone
+            .delete_record("42")
+            .await
+            .expect_err("Deletion should fail");
+        assert_eq!(
+            err.kind(),
+            kind,
+            "{} should be classified as {:?}",
+            code,
+            kind
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_non_json_response_is_an_error() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html>Bad Gateway</html>"))
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).list_zones().await;
+    assert!(matches!(
+        result,
+        Err(RetrieveZoneError::Custom(TencentError::Request(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_unexpected_response_shape_is_an_error() {
+    let server = setup_mock_server().await;
+
+    // A successful response lacking the required `DomainInfo`
+    Mock::given(method("POST"))
+        .and(action("DescribeDomain"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_empty_response()))
+        .mount(&server)
+        .await;
+
+    let result = provider(&server).get_zone("example.com").await;
+    assert!(matches!(
+        result,
+        Err(RetrieveZoneError::Custom(TencentError::Serialization(_)))
+    ));
+}
+
+// =============================================================================
+// Retry Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_rate_limited_request_is_retried() {
+    let server = setup_mock_server().await;
+
+    // Tencent reports rate limits in the body of successful HTTP responses
+    Mock::given(method("POST"))
+        .and(action("DescribeDomainList"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "RequestLimitExceeded",
+                "The request frequency exceeds the limit.",
+            )),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(action("DescribeDomainList"))
+        .and(ValidSignature)
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_domain_list_response(vec![(123, "example.com")])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 1);
+}
+
+#[tokio::test]
+async fn test_rate_limited_status_is_retried() {
+    let server = setup_mock_server().await;
+
+    // Gateways in front of the API may reject requests by HTTP status alone
+    Mock::given(method("POST"))
+        .and(action("DescribeDomainList"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(action("DescribeDomainList"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("<html>Unavailable</html>"))
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(action("DescribeDomainList"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_domain_list_response(vec![(123, "example.com")])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zones = provider(&server)
+        .list_zones()
+        .await
+        .expect("Failed to list zones");
+    assert_eq!(zones.len(), 1);
+}
+
+#[tokio::test]
+async fn test_retry_after_beyond_backoff_is_not_waited_for() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(action("DescribeDomainList"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "60"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = provider(&server)
+        .list_zones()
+        .await
+        .err()
+        .expect("Listing should fail");
+    assert_eq!(err.kind(), ErrorKind::RateLimited);
+}
+
+#[tokio::test]
+async fn test_retries_are_limited() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(action("DescribeDomain"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "InternalError",
+                "An internal error occurred.",
+            )),
+        )
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let provider = TencentProvider::builder(SECRET_ID, SECRET_KEY)
+        .with_base_url(&server.uri())
+        .with_retry_policy(fast_retries().with_max_retries(2))
+        .build()
+        .expect("Failed to create provider");
+
+    let err = provider
+        .get_zone("example.com")
+        .await
+        .err()
+        .expect("Retrieval should fail");
+    assert_eq!(err.kind(), ErrorKind::Transient);
+}
+
+#[tokio::test]
+async fn test_failed_creation_is_not_retried() {
+    let server = setup_mock_server().await;
+
+    mount_zone(&server).await;
+    // The record may have been created before the error occurred
+    Mock::given(method("POST"))
+        .and(action("CreateRecord"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_error_response(
+                "InternalError",
+                "An internal error occurred.",
+            )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let zone = provider(&server)
+        .get_zone("example.com")
+        .await
+        .expect("Failed to get zone");
+    let err = zone
+        .create_record("www", &RecordData::A("192.168.1.1".parse().unwrap()), 600)
+        .await
+        .expect_err("Creation should fail");
+    assert_eq!(err.kind(), ErrorKind::Transient);
+}
@@ -84,6 +84,94 @@ fn record_data_strategy() -> impl Strategy<Value = RecordData> {
     ]
 }
 
+/// Strategy for generating hexadecimal digests and fingerprints.
+fn hex_strategy() -> impl Strategy<Value = String> {
+    "([0-9a-f]{2}){1,48}"
+}
+
+/// Strategy for generating data of the record types that are parsed from presentation format.
+fn typed_record_data_strategy() -> impl Strategy<Value = RecordData> {
+    prop_oneof![
+        (any::<u8>(), "[a-z]{1,15}", "[ -~]{0,40}")
+            .prop_map(|(flags, tag, value)| RecordData::CAA { flags, tag, value }),
+        (any::<u8>(), any::<u8>(), any::<u8>(), hex_strategy()).prop_map(
+            |(usage, selector, matching_type, certificate)| RecordData::TLSA {
+                usage,
+                selector,
+                matching_type,
+                certificate
+            }
+        ),
+        (any::<u8>(), any::<u8>(), hex_strategy()).prop_map(
+            |(algorithm, fingerprint_type, fingerprint)| RecordData::SSHFP {
+                algorithm,
+                fingerprint_type,
+                fingerprint
+            }
+        ),
+        domain_name_strategy().prop_map(RecordData::PTR),
+        (
+            domain_name_strategy(),
+            domain_name_strategy(),
+            any::<[u32; 5]>()
+        )
+            .prop_map(
+                |(mname, rname, [serial, refresh, retry, expire, minimum])| RecordData::SOA {
+                    mname,
+                    rname,
+                    serial,
+                    refresh,
+                    retry,
+                    expire,
+                    minimum
+                }
+            ),
+        (any::<u16>(), any::<u8>(), any::<u8>(), hex_strategy()).prop_map(
+            |(key_tag, algorithm, digest_type, digest)| RecordData::DS {
+                key_tag,
+                algorithm,
+                digest_type,
+                digest
+            }
+        ),
+        (
+            any::<u16>(),
+            domain_name_strategy(),
+            "(alpn=h2 )?(port=[0-9]{1,5})?"
+        )
+            .prop_map(|(priority, target, params)| RecordData::HTTPS {
+                priority,
+                target,
+                params: params.trim().to_string()
+            }),
+        (any::<u16>(), domain_name_strategy(), "(alpn=h3)?").prop_map(
+            |(priority, target, params)| RecordData::SVCB {
+                priority,
+                target,
+                params
+            }
+        ),
+        (
+            any::<u16>(),
+            any::<u16>(),
+            "[A-Z]{0,2}",
+            "[A-Za-z0-9+:]{0,20}",
+            "[ -~]{0,30}",
+            domain_name_strategy()
+        )
+            .prop_map(
+                |(order, preference, flags, services, regexp, replacement)| RecordData::NAPTR {
+                    order,
+                    preference,
+                    flags,
+                    services,
+                    regexp,
+                    replacement
+                }
+            ),
+    ]
+}
+
 // =============================================================================
 // Label Tests
 // =============================================================================
@@ -137,13 +225,57 @@ proptest! {
     fn domain_name_wire_format_valid(domain in domain_name_strategy()) {
         if let Some(dn) = DomainName::from_dotted(&domain) {
             let wire = dn.as_wire_bytes();
-            prop_assert!(wire.len() > 0);
+            prop_assert!(!wire.is_empty());
             prop_assert!(wire.len() <= MAX_DOMAIN_LEN);
             // Wire format ends with null byte
             prop_assert_eq!(*wire.last().unwrap(), 0);
         }
     }
 
+    #[test]
+    fn domain_name_eq_ignores_case(domain in domain_name_strategy()) {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |name: &DomainName| {
+            let mut hasher = DefaultHasher::new();
+            name.hash(&mut hasher);
+            hasher.finish()
+        };
+        let lower = DomainName::from_dotted(&domain.to_ascii_lowercase()).unwrap();
+        let upper = DomainName::from_dotted(&domain.to_ascii_uppercase()).unwrap();
+
+        prop_assert_eq!(&lower, &upper);
+        prop_assert_eq!(hash(&lower), hash(&upper));
+        prop_assert_eq!(lower.cmp(&upper), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn domain_name_order_is_canonical(a in domain_name_strategy(), b in domain_name_strategy()) {
+        let (a, b) = (DomainName::from_dotted(&a).unwrap(), DomainName::from_dotted(&b).unwrap());
+
+        prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        prop_assert_eq!(a.cmp(&b) == std::cmp::Ordering::Equal, a == b);
+        // Names sort right after their parents
+        if let Some(parent) = a.parent() {
+            prop_assert!(parent < a);
+        }
+    }
+
+    #[test]
+    fn domain_name_relative_roundtrip(host in domain_name_strategy(), zone in domain_name_strategy()) {
+        let (host, zone) = (DomainName::from_dotted(&host).unwrap(), DomainName::from_dotted(&zone).unwrap());
+
+        if let Some(name) = host.append(&zone) {
+            prop_assert!(name.is_subdomain_of(&zone));
+            prop_assert_eq!(name.label_count(), host.label_count() + zone.label_count());
+            prop_assert_eq!(name.relative_to(&zone), Some(host.to_dotted()));
+
+            let first = name.labels().next().unwrap();
+            prop_assert_eq!(name.parent().unwrap().prepend(&first), Some(name));
+        }
+    }
+
     #[test]
     fn domain_name_default_is_root(_dummy in Just(())) {
         let dn = DomainName::default();
@@ -213,7 +345,7 @@ proptest! {
         Just("NS"), Just("ns"),
         Just("SRV"), Just("srv"),
     ]) {
-        let parsed = RecordType::from_str(&rt);
+        let parsed = RecordType::from_str(rt);
         prop_assert!(parsed.is_some());
     }
 }
@@ -251,10 +383,25 @@ proptest! {
             RecordData::NS(_) => prop_assert_eq!(typ, "NS"),
             RecordData::TXT(_) => prop_assert_eq!(typ, "TXT"),
             RecordData::SRV { .. } => prop_assert_eq!(typ, "SRV"),
+            RecordData::CAA { .. } => prop_assert_eq!(typ, "CAA"),
+            RecordData::TLSA { .. } => prop_assert_eq!(typ, "TLSA"),
+            RecordData::SSHFP { .. } => prop_assert_eq!(typ, "SSHFP"),
+            RecordData::PTR(_) => prop_assert_eq!(typ, "PTR"),
+            RecordData::SOA { .. } => prop_assert_eq!(typ, "SOA"),
+            RecordData::DS { .. } => prop_assert_eq!(typ, "DS"),
+            RecordData::HTTPS { .. } => prop_assert_eq!(typ, "HTTPS"),
+            RecordData::SVCB { .. } => prop_assert_eq!(typ, "SVCB"),
+            RecordData::NAPTR { .. } => prop_assert_eq!(typ, "NAPTR"),
             RecordData::Other { typ: t, .. } => prop_assert_eq!(typ, t.as_str()),
         }
     }
 
+    #[test]
+    fn record_data_typed_roundtrip(data in typed_record_data_strategy()) {
+        let parsed = RecordData::from_raw(data.get_type(), &data.get_value());
+        prop_assert_eq!(parsed, data);
+    }
+
     #[test]
     fn record_data_get_value_not_empty(data in record_data_strategy()) {
         let value = data.get_value();
@@ -311,7 +458,11 @@ proptest! {
     #[test]
     fn record_data_from_raw_unknown_type_preserves_data(typ in "[A-Z]{2,6}", value in ".*") {
         // Skip known types
-        prop_assume!(!["A", "AAAA", "CNAME", "MX", "NS", "SRV", "TXT"].contains(&typ.as_str()));
+        prop_assume!(![
+            "A", "AAAA", "CNAME", "MX", "NS", "SRV", "TXT", "CAA", "TLSA", "SSHFP", "PTR", "SOA", "DS",
+            "HTTPS", "SVCB", "NAPTR",
+        ]
+        .contains(&typ.as_str()));
         let data = RecordData::from_raw(&typ, &value);
         match data {
             RecordData::Other { typ: t, value: v } => {
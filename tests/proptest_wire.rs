@@ -0,0 +1,287 @@
+//! Property-based tests for the DNS wire format.
+//!
+//! These tests use proptest to generate arbitrary resource records and verify
+//! that encoding and decoding them round-trips, and that decoding never panics.
+
+use libdns::types::*;
+use libdns::wire::{ResourceRecord, WireError};
+use libdns::RecordData;
+use proptest::prelude::*;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// =============================================================================
+// Strategies for generating resource records
+// =============================================================================
+
+/// Strategy for generating names in mixed case, without trailing dot.
+fn name_strategy() -> impl Strategy<Value = String> {
+    prop::collection::vec("[a-zA-Z0-9]([a-zA-Z0-9-]{0,20}[a-zA-Z0-9])?", 1..5)
+        .prop_map(|labels| labels.join("."))
+}
+
+/// Strategy for generating targets, which may be the root name.
+fn target_strategy() -> impl Strategy<Value = String> {
+    prop_oneof![Just(".".to_string()), name_strategy()]
+}
+
+/// Strategy for generating hexadecimal digests and fingerprints.
+fn hex_strategy() -> impl Strategy<Value = String> {
+    "([0-9a-f]{2}){0,48}"
+}
+
+/// Strategy for generating service parameters in the presentation format they are decoded to.
+fn params_strategy() -> impl Strategy<Value = String> {
+    (
+        prop::option::of(prop::sample::subsequence(
+            vec!["alpn", "port", "ipv4hint", "key999"],
+            1..=4,
+        )),
+        prop::option::of(prop::collection::vec("[a-z0-9-]{1,10}", 1..4)),
+        any::<bool>(),
+        prop::option::of(any::<u16>()),
+        prop::option::of(prop::collection::vec(any::<[u8; 4]>(), 1..3)),
+        prop::option::of(prop::collection::vec(any::<[u8; 16]>(), 1..3)),
+        prop::option::of((9..=65534u16, "[a-zA-Z0-9,.=]{0,10}")),
+    )
+        .prop_map(
+            |(mandatory, alpn, no_default_alpn, port, ipv4hint, ipv6hint, generic)| {
+                let join = |items: Vec<String>| items.join(",");
+                [
+                    mandatory.map(|keys| format!("mandatory={}", keys.join(","))),
+                    alpn.map(|ids| format!("alpn={}", ids.join(","))),
+                    no_default_alpn.then(|| "no-default-alpn".to_string()),
+                    port.map(|port| format!("port={}", port)),
+                    ipv4hint.map(|ips| {
+                        format!(
+                            "ipv4hint={}",
+                            join(
+                                ips.into_iter()
+                                    .map(|ip| Ipv4Addr::from(ip).to_string())
+                                    .collect()
+                            )
+                        )
+                    }),
+                    ipv6hint.map(|ips| {
+                        format!(
+                            "ipv6hint={}",
+                            join(
+                                ips.into_iter()
+                                    .map(|ip| Ipv6Addr::from(ip).to_string())
+                                    .collect()
+                            )
+                        )
+                    }),
+                    generic.map(|(key, value)| {
+                        if value.is_empty() {
+                            format!("key{}", key)
+                        } else {
+                            format!("key{}={}", key, value)
+                        }
+                    }),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" ")
+            },
+        )
+}
+
+/// Strategy for generating record data in the form it is decoded to.
+fn record_data_strategy() -> impl Strategy<Value = RecordData> {
+    prop_oneof![
+        any::<[u8; 4]>().prop_map(|ip| RecordData::A(Ipv4Addr::from(ip))),
+        any::<[u8; 16]>().prop_map(|ip| RecordData::AAAA(Ipv6Addr::from(ip))),
+        name_strategy().prop_map(RecordData::CNAME),
+        name_strategy().prop_map(RecordData::NS),
+        name_strategy().prop_map(RecordData::PTR),
+        (any::<u16>(), name_strategy()).prop_map(|(priority, mail_server)| RecordData::MX {
+            priority,
+            mail_server
+        }),
+        (any::<u16>(), any::<u16>(), any::<u16>(), name_strategy()).prop_map(
+            |(priority, weight, port, target)| RecordData::SRV {
+                priority,
+                weight,
+                port,
+                target
+            }
+        ),
+        // Long values are split into multiple character strings
+        "[^\"].{0,600}".prop_map(RecordData::TXT),
+        (name_strategy(), name_strategy(), any::<[u32; 5]>()).prop_map(
+            |(mname, rname, [serial, refresh, retry, expire, minimum])| RecordData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum
+            }
+        ),
+        (any::<u8>(), "[a-z0-9]{1,15}", ".{0,40}")
+            .prop_map(|(flags, tag, value)| RecordData::CAA { flags, tag, value }),
+        (any::<[u8; 3]>(), hex_strategy()).prop_map(
+            |([usage, selector, matching_type], certificate)| RecordData::TLSA {
+                usage,
+                selector,
+                matching_type,
+                certificate
+            }
+        ),
+        (any::<[u8; 2]>(), hex_strategy()).prop_map(
+            |([algorithm, fingerprint_type], fingerprint)| RecordData::SSHFP {
+                algorithm,
+                fingerprint_type,
+                fingerprint
+            }
+        ),
+        (any::<u16>(), any::<[u8; 2]>(), hex_strategy()).prop_map(
+            |(key_tag, [algorithm, digest_type], digest)| RecordData::DS {
+                key_tag,
+                algorithm,
+                digest_type,
+                digest
+            }
+        ),
+        (
+            any::<[u16; 2]>(),
+            "[A-Z]{0,2}",
+            "[A-Za-z0-9+:]{0,20}",
+            "[ -~]{0,30}",
+            target_strategy()
+        )
+            .prop_map(
+                |([order, preference], flags, services, regexp, replacement)| RecordData::NAPTR {
+                    order,
+                    preference,
+                    flags,
+                    services,
+                    regexp,
+                    replacement
+                }
+            ),
+        (any::<u16>(), target_strategy(), params_strategy()).prop_map(
+            |(priority, target, params)| RecordData::HTTPS {
+                priority,
+                target,
+                params
+            }
+        ),
+        (any::<u16>(), target_strategy(), params_strategy()).prop_map(
+            |(priority, target, params)| RecordData::SVCB {
+                priority,
+                target,
+                params
+            }
+        ),
+        (
+            (256..=65279u16).prop_filter("Type must be unknown", |typ| {
+                RecordType::from_u16(*typ).is_none()
+            }),
+            prop::collection::vec(any::<u8>(), 0..64)
+        )
+            .prop_map(|(typ, rdata)| RecordData::Other {
+                typ: format!("TYPE{}", typ),
+                value: format!("\\# {} {}", rdata.len(), hex(&rdata))
+                    .trim_end()
+                    .to_string()
+            }),
+    ]
+}
+
+/// Strategy for generating resource records.
+fn record_strategy() -> impl Strategy<Value = ResourceRecord> {
+    (
+        name_strategy(),
+        prop::sample::select(vec![
+            RecordClass::IN,
+            RecordClass::CS,
+            RecordClass::CH,
+            RecordClass::HS,
+            RecordClass::NONE,
+            RecordClass::ANY,
+        ]),
+        0..=MAX_TTL,
+        record_data_strategy(),
+    )
+        .prop_map(|(name, class, ttl, data)| ResourceRecord {
+            name: DomainName::from_dotted(&name).unwrap(),
+            class,
+            ttl: Ttl::new(ttl),
+            data,
+        })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// =============================================================================
+// ResourceRecord Tests
+// =============================================================================
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+
+    #[test]
+    fn record_roundtrip(record in record_strategy()) {
+        let bytes = record.to_wire().expect("Failed to encode record");
+        prop_assert_eq!(ResourceRecord::from_wire(&bytes), Ok(record));
+    }
+
+    #[test]
+    fn record_decode_within_message(
+        prefix in prop::collection::vec(any::<u8>(), 0..64),
+        record in record_strategy(),
+    ) {
+        let mut msg = prefix.clone();
+        record.encode(&mut msg).expect("Failed to encode record");
+        let (decoded, end) = ResourceRecord::decode(&msg, prefix.len()).expect("Failed to decode record");
+
+        prop_assert_eq!(decoded, record);
+        prop_assert_eq!(end, msg.len());
+    }
+
+    #[test]
+    fn record_decode_compressed_names(
+        owner in name_strategy(),
+        host in "[a-zA-Z0-9]{1,20}",
+        ttl in 0..=MAX_TTL,
+    ) {
+        let owner_name = DomainName::from_dotted(&owner).unwrap();
+
+        // The zone name comes first, both names of the record point to it
+        let mut msg = owner_name.as_wire_bytes().to_vec();
+        let offset = msg.len();
+        msg.extend_from_slice(&[0xc0, 0x00]);
+        msg.extend_from_slice(&RecordType::CNAME.as_u16().to_be_bytes());
+        msg.extend_from_slice(&RecordClass::IN.as_u16().to_be_bytes());
+        msg.extend_from_slice(&ttl.to_be_bytes());
+        msg.extend_from_slice(&(host.len() as u16 + 3).to_be_bytes());
+        msg.push(host.len() as u8);
+        msg.extend_from_slice(host.as_bytes());
+        msg.extend_from_slice(&[0xc0, 0x00]);
+
+        let (decoded, end) = ResourceRecord::decode(&msg, offset).expect("Failed to decode record");
+        prop_assert_eq!(end, msg.len());
+        prop_assert_eq!(decoded, ResourceRecord::new(
+            owner_name,
+            Ttl::new(ttl),
+            RecordData::CNAME(format!("{}.{}", host, owner)),
+        ));
+    }
+
+    #[test]
+    fn record_decode_truncated_fails(record in record_strategy(), cut in any::<prop::sample::Index>()) {
+        let bytes = record.to_wire().expect("Failed to encode record");
+        let truncated = &bytes[..cut.index(bytes.len())];
+        prop_assert_eq!(ResourceRecord::from_wire(truncated), Err(WireError::Malformed));
+    }
+
+    #[test]
+    fn record_decode_arbitrary_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..512)) {
+        let _ = ResourceRecord::from_wire(&bytes);
+    }
+}
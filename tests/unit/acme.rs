@@ -0,0 +1,57 @@
+//! Unit tests for ACME DNS-01 challenges.
+
+use libdns::acme::Dns01Challenge;
+
+#[test]
+fn test_challenge_digest() {
+    let challenge = Dns01Challenge::new(
+        "example.com",
+        "evaGxfADs6pSRb2LAv9IZf17Dt3juxGJ-PCt92wr-oA.nP1qzpXGymHBrUEepNY9HCsQk7K8KhOypzEt62jcerQ",
+    );
+    assert_eq!(
+        challenge.value(),
+        "NGwKoXBgCT8JhEa0bK7AwfSqHyu_ZWeugV07fLGIVq0"
+    );
+
+    // Inputs whose digest encoding ends in a partial group
+    assert_eq!(
+        Dns01Challenge::new("example.com", "a").value(),
+        "ypeBEsobvcr6wjGzmiPcTaeG7_gUfE5yuYB3ha_uSLs"
+    );
+}
+
+#[test]
+fn test_challenge_record_host() {
+    let apex = Dns01Challenge::from_digest("example.com", "value");
+    let wildcard = Dns01Challenge::from_digest("*.Example.com.", "value");
+    let sub = Dns01Challenge::from_digest("api.example.com", "value");
+
+    assert_eq!(apex.record_name(), "_acme-challenge.example.com");
+    assert_eq!(wildcard.record_name(), "_acme-challenge.example.com");
+    assert_eq!(
+        apex.record_host("example.com").as_deref(),
+        Some("_acme-challenge")
+    );
+    assert_eq!(
+        sub.record_host("example.com.").as_deref(),
+        Some("_acme-challenge.api")
+    );
+    // Delegated subzone
+    assert_eq!(
+        sub.record_host("api.example.com").as_deref(),
+        Some("_acme-challenge")
+    );
+    assert_eq!(apex.record_host("example.org"), None);
+
+    // Internationalized identifiers match zones in either form
+    let idn = Dns01Challenge::from_digest("*.bücher.example", "value");
+    assert_eq!(
+        idn.record_host("xn--bcher-kva.example").as_deref(),
+        Some("_acme-challenge")
+    );
+    assert_eq!(
+        idn.record_host("bücher.example").as_deref(),
+        Some("_acme-challenge")
+    );
+    assert_eq!(idn.record_host("example.com"), None);
+}
@@ -0,0 +1,131 @@
+//! Unit tests for record fingerprints.
+
+use std::net::Ipv4Addr;
+
+use libdns::{Fingerprint, Record, RecordData};
+
+const DOMAIN: &str = "example.com";
+
+fn record(id: &str, host: &str, data: RecordData, ttl: u64) -> Record {
+    Record {
+        id: id.to_string(),
+        host: host.to_string(),
+        data,
+        ttl,
+    }
+}
+
+fn a(last: u8) -> RecordData {
+    RecordData::A(Ipv4Addr::new(192, 0, 2, last))
+}
+
+#[test]
+fn test_fingerprint_is_stable() {
+    // Fingerprints are stored by callers, so they must never change between releases
+    let www = Fingerprint::new("www.example.com", &a(1));
+    assert_eq!(www.to_string(), "73d6fca453b1a9f7");
+    assert_eq!(www.as_u64(), 0x73d6_fca4_53b1_a9f7);
+    assert_eq!(Fingerprint::from_u64(www.as_u64()), www);
+
+    let spf = Fingerprint::new("example.com", &RecordData::TXT("v=spf1 -all".to_string()));
+    assert_eq!(spf.to_string(), "5e795d5c199cf931");
+
+    // The string representation has a fixed width
+    assert_eq!(Fingerprint::from_u64(1).to_string(), "0000000000000001");
+}
+
+#[test]
+fn test_fingerprint_ignores_host_form() {
+    let expected = Fingerprint::new("www.example.com", &a(1));
+
+    for host in [
+        "www",
+        "www.example.com",
+        "WWW.Example.com.",
+        "www.example.com.",
+    ] {
+        let record = record("1", host, a(1), 300);
+        assert_eq!(record.fingerprint(DOMAIN), expected, "{}", host);
+        assert_eq!(record.fingerprint("Example.COM."), expected, "{}", host);
+    }
+
+    let apex = Fingerprint::new("example.com", &a(1));
+    for host in ["@", "", "example.com", "example.com."] {
+        assert_eq!(record("1", host, a(1), 300).fingerprint(DOMAIN), apex);
+    }
+}
+
+#[test]
+fn test_fingerprint_ignores_id_and_ttl() {
+    let first = record("1", "www", a(1), 300);
+    let second = record("www/A/192.0.2.1", "www", a(1), 3600);
+    assert_eq!(first.fingerprint(DOMAIN), second.fingerprint(DOMAIN));
+}
+
+#[test]
+fn test_fingerprint_canonicalizes_values() {
+    let same = [
+        (
+            RecordData::CNAME("Target.Example.net.".to_string()),
+            RecordData::CNAME("target.example.net".to_string()),
+        ),
+        (
+            RecordData::MX {
+                priority: 10,
+                mail_server: "MAIL.example.com.".to_string(),
+            },
+            RecordData::MX {
+                priority: 10,
+                mail_server: "mail.example.com".to_string(),
+            },
+        ),
+        (
+            RecordData::TXT("\"hello world\"".to_string()),
+            RecordData::TXT("hello world".to_string()),
+        ),
+        (
+            RecordData::Other {
+                typ: "caa".to_string(),
+                value: "0  issue   \"letsencrypt.org\"".to_string(),
+            },
+            RecordData::Other {
+                typ: "CAA".to_string(),
+                value: "0 issue \"letsencrypt.org\"".to_string(),
+            },
+        ),
+    ];
+    for (a, b) in same {
+        assert_eq!(
+            Fingerprint::new("www.example.com", &a),
+            Fingerprint::new("www.example.com", &b),
+            "{:?} {:?}",
+            a,
+            b
+        );
+    }
+}
+
+#[test]
+fn test_fingerprint_distinguishes_records() {
+    let base = Fingerprint::new("www.example.com", &a(1));
+
+    // Value, host and type all contribute
+    assert_ne!(Fingerprint::new("www.example.com", &a(2)), base);
+    assert_ne!(Fingerprint::new("web.example.com", &a(1)), base);
+    assert_ne!(
+        Fingerprint::new(
+            "www.example.com",
+            &RecordData::Other {
+                typ: "TYPE1".to_string(),
+                value: "192.0.2.1".to_string()
+            }
+        ),
+        base
+    );
+
+    // TXT values are case-sensitive, unlike names
+    assert_ne!(
+        Fingerprint::new("example.com", &RecordData::TXT("Token".to_string())),
+        Fingerprint::new("example.com", &RecordData::TXT("token".to_string()))
+    );
+}
@@ -0,0 +1,379 @@
+//! Unit tests for the in-memory provider.
+
+use std::net::Ipv4Addr;
+
+use libdns::dynamic::{DynError, DynProvider, DynProviderAdapter};
+use libdns::memory::{Fault, MemoryError, MemoryProvider, MemoryZone, Operation};
+use libdns::{
+    ApplyChanges, Capabilities, ClassifyError, CreateRecord, CreateRecordError, CreateZone,
+    CreateZoneError, DeleteRecord, DeleteRecordError, DeleteRecords, DeleteZone, ErrorKind,
+    Provider, Record, RecordChange, RecordData, RetrieveRecordError, RetrieveZoneError, SetRecords,
+    SetRecordsError, UpdateRecord, UpdateRecordError, Zone,
+};
+
+fn a(last: u8) -> RecordData {
+    RecordData::A(Ipv4Addr::new(10, 0, 0, last))
+}
+
+async fn zone(provider: &MemoryProvider) -> MemoryZone {
+    provider
+        .create_zone("Example.com.")
+        .await
+        .expect("Failed to create zone")
+}
+
+#[tokio::test]
+async fn test_zone_lifecycle() {
+    let provider = MemoryProvider::new();
+    let zone = zone(&provider).await;
+    assert_eq!(zone.domain(), "example.com");
+
+    assert_eq!(
+        provider.create_zone("example.com").await.unwrap_err(),
+        CreateZoneError::Custom(MemoryError::ZoneExists)
+    );
+    assert_eq!(
+        provider.create_zone("invalid..name").await.unwrap_err(),
+        CreateZoneError::InvalidDomainName
+    );
+
+    let fetched = provider.get_zone(zone.id()).await.unwrap();
+    assert_eq!(fetched.domain(), "example.com");
+    assert_eq!(provider.list_zones().await.unwrap().len(), 1);
+
+    provider.delete_zone(zone.id()).await.unwrap();
+    assert_eq!(
+        provider.get_zone(zone.id()).await.unwrap_err(),
+        RetrieveZoneError::NotFound
+    );
+    assert_eq!(
+        zone.list_records().await.unwrap_err(),
+        RetrieveRecordError::NotFound
+    );
+    assert_eq!(
+        zone.create_record("www", &a(1), 300).await.unwrap_err(),
+        CreateRecordError::Custom(MemoryError::ZoneNotFound)
+    );
+}
+
+#[tokio::test]
+async fn test_internationalized_names() {
+    let provider = MemoryProvider::new();
+    let zone = provider
+        .create_zone("münchen.de")
+        .await
+        .expect("Failed to create zone");
+    assert_eq!(zone.domain(), "xn--mnchen-3ya.de");
+
+    let record = zone
+        .create_record("bücher", &a(1), 300)
+        .await
+        .expect("Failed to create record");
+    assert_eq!(record.host, "xn--bcher-kva");
+    let record = zone
+        .update_record(&record.id, "_acme-challenge.straße", &a(2), 300)
+        .await
+        .expect("Failed to update record");
+    assert_eq!(record.host, "_acme-challenge.xn--strae-oqa");
+
+    assert_eq!(
+        zone.create_record("a\u{200d}b", &a(1), 300)
+            .await
+            .unwrap_err(),
+        CreateRecordError::InvalidRecord
+    );
+}
+
+#[tokio::test]
+async fn test_internationalized_hosts_match_existing_records() {
+    let provider = MemoryProvider::new();
+    let zone = zone(&provider).await;
+
+    let record = Record {
+        id: String::new(),
+        host: "büro".to_string(),
+        data: a(1),
+        ttl: 300,
+    };
+
+    // Repeating the same set matches the record created before instead of creating it again
+    for _ in 0..2 {
+        let records = zone
+            .set_records(std::slice::from_ref(&record))
+            .await
+            .expect("Failed to set records");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].host, "xn--bro-hoa");
+    }
+    assert_eq!(zone.list_records().await.unwrap().len(), 1);
+
+    let deleted = zone
+        .delete_records(std::slice::from_ref(&record))
+        .await
+        .expect("Failed to delete records");
+    assert_eq!(deleted.len(), 1);
+    assert!(zone.list_records().await.unwrap().is_empty());
+    assert_eq!(zone.delete_records(&[record]).await, Ok(Vec::new()));
+}
+
+#[tokio::test]
+async fn test_record_ids_are_stable() {
+    let provider = MemoryProvider::new();
+    let zone = zone(&provider).await;
+
+    let created = zone
+        .create_record("www.example.com.", &a(1), 300)
+        .await
+        .unwrap();
+    assert_eq!(created.host, "www");
+
+    let updated = zone
+        .update_record(&created.id, "www", &a(2), 600)
+        .await
+        .unwrap();
+    assert_eq!(updated.id, created.id);
+    assert_eq!(zone.get_record(&created.id).await.unwrap(), updated);
+
+    let other = zone.create_record("@", &a(1), 300).await.unwrap();
+    assert_ne!(other.id, created.id);
+
+    zone.delete_record(&created.id).await.unwrap();
+    assert_eq!(
+        zone.delete_record(&created.id).await.unwrap_err(),
+        DeleteRecordError::NotFound
+    );
+    assert_eq!(
+        zone.update_record(&created.id, "www", &a(3), 300)
+            .await
+            .unwrap_err(),
+        UpdateRecordError::NotFound
+    );
+    assert_eq!(zone.list_records().await.unwrap(), vec![other]);
+}
+
+#[tokio::test]
+async fn test_set_records_creates_before_deleting() {
+    let provider = MemoryProvider::new();
+    let zone = zone(&provider).await;
+    let kept = zone.create_record("www", &a(1), 300).await.unwrap();
+
+    let record = |data: RecordData| Record {
+        id: String::new(),
+        host: "www".to_string(),
+        data,
+        ttl: 600,
+    };
+
+    // Changing the TTL of a kept value requires a delete, which only happens once the new value exists
+    provider.fail_next(Operation::DeleteRecord, Fault::Unauthorized);
+    assert_eq!(
+        zone.set_records(&[record(a(1)), record(a(2))]).await,
+        Err(SetRecordsError::Delete(DeleteRecordError::Unauthorized))
+    );
+    let records = zone.list_records().await.unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(records.contains(&kept));
+
+    let result = zone
+        .set_records(&[record(a(1)), record(a(2))])
+        .await
+        .expect("Failed to set records");
+    assert_eq!(result.len(), 2);
+    assert!(result.iter().all(|r| r.ttl == 600));
+    assert_eq!(zone.list_records().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_invalid_records_are_rejected() {
+    let provider = MemoryProvider::new()
+        .with_supported_types(["A", "CNAME", "TXT"])
+        .with_ttl_range(60..=86400);
+    let zone = zone(&provider).await;
+
+    zone.create_record("www", &a(1), 300).await.unwrap();
+
+    let rejected = [
+        // Duplicate value at the same host
+        ("www", a(1), 300),
+        // CNAME next to other records
+        ("www", RecordData::CNAME("example.net".to_string()), 300),
+        ("www", a(2), 30),
+        ("www", a(2), 100_000),
+        ("bad..host", a(2), 300),
+        ("txt", RecordData::CNAME("not a..name".to_string()), 300),
+    ];
+    for (host, data, ttl) in rejected {
+        assert_eq!(
+            zone.create_record(host, &data, ttl).await.unwrap_err(),
+            CreateRecordError::InvalidRecord,
+            "{} {:?} {}",
+            host,
+            data,
+            ttl
+        );
+    }
+
+    let mx = RecordData::MX {
+        priority: 10,
+        mail_server: "mail.example.com".to_string(),
+    };
+    assert_eq!(
+        zone.create_record("@", &mx, 300).await.unwrap_err(),
+        CreateRecordError::UnsupportedType
+    );
+    assert_eq!(zone.list_records().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_apply_changes_is_atomic() {
+    let provider = MemoryProvider::new();
+    let zone = zone(&provider).await;
+    let www = zone.create_record("www", &a(1), 300).await.unwrap();
+
+    let err = zone
+        .apply_changes(&[
+            RecordChange::Create {
+                host: "api".to_string(),
+                data: a(2),
+                ttl: 300,
+            },
+            RecordChange::Delete {
+                record_id: www.id.clone(),
+            },
+            RecordChange::Delete {
+                record_id: "missing".to_string(),
+            },
+        ])
+        .await
+        .unwrap_err();
+    assert_eq!(err.index, Some(2));
+    assert!(err.rolled_back);
+    assert_eq!(zone.list_records().await.unwrap(), vec![www.clone()]);
+
+    let records = zone
+        .apply_changes(&[
+            RecordChange::Delete {
+                record_id: www.id.clone(),
+            },
+            // The deletion above frees the value for the new record
+            RecordChange::Create {
+                host: "www".to_string(),
+                data: a(1),
+                ttl: 600,
+            },
+        ])
+        .await
+        .unwrap();
+    assert_eq!(zone.list_records().await.unwrap(), records);
+    assert_ne!(records[0].id, www.id);
+}
+
+#[tokio::test]
+async fn test_fault_injection() {
+    let provider = MemoryProvider::new();
+    let zone = zone(&provider).await;
+
+    provider.fail_next(Operation::CreateRecord, Fault::Unavailable);
+    provider.fail_next(Operation::CreateRecord, Fault::Unauthorized);
+    assert_eq!(
+        zone.create_record("www", &a(1), 300).await.unwrap_err(),
+        CreateRecordError::Custom(MemoryError::Unavailable)
+    );
+    assert_eq!(
+        zone.create_record("www", &a(1), 300).await.unwrap_err(),
+        CreateRecordError::Unauthorized
+    );
+    // Failed calls leave no trace
+    assert!(zone.list_records().await.unwrap().is_empty());
+    zone.create_record("www", &a(1), 300).await.unwrap();
+
+    provider.fail_always(Operation::ListRecords, Fault::Unauthorized);
+    for _ in 0..3 {
+        assert_eq!(
+            zone.list_records().await.unwrap_err(),
+            RetrieveRecordError::Unauthorized
+        );
+    }
+    // Other operations are not affected
+    assert!(provider.get_zone(zone.id()).await.is_ok());
+
+    provider.clear_faults();
+    assert_eq!(zone.list_records().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_error_kinds() {
+    let provider = MemoryProvider::new().with_supported_types(["A"]);
+    let zone = zone(&provider).await;
+
+    let err = provider.create_zone("example.com").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    let err = zone
+        .create_record("www", &RecordData::TXT("text".to_string()), 300)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Validation);
+    assert_eq!(
+        zone.get_record("missing").await.unwrap_err().kind(),
+        ErrorKind::NotFound
+    );
+
+    provider.fail_next(Operation::CreateRecord, Fault::Unavailable);
+    let err = zone.create_record("www", &a(1), 300).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Transient);
+    assert!(err.kind().is_retryable());
+
+    // The kind survives boxing the custom error
+    let dyn_provider: Box<dyn DynProvider> = provider.clone().into();
+    let Err(err) = dyn_provider.create_zone("example.com").await else {
+        panic!("Creation should fail");
+    };
+    assert!(matches!(err, DynError::Custom(ErrorKind::AlreadyExists, _)));
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+}
+
+#[tokio::test]
+async fn test_capabilities() {
+    let provider = MemoryProvider::new()
+        .with_supported_types(["a", "TXT"])
+        .with_ttl_range(60..=86400);
+    let zone = zone(&provider).await;
+
+    let capabilities = provider.capabilities();
+    assert_eq!(zone.capabilities(), capabilities);
+    assert!(capabilities.list_zones && capabilities.create_zone && capabilities.delete_zone);
+    assert!(capabilities.create_record && capabilities.update_record);
+    assert!(capabilities.delete_record && capabilities.atomic_update);
+    assert!(capabilities.supports_type("A"));
+    assert!(capabilities.supports_type("txt"));
+    assert!(!capabilities.supports_type("MX"));
+    assert!(capabilities.supports_ttl(60));
+    assert!(!capabilities.supports_ttl(30));
+    assert_eq!(capabilities.clamp_ttl(30), 60);
+    assert_eq!(capabilities.clamp_ttl(100_000), 86400);
+
+    // Without restrictions, any type is accepted
+    let defaults = Capabilities::default();
+    assert!(defaults.supports_type("TYPE65534"));
+    assert!(!defaults.create_record);
+
+    // Only the enabled operations are reported through the dynamic wrappers
+    let dyn_provider: Box<dyn DynProvider> = provider.clone().into();
+    assert_eq!(dyn_provider.capabilities(), capabilities);
+
+    let dyn_provider: Box<dyn DynProvider> =
+        Box::new(DynProviderAdapter::new(provider.clone()).with_create_record());
+    let restricted = dyn_provider.capabilities();
+    assert!(!restricted.create_zone && !restricted.delete_zone);
+    assert!(restricted.create_record);
+    assert!(!restricted.update_record && !restricted.delete_record);
+    assert_eq!(restricted.record_types, capabilities.record_types);
+
+    let dyn_zone = dyn_provider
+        .get_zone(zone.id())
+        .await
+        .unwrap()
+        .capabilities();
+    assert!(dyn_zone.create_record);
+    assert!(!dyn_zone.update_record && !dyn_zone.delete_record);
+}
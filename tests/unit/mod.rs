@@ -5,5 +5,26 @@
 
 mod types;
 
+mod fingerprint;
+
+mod send_sync;
+
+mod reconcile;
+
+mod zonefile;
+
+mod typed;
+
+mod wire;
+
+#[cfg(feature = "acme")]
+mod acme;
+
+#[cfg(feature = "memory")]
+mod memory;
+
 #[cfg(feature = "namecheap")]
 mod namecheap;
+
+#[cfg(all(feature = "testing", feature = "memory"))]
+mod testing;
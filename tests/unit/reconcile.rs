@@ -0,0 +1,250 @@
+//! Unit tests for the declarative reconciliation engine.
+//!
+//! Plans are computed from plain record lists, so no zone or mock server is involved.
+//! Applying them is tested against the in-memory provider.
+
+use std::net::Ipv4Addr;
+
+use libdns::reconcile::{PlannedChange, Reconciler};
+use libdns::{Record, RecordData};
+
+const DOMAIN: &str = "example.com";
+
+fn record(id: &str, host: &str, data: RecordData, ttl: u64) -> Record {
+    Record {
+        id: id.to_string(),
+        host: host.to_string(),
+        data,
+        ttl,
+    }
+}
+
+fn a(host: &str, ip: [u8; 4], ttl: u64) -> Record {
+    record("", host, RecordData::A(Ipv4Addr::from(ip)), ttl)
+}
+
+/// Existing records as a provider reporting fully qualified hosts would return them.
+fn existing() -> Vec<Record> {
+    vec![
+        record(
+            "1",
+            "example.com",
+            RecordData::NS("ns1.provider.net.".to_string()),
+            86400,
+        ),
+        record(
+            "2",
+            "example.com",
+            RecordData::Other {
+                typ: "SOA".to_string(),
+                value: "ns1.provider.net. hostmaster.example.com. 1 7200 3600 86400 300"
+                    .to_string(),
+            },
+            86400,
+        ),
+        Record {
+            id: "3".to_string(),
+            ..a("www.example.com", [10, 0, 0, 1], 300)
+        },
+        Record {
+            id: "4".to_string(),
+            ..a("www.example.com", [10, 0, 0, 2], 300)
+        },
+        record(
+            "5",
+            "_acme-challenge.example.com",
+            RecordData::TXT("token".to_string()),
+            60,
+        ),
+    ]
+}
+
+#[test]
+fn test_plan_matching_zone_is_empty() {
+    let plan = Reconciler::new()
+        .ignore(|r| r.host.starts_with("_acme-challenge"))
+        .plan(
+            DOMAIN,
+            &existing(),
+            &[a("www", [10, 0, 0, 1], 300), a("www", [10, 0, 0, 2], 300)],
+        );
+
+    assert!(plan.is_empty());
+    assert_eq!(plan.unchanged().len(), 2);
+}
+
+#[test]
+fn test_plan_ignores_apex_authority() {
+    let plan = Reconciler::new().plan(DOMAIN, &existing(), &[]);
+
+    // Only the records outside of the apex SOA/NS are deleted
+    let deleted: Vec<&str> = plan
+        .changes()
+        .iter()
+        .map(|change| match change {
+            PlannedChange::Delete(r) => r.id.as_str(),
+            other => panic!("unexpected change {:?}", other),
+        })
+        .collect();
+    assert_eq!(deleted, vec!["3", "4", "5"]);
+
+    let plan = Reconciler::new()
+        .manage_apex_authority()
+        .plan(DOMAIN, &existing(), &[]);
+    assert_eq!(plan.changes().len(), 5);
+}
+
+#[test]
+fn test_plan_pairs_record_set_values_as_updates() {
+    let plan = Reconciler::new()
+        .ignore(|r| r.host.starts_with("_acme-challenge"))
+        .plan(
+            DOMAIN,
+            &existing(),
+            &[
+                a("www", [10, 0, 0, 1], 600),
+                a("www", [10, 0, 0, 3], 300),
+                a("api", [10, 0, 0, 4], 300),
+            ],
+        );
+
+    assert_eq!(
+        plan.changes(),
+        &[
+            // TTL change of an existing value
+            PlannedChange::Update {
+                from: existing()[2].clone(),
+                to: a("www", [10, 0, 0, 1], 600),
+            },
+            // The stale value is replaced by the new one
+            PlannedChange::Update {
+                from: existing()[3].clone(),
+                to: a("www", [10, 0, 0, 3], 300),
+            },
+            PlannedChange::Create(a("api", [10, 0, 0, 4], 300)),
+        ]
+    );
+}
+
+#[test]
+fn test_plan_skips_duplicate_desired_records() {
+    let plan = Reconciler::new().plan(
+        DOMAIN,
+        &[],
+        &[
+            a("www", [10, 0, 0, 1], 300),
+            a("www.example.com.", [10, 0, 0, 1], 300),
+        ],
+    );
+
+    assert_eq!(
+        plan.changes(),
+        &[PlannedChange::Create(a("www", [10, 0, 0, 1], 300))]
+    );
+}
+
+#[test]
+fn test_plan_owned_only_leaves_foreign_records_alone() {
+    // Only 10.0.0.2 was created by an earlier run
+    let plan = Reconciler::new()
+        .owned_only(vec![a("www", [10, 0, 0, 2], 300)])
+        .plan(
+            DOMAIN,
+            &existing(),
+            &[a("www", [10, 0, 0, 1], 300), a("www", [10, 0, 0, 3], 300)],
+        );
+
+    // 10.0.0.1 exists but is foreign, so it is neither adopted nor recreated
+    assert!(plan.unchanged().is_empty());
+    assert_eq!(
+        plan.changes(),
+        &[PlannedChange::Update {
+            from: existing()[3].clone(),
+            to: a("www", [10, 0, 0, 3], 300),
+        }]
+    );
+}
+
+#[cfg(feature = "memory")]
+#[tokio::test]
+async fn test_apply_creates_before_deleting() {
+    use libdns::memory::{Fault, MemoryProvider, Operation};
+    use libdns::reconcile::ApplyPlanError;
+    use libdns::{CreateRecord, CreateZone, DeleteRecordError, Zone};
+
+    let provider = MemoryProvider::new();
+    let zone = provider.create_zone(DOMAIN).await.unwrap();
+    zone.create_record("www", &RecordData::A(Ipv4Addr::new(10, 0, 0, 1)), 300)
+        .await
+        .unwrap();
+    zone.create_record("api", &RecordData::A(Ipv4Addr::new(10, 0, 0, 1)), 300)
+        .await
+        .unwrap();
+
+    // The value of www changes, while api only changes its TTL
+    let desired = [a("www", [10, 0, 0, 2], 300), a("api", [10, 0, 0, 1], 600)];
+    let plan = Reconciler::new().plan_zone(&zone, &desired).await.unwrap();
+    assert_eq!(plan.changes().len(), 2);
+
+    provider.fail_next(Operation::DeleteRecord, Fault::Unauthorized);
+    assert_eq!(
+        plan.apply(&zone).await,
+        Err(ApplyPlanError::Delete(DeleteRecordError::Unauthorized))
+    );
+    // The new value of www exists before the old one is deleted
+    assert_eq!(zone.list_records().await.unwrap().len(), 3);
+
+    // Records already replaced are looked up by their content
+    let plan = Reconciler::new().plan_zone(&zone, &desired).await.unwrap();
+    let owned = plan.apply(&zone).await.expect("Failed to apply plan");
+    assert_eq!(owned.len(), 2);
+    let mut records = zone.list_records().await.unwrap();
+    records.iter_mut().for_each(|r| r.id.clear());
+    records.sort_by(|a, b| a.host.cmp(&b.host));
+    assert_eq!(records, desired.iter().rev().cloned().collect::<Vec<_>>());
+}
+
+#[cfg(feature = "memory")]
+#[tokio::test]
+async fn test_apply_updates_in_place() {
+    use libdns::memory::MemoryProvider;
+    use libdns::{CreateRecord, CreateZone, Zone};
+
+    let provider = MemoryProvider::new();
+    let zone = provider.create_zone(DOMAIN).await.unwrap();
+    let www = zone
+        .create_record("www", &RecordData::A(Ipv4Addr::new(10, 0, 0, 1)), 300)
+        .await
+        .unwrap();
+    let old = zone
+        .create_record("old", &RecordData::A(Ipv4Addr::new(10, 0, 0, 1)), 300)
+        .await
+        .unwrap();
+
+    // IDs captured when planning may be outdated, like after a provider reassigned them
+    let outdated: Vec<Record> = zone
+        .list_records()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| Record {
+            id: format!("outdated-{}", r.id),
+            ..r
+        })
+        .collect();
+    let plan = Reconciler::new().plan(DOMAIN, &outdated, &[a("www", [10, 0, 0, 2], 600)]);
+
+    let owned = plan
+        .apply_updates(&zone)
+        .await
+        .expect("Failed to apply plan");
+    assert_eq!(owned.len(), 1);
+    assert_eq!(owned[0].id, www.id);
+    assert_eq!(zone.list_records().await.unwrap(), owned);
+    assert!(zone
+        .list_records()
+        .await
+        .unwrap()
+        .iter()
+        .all(|r| r.id != old.id));
+}
@@ -0,0 +1,169 @@
+//! Compile-time checks for thread safety.
+//!
+//! These tests never touch the network; they only fail to compile if a provider's
+//! types or futures stop being `Send`/`Sync`.
+
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use libdns::dynamic::{DynError, DynProvider, DynZone};
+use libdns::{
+    ApplyChanges, CreateRecord, DeleteRecord, Provider, RecordChange, RecordData, UpdateRecord,
+    Zone,
+};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+fn assert_send<T: Send>(_: &T) {}
+
+/// Asserts that every future of a provider and its zones is `Send`.
+fn assert_futures_send<P>(provider: &P, zone: &P::Zone)
+where
+    P: Provider,
+    P::Zone: CreateRecord + UpdateRecord + DeleteRecord + ApplyChanges,
+{
+    let data = RecordData::TXT("test".to_string());
+
+    assert_send(&provider.list_zones());
+    assert_send(&provider.get_zone("example.com"));
+    assert_send(&zone.list_records());
+    assert_send(&zone.get_record("id"));
+    assert_send(&zone.create_record("test", &data, 300));
+    assert_send(&zone.update_record("id", "test", &data, 300));
+    assert_send(&zone.delete_record("id"));
+    assert_send(&zone.apply_changes(&[RecordChange::Delete {
+        record_id: "id".to_string(),
+    }]));
+}
+
+/// Generic code must be able to spawn trait futures onto a multi-threaded runtime.
+fn spawn_list_records<Z>(zone: Arc<Z>)
+where
+    Z: Zone + 'static,
+    Z::CustomRetrieveError: 'static,
+{
+    drop(tokio::spawn(async move { zone.list_records().await }));
+}
+
+/// The DDNS update loop is meant to be spawned as a background task.
+#[cfg(feature = "ddns")]
+fn spawn_ddns_updater<Z>(zone: Arc<Z>)
+where
+    Z: libdns::ddns::DynamicDns + 'static,
+{
+    use libdns::ddns::{Addresses, DdnsUpdater};
+
+    drop(tokio::spawn(async move {
+        DdnsUpdater::new(zone.as_ref(), Addresses::default(), ["@"])
+            .run(|_| {})
+            .await
+    }));
+}
+
+#[test]
+fn test_dyn_types_are_send_sync() {
+    assert_send_sync::<Box<dyn DynProvider>>();
+    assert_send_sync::<Box<dyn DynZone>>();
+    assert_send_sync::<DynError>();
+}
+
+#[cfg(feature = "cloudflare")]
+#[test]
+fn test_cloudflare_is_send_sync() {
+    use libdns::cloudflare::{CloudflareError, CloudflareProvider, CloudflareZone};
+
+    assert_send_sync::<CloudflareProvider>();
+    assert_send_sync::<CloudflareZone>();
+    assert_send_sync::<CloudflareError>();
+    let _ = assert_futures_send::<CloudflareProvider>;
+    let _ = spawn_list_records::<CloudflareZone>;
+    #[cfg(feature = "ddns")]
+    let _ = spawn_ddns_updater::<CloudflareZone>;
+}
+
+#[cfg(feature = "dnspod")]
+#[test]
+fn test_dnspod_is_send_sync() {
+    use libdns::dnspod::{DnspodError, DnspodProvider, DnspodZone};
+
+    assert_send_sync::<DnspodProvider>();
+    assert_send_sync::<DnspodZone>();
+    assert_send_sync::<DnspodError>();
+    let _ = assert_futures_send::<DnspodProvider>;
+    let _ = spawn_list_records::<DnspodZone>;
+    #[cfg(feature = "ddns")]
+    let _ = spawn_ddns_updater::<DnspodZone>;
+}
+
+#[cfg(feature = "tencent")]
+#[test]
+fn test_tencent_is_send_sync() {
+    use libdns::tencent::{TencentError, TencentProvider, TencentZone};
+
+    assert_send_sync::<TencentProvider>();
+    assert_send_sync::<TencentZone>();
+    assert_send_sync::<TencentError>();
+    let _ = assert_futures_send::<TencentProvider>;
+    let _ = spawn_list_records::<TencentZone>;
+}
+
+#[cfg(feature = "hetzner")]
+#[test]
+fn test_hetzner_is_send_sync() {
+    use libdns::hetzner::{HetznerProvider, HetznerZone};
+
+    assert_send_sync::<HetznerProvider>();
+    assert_send_sync::<HetznerZone>();
+    assert_send_sync::<reqwest::Error>();
+    let _ = assert_futures_send::<HetznerProvider>;
+    let _ = spawn_list_records::<HetznerZone>;
+}
+
+#[cfg(feature = "technitium-dns")]
+#[test]
+fn test_technitium_is_send_sync() {
+    use libdns::technitium::{api::ApiError, TechnitiumProvider, TechnitiumZone};
+
+    assert_send_sync::<TechnitiumProvider>();
+    assert_send_sync::<TechnitiumZone>();
+    assert_send_sync::<ApiError>();
+    let _ = assert_futures_send::<TechnitiumProvider>;
+    let _ = spawn_list_records::<TechnitiumZone>;
+}
+
+#[cfg(feature = "namecheap")]
+#[test]
+fn test_namecheap_is_send_sync() {
+    use libdns::namecheap::{NamecheapError, NamecheapProvider, NamecheapZone};
+
+    assert_send_sync::<NamecheapProvider>();
+    assert_send_sync::<NamecheapZone>();
+    assert_send_sync::<NamecheapError>();
+    let _ = assert_futures_send::<NamecheapProvider>;
+    let _ = spawn_list_records::<NamecheapZone>;
+}
+
+#[cfg(feature = "rfc2136")]
+#[test]
+fn test_rfc2136_is_send_sync() {
+    use libdns::rfc2136::{api::Rfc2136Error, Rfc2136Provider, Rfc2136Zone};
+
+    assert_send_sync::<Rfc2136Provider>();
+    assert_send_sync::<Rfc2136Zone>();
+    assert_send_sync::<Rfc2136Error>();
+    let _ = assert_futures_send::<Rfc2136Provider>;
+    let _ = spawn_list_records::<Rfc2136Zone>;
+}
+
+#[cfg(feature = "memory")]
+#[test]
+fn test_memory_is_send_sync() {
+    use libdns::memory::{MemoryError, MemoryProvider, MemoryZone};
+
+    assert_send_sync::<MemoryProvider>();
+    assert_send_sync::<MemoryZone>();
+    assert_send_sync::<MemoryError>();
+    let _ = assert_futures_send::<MemoryProvider>;
+    let _ = spawn_list_records::<MemoryZone>;
+}
@@ -0,0 +1,86 @@
+//! Unit tests for the conformance suite, run against the in-memory provider.
+
+use libdns::memory::{Fault, MemoryProvider, Operation};
+use libdns::testing::{ConformanceSuite, Outcome, Scenario};
+use libdns::{CreateZone, RecordData};
+
+#[tokio::test]
+async fn test_memory_provider_conforms() {
+    let provider = MemoryProvider::new();
+
+    let report = ConformanceSuite::new("Example.com.").run(&provider).await;
+
+    assert!(report.is_conformant(), "{}", report);
+    assert!(report
+        .results
+        .iter()
+        .all(|result| result.outcome == Outcome::Passed));
+    assert_eq!(
+        report.supported_types(),
+        ["A", "AAAA", "CNAME", "MX", "SRV", "TXT"]
+    );
+    assert_eq!(report.zone_id.as_deref(), Some("zone-1"));
+}
+
+#[tokio::test]
+async fn test_unsupported_types_are_reported() {
+    let provider = MemoryProvider::new().with_supported_types(["TXT", "MX"]);
+
+    let report = ConformanceSuite::new("example.com").run(&provider).await;
+
+    assert!(report.is_conformant(), "{}", report);
+    assert_eq!(report.supported_types(), ["MX", "TXT"]);
+    let outcome = |scenario: Scenario| {
+        report
+            .results
+            .iter()
+            .find(|result| result.scenario == scenario)
+            .map(|result| result.outcome.clone())
+    };
+    assert_eq!(
+        outcome(Scenario::RecordRoundtrip("A".to_string())),
+        Some(Outcome::Unsupported)
+    );
+    assert_eq!(outcome(Scenario::HostForms), Some(Outcome::Passed));
+    assert_eq!(outcome(Scenario::MissingRecord), Some(Outcome::Passed));
+}
+
+#[tokio::test]
+async fn test_deviations_are_reported() {
+    let provider = MemoryProvider::new();
+    provider.fail_always(Operation::GetRecord, Fault::Unauthorized);
+
+    let report = ConformanceSuite::new("example.com")
+        .with_samples([RecordData::TXT("sample".to_string())])
+        .run(&provider)
+        .await;
+
+    assert!(!report.is_conformant());
+    let deviations = report.deviations();
+    assert!(deviations.iter().all(|(scenario, _)| matches!(
+        scenario,
+        Scenario::RecordRoundtrip(_) | Scenario::MissingRecord
+    )));
+    assert!(deviations.contains(&(
+        &Scenario::MissingRecord,
+        "get_record returned Unauthorized instead of NotFound"
+    )));
+    assert!(report
+        .to_string()
+        .contains("record roundtrip (TXT): deviated"));
+}
+
+#[tokio::test]
+async fn test_existing_zone_skips_record_scenarios() {
+    let provider = MemoryProvider::new();
+    provider.create_zone("example.com").await.unwrap();
+
+    let report = ConformanceSuite::new("example.com").run(&provider).await;
+
+    assert_eq!(report.zone_id, None);
+    assert_eq!(report.deviations().len(), 1);
+    assert_eq!(report.results[1].outcome, Outcome::Passed);
+    assert!(report.results[2..]
+        .iter()
+        .all(|result| result.outcome == Outcome::Skipped));
+}
@@ -0,0 +1,235 @@
+//! Unit tests for typed records.
+
+use std::net::Ipv4Addr;
+
+use libdns::typed::{TypedRecord, TypedRecordData, TypedRecordError};
+use libdns::types::{DomainName, RecordType, SrvData, Ttl};
+use libdns::{ClassifyError, ErrorKind, Record, RecordData};
+
+fn name(name: &str) -> DomainName {
+    DomainName::from_dotted(name).unwrap()
+}
+
+fn record(host: &str, data: RecordData, ttl: u64) -> Record {
+    Record {
+        id: "1".to_string(),
+        host: host.to_string(),
+        data,
+        ttl,
+    }
+}
+
+#[test]
+fn test_from_record() {
+    let srv = record(
+        "_sip._tcp",
+        RecordData::SRV {
+            priority: 10,
+            weight: 60,
+            port: 5060,
+            target: "sip.example.com.".to_string(),
+        },
+        300,
+    );
+    let typed = TypedRecord::from_record(&srv, "example.com.").expect("Failed to convert");
+
+    assert_eq!(typed.id, "1");
+    assert_eq!(typed.name, name("_sip._tcp.example.com"));
+    assert_eq!(typed.ttl, Ttl::new(300));
+    assert_eq!(
+        typed.data,
+        TypedRecordData::SRV(SrvData::new(10, 60, 5060, name("sip.example.com")))
+    );
+    assert_eq!(typed.data.record_type(), Some(RecordType::SRV));
+
+    // Fully qualified hosts and the apex resolve to the same names
+    let apex = record("example.com", RecordData::A(Ipv4Addr::LOCALHOST), 60);
+    let typed = TypedRecord::from_record(&apex, "example.com").expect("Failed to convert");
+    assert_eq!(typed.name, name("example.com"));
+    assert_eq!(typed.to_record("example.com").unwrap().host, "@");
+}
+
+#[test]
+fn test_from_record_internationalized() {
+    let cname = record("bücher", RecordData::CNAME("MÜNCHEN.de".to_string()), 300);
+    let typed = TypedRecord::from_record(&cname, "münchen.de").expect("Failed to convert");
+
+    // Unicode labels are converted to ASCII, which makes them compare equal regardless of case
+    assert_eq!(typed.name, name("xn--bcher-kva.xn--mnchen-3ya.de"));
+    assert_eq!(
+        typed.data,
+        TypedRecordData::CNAME(name("xn--mnchen-3ya.de"))
+    );
+    assert_eq!(typed.to_record("münchen.de").unwrap().host, "xn--bcher-kva");
+}
+
+#[test]
+fn test_from_record_validation() {
+    let convert = |host: &str, data: RecordData, ttl: u64| {
+        TypedRecord::from_record(&record(host, data, ttl), "example.com")
+    };
+    let a = RecordData::A(Ipv4Addr::LOCALHOST);
+
+    assert_eq!(
+        convert("www", a.clone(), 1 << 31),
+        Err(TypedRecordError::InvalidTtl(1 << 31))
+    );
+    assert_eq!(
+        convert("invalid..name", a.clone(), 300),
+        Err(TypedRecordError::InvalidName(
+            "invalid..name.example.com".to_string()
+        ))
+    );
+    assert_eq!(
+        convert("www", RecordData::CNAME("x".repeat(64)), 300),
+        Err(TypedRecordError::InvalidName("x".repeat(64)))
+    );
+    assert_eq!(
+        convert("www", RecordData::CNAME(String::new()), 300),
+        Err(TypedRecordError::InvalidName(String::new()))
+    );
+
+    // Names within data without a dedicated type are validated as well
+    let https = |target: &str| RecordData::HTTPS {
+        priority: 1,
+        target: target.to_string(),
+        params: String::new(),
+    };
+    assert!(convert("@", https("."), 300).is_ok());
+    assert!(matches!(
+        convert("@", https("a..b"), 300),
+        Err(TypedRecordError::InvalidName(_))
+    ));
+
+    let err = convert("www", a, u64::MAX).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Validation);
+}
+
+#[test]
+fn test_to_record() {
+    let typed = TypedRecord::new(
+        name("mail.example.com"),
+        Ttl::ONE_HOUR,
+        TypedRecordData::CNAME(name("example.com")),
+    );
+
+    let record = typed.to_record("Example.com.").expect("Failed to convert");
+    assert_eq!(record.id, "");
+    assert_eq!(record.host, "mail");
+    assert_eq!(record.data, RecordData::CNAME("example.com".to_string()));
+    assert_eq!(record.ttl, 3600);
+    assert_eq!(
+        TypedRecord::from_record(&record, "example.com"),
+        Ok(typed.clone())
+    );
+
+    assert_eq!(
+        typed.to_record("example.org"),
+        Err(TypedRecordError::OutsideZone {
+            name: "mail.example.com".to_string(),
+            zone: "example.org".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_data_conversions() {
+    let caa = RecordData::CAA {
+        flags: 0,
+        tag: "issue".to_string(),
+        value: "letsencrypt.org".to_string(),
+    };
+    let typed = TypedRecordData::try_from(caa.clone()).expect("Failed to convert");
+    assert_eq!(typed, TypedRecordData::Other(caa.clone()));
+    assert_eq!(typed.record_type(), Some(RecordType::CAA));
+    assert_eq!(RecordData::from(typed), caa);
+
+    let ptr = TypedRecordData::try_from(RecordData::PTR("host.example.com.".to_string()))
+        .expect("Failed to convert");
+    assert_eq!(ptr, TypedRecordData::PTR(name("host.example.com")));
+    // Names are converted back without their trailing dot
+    assert_eq!(
+        RecordData::from(ptr),
+        RecordData::PTR("host.example.com".to_string())
+    );
+
+    let unknown = TypedRecordData::Other(RecordData::Other {
+        typ: "UNKNOWN".to_string(),
+        value: "value".to_string(),
+    });
+    assert_eq!(unknown.record_type(), None);
+}
+
+#[cfg(feature = "memory")]
+#[tokio::test]
+async fn test_typed_records_are_checked_before_sending() {
+    use libdns::memory::{Fault, MemoryProvider, Operation};
+    use libdns::typed::TypedRecords;
+    use libdns::{CreateRecordError, CreateZone, UpdateRecordError, Zone};
+
+    let provider = MemoryProvider::new()
+        .with_supported_types(["A", "TXT"])
+        .with_ttl_range(60..=86400);
+    let zone = provider
+        .create_zone("example.com")
+        .await
+        .expect("Failed to create zone");
+
+    let a = |ttl: u32| {
+        TypedRecord::new(
+            name("www.example.com"),
+            Ttl::new(ttl),
+            TypedRecordData::A(Ipv4Addr::new(192, 0, 2, 1)),
+        )
+    };
+    let created = zone
+        .create_typed_record(&a(300))
+        .await
+        .expect("Failed to create record");
+    assert_eq!(created.host, "www");
+    assert_eq!(created.ttl, 300);
+
+    // Rejected records never reach the provider, which would fail otherwise
+    provider.fail_always(Operation::CreateRecord, Fault::Unauthorized);
+    provider.fail_always(Operation::UpdateRecord, Fault::Unauthorized);
+
+    assert_eq!(
+        zone.create_typed_record(&a(30)).await,
+        Err(CreateRecordError::InvalidRecord)
+    );
+    let cname = TypedRecord::new(
+        name("api.example.com"),
+        Ttl::ONE_HOUR,
+        TypedRecordData::CNAME(name("www.example.com")),
+    );
+    assert_eq!(
+        zone.create_typed_record(&cname).await,
+        Err(CreateRecordError::UnsupportedType)
+    );
+    let outside = TypedRecord::new(name("www.example.org"), Ttl::ONE_HOUR, a(300).data);
+    assert_eq!(
+        zone.create_typed_record(&outside).await,
+        Err(CreateRecordError::InvalidRecord)
+    );
+
+    let update = TypedRecord {
+        id: created.id.clone(),
+        ..a(Ttl::MAX.as_secs())
+    };
+    assert_eq!(
+        zone.update_typed_record(&update).await,
+        Err(UpdateRecordError::InvalidRecord)
+    );
+
+    provider.clear_faults();
+    let update = TypedRecord {
+        id: created.id,
+        ..a(600)
+    };
+    let updated = zone
+        .update_typed_record(&update)
+        .await
+        .expect("Failed to update record");
+    assert_eq!(updated.ttl, 600);
+    assert_eq!(zone.list_records().await.unwrap().len(), 1);
+}
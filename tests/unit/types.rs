@@ -2,6 +2,8 @@
 //!
 //! Tests Label, DomainName, Ttl, RecordType, and related type properties.
 
+use std::collections::HashSet;
+
 use libdns::types::{DomainName, Label, RecordClass, RecordType, Ttl, MAX_TTL};
 
 #[test]
@@ -61,6 +63,128 @@ fn test_domain_name_creation() {
     assert!(root.is_root());
 }
 
+fn name(name: &str) -> DomainName {
+    DomainName::from_dotted(name).unwrap()
+}
+
+#[test]
+fn test_domain_name_case_insensitive() {
+    assert_eq!(name("Example.COM"), name("example.com"));
+    assert_eq!(Label::from_str("WWW"), Label::from_str("www"));
+    assert_ne!(name("example.com"), name("example.org"));
+    // Only ASCII letters are folded
+    assert_ne!(name("MÜNCHEN.de"), name("münchen.de"));
+
+    let names: HashSet<DomainName> = ["example.com", "EXAMPLE.com.", "www.example.com"]
+        .into_iter()
+        .map(name)
+        .collect();
+    assert_eq!(names.len(), 2);
+
+    // The original case is kept
+    assert_eq!(name("Example.COM").to_dotted(), "Example.COM");
+}
+
+#[test]
+fn test_domain_name_canonical_order() {
+    // The example of RFC 4034 §6.1
+    let z = name("z.example");
+    let mut expected = vec![
+        name("example"),
+        name("a.example"),
+        name("yljkjljk.a.example"),
+        name("Z.a.example"),
+        name("zABC.a.EXAMPLE"),
+        z.clone(),
+        z.prepend(&Label::new(&[1]).unwrap()).unwrap(),
+        name("*.z.example"),
+        z.prepend(&Label::new(&[200]).unwrap()).unwrap(),
+    ];
+
+    let mut sorted = expected.clone();
+    sorted.reverse();
+    sorted.sort();
+    assert_eq!(sorted, expected);
+
+    expected.dedup();
+    assert_eq!(expected.len(), 9);
+    assert!(DomainName::default() < name("example"));
+}
+
+#[test]
+fn test_domain_name_labels() {
+    let www = name("www.Example.com");
+    let labels: Vec<Label> = www.labels().collect();
+    assert_eq!(
+        labels,
+        ["www", "example", "com"].map(|l| Label::from_str(l).unwrap())
+    );
+    assert_eq!(www.label_count(), 3);
+    assert_eq!(DomainName::default().label_count(), 0);
+
+    assert_eq!(www.parent(), Some(name("example.com")));
+    assert_eq!(name("com").parent(), Some(DomainName::default()));
+    assert_eq!(DomainName::default().parent(), None);
+
+    let www_label = Label::from_str("www").unwrap();
+    assert_eq!(name("example.com").prepend(&www_label), Some(www.clone()));
+    assert_eq!(name("example.com").prepend(&Label::default()), None);
+    assert_eq!(name("www").append(&name("example.com")), Some(www.clone()));
+    assert_eq!(DomainName::default().append(&www), Some(www.clone()));
+    let long = name(&vec!["a".repeat(63); 3].join("."));
+    assert_eq!(long.append(&name(&"b".repeat(63))), None);
+}
+
+#[test]
+fn test_domain_name_relative_to_zone() {
+    let zone = name("example.com");
+    let www = name("_acme-challenge.WWW.example.COM");
+
+    assert!(www.is_subdomain_of(&zone));
+    assert!(zone.is_subdomain_of(&zone));
+    assert!(www.is_subdomain_of(&DomainName::default()));
+    assert!(!zone.is_subdomain_of(&www));
+    // Zones only match whole labels
+    assert!(!name("badexample.com").is_subdomain_of(&zone));
+    assert!(!name("example.com.evil").is_subdomain_of(&zone));
+
+    assert_eq!(
+        www.relative_to(&zone).as_deref(),
+        Some("_acme-challenge.WWW")
+    );
+    assert_eq!(name("Example.com").relative_to(&zone).as_deref(), Some("@"));
+    assert_eq!(name("example.org").relative_to(&zone), None);
+}
+
+#[test]
+fn test_domain_name_idna() {
+    let unicode = DomainName::from_dotted("münchen.de").unwrap();
+    let ascii = unicode.to_ascii().unwrap();
+    assert_eq!(ascii.to_dotted(), "xn--mnchen-3ya.de");
+    assert_eq!(ascii.to_unicode(), "münchen.de");
+    assert_eq!(
+        DomainName::from_unicode("_acme-challenge.BÜCHER.example.").unwrap(),
+        DomainName::from_dotted("_acme-challenge.xn--bcher-kva.example").unwrap()
+    );
+
+    // ASCII names are kept as they are
+    let ascii = DomainName::from_dotted("_sip._tcp.Example.com").unwrap();
+    assert_eq!(ascii.to_ascii(), Some(ascii.clone()));
+    assert_eq!(ascii.to_unicode(), "_sip._tcp.Example.com");
+    assert_eq!(
+        DomainName::from_dotted("xn--invalid-.de")
+            .unwrap()
+            .to_unicode(),
+        "xn--invalid-.de"
+    );
+
+    // Unicode labels above 63 octets in UTF-8 may still fit in ASCII
+    let long = "ü".repeat(40);
+    assert!(DomainName::from_dotted(&long).is_none());
+    assert!(DomainName::from_unicode(&long).is_some());
+    assert!(DomainName::from_unicode(&"ü".repeat(80)).is_none());
+}
+
 #[test]
 fn test_ttl_clamping() {
     let ttl = Ttl::new(u32::MAX);
@@ -0,0 +1,238 @@
+//! Unit tests for the DNS wire format.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use libdns::types::{DomainName, RecordClass, Ttl};
+use libdns::wire::{ResourceRecord, WireError};
+use libdns::{ClassifyError, ErrorKind, RecordData};
+
+fn name(name: &str) -> DomainName {
+    DomainName::from_dotted(name).unwrap()
+}
+
+/// Returns an `IN` record of `example.com` with a TTL of 300 seconds in wire format.
+fn wire(typ: u16, rdata: &[u8]) -> Vec<u8> {
+    let mut bytes = name("example.com").as_wire_bytes().to_vec();
+    bytes.extend_from_slice(&typ.to_be_bytes());
+    bytes.extend_from_slice(&[0x00, 0x01, 0x00, 0x00, 0x01, 0x2c]);
+    bytes.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(rdata);
+    bytes
+}
+
+fn record(data: RecordData) -> ResourceRecord {
+    ResourceRecord::new(name("example.com"), Ttl::new(300), data)
+}
+
+#[test]
+fn test_encode_record() {
+    let a = record(RecordData::A(Ipv4Addr::new(192, 0, 2, 1)));
+    assert_eq!(a.to_wire(), Ok(wire(1, &[192, 0, 2, 1])));
+
+    let mx = record(RecordData::MX {
+        priority: 10,
+        mail_server: "Mail.example.com.".to_string(),
+    });
+    let bytes = mx.to_wire().expect("Failed to encode record");
+    assert_eq!(bytes, wire(15, b"\x00\x0a\x04Mail\x07example\x03com\x00"));
+    // Names keep their case, but lose their trailing dot
+    assert_eq!(
+        ResourceRecord::from_wire(&bytes).unwrap().data,
+        RecordData::MX {
+            priority: 10,
+            mail_server: "Mail.example.com".to_string()
+        }
+    );
+
+    // Records are appended to the buffer
+    let mut buf = vec![0xff];
+    a.encode(&mut buf).expect("Failed to encode record");
+    assert_eq!(ResourceRecord::decode(&buf, 1), Ok((a, buf.len())));
+}
+
+#[test]
+fn test_encode_internationalized_names() {
+    let cname = record(RecordData::CNAME("Bücher.MÜNCHEN.de".to_string()));
+    let bytes = cname.to_wire().expect("Failed to encode record");
+
+    // Unicode labels are written in ASCII form
+    assert_eq!(
+        bytes,
+        wire(5, b"\x0dxn--bcher-kva\x0exn--mnchen-3ya\x02de\x00")
+    );
+}
+
+#[test]
+fn test_service_binding_vectors() {
+    // Test vectors of RFC 9460 Appendix D
+    let svcb = |target: &str, params: &str| {
+        record(RecordData::SVCB {
+            priority: 1,
+            target: target.to_string(),
+            params: params.to_string(),
+        })
+    };
+    let target = b"\x00\x01\x03foo\x07example\x03com\x00";
+
+    let alias = record(RecordData::HTTPS {
+        priority: 0,
+        target: "foo.example.com".to_string(),
+        params: String::new(),
+    });
+    assert_eq!(
+        alias.to_wire(),
+        Ok(wire(65, b"\x00\x00\x03foo\x07example\x03com\x00"))
+    );
+
+    let port = svcb(".", "port=53");
+    assert_eq!(
+        port.to_wire(),
+        Ok(wire(64, b"\x00\x01\x00\x00\x03\x00\x02\x00\x35"))
+    );
+
+    let generic = svcb("foo.example.com", "key667=hello");
+    assert_eq!(
+        generic.to_wire(),
+        Ok(wire(64, &[&target[..], b"\x02\x9b\x00\x05hello"].concat()))
+    );
+
+    let escaped = svcb("foo.example.com", "key667=\"hello\\210qoo\"");
+    let bytes = escaped.to_wire().expect("Failed to encode record");
+    assert_eq!(
+        bytes,
+        wire(64, &[&target[..], b"\x02\x9b\x00\x09hello\xd2qoo"].concat())
+    );
+    assert_eq!(
+        ResourceRecord::from_wire(&bytes).unwrap(),
+        svcb("foo.example.com", "key667=hello\\210qoo")
+    );
+
+    let hints = svcb("foo.example.com", "ipv6hint=2001:db8::1,2001:db8::53:1");
+    let ips: Vec<u8> = ["2001:db8::1", "2001:db8::53:1"]
+        .iter()
+        .flat_map(|ip| ip.parse::<Ipv6Addr>().unwrap().octets())
+        .collect();
+    assert_eq!(
+        hints.to_wire(),
+        Ok(wire(64, &[&target[..], b"\x00\x06\x00\x20", &ips].concat()))
+    );
+
+    // Parameters and mandatory keys are sorted by their key
+    let unsorted = svcb(
+        "foo.example.com",
+        "alpn=h2,h3-19 mandatory=ipv4hint,alpn ipv4hint=192.0.2.1",
+    );
+    let bytes = unsorted.to_wire().expect("Failed to encode record");
+    assert_eq!(
+        bytes,
+        wire(
+            64,
+            &[
+                &target[..],
+                b"\x00\x00\x00\x04\x00\x01\x00\x04",
+                b"\x00\x01\x00\x09\x02h2\x05h3-19",
+                b"\x00\x04\x00\x04\xc0\x00\x02\x01"
+            ]
+            .concat()
+        )
+    );
+    assert_eq!(
+        ResourceRecord::from_wire(&bytes).unwrap(),
+        svcb(
+            "foo.example.com",
+            "mandatory=alpn,ipv4hint alpn=h2,h3-19 ipv4hint=192.0.2.1"
+        )
+    );
+
+    let ech = svcb(".", "no-default-alpn ech=AEX+/w==");
+    let bytes = ech.to_wire().expect("Failed to encode record");
+    assert_eq!(ResourceRecord::from_wire(&bytes), Ok(ech));
+}
+
+#[test]
+fn test_decode_record() {
+    let mut bytes = wire(1, &[192, 0, 2, 1]);
+
+    // TTLs above 2^31 - 1 seconds are treated as zero
+    bytes[17..21].copy_from_slice(&0x8000_0000u32.to_be_bytes());
+    let record = ResourceRecord::from_wire(&bytes).expect("Failed to decode record");
+    assert_eq!(record.ttl, Ttl::ZERO);
+    assert_eq!(record.class, RecordClass::IN);
+
+    bytes[15..17].copy_from_slice(&5u16.to_be_bytes());
+    assert_eq!(
+        ResourceRecord::from_wire(&bytes),
+        Err(WireError::UnknownClass(5))
+    );
+
+    // Unknown types are decoded in the generic form
+    let other = ResourceRecord::from_wire(&wire(4321, &[0xab, 0xcd])).unwrap();
+    assert_eq!(
+        other.data,
+        RecordData::Other {
+            typ: "TYPE4321".to_string(),
+            value: "\\# 2 abcd".to_string()
+        }
+    );
+    assert_eq!(other.to_wire(), Ok(wire(4321, &[0xab, 0xcd])));
+}
+
+#[test]
+fn test_decode_errors() {
+    let malformed = |bytes: &[u8]| {
+        assert_eq!(
+            ResourceRecord::from_wire(bytes),
+            Err(WireError::Malformed),
+            "{:02x?}",
+            bytes
+        )
+    };
+
+    // RDATA shorter or longer than its type requires
+    malformed(&wire(1, &[192, 0, 2]));
+    malformed(&wire(1, &[192, 0, 2, 1, 0]));
+    // CAA record whose tag exceeds the RDATA
+    malformed(&wire(257, &[0, 5, b'i']));
+    // Trailing bytes after the record
+    malformed(&[wire(1, &[192, 0, 2, 1]), vec![0]].concat());
+    // Compression pointer to itself
+    malformed(&[&[0xc0, 0x00][..], &wire(1, &[192, 0, 2, 1])[13..]].concat());
+    // Service parameters out of order or with an invalid value
+    malformed(&wire(
+        64,
+        b"\x00\x01\x00\x00\x03\x00\x02\x00\x35\x00\x01\x00\x03\x02h2",
+    ));
+    malformed(&wire(64, b"\x00\x01\x00\x00\x03\x00\x01\x35"));
+}
+
+#[test]
+fn test_encode_errors() {
+    let invalid = |data: RecordData| {
+        let err = record(data).to_wire().unwrap_err();
+        assert_eq!(err, WireError::InvalidData);
+        assert_eq!(err.kind(), ErrorKind::Validation);
+    };
+
+    invalid(RecordData::CNAME("invalid..name".to_string()));
+    invalid(RecordData::TLSA {
+        usage: 3,
+        selector: 1,
+        matching_type: 1,
+        certificate: "xyz".to_string(),
+    });
+    invalid(RecordData::HTTPS {
+        priority: 1,
+        target: ".".to_string(),
+        params: "port=1 port=2".to_string(),
+    });
+    invalid(RecordData::SVCB {
+        priority: 1,
+        target: ".".to_string(),
+        params: "unknown=1".to_string(),
+    });
+    invalid(RecordData::Other {
+        typ: "HINFO".to_string(),
+        value: "PC Linux".to_string(),
+    });
+    invalid(RecordData::TXT("a".repeat(u16::MAX as usize)));
+}
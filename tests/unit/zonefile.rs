@@ -0,0 +1,262 @@
+//! Unit tests for zone file import and export.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use libdns::types::DomainName;
+use libdns::zonefile::{ParseErrorKind, ZoneFile};
+use libdns::{Record, RecordData};
+
+const ZONE: &str = r#"
+; Example zone
+$ORIGIN example.com.
+$TTL 1h
+@           IN  SOA ns1 hostmaster (
+                    2024010101 ; serial
+                    2h         ; refresh
+                    1h         ; retry
+                    2w         ; expire
+                    300 )      ; minimum
+            IN  NS  ns1
+            IN  NS  ns2.provider.net.
+            IN  MX  10 mail
+ns1     300 IN  A   192.0.2.53
+www     IN  300 A   192.0.2.1
+            AAAA    2001:db8::1
+_sip._tcp   SRV 10 60 5060 sip
+@           TXT "v=spf1 include:_spf.example.net ~all"
+dkim        TXT ( "first half; "
+                  "second \"half\"" )
+$ORIGIN sub.example.com.
+api         CNAME www.example.com.
+"#;
+
+fn parse() -> ZoneFile {
+    ZoneFile::parse(ZONE, "example.com").expect("Failed to parse zone")
+}
+
+#[test]
+fn test_parse_records() {
+    let zone = parse();
+    let records: Vec<(&str, &RecordData, u64)> = zone
+        .records
+        .iter()
+        .map(|r| (r.host.as_str(), &r.data, r.ttl))
+        .collect();
+
+    assert_eq!(records.len(), 11);
+    assert_eq!(
+        records[1],
+        ("@", &RecordData::NS("ns1.example.com.".to_string()), 3600)
+    );
+    assert_eq!(
+        records[2],
+        ("@", &RecordData::NS("ns2.provider.net.".to_string()), 3600)
+    );
+    assert_eq!(
+        records[3].1,
+        &RecordData::MX {
+            priority: 10,
+            mail_server: "mail.example.com.".to_string()
+        }
+    );
+    assert_eq!(
+        records[4],
+        ("ns1", &RecordData::A(Ipv4Addr::new(192, 0, 2, 53)), 300)
+    );
+    // TTL and class in reverse order
+    assert_eq!(
+        records[5],
+        ("www", &RecordData::A(Ipv4Addr::new(192, 0, 2, 1)), 300)
+    );
+    // Omitted owner is taken from the previous record, omitted TTL from $TTL
+    assert_eq!(
+        records[6],
+        (
+            "www",
+            &RecordData::AAAA("2001:db8::1".parse::<Ipv6Addr>().unwrap()),
+            3600
+        )
+    );
+    assert_eq!(
+        records[7],
+        (
+            "_sip._tcp",
+            &RecordData::SRV {
+                priority: 10,
+                weight: 60,
+                port: 5060,
+                target: "sip.example.com.".to_string()
+            },
+            3600
+        )
+    );
+    assert_eq!(
+        records[9],
+        (
+            "dkim",
+            &RecordData::TXT("first half; second \"half\"".to_string()),
+            3600
+        )
+    );
+    assert_eq!(
+        records[10],
+        (
+            "api.sub",
+            &RecordData::CNAME("www.example.com.".to_string()),
+            3600
+        )
+    );
+}
+
+#[test]
+fn test_parse_ttl_without_directive() {
+    let zone = ZoneFile::parse("www 1h30m A 192.0.2.1\n    A 192.0.2.2\n", "example.com")
+        .expect("Failed to parse zone");
+
+    // Without $TTL, the last explicit TTL is reused
+    assert_eq!(zone.records[0].ttl, 5400);
+    assert_eq!(zone.records[1].ttl, 5400);
+}
+
+#[test]
+fn test_parse_soa() {
+    let soa = parse().soa().expect("Missing SOA record");
+
+    assert_eq!(
+        soa.mname,
+        DomainName::from_dotted("ns1.example.com").unwrap()
+    );
+    assert_eq!(
+        soa.rname,
+        DomainName::from_dotted("hostmaster.example.com").unwrap()
+    );
+    assert_eq!(soa.serial, 2024010101);
+    assert_eq!(soa.refresh, 7200);
+    assert_eq!(soa.retry, 3600);
+    assert_eq!(soa.expire, 1209600);
+    assert_eq!(soa.minimum, 300);
+}
+
+#[test]
+fn test_round_trip() {
+    let zone = parse();
+    let exported = zone.to_string();
+    let reparsed = ZoneFile::parse(&exported, "example.com").expect("Failed to parse export");
+
+    assert_eq!(reparsed.records, zone.records);
+    assert_eq!(reparsed.default_ttl, Some(3600));
+}
+
+#[test]
+fn test_typed_records() {
+    let zone = ZoneFile::parse(
+        r#"
+$TTL 300
+@           CAA     0 issue "letsencrypt.org"
+_443._tcp   TLSA    3 1 1 ( 0C72AC70B745AC19
+                            998811B131D662C9 )
+1.2         PTR     host
+@           HTTPS   1 . alpn=h2
+sip         NAPTR   100 10 "S" "SIP+D2U" "" _sip._udp
+"#,
+        "example.com",
+    )
+    .expect("Failed to parse zone");
+    let data: Vec<&RecordData> = zone.records.iter().map(|r| &r.data).collect();
+
+    assert_eq!(
+        data,
+        [
+            &RecordData::CAA {
+                flags: 0,
+                tag: "issue".to_string(),
+                value: "letsencrypt.org".to_string()
+            },
+            &RecordData::TLSA {
+                usage: 3,
+                selector: 1,
+                matching_type: 1,
+                certificate: "0C72AC70B745AC19998811B131D662C9".to_string()
+            },
+            &RecordData::PTR("host.example.com.".to_string()),
+            &RecordData::HTTPS {
+                priority: 1,
+                target: ".".to_string(),
+                params: "alpn=h2".to_string()
+            },
+            &RecordData::NAPTR {
+                order: 100,
+                preference: 10,
+                flags: "S".to_string(),
+                services: "SIP+D2U".to_string(),
+                regexp: String::new(),
+                replacement: "_sip._udp.example.com.".to_string()
+            },
+        ]
+    );
+
+    let reparsed =
+        ZoneFile::parse(&zone.to_string(), "example.com").expect("Failed to parse export");
+    assert_eq!(reparsed.records, zone.records);
+}
+
+#[test]
+fn test_export_provider_records() {
+    let record = |host: &str, data: RecordData| Record {
+        id: "1".to_string(),
+        host: host.to_string(),
+        data,
+        ttl: 300,
+    };
+    let zone = ZoneFile::new(
+        "example.com.",
+        vec![
+            record(
+                "www.example.com",
+                RecordData::CNAME("example.com".to_string()),
+            ),
+            record("@", RecordData::TXT("\"quoted\"".to_string())),
+            record("x".repeat(10).as_str(), RecordData::TXT("a".repeat(300))),
+        ],
+    );
+    let exported = zone.to_string();
+    let lines: Vec<&str> = exported.lines().collect();
+
+    assert_eq!(lines[0], "$ORIGIN example.com.");
+    assert_eq!(lines[1], "www\t300\tIN\tCNAME\texample.com.");
+    assert_eq!(lines[2], "@\t300\tIN\tTXT\t\"quoted\"");
+    // Long TXT values are split into multiple character strings
+    assert!(lines[3].ends_with(&format!("\"{}\" \"{}\"", "a".repeat(255), "a".repeat(45))));
+}
+
+#[test]
+fn test_parse_errors() {
+    let error =
+        |input: &str| ZoneFile::parse(input, "example.com").expect_err("Parsing should fail");
+
+    let err = error("www 300 IN A 192.0.2.1\nwww IN A 192.0.2.2 (\n");
+    assert_eq!(err.line, 2);
+    assert_eq!(err.kind, ParseErrorKind::UnbalancedParentheses);
+
+    assert_eq!(error("www A 192.0.2.1").kind, ParseErrorKind::MissingTtl);
+    assert_eq!(
+        error("   300 A 192.0.2.1").kind,
+        ParseErrorKind::MissingOwner
+    );
+    assert_eq!(
+        error("www 300 CH A 192.0.2.1").kind,
+        ParseErrorKind::UnsupportedClass("CH".to_string())
+    );
+    assert_eq!(
+        error("$INCLUDE other.zone").kind,
+        ParseErrorKind::UnsupportedDirective("$INCLUDE".to_string())
+    );
+    assert_eq!(
+        error("www 300 TXT \"open").kind,
+        ParseErrorKind::UnterminatedString
+    );
+    assert!(matches!(
+        error("www 300 A 192.0.2").kind,
+        ParseErrorKind::InvalidData { .. }
+    ));
+}